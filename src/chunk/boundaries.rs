@@ -60,6 +60,11 @@ pub fn is_in_code_block(position: usize, code_blocks: &[(usize, usize)]) -> bool
         .any(|(start, end)| position >= *start && position < *end)
 }
 
+/// Check if a position falls strictly inside a span, so breaking there would split it
+pub fn is_inside_span(position: usize, (start, end): (usize, usize)) -> bool {
+    position > start && position < end
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +93,11 @@ mod tests {
         assert!(!is_in_code_block(35, &blocks));
         assert!(is_in_code_block(60, &blocks));
     }
+
+    #[test]
+    fn test_is_inside_span() {
+        assert!(!is_inside_span(10, (10, 30)));
+        assert!(is_inside_span(11, (10, 30)));
+        assert!(!is_inside_span(30, (10, 30)));
+    }
 }
@@ -1,8 +1,10 @@
 //! Text chunking with structure awareness
 //!
-//! This module handles splitting documents into chunks while:
+//! This module handles splitting documents into chunks, using the configured
+//! strategy (fixed windows, packed sentences, or heading sections), while:
 //! - Respecting heading boundaries when possible
 //! - Maintaining code block integrity
+//! - Keeping tables whole
 //! - Providing stable, deterministic chunk boundaries
 //! - Computing content hashes for incremental updates
 
@@ -10,10 +12,11 @@ mod boundaries;
 
 pub use boundaries::*;
 
-use crate::config::ChunkConfig;
-use crate::error::Result;
+use crate::config::{ChunkConfig, ChunkStrategy};
+use crate::error::{Error, Result};
 use crate::parse::{Heading, ParsedDocument};
 use blake3::Hasher;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// A text chunk with metadata
 #[derive(Debug, Clone)]
@@ -30,8 +33,8 @@ pub struct TextChunk {
     /// Chunk index (0-based)
     pub index: usize,
 
-    /// Headings that apply to this chunk
-    pub headings: Vec<String>,
+    /// Headings that apply to this chunk, outermost first
+    pub headings: Vec<SectionHeading>,
 
     /// Blake3 hash of the normalized text
     pub hash: String,
@@ -39,32 +42,69 @@ pub struct TextChunk {
 
 impl TextChunk {
     /// Compute the hash for this chunk
-    pub fn compute_hash(text: &str, doc_hash: &str) -> String {
+    pub fn compute_hash(text: &str) -> String {
         let mut hasher = Hasher::new();
-        hasher.update(doc_hash.as_bytes());
         hasher.update(text.as_bytes());
         hasher.finalize().to_hex().to_string()
     }
 }
 
-/// Chunk a parsed document
-pub fn chunk_document(
-    doc: &ParsedDocument,
-    doc_hash: &str,
-    config: &ChunkConfig,
-) -> Result<Vec<TextChunk>> {
+/// Chunk a parsed document using the configured strategy
+pub fn chunk_document(doc: &ParsedDocument, config: &ChunkConfig) -> Result<Vec<TextChunk>> {
     let text = &doc.text;
 
     if text.is_empty() {
         return Ok(Vec::new());
     }
 
-    // Find potential break points
-    let break_points = find_break_points(text, &doc.headings, config);
+    // Tables are atomic: never break inside one
+    let tables: Vec<(usize, usize)> = doc
+        .tables
+        .iter()
+        .map(|t| t.span())
+        .filter(|&(start, end)| {
+            end <= text.len() && text.is_char_boundary(start) && text.is_char_boundary(end)
+        })
+        .collect();
 
     let mut chunks = Vec::new();
+    match config.strategy {
+        ChunkStrategy::Fixed => chunk_fixed(doc, &tables, config, &mut chunks),
+        ChunkStrategy::Sentence => {
+            let points = find_break_points(text, &doc.headings, &tables, config);
+            let units = split_units(text, 0, text.len(), &points, &tables, config);
+            pack_units(doc, &units, config, &mut chunks);
+        }
+        ChunkStrategy::Heading => {
+            let points = find_break_points(text, &doc.headings, &tables, config);
+            for (start, end) in heading_sections(text, &doc.headings, &tables) {
+                let units = split_units(text, start, end, &points, &tables, config);
+                pack_units(doc, &units, config, &mut chunks);
+            }
+        }
+        ChunkStrategy::Semantic => {
+            return Err(Error::Config(
+                "chunk.strategy \"semantic\" is not yet implemented".to_string(),
+            ));
+        }
+    }
+
+    Ok(chunks)
+}
+
+/// Fixed-size windows, breaking at the best boundary near `max_chars`
+fn chunk_fixed(
+    doc: &ParsedDocument,
+    tables: &[(usize, usize)],
+    config: &ChunkConfig,
+    chunks: &mut Vec<TextChunk>,
+) {
+    let text = &doc.text;
+
+    // Find potential break points
+    let break_points = find_break_points(text, &doc.headings, tables, config);
+
     let mut current_start = 0;
-    let mut chunk_index = 0;
 
     while current_start < text.len() {
         // Ensure current_start is on a char boundary
@@ -83,62 +123,274 @@ pub fn chunk_document(
             find_best_break(text, current_start, target_end, &break_points, config)
         };
 
-        // Ensure chunk_end is valid
-        let chunk_end = ensure_char_boundary(text, chunk_end);
+        // Ensure chunk_end is valid and outside any table
+        let chunk_end =
+            keep_spans_whole(current_start, ensure_char_boundary(text, chunk_end), tables);
         if chunk_end <= current_start {
             current_start = chunk_end + 1;
             continue;
         }
 
-        // Extract chunk text (now guaranteed to be on char boundaries)
-        let chunk_text = text[current_start..chunk_end].trim().to_string();
-
         // Skip if too small (unless it's the last chunk)
-        if chunk_text.len() < config.min_chars && chunk_end < text.len() {
+        if text[current_start..chunk_end].trim().len() < config.min_chars && chunk_end < text.len()
+        {
             current_start = chunk_end;
             continue;
         }
 
-        if !chunk_text.is_empty() {
-            // Get headings that apply to this chunk
-            let headings = doc
-                .headings_at_position(current_start)
-                .iter()
-                .map(|h| h.text.clone())
-                .collect();
-
-            let hash = TextChunk::compute_hash(&chunk_text, doc_hash);
-
-            chunks.push(TextChunk {
-                text: chunk_text,
-                char_start: current_start,
-                char_end: chunk_end,
-                index: chunk_index,
-                headings,
-                hash,
-            });
-
-            chunk_index += 1;
-        }
+        push_chunk(doc, current_start, chunk_end, chunks);
 
         // Move to next chunk with overlap
         if chunk_end >= text.len() {
             break;
         }
 
-        // Ensure overlap position is on a char boundary
-        current_start = if chunk_end > config.overlap_chars {
+        // Ensure overlap position is on a char boundary, and don't start mid-table.
+        // A chunk cut short before a table gets no overlap, so the next one
+        // starts with the table itself.
+        current_start = if tables.iter().any(|&(start, _)| start == chunk_end) {
+            chunk_end
+        } else if chunk_end > config.overlap_chars {
             ensure_char_boundary(text, chunk_end - config.overlap_chars)
         } else {
             chunk_end
         };
+        if let Some(&(_, end)) = tables
+            .iter()
+            .find(|&&span| is_inside_span(current_start, span))
+        {
+            current_start = end;
+        }
     }
+}
 
-    Ok(chunks)
+/// One entry of a chunk's heading trail
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionHeading {
+    /// Heading level (1-6)
+    pub level: u8,
+    pub text: String,
+}
+
+impl SectionHeading {
+    pub fn new(level: u8, text: impl Into<String>) -> Self {
+        Self {
+            level,
+            text: text.into(),
+        }
+    }
+}
+
+/// A stored heading trail entry: `{level, text}`, or a bare string written
+/// before levels were kept
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredHeading {
+    Leveled(SectionHeading),
+    Text(String),
+}
+
+/// Bare strings get their depth in the trail as their level
+fn from_stored(trail: Vec<StoredHeading>) -> Vec<SectionHeading> {
+    trail
+        .into_iter()
+        .enumerate()
+        .map(|(depth, heading)| match heading {
+            StoredHeading::Leveled(heading) => heading,
+            StoredHeading::Text(text) => SectionHeading::new((depth + 1).min(6) as u8, text),
+        })
+        .collect()
+}
+
+/// Parse a heading trail stored as JSON, accepting the old string-only form
+pub fn parse_heading_trail(json: &str) -> Vec<SectionHeading> {
+    serde_json::from_str(json)
+        .map(from_stored)
+        .unwrap_or_default()
+}
+
+/// `serde` reader for an optional heading trail in either stored form
+pub fn deserialize_heading_trail<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Vec<SectionHeading>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Vec<StoredHeading>>::deserialize(deserializer)?.map(from_stored))
+}
+
+/// Heading texts of a trail, outermost first
+pub fn heading_texts(trail: &[SectionHeading]) -> Vec<String> {
+    trail.iter().map(|heading| heading.text.clone()).collect()
+}
+
+/// Normalize a `/`-separated section path for matching: parts are trimmed,
+/// lower-cased and have inner whitespace collapsed, and empty parts dropped
+pub fn normalize_section_path(path: &str) -> String {
+    path.split('/')
+        .map(|part| {
+            part.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Every leading path of a heading trail, normalized as by
+/// [`normalize_section_path`]: `Guide > Configuration` gives `guide` and
+/// `guide/configuration`
+pub fn section_paths<S: AsRef<str>>(headings: &[S]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut current = String::new();
+    for heading in headings {
+        let part = normalize_section_path(heading.as_ref());
+        if part.is_empty() {
+            continue;
+        }
+        if !current.is_empty() {
+            current.push('/');
+        }
+        current.push_str(&part);
+        paths.push(current.clone());
+    }
+    paths
+}
+
+/// Append a chunk for `text[start..end]`, skipping it if only whitespace
+fn push_chunk(doc: &ParsedDocument, start: usize, end: usize, chunks: &mut Vec<TextChunk>) {
+    let chunk_text = doc.text[start..end].trim().to_string();
+    if chunk_text.is_empty() {
+        return;
+    }
+
+    // Get headings that apply to this chunk
+    let headings = doc
+        .headings_at_position(start)
+        .iter()
+        .map(|h| SectionHeading::new(h.level, h.text.clone()))
+        .collect();
+
+    let hash = TextChunk::compute_hash(&chunk_text);
+
+    chunks.push(TextChunk {
+        text: chunk_text,
+        char_start: start,
+        char_end: end,
+        index: chunks.len(),
+        headings,
+        hash,
+    });
+}
+
+/// Sections running from each heading to the next
+fn heading_sections(
+    text: &str,
+    headings: &[Heading],
+    tables: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let mut starts: Vec<usize> = headings
+        .iter()
+        .map(|h| h.position)
+        .filter(|&pos| {
+            pos < text.len()
+                && text.is_char_boundary(pos)
+                && !tables.iter().any(|&span| is_inside_span(pos, span))
+        })
+        .collect();
+    starts.push(0);
+    starts.sort_unstable();
+    starts.dedup();
+
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain(std::iter::once(&text.len())))
+        .map(|(&start, &end)| (start, end))
+        .collect()
+}
+
+/// Split `start..end` into units between break points. Units longer than
+/// `max_chars` are cut at word boundaries, except tables, which stay whole.
+fn split_units(
+    text: &str,
+    start: usize,
+    end: usize,
+    points: &[BreakPoint],
+    tables: &[(usize, usize)],
+    config: &ChunkConfig,
+) -> Vec<(usize, usize)> {
+    let mut bounds = vec![start];
+    bounds.extend(
+        points
+            .iter()
+            .map(|p| p.position)
+            .filter(|&pos| pos > start && pos < end),
+    );
+    bounds.push(end);
+
+    let mut units = Vec::new();
+    for pair in bounds.windows(2) {
+        let (mut unit_start, unit_end) = (pair[0], pair[1]);
+        let is_table = tables
+            .iter()
+            .any(|&(s, e)| s >= unit_start && e <= unit_end && s < e);
+        while !is_table && unit_end - unit_start > config.max_chars {
+            let target = ensure_char_boundary(text, unit_start + config.max_chars);
+            let cut = text[unit_start..target]
+                .rfind(char::is_whitespace)
+                .map(|i| unit_start + i + 1)
+                .filter(|&pos| pos > unit_start && text.is_char_boundary(pos))
+                .unwrap_or(target);
+            if cut <= unit_start {
+                break;
+            }
+            units.push((unit_start, cut));
+            unit_start = cut;
+        }
+        units.push((unit_start, unit_end));
+    }
+    units
+}
+
+/// Pack consecutive units into chunks of up to `max_chars`, carrying whole
+/// trailing units that fit in `overlap_chars` into the next chunk
+fn pack_units(
+    doc: &ParsedDocument,
+    units: &[(usize, usize)],
+    config: &ChunkConfig,
+    chunks: &mut Vec<TextChunk>,
+) {
+    let mut first = 0;
+    while first < units.len() {
+        let start = units[first].0;
+        let mut last = first + 1;
+        while last < units.len() && units[last].1 - start <= config.max_chars {
+            last += 1;
+        }
+        let end = units[last - 1].1;
+        push_chunk(doc, start, end, chunks);
+
+        if last >= units.len() {
+            break;
+        }
+
+        let mut next = last;
+        while next > first + 1 && end - units[next - 1].0 <= config.overlap_chars {
+            next -= 1;
+        }
+        first = next;
+    }
 }
 
 /// Find potential break points in the text
-fn find_break_points(text: &str, headings: &[Heading], config: &ChunkConfig) -> Vec<BreakPoint> {
+fn find_break_points(
+    text: &str,
+    headings: &[Heading],
+    tables: &[(usize, usize)],
+    config: &ChunkConfig,
+) -> Vec<BreakPoint> {
     let mut points = Vec::new();
 
     // Add heading positions as high-priority breaks
@@ -201,6 +453,9 @@ fn find_break_points(text: &str, headings: &[Heading], config: &ChunkConfig) ->
         });
     }
 
+    // Drop anything that would split a table
+    points.retain(|p| !tables.iter().any(|&span| is_inside_span(p.position, span)));
+
     // Sort by position
     points.sort_by_key(|p| p.position);
     points.dedup_by_key(|p| p.position);
@@ -208,6 +463,16 @@ fn find_break_points(text: &str, headings: &[Heading], config: &ChunkConfig) ->
     points
 }
 
+/// Move a chunk end that falls inside a span to the span's start, or past
+/// its end when the span begins at or before the chunk start
+fn keep_spans_whole(start: usize, end: usize, spans: &[(usize, usize)]) -> usize {
+    match spans.iter().find(|&&span| is_inside_span(end, span)) {
+        Some(&(span_start, _)) if span_start > start => span_start,
+        Some(&(_, span_end)) => span_end,
+        None => end,
+    }
+}
+
 /// Ensure a position is on a valid UTF-8 character boundary
 fn ensure_char_boundary(text: &str, pos: usize) -> usize {
     if pos >= text.len() {
@@ -297,6 +562,7 @@ mod tests {
             content_type: ContentType::PlainText,
             headings: Vec::new(),
             code_blocks: Vec::new(),
+            tables: Vec::new(),
             links: Vec::new(),
             media: Vec::new(),
         }
@@ -304,10 +570,12 @@ mod tests {
 
     fn default_chunk_config() -> ChunkConfig {
         ChunkConfig {
+            strategy: ChunkStrategy::Fixed,
             max_chars: 500,
             overlap_chars: 50,
             prefer_heading_boundaries: true,
             min_chars: 50,
+            notebook_outputs: false,
         }
     }
 
@@ -315,9 +583,8 @@ mod tests {
     fn test_chunk_short_document() {
         let doc = make_test_doc("This is a short document.");
         let config = default_chunk_config();
-        let doc_hash = compute_text_hash(&doc.text);
 
-        let chunks = chunk_document(&doc, &doc_hash, &config).unwrap();
+        let chunks = chunk_document(&doc, &config).unwrap();
 
         assert_eq!(chunks.len(), 1);
         assert_eq!(chunks[0].text, "This is a short document.");
@@ -329,9 +596,8 @@ mod tests {
         let text = "Lorem ipsum dolor sit amet. ".repeat(100);
         let doc = make_test_doc(&text);
         let config = default_chunk_config();
-        let doc_hash = compute_text_hash(&doc.text);
 
-        let chunks = chunk_document(&doc, &doc_hash, &config).unwrap();
+        let chunks = chunk_document(&doc, &config).unwrap();
 
         assert!(chunks.len() > 1);
         // Check chunks don't exceed max size
@@ -344,10 +610,9 @@ mod tests {
     fn test_chunk_hash_stability() {
         let doc = make_test_doc("Test content for hashing.");
         let config = default_chunk_config();
-        let doc_hash = compute_text_hash(&doc.text);
 
-        let chunks1 = chunk_document(&doc, &doc_hash, &config).unwrap();
-        let chunks2 = chunk_document(&doc, &doc_hash, &config).unwrap();
+        let chunks1 = chunk_document(&doc, &config).unwrap();
+        let chunks2 = chunk_document(&doc, &config).unwrap();
 
         assert_eq!(chunks1[0].hash, chunks2[0].hash);
     }
@@ -379,14 +644,15 @@ mod tests {
         ];
 
         let config = ChunkConfig {
+            strategy: ChunkStrategy::Fixed,
             max_chars: 100,
             overlap_chars: 10,
             prefer_heading_boundaries: true,
             min_chars: 10,
+            notebook_outputs: false,
         };
-        let doc_hash = compute_text_hash(&doc.text);
 
-        let chunks = chunk_document(&doc, &doc_hash, &config).unwrap();
+        let chunks = chunk_document(&doc, &config).unwrap();
 
         // Verify headings are captured
         for chunk in &chunks {
@@ -394,4 +660,148 @@ mod tests {
             assert!(chunk.headings.len() <= doc.headings.len());
         }
     }
+
+    #[test]
+    fn test_chunk_keeps_markdown_tables_whole() {
+        let markdown = "# Settings\n\n\
+            The chunker reads a few settings. Each one has a sensible default. \
+            Most users never need to change them.\n\n\
+            | Key | Type | Default |\n\
+            |-----|------|---------|\n\
+            | max_chars | integer | 1500 |\n\
+            | min_chars | integer | 100 |\n\
+            | overlap_chars | integer | 200 |\n\
+            | prefer_heading_boundaries | boolean | true |\n\n\
+            Smaller chunks give more precise matches. Larger chunks keep more context.";
+        let doc = crate::parse::parse_markdown(markdown).unwrap();
+        assert_eq!(doc.tables.len(), 1);
+        let table = &doc.tables[0].content;
+        let (table_start, table_end) = doc.tables[0].span();
+
+        let config = ChunkConfig {
+            strategy: ChunkStrategy::Fixed,
+            max_chars: 120,
+            overlap_chars: 20,
+            prefer_heading_boundaries: true,
+            min_chars: 10,
+            notebook_outputs: false,
+        };
+        let chunks = chunk_document(&doc, &config).unwrap();
+        assert!(chunks.len() > 1);
+
+        // Exactly one chunk holds the table, and it holds all of it
+        assert_eq!(chunks.iter().filter(|c| c.text.contains(table)).count(), 1);
+        for chunk in &chunks {
+            assert!(!is_inside_span(chunk.char_start, (table_start, table_end)));
+            assert!(!is_inside_span(chunk.char_end, (table_start, table_end)));
+        }
+    }
+
+    fn sectioned_doc() -> ParsedDocument {
+        let text = "Guide\n\nAn overview of the tool. It indexes docs.\n\n\
+                    Install\n\nRun the installer. Then restart your shell.\n\n\
+                    Usage\n\nQuery with a question. Results are ranked.";
+        let mut doc = make_test_doc(text);
+        doc.headings = [(1, "Guide"), (2, "Install"), (2, "Usage")]
+            .into_iter()
+            .map(|(level, heading)| Heading {
+                level,
+                text: heading.to_string(),
+                position: text.find(heading).unwrap(),
+            })
+            .collect();
+        doc
+    }
+
+    #[test]
+    fn test_heading_strategy_breaks_at_every_heading() {
+        let doc = sectioned_doc();
+        let config = ChunkConfig {
+            strategy: ChunkStrategy::Heading,
+            ..default_chunk_config()
+        };
+
+        let chunks = chunk_document(&doc, &config).unwrap();
+
+        // Every section fits within max_chars, so each heading starts exactly one chunk
+        assert_eq!(chunks.len(), doc.headings.len());
+        for (chunk, heading) in chunks.iter().zip(&doc.headings) {
+            assert_eq!(chunk.char_start, heading.position);
+            assert!(chunk.text.starts_with(&heading.text));
+            assert_eq!(chunk.headings.last().map(|h| &h.text), Some(&heading.text));
+        }
+        assert_eq!(
+            chunks[1].headings,
+            vec![
+                SectionHeading::new(1, "Guide"),
+                SectionHeading::new(2, "Install")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heading_trail_round_trips_with_levels() {
+        let trail = vec![
+            SectionHeading::new(1, "API"),
+            SectionHeading::new(2, "Auth"),
+            SectionHeading::new(4, "Tokens"),
+        ];
+        let json = serde_json::to_string(&trail).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"level":1,"text":"API"},{"level":2,"text":"Auth"},{"level":4,"text":"Tokens"}]"#
+        );
+        assert_eq!(parse_heading_trail(&json), trail);
+
+        // Trails stored before levels were kept read back by depth
+        assert_eq!(
+            parse_heading_trail(r#"["API","Auth"]"#),
+            vec![
+                SectionHeading::new(1, "API"),
+                SectionHeading::new(2, "Auth")
+            ]
+        );
+        assert!(parse_heading_trail("not json").is_empty());
+    }
+
+    #[test]
+    fn test_sentence_strategy_packs_whole_sentences() {
+        let doc = make_test_doc(
+            "The first sentence is here. The second one follows it. \
+             A third sentence appears. And the fourth ends it.",
+        );
+        let config = ChunkConfig {
+            strategy: ChunkStrategy::Sentence,
+            max_chars: 60,
+            overlap_chars: 0,
+            ..default_chunk_config()
+        };
+
+        let chunks = chunk_document(&doc, &config).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0].text,
+            "The first sentence is here. The second one follows it."
+        );
+        assert_eq!(
+            chunks[1].text,
+            "A third sentence appears. And the fourth ends it."
+        );
+        for chunk in &chunks {
+            assert!(chunk.text.len() <= config.max_chars);
+        }
+    }
+
+    #[test]
+    fn test_semantic_strategy_not_implemented() {
+        let doc = sectioned_doc();
+        let config = ChunkConfig {
+            strategy: ChunkStrategy::Semantic,
+            ..default_chunk_config()
+        };
+
+        let err = chunk_document(&doc, &config).unwrap_err();
+        assert!(err.to_string().contains("not yet implemented"));
+    }
 }
@@ -0,0 +1,453 @@
+//! Config command - read and change settings in config.toml
+
+use crate::commands::init::compute_irrelevant_paths;
+use crate::config::{render_config_toml, shorten_float, Config};
+use crate::error::{Error, Result};
+use crate::store::QdrantStore;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::info;
+
+/// A setting as read by `config get`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSetting {
+    pub key: String,
+    /// Current value; `None` for optional settings that are unset
+    pub value: Option<toml::Value>,
+    pub is_default: bool,
+}
+
+/// Read a setting by dotted key (e.g. `query.min_score`)
+pub fn cmd_config_get(config: &Config, key: &str) -> Result<ConfigSetting> {
+    setting_type(key)?;
+
+    let value = lookup(&toml::Value::try_from(config)?, key);
+    let default = lookup(&toml::Value::try_from(Config::default())?, key);
+
+    Ok(ConfigSetting {
+        key: key.to_string(),
+        is_default: value == default,
+        value,
+    })
+}
+
+/// Change a setting by dotted key, validate, and rewrite the config file
+///
+/// The file is rendered the same way `init` writes it, so settings left at
+/// their defaults stay commented out.
+pub fn cmd_config_set(config: &Config, key: &str, value: &str) -> Result<ConfigSetting> {
+    if let Some(profile) = &config.active_profile {
+        return Err(Error::Config(format!(
+            "config set edits the base settings; run it without --profile (active: '{}')",
+            profile
+        )));
+    }
+    if let Some(collection) = &config.collection_override {
+        return Err(Error::Config(format!(
+            "config set edits the base settings; run it without --collection (active: '{}')",
+            collection
+        )));
+    }
+    let updated = with_setting(config, key, value)?;
+
+    let defaults = Config::default();
+    let irrelevant = compute_irrelevant_paths(&updated);
+    let rendered = render_config_toml(&updated, &defaults, &irrelevant);
+    std::fs::write(&updated.paths.config_file, rendered)?;
+    info!("Updated {} in {:?}", key, updated.paths.config_file);
+
+    cmd_config_get(&updated, key)
+}
+
+/// Copy of `config` with `key` set from its string form
+fn with_setting(config: &Config, key: &str, raw: &str) -> Result<Config> {
+    let value = parse_value(key, raw, &setting_type(key)?)?;
+
+    let mut document = toml::Value::try_from(config)?;
+    let (path, field) = key.rsplit_once('.').unwrap_or(("", key));
+    let mut table = document
+        .as_table_mut()
+        .ok_or_else(|| Error::Other("config did not serialize to a table".to_string()))?;
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        table = table
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| Error::Config(format!("'{}' is not a config section", part)))?;
+    }
+    table.insert(field.to_string(), value);
+
+    let mut updated: Config = document.try_into()?;
+    updated.paths = config.paths.clone();
+    updated.validate()?;
+    Ok(updated)
+}
+
+/// Example value of a setting, taken from the rendered defaults so unset
+/// optional settings are known too
+fn setting_type(key: &str) -> Result<toml::Value> {
+    let defaults = Config::default();
+    let rendered = render_config_toml(&defaults, &defaults, &HashSet::new());
+
+    let mut section = String::new();
+    for line in rendered.lines() {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = format!("{}.", name);
+            continue;
+        }
+        let line = line.strip_prefix("# ").unwrap_or(line);
+        let Some((name, example)) = line.split_once(" = ") else {
+            continue;
+        };
+        if format!("{}{}", section, name) == key {
+            let parsed: toml::Table = toml::from_str(&format!("value = {}", example))?;
+            if let Some(value) = parsed.get("value") {
+                return Ok(value.clone());
+            }
+        }
+    }
+
+    Err(Error::Config(format!("Unknown config key '{}'", key)))
+}
+
+/// Parse `raw` as the same TOML type as `example`
+fn parse_value(key: &str, raw: &str, example: &toml::Value) -> Result<toml::Value> {
+    let invalid = |expected: &str| {
+        Error::Config(format!(
+            "Invalid value '{}' for {}: expected {}",
+            raw, key, expected
+        ))
+    };
+
+    match example {
+        toml::Value::String(_) => Ok(toml::Value::String(raw.to_string())),
+        toml::Value::Integer(_) => raw
+            .parse()
+            .map(toml::Value::Integer)
+            .map_err(|_| invalid("an integer")),
+        toml::Value::Float(_) => raw
+            .parse()
+            .map(toml::Value::Float)
+            .map_err(|_| invalid("a number")),
+        toml::Value::Boolean(_) => raw
+            .parse()
+            .map(toml::Value::Boolean)
+            .map_err(|_| invalid("true or false")),
+        _ => Err(Error::Config(format!(
+            "{} is not a scalar setting; edit config.toml to change it",
+            key
+        ))),
+    }
+}
+
+fn lookup(document: &toml::Value, key: &str) -> Option<toml::Value> {
+    let value = key
+        .split('.')
+        .try_fold(document, |value, part| value.get(part))?;
+    match value {
+        toml::Value::Float(float) => Some(toml::Value::Float(shorten_float(*float))),
+        value => Some(value.clone()),
+    }
+}
+
+/// Options for `config validate`
+#[derive(Debug, Clone, Default)]
+pub struct ValidateOptions {
+    /// Profile to merge over the base settings before checking
+    pub profile: Option<String>,
+    /// Collection to check in place of the configured one
+    pub collection: Option<String>,
+    /// Resolve the embedding model against the backend
+    pub check_backend: bool,
+    /// Check that Qdrant answers
+    pub check_qdrant: bool,
+}
+
+/// Outcome of a single `config validate` check
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Results of `config validate`, in the order the checks ran
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub config_path: String,
+    pub checks: Vec<ValidationCheck>,
+}
+
+impl ValidationReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    fn record(&mut self, name: &str, outcome: Result<String>) -> bool {
+        let (passed, detail) = match outcome {
+            Ok(detail) => (true, detail),
+            Err(e) => (false, e.to_string()),
+        };
+        self.checks.push(ValidationCheck {
+            name: name.to_string(),
+            passed,
+            detail,
+        });
+        passed
+    }
+}
+
+/// Check a config file, and optionally the services it points at
+///
+/// Later checks are skipped when the file cannot be parsed.
+pub async fn cmd_config_validate(config_path: &Path, options: ValidateOptions) -> ValidationReport {
+    let mut report = ValidationReport {
+        config_path: config_path.display().to_string(),
+        ..Default::default()
+    };
+
+    let mut config = match Config::read(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            report.record("parse", Err(e));
+            return report;
+        }
+    };
+    report.record("parse", Ok("config file parsed".to_string()));
+    if let Some(profile) = &options.profile {
+        let applied = config
+            .apply_profile(profile)
+            .map(|_| format!("merged profile '{}'", profile));
+        if !report.record("profile", applied) {
+            return report;
+        }
+    }
+    if let Some(collection) = &options.collection {
+        let applied = config
+            .apply_collection_override(collection)
+            .map(|_| format!("using collection '{}'", collection));
+        if !report.record("collection", applied) {
+            return report;
+        }
+    }
+    report.record(
+        "settings",
+        config
+            .validate()
+            .map(|_| "all settings in range".to_string()),
+    );
+
+    let mut embedding = None;
+    if options.check_backend {
+        let resolved = config.resolve_embedding_config().await;
+        let outcome = resolved.as_ref().map(|resolved| {
+            format!(
+                "{} at {} ({} dimensions)",
+                resolved.model_id, resolved.backend.url, resolved.dimension
+            )
+        });
+        report.record("backend", outcome.map_err(|e| Error::Other(e.to_string())));
+        embedding = resolved.ok();
+    }
+
+    if options.check_qdrant {
+        let outcome = check_qdrant(&config, embedding.as_ref()).await;
+        report.record("qdrant", outcome);
+    }
+
+    report
+}
+
+async fn check_qdrant(
+    config: &Config,
+    embedding: Option<&crate::config::ResolvedEmbeddingConfig>,
+) -> Result<String> {
+    let store = match embedding {
+        Some(embedding) => QdrantStore::connect(config, embedding).await?,
+        None => QdrantStore::connect_offline(config).await?,
+    };
+    store.ensure_reachable().await?;
+
+    let collection = if store.collection_exists().await? {
+        "exists"
+    } else {
+        "has not been created yet"
+    };
+    Ok(format!(
+        "{} reachable; collection '{}' {}",
+        config.qdrant_url, config.collection_name, collection
+    ))
+}
+
+/// Print one pass/fail line per check
+pub fn print_validation_report(report: &ValidationReport) {
+    println!("Config: {}", report.config_path);
+    for check in &report.checks {
+        let mark = if check.passed { "✓" } else { "✗" };
+        println!("  {} {}: {}", mark, check.name, check.detail);
+    }
+    if report.passed() {
+        println!("\nConfig is valid");
+    } else {
+        println!("\nConfig has problems");
+    }
+}
+
+/// Print a setting as `key = value`
+pub fn print_config_setting(setting: &ConfigSetting) {
+    match &setting.value {
+        Some(value) if setting.is_default => println!("{} = {} (default)", setting.key, value),
+        Some(value) => println!("{} = {}", setting.key, value),
+        None => println!("{} is not set", setting.key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn config_in(tmp: &TempDir) -> Config {
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.paths.config_file = tmp.path().join("config.toml");
+        config
+    }
+
+    #[test]
+    fn test_config_set_writes_nested_value() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_in(&tmp);
+
+        let setting = cmd_config_set(&config, "store.hnsw.m", "32").unwrap();
+        assert_eq!(setting.value, Some(toml::Value::Integer(32)));
+        assert!(!setting.is_default);
+
+        let reloaded = Config::load(&config.paths.config_file).unwrap();
+        assert_eq!(reloaded.store.hnsw.m, Some(32));
+        let written = std::fs::read_to_string(&config.paths.config_file).unwrap();
+        assert!(written.contains("\nm = 32"));
+        assert!(written.contains("# ef_construct = 100"));
+
+        // Unset optional settings can be set as well
+        let reloaded = with_setting(&reloaded, "embedding.dimension", "256").unwrap();
+        assert_eq!(reloaded.embedding.dimension, Some(256));
+        assert_eq!(reloaded.store.hnsw.m, Some(32));
+    }
+
+    #[test]
+    fn test_config_set_rejects_invalid_values() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_in(&tmp);
+
+        let err = cmd_config_set(&config, "query.min_score", "high").unwrap_err();
+        assert!(err.to_string().contains("expected a number"), "{err}");
+        let err = cmd_config_set(&config, "store.hnsw.m", "2").unwrap_err();
+        assert!(err.to_string().contains("store.hnsw.m"), "{err}");
+        let err = cmd_config_set(&config, "query.min_scor", "0.5").unwrap_err();
+        assert!(err.to_string().contains("Unknown config key"), "{err}");
+        assert!(cmd_config_set(&config, "crawl.allowed_domains", "a.com").is_err());
+
+        assert!(!config.paths.config_file.exists());
+    }
+
+    #[test]
+    fn test_config_get_reports_defaults() {
+        let mut config = Config::default();
+
+        let setting = cmd_config_get(&config, "crawl.max_pages").unwrap();
+        assert_eq!(setting.value, Some(toml::Value::Integer(1000)));
+        assert!(setting.is_default);
+
+        let setting = cmd_config_get(&config, "query.synonyms_file").unwrap();
+        assert_eq!(setting.value, None);
+        assert!(setting.is_default);
+
+        config.crawl.max_pages = 50;
+        let setting = cmd_config_get(&config, "crawl.max_pages").unwrap();
+        assert_eq!(setting.value, Some(toml::Value::Integer(50)));
+        assert!(!setting.is_default);
+
+        // f32 settings read back as written
+        config.query.min_score = 0.3;
+        let setting = cmd_config_get(&config, "query.min_score").unwrap();
+        assert_eq!(setting.value, Some(toml::Value::Float(0.3)));
+    }
+
+    fn write_config(tmp: &TempDir, content: &str) -> std::path::PathBuf {
+        let config_path = tmp.path().join("config.toml");
+        std::fs::write(&config_path, content).unwrap();
+        config_path
+    }
+
+    #[tokio::test]
+    async fn test_config_validate_passes_valid_config() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = write_config(&tmp, "[chunk]\nmax_chars = 800\n");
+
+        let report = cmd_config_validate(&config_path, ValidateOptions::default()).await;
+        assert!(report.passed(), "{:?}", report.checks);
+        let names: Vec<_> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["parse", "settings"]);
+    }
+
+    #[tokio::test]
+    async fn test_config_validate_reports_invalid_chunk_range() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = write_config(&tmp, "[chunk]\nmax_chars = 100\nmin_chars = 500\n");
+
+        let report = cmd_config_validate(&config_path, ValidateOptions::default()).await;
+        assert!(!report.passed());
+        let settings = &report.checks[1];
+        assert!(!settings.passed);
+        assert!(
+            settings.detail.contains("chunk.max_chars"),
+            "{}",
+            settings.detail
+        );
+
+        // A file that does not parse stops before the settings check
+        let config_path = write_config(&tmp, "[chunk\n");
+        let report = cmd_config_validate(&config_path, ValidateOptions::default()).await;
+        assert_eq!(report.checks.len(), 1);
+        assert!(!report.checks[0].passed);
+    }
+
+    #[tokio::test]
+    async fn test_config_validate_checks_backend() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/probe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "BAAI/bge-small-en-v1.5",
+                "embedding_dim": 384,
+                "text_embeddings": [vec![0.0_f32; 384]]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let tmp = TempDir::new().unwrap();
+        let content = format!("[embedding]\nurl = \"{}\"\n", mock_server.uri());
+        let config_path = write_config(&tmp, &content);
+        let options = ValidateOptions {
+            check_backend: true,
+            ..Default::default()
+        };
+
+        let report = cmd_config_validate(&config_path, options.clone()).await;
+        assert!(report.passed(), "{:?}", report.checks);
+        assert!(report.checks[2].detail.contains("384 dimensions"));
+
+        // An unreachable backend fails the check
+        let content = "[embedding]\nurl = \"http://127.0.0.1:9\"\n";
+        let config_path = write_config(&tmp, content);
+        let report = cmd_config_validate(&config_path, options).await;
+        assert!(!report.passed());
+        assert_eq!(report.checks[2].name, "backend");
+        assert!(!report.checks[2].passed);
+    }
+}
@@ -1,26 +1,41 @@
 //! Ingest command implementation
 
 use crate::chunk::{chunk_document, compute_content_hash, TextChunk};
-use crate::config::{Config, ResolvedEmbeddingConfig};
-use crate::crawl::{CrawledPage, Crawler};
+use crate::commands::sources::unique_source_name;
+use crate::config::{ChunkConfig, Config, CrawlConfig, JsMode, ResolvedEmbeddingConfig};
+use crate::crawl::{CrawlReport, CrawledPage, Crawler};
 use crate::embed::{
     embed_image_text_in_batches, embed_images_in_batches, embed_in_batches, Embedder,
     ImageEmbedInput, fuse_embeddings,
 };
 use crate::error::{Error, Result};
-use crate::meta::{Chunk, Document, MetaDb, RunOperation, RunStatus, Source, SourceType};
-use crate::parse::{is_binary_content, parse_content, should_skip_file, ContentType};
+use crate::meta::{
+    Chunk, Document, MetaDb, RunOperation, RunStatus, Source, SourceType, StaleDocuments,
+};
+use crate::output::format_size;
+use crate::parse::{
+    is_binary_content, parse_content, parse_notebook, parse_openapi, should_skip_file,
+    ApiOperation, ContentType,
+};
 use crate::parse::{ParsedDocument, ExtractedMedia};
-use crate::progress::add_progress_bar;
+use crate::progress::{
+    advance_progress, finish_progress, set_progress_chunks, start_progress_bar, start_spinner,
+};
+use crate::say;
 use crate::store::{ChunkPayload, ChunkPoint, QdrantStore};
 use chrono::Utc;
+use futures::StreamExt;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
 use image::imageops::FilterType;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressBar;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 use url::Url;
 use uuid::Uuid;
@@ -36,6 +51,9 @@ pub struct IngestStats {
     pub errors: Vec<String>,
     /// Warnings about source overlaps (potential duplicates)
     pub overlap_warnings: Vec<String>,
+    /// Crawl outcome tallies, for URL ingestion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crawl: Option<CrawlReport>,
 }
 
 #[derive(Debug, Clone)]
@@ -403,41 +421,67 @@ async fn fetch_and_cache_images(config: &Config, images: &[(ExtractedMedia, f32)
     cached
 }
 
-async fn embed_cached_images(
+/// Cache a rendered page's screenshot under base_dir/assets as an image of that page
+async fn cache_page_screenshot(
     config: &Config,
     embedding: &ResolvedEmbeddingConfig,
-    db: &MetaDb,
-    store: &QdrantStore,
-    embedder: &dyn Embedder,
-    source: &Source,
-    doc: &Document,
-    doc_uri: &str,
-    parsed: &ParsedDocument,
-    cached_images: Vec<CachedAsset>,
-) -> Result<(i32, i32)> {
-    if cached_images.is_empty() {
-        return Ok((0, 0));
+    page: &CrawledPage,
+) -> Option<CachedAsset> {
+    let mm = &config.crawl.multimodal;
+    let bytes = page.screenshot.as_deref()?;
+    if !mm.captures_page_screenshots()
+        || !embedding.supports_image_inputs()
+        || embedding.supports_multi_vector
+    {
+        return None;
     }
-
-    if !embedding.supports_image_inputs() {
-        debug!(uri = %doc_uri, model = %embedding.model_id, "Skipping image embedding (model not multimodal)");
-        return Ok((0, 0));
+    if bytes.len() > mm.max_asset_bytes {
+        debug!(url = %page.url, size = bytes.len(), limit = mm.max_asset_bytes, "Skipping page screenshot (size exceeds limit)");
+        return None;
     }
 
-    if embedding.supports_multi_vector {
-        return Err(Error::Embedding(format!(
-            "Late-interaction embedding model '{}' does not support image ingestion",
-            embedding.model_id
-        )));
+    let assets_dir = config.paths.base_dir.join("assets");
+    if let Err(e) = tokio::fs::create_dir_all(&assets_dir).await {
+        warn!("Failed to create assets dir: {}", e);
+    }
+    let hash = compute_content_hash(bytes);
+    let target = assets_dir.join(format!("{}.jpg", hash));
+    if tokio::fs::metadata(&target).await.is_err() {
+        if let Err(e) = tokio::fs::write(&target, bytes).await {
+            warn!(url = %page.url, path = %target.display(), "Failed to write page screenshot: {}", e);
+            return None;
+        }
     }
+    debug!(url = %page.url, path = %target.display(), size = bytes.len(), "Cached page screenshot");
+
+    let title = page.title.as_deref().unwrap_or(&page.url);
+    Some(CachedAsset {
+        media: ExtractedMedia {
+            url: page.url.clone(),
+            alt: Some(format!("Screenshot of {}", title)),
+            tag: "screenshot".to_string(),
+            css_background: false,
+        },
+        hash,
+        path: target,
+    })
+}
 
+/// Embed cached image assets; dual encoders fuse each image with its text context
+async fn embed_asset_vectors(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    parsed: &ParsedDocument,
+    cached_images: &[CachedAsset],
+) -> Result<Vec<Vec<f32>>> {
     let batch_size = embedding.effective_batch_size(config.embedding.batch_size);
     let contexts: Vec<Option<String>> = cached_images
         .iter()
         .map(|asset| build_image_context(parsed, &asset.media))
         .collect();
 
-    let embeddings = if embedding.supports_joint_inputs {
+    if embedding.supports_joint_inputs {
         let inputs = cached_images
             .iter()
             .zip(contexts.iter())
@@ -447,7 +491,7 @@ async fn embed_cached_images(
             })
             .collect::<Vec<_>>();
 
-        embed_image_text_in_batches(embedder, inputs, batch_size).await?
+        embed_image_text_in_batches(embedder, inputs, batch_size).await
     } else {
         let image_paths: Vec<String> = cached_images
             .iter()
@@ -502,32 +546,90 @@ async fn embed_cached_images(
             }
         }
 
-        fused_embeddings
-    };
+        Ok(fused_embeddings)
+    }
+}
 
-    if embeddings.is_empty() {
+/// Fail when the embedder's vectors would not fit the collection
+pub(crate) fn check_embedder_dimension(
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    store: &QdrantStore,
+) -> Result<()> {
+    if embedder.dimension() == store.dimension() {
+        return Ok(());
+    }
+    Err(dimension_mismatch(
+        embedding,
+        store.dimension(),
+        embedder.dimension(),
+        "embedder vs collection",
+    ))
+}
+
+/// A vector of `actual` dimensions where the collection holds `expected`
+pub(crate) fn dimension_mismatch(
+    embedding: &ResolvedEmbeddingConfig,
+    expected: usize,
+    actual: usize,
+    what: &str,
+) -> Error {
+    Error::DimensionMismatch {
+        model: embedding.model_id.clone(),
+        expected,
+        actual,
+        context: format!(
+            "{}; family '{}', source {}",
+            what, embedding.family, embedding.dimension_source
+        ),
+    }
+}
+
+async fn embed_cached_images(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    source: &Source,
+    doc: &Document,
+    doc_uri: &str,
+    parsed: &ParsedDocument,
+    cached_images: Vec<CachedAsset>,
+) -> Result<(i32, i32)> {
+    if cached_images.is_empty() {
         return Ok((0, 0));
     }
 
-    let expected_dim = store.dimension();
-    if embedder.dimension() != expected_dim {
+    if !embedding.supports_image_inputs() {
+        debug!(uri = %doc_uri, model = %embedding.model_id, "Skipping image embedding (model not multimodal)");
+        return Ok((0, 0));
+    }
+
+    if embedding.supports_multi_vector {
         return Err(Error::Embedding(format!(
-            "Embedding dimension mismatch for model '{}' (family '{}', source {}): embedder {} != collection {}",
-            embedding.model_id,
-            embedding.family,
-            embedding.dimension_source,
-            embedder.dimension(),
-            expected_dim
+            "Late-interaction embedding model '{}' does not support image ingestion",
+            embedding.model_id
         )));
     }
 
+    let embeddings =
+        embed_asset_vectors(config, embedding, embedder, parsed, &cached_images).await?;
+
+    if embeddings.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let expected_dim = store.dimension();
+    check_embedder_dimension(embedding, embedder, store)?;
+
     if embeddings[0].len() != expected_dim {
-        return Err(Error::Embedding(format!(
-            "Image embedding dimension mismatch for model '{}': expected {}, got {}",
-            embedding.model_id,
+        return Err(dimension_mismatch(
+            embedding,
             expected_dim,
-            embeddings[0].len()
-        )));
+            embeddings[0].len(),
+            "image embedding",
+        ));
     }
 
     if embeddings.len() != cached_images.len() {
@@ -552,6 +654,7 @@ async fn embed_cached_images(
 
     let mut points: Vec<ChunkPoint> = Vec::new();
     let mut created = 0i32;
+    let model_id = &embedding.model_id;
 
     for (i, (asset, embedding)) in cached_images.iter().zip(embeddings.iter()).enumerate() {
         let chunk_index = -(i as i32) - 1;
@@ -586,6 +689,7 @@ async fn embed_cached_images(
         payload.modality = Some("image".to_string());
         payload.media_url = Some(asset.media.url.clone());
         payload.media_hash = Some(asset.hash.clone());
+        payload.model_id = Some(model_id.clone());
 
         let point_id = Uuid::try_parse(&meta_chunk.qdrant_point_id).unwrap_or_else(|_| {
             Uuid::new_v5(&Uuid::NAMESPACE_OID, meta_chunk.qdrant_point_id.as_bytes())
@@ -606,11 +710,101 @@ async fn embed_cached_images(
 }
 
 /// CLI overrides for crawl configuration
-#[derive(Debug, Default)]
+///
+/// URL sources keep their overrides in `config_json`, so `update` re-crawls a
+/// site the way it was first ingested.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CrawlOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_pages: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_depth: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub path_prefix: Option<String>,
+    /// Follow (`Some(true)`) or ignore (`Some(false)`) sibling subdomains
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_subdomains: Option<bool>,
+    /// JS rendering mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub js_mode: Option<JsMode>,
+    /// CSS selector the renderer waits for before capturing a page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_for_selector: Option<String>,
+    /// Seed the crawl from sitemaps listed in robots.txt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_sitemap: Option<bool>,
+    /// Skip robots.txt checks for this crawl only; never stored on the source
+    #[serde(skip)]
+    pub ignore_robots: bool,
+}
+
+impl CrawlOverrides {
+    /// Read the overrides stored on a source, falling back to none
+    pub fn from_source(source: &Source) -> Self {
+        source
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Fill the overrides not set here from `stored`
+    pub fn or(self, stored: CrawlOverrides) -> Self {
+        Self {
+            max_pages: self.max_pages.or(stored.max_pages),
+            max_depth: self.max_depth.or(stored.max_depth),
+            path_prefix: self.path_prefix.or(stored.path_prefix),
+            allow_subdomains: self.allow_subdomains.or(stored.allow_subdomains),
+            js_mode: self.js_mode.or(stored.js_mode),
+            wait_for_selector: self.wait_for_selector.or(stored.wait_for_selector),
+            use_sitemap: self.use_sitemap.or(stored.use_sitemap),
+            ignore_robots: self.ignore_robots,
+        }
+    }
+
+    /// Apply the overrides on top of the configured crawl settings
+    pub fn apply(&self, crawl: &CrawlConfig) -> CrawlConfig {
+        let mut crawl_config = crawl.clone();
+        if let Some(max_pages) = self.max_pages {
+            crawl_config.max_pages = max_pages;
+        }
+        if let Some(max_depth) = self.max_depth {
+            crawl_config.max_depth = max_depth;
+        }
+        if self.path_prefix.is_some() {
+            crawl_config.path_prefix = self.path_prefix.clone();
+        }
+        if let Some(allow_subdomains) = self.allow_subdomains {
+            crawl_config.allow_subdomains = allow_subdomains;
+        }
+        if let Some(js_mode) = self.js_mode {
+            crawl_config.js_mode = js_mode;
+            // An explicit mode is not subject to the auto-rendering switch
+            crawl_config.auto_js_rendering = true;
+        }
+        if self.wait_for_selector.is_some() {
+            crawl_config.js_wait_for_selector = self.wait_for_selector.clone();
+        }
+        if let Some(use_sitemap) = self.use_sitemap {
+            crawl_config.use_sitemap = use_sitemap;
+        }
+        if self.ignore_robots {
+            crawl_config.respect_robots_txt = false;
+        }
+        crawl_config
+    }
+}
+
+/// Merge `overrides` over those stored on `source` and store the result
+pub(crate) async fn persist_crawl_overrides(
+    db: &MetaDb,
+    source: &Source,
+    overrides: CrawlOverrides,
+) -> Result<CrawlOverrides> {
+    let merged = overrides.or(CrawlOverrides::from_source(source));
+    db.update_source_config(&source.id, Some(serde_json::to_string(&merged)?))
+        .await?;
+    Ok(merged)
 }
 
 /// Describes an overlap between two sources
@@ -761,6 +955,7 @@ pub async fn cmd_ingest_dir(
     store: &QdrantStore,
     path: &Path,
     name: Option<String>,
+    options: DirIngestOptions,
     operation: RunOperation,
     interactive: bool,
 ) -> Result<IngestStats> {
@@ -771,6 +966,11 @@ pub async fn cmd_ingest_dir(
     let uri = canonical_path.display().to_string();
     info!("Ingesting directory: {}", uri);
 
+    // Reject invalid exclude patterns before touching the database
+    if let Some(filter) = &options.filter {
+        filter.exclude_set()?;
+    }
+
     let mut stats = IngestStats::default();
 
     store.ensure_collection().await?;
@@ -789,204 +989,667 @@ pub async fn cmd_ingest_dir(
 
     // Start ingestion run
     let run = db.start_ingestion_run(&source.id, operation).await?;
+    let _heartbeat = db.start_run_heartbeat(&run.id);
 
-    if embedder.dimension() != store.dimension() {
-        return Err(Error::Embedding(format!(
-            "Embedding dimension mismatch for model '{}' (family '{}', source {}): embedder {} != collection {}",
-            embedding.model_id,
-            embedding.family,
-            embedding.dimension_source,
-            embedder.dimension(),
-            store.dimension()
-        )));
-    }
-
-    // Collect all files
-    let mut files: Vec<std::path::PathBuf> = Vec::new();
-    let walker = WalkBuilder::new(&canonical_path)
-        .hidden(false)
-        .git_ignore(true)
-        .git_exclude(true)
-        .build();
+    check_embedder_dimension(embedding, embedder, store)?;
 
-    for entry in walker {
-        match entry {
-            Ok(e) if e.file_type().map(|t| t.is_file()).unwrap_or(false) => {
-                let path = e.path().to_path_buf();
-                if !should_skip_file(&path) {
-                    files.push(path);
-                }
-            }
-            _ => {}
+    // Explicit filters replace the stored ones; otherwise reuse what the source was ingested with
+    let filter = match options.filter {
+        Some(filter) => {
+            db.update_source_config(&source.id, Some(serde_json::to_string(&filter)?))
+                .await?;
+            filter
         }
-    }
+        None => DirFilter::from_source(&source),
+    };
+
+    // Collect all files
+    let files = collect_dir_files(&canonical_path, &filter, config.ingest.max_file_bytes)?;
 
     info!("Found {} files to process", files.len());
 
-    let mut current_uris: Vec<String> = Vec::new();
+    let current_uris: Vec<String> = files.iter().map(|p| p.display().to_string()).collect();
     let file_progress = start_progress_bar(files.len(), "Processing files");
 
-    for file_path in files {
-        let file_uri = file_path.display().to_string();
-        current_uris.push(file_uri.clone());
-
-        match process_file(
-            config,
-            embedding,
-            db,
-            store,
-            embedder,
-            &source,
-            &file_path,
-        )
-        .await {
-            Ok((created, updated)) => {
-                stats.docs_processed += 1;
-                stats.chunks_created += created;
-                stats.chunks_updated += updated;
-            }
-            Err(e) => {
-                let error_msg = format!("{}: {}", file_path.display(), e);
-                warn!("{}", error_msg);
-                stats.errors.push(error_msg);
-                stats.docs_skipped += 1;
-            }
-        }
-
-        advance_progress(&file_progress);
-    }
+    ingest_files(
+        config,
+        embedding,
+        embedder,
+        db,
+        store,
+        &source,
+        &files,
+        options.concurrency,
+        &file_progress,
+        &mut stats,
+    )
+    .await;
 
     finish_progress(file_progress, "Files processed");
 
     // Delete stale documents
-    let stale_ids = db.delete_stale_documents(&source.id, &current_uris).await?;
-    if !stale_ids.is_empty() {
-        info!("Deleted {} stale documents", stale_ids.len());
-        // Get point IDs for stale docs and delete from Qdrant
-        for doc_id in &stale_ids {
-            if let Ok(chunks) = db.get_chunks(doc_id).await {
-                let point_ids: Vec<Uuid> = chunks
-                    .iter()
-                    .filter_map(|c| Uuid::try_parse(&c.qdrant_point_id).ok())
-                    .collect();
-                if !point_ids.is_empty() {
-                    if let Err(e) = store.delete_points(&point_ids).await {
-                        warn!("Failed to delete Qdrant points: {}", e);
-                    }
-                }
-            }
-        }
+    remove_stale_documents(db, store, &source.id, &current_uris).await?;
+
+    // Complete ingestion run, recording per-document errors
+    complete_run(db, &run.id, &stats).await?;
+
+    info!(
+        "Ingestion complete: {} docs, {} chunks created, {} chunks updated",
+        stats.docs_processed, stats.chunks_created, stats.chunks_updated
+    );
+
+    Ok(stats)
+}
+
+/// Warn that a crawl of `url` bypasses robots.txt
+fn warn_robots_ignored(url: &str) {
+    warn!(
+        "⚠ robots.txt is IGNORED for this crawl of {} (--no-robots); only use this on sites you may crawl",
+        url
+    );
+}
+
+/// Count pages the crawler could not fetch as skipped documents with errors,
+/// and keep the crawl's outcome tallies
+async fn record_crawl_report(crawler: &Crawler, stats: &mut IngestStats) {
+    for failure in crawler.take_failures().await {
+        stats.errors.push(failure);
+        stats.docs_skipped += 1;
     }
+    stats.crawl = Some(crawler.take_report().await);
+}
 
-    // Complete ingestion run
-    let errors = if stats.errors.is_empty() {
-        None
-    } else {
-        Some(stats.errors.clone())
-    };
+/// Record a cancelled crawl as a run error, returning whether it was cancelled
+///
+/// Pages fetched before the cancellation are still ingested, so the run is
+/// kept but marked failed.
+fn record_cancellation(
+    cancel: Option<&CancellationToken>,
+    pages: usize,
+    stats: &mut IngestStats,
+) -> bool {
+    let cancelled = cancel.is_some_and(|token| token.is_cancelled());
+    if cancelled {
+        let msg = format!(
+            "Crawl cancelled; ingesting the {} pages fetched before it stopped",
+            pages
+        );
+        warn!("{}", msg);
+        stats.errors.push(msg);
+    }
+    cancelled
+}
+
+/// Mark an ingestion run finished, failing it if no document made it through
+/// and any errored. Deliberately skipped documents (too short, aliases) count
+/// as neither.
+pub(crate) async fn complete_run(db: &MetaDb, run_id: &str, stats: &IngestStats) -> Result<()> {
+    let handled = stats.docs_processed.max(0) as usize + stats.errors.len();
+    let status = RunStatus::finished(handled, stats.errors.len());
+    let errors = (!stats.errors.is_empty()).then(|| stats.errors.clone());
 
     db.complete_ingestion_run(
-        &run.id,
-        if stats.errors.is_empty() {
-            RunStatus::Completed
-        } else {
-            RunStatus::Failed
-        },
+        run_id,
+        status,
         stats.docs_processed,
         stats.chunks_created,
         stats.chunks_updated,
         stats.chunks_deleted,
         errors,
     )
-    .await?;
-
-    info!(
-        "Ingestion complete: {} docs, {} chunks created, {} chunks updated",
-        stats.docs_processed, stats.chunks_created, stats.chunks_updated
-    );
+    .await
+}
 
-    Ok(stats)
+/// Directory ingest filters persisted in a source's `config_json`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirFilter {
+    /// File extensions to include (without leading dot); `None` means all supported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+    /// Gitignore-style glob patterns, relative to the source root, to exclude
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+    /// Walk into symlinked directories and ingest symlinked files; off by
+    /// default, when symlinks are skipped
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub follow_symlinks: bool,
+    /// Also ingest paths excluded by `.gitignore` and `.ignore` files, which
+    /// are honored by default
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_ignored: bool,
 }
 
-/// Process a single file
-async fn process_file(
-    config: &Config,
-    embedding: &ResolvedEmbeddingConfig,
-    db: &MetaDb,
-    store: &QdrantStore,
-    embedder: &dyn Embedder,
-    source: &Source,
-    path: &Path,
-) -> Result<(i32, i32)> {
-    let file_uri = path.display().to_string();
-    debug!("Processing file: {}", file_uri);
+impl DirFilter {
+    /// Read the filter stored on a source, falling back to no filtering
+    pub fn from_source(source: &Source) -> Self {
+        source
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
 
-    // Read file content
-    let content = std::fs::read(path)?;
+    /// Build a filter from CLI options, returning `None` when none is set
+    pub fn from_options(
+        extensions: Option<String>,
+        exclude: Option<Vec<String>>,
+        follow_symlinks: bool,
+        respect_gitignore: Option<bool>,
+    ) -> Option<Self> {
+        let extensions: Option<Vec<String>> = extensions.map(|list| {
+            list.split(',')
+                .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+                .filter(|e| !e.is_empty())
+                .collect()
+        });
+        let extensions = extensions.filter(|e| !e.is_empty());
+        let exclude = exclude.filter(|p| !p.is_empty());
 
-    // Skip binary files
-    if is_binary_content(&content) {
-        debug!("Skipping binary file: {}", file_uri);
-        return Ok((0, 0));
+        if extensions.is_none()
+            && exclude.is_none()
+            && !follow_symlinks
+            && respect_gitignore.is_none()
+        {
+            None
+        } else {
+            Some(Self {
+                extensions,
+                exclude,
+                follow_symlinks,
+                include_ignored: respect_gitignore == Some(false),
+            })
+        }
     }
 
-    // Convert to string
-    let text = String::from_utf8_lossy(&content).to_string();
-    let content_hash = compute_content_hash(text.as_bytes());
+    /// Compile the exclude patterns, returning `None` when there are none
+    pub fn exclude_set(&self) -> Result<Option<GlobSet>> {
+        let Some(patterns) = self.exclude.as_ref().filter(|p| !p.is_empty()) else {
+            return Ok(None);
+        };
 
-    // Check if content changed
-    let existing_doc = db.get_document_by_uri(&source.id, &file_uri).await?;
-    if let Some(existing_doc) = existing_doc.as_ref() {
-        if existing_doc.content_hash == content_hash {
-            debug!("File unchanged: {}", file_uri);
-            return Ok((0, 0));
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern).map_err(|e| {
+                Error::Config(format!("Invalid exclude pattern '{}': {}", pattern, e))
+            })?;
+            builder.add(glob);
         }
+        let set = builder
+            .build()
+            .map_err(|e| Error::Config(format!("Invalid exclude patterns: {}", e)))?;
+        Ok(Some(set))
     }
-    let was_existing = existing_doc.is_some();
 
-    // Detect content type and parse
-    let content_type = ContentType::from_extension(path);
-    let parsed = parse_content(&text, content_type, None)?;
+    /// Check whether a file's extension is allowed by this filter
+    pub fn allows_extension(&self, path: &Path) -> bool {
+        let Some(extensions) = &self.extensions else {
+            return true;
+        };
+        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+            return false;
+        };
+        extensions
+            .iter()
+            .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
+    }
+}
 
-    // Create/update document
-    let mut doc = Document::new(source.id.clone(), file_uri.clone(), content_hash.clone());
-    doc.title = parsed.title.clone();
-    doc.content_type = Some(format!("{:?}", content_type).to_lowercase());
-    let doc = db.upsert_document(&doc).await?;
-    debug!(
-        doc_id = %doc.id,
-        source_id = %doc.source_id,
-        existing = was_existing,
-        uri = %doc.uri,
-        "Upserted document for file ingestion"
-    );
+/// Leading bytes read to tell binary files from text, as `is_binary_content` checks
+const BINARY_SNIFF_BYTES: u64 = 8192;
 
-    // Chunk the document
-    let chunks = chunk_document(&parsed, &content_hash, &config.chunk)?;
+/// Whether a walked file is worth reading: no larger than `max_bytes`, and
+/// not binary judging by its first bytes, whatever its extension says
+fn is_text_file_within(path: &Path, max_bytes: u64) -> bool {
+    if std::fs::metadata(path).is_ok_and(|meta| meta.len() > max_bytes) {
+        debug!("Skipping file over {} bytes: {}", max_bytes, path.display());
+        return false;
+    }
 
-    if chunks.is_empty() {
-        debug!("No chunks generated for: {}", file_uri);
-        return Ok((0, 0));
+    let mut prefix = Vec::new();
+    let sniffed = std::fs::File::open(path)
+        .and_then(|file| file.take(BINARY_SNIFF_BYTES).read_to_end(&mut prefix));
+    if sniffed.is_ok() && is_binary_content(&prefix) {
+        debug!("Skipping binary file: {}", path.display());
+        return false;
     }
+    true
+}
 
-    // Process chunks
-    let (created, updated) = process_chunks(
-        config,
-        embedding,
-        db,
-        store,
-        embedder,
+/// Ignore files read by the directory walk rather than ingested
+const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// Collect ingestible files under a directory, honoring .gitignore and the source filter
+///
+/// `.gitignore`, `.ignore` and git exclude files are honored even outside a
+/// git checkout, unless the filter includes ignored paths. The ignore files
+/// themselves, files on the extension denylist, binary files and files over
+/// `max_file_bytes` are left out.
+///
+/// When following symlinks, each canonical path is walked once: a link back
+/// up the tree ends the descent instead of looping, and a file reachable
+/// through several links is collected only under the first path found. The
+/// walk visits entries in file-name order, so that path is the same every run.
+pub fn collect_dir_files(
+    root: &Path,
+    filter: &DirFilter,
+    max_file_bytes: u64,
+) -> Result<Vec<PathBuf>> {
+    let excludes = filter.exclude_set()?;
+    let visited = filter.follow_symlinks.then(|| {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        Mutex::new(HashSet::from([root]))
+    });
+
+    // Ignore files apply whether or not the directory is a git checkout
+    let respect_ignores = !filter.include_ignored;
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .require_git(false)
+        .git_ignore(respect_ignores)
+        .git_exclude(respect_ignores)
+        .git_global(respect_ignores)
+        .ignore(respect_ignores)
+        .parents(respect_ignores)
+        .follow_links(filter.follow_symlinks)
+        .sort_by_file_name(|a, b| a.cmp(b));
+    let root = root.to_path_buf();
+    builder.filter_entry(move |entry| {
+        // Hidden files are walked, but git's own metadata and the ignore files
+        // are never documentation
+        let name = entry.file_name();
+        let skipped = if entry.file_type().is_some_and(|t| t.is_dir()) {
+            name == ".git"
+        } else {
+            IGNORE_FILE_NAMES.contains(&name.to_str().unwrap_or_default())
+        };
+        if skipped {
+            return false;
+        }
+        if let Some(excludes) = &excludes {
+            let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+            if excludes.is_match(relative) {
+                return false;
+            }
+        }
+        match (&visited, entry.path().canonicalize()) {
+            (Some(visited), Ok(canonical)) => visited.lock().unwrap().insert(canonical),
+            _ => true,
+        }
+    });
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        match entry {
+            Ok(e) if e.file_type().map(|t| t.is_file()).unwrap_or(false) => {
+                let path = e.path().to_path_buf();
+                if !should_skip_file(&path)
+                    && filter.allows_extension(&path)
+                    && is_text_file_within(&path, max_file_bytes)
+                {
+                    files.push(path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(files)
+}
+
+/// Delete documents of a source that are not in `current_uris`, along with their Qdrant points.
+///
+/// Stale documents with an alias URI in `current_uris` are moved to that alias
+/// instead. Returns the removed and moved documents.
+pub(crate) async fn remove_stale_documents(
+    db: &MetaDb,
+    store: &QdrantStore,
+    source_id: &str,
+    current_uris: &[String],
+) -> Result<StaleDocuments> {
+    let stale = db.delete_stale_documents(source_id, current_uris).await?;
+
+    for doc in &stale.promoted {
+        debug!("Promoted alias {} to document {}", doc.uri, doc.doc_id);
+        let point_ids: Vec<Uuid> = doc
+            .point_ids
+            .iter()
+            .filter_map(|id| Uuid::try_parse(id).ok())
+            .collect();
+        if let Err(e) = store.set_doc_uri(&point_ids, &doc.uri).await {
+            warn!("Failed to update Qdrant points of {}: {}", doc.uri, e);
+        }
+    }
+
+    if !stale.deleted.is_empty() {
+        info!("Deleted {} stale documents", stale.deleted.len());
+        let point_ids: Vec<Uuid> = stale
+            .deleted_point_ids
+            .iter()
+            .filter_map(|id| Uuid::try_parse(id).ok())
+            .collect();
+        if let Err(e) = store.delete_points(&point_ids).await {
+            warn!("Failed to delete Qdrant points: {}", e);
+        }
+    }
+
+    Ok(stale)
+}
+
+/// Options for directory ingestion
+#[derive(Debug, Clone)]
+pub struct DirIngestOptions {
+    /// Extension/exclude filter; `None` reuses the filter stored on the source
+    pub filter: Option<DirFilter>,
+    /// Number of files read, parsed and embedded in parallel
+    pub concurrency: usize,
+}
+
+impl Default for DirIngestOptions {
+    fn default() -> Self {
+        Self {
+            filter: None,
+            concurrency: 4,
+        }
+    }
+}
+
+/// Process files with a bounded worker pool.
+///
+/// Workers read, parse, chunk and embed files concurrently, while every
+/// SQLite/Qdrant write goes through a single writer so the database never sees
+/// concurrent writers. Stats are aggregated by the writer and errors are sorted,
+/// so results do not depend on completion order.
+#[allow(clippy::too_many_arguments)]
+async fn ingest_files(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &MetaDb,
+    store: &QdrantStore,
+    source: &Source,
+    files: &[PathBuf],
+    concurrency: usize,
+    progress: &Option<ProgressBar>,
+    stats: &mut IngestStats,
+) {
+    let concurrency = concurrency.max(1);
+    // Unbounded so workers never stall on send: a stalled stream would stop
+    // polling in-flight reads that may be holding pooled SQLite connections.
+    // In-flight work is still bounded by `buffer_unordered`.
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let workers = async move {
+        // Futures are lazy; buffer_unordered bounds how many run at once
+        let tasks: Vec<_> = files
+            .iter()
+            .map(|path| prepare_file_entry(config, embedding, db, store, embedder, source, path))
+            .collect();
+        let mut prepared = futures::stream::iter(tasks).buffer_unordered(concurrency);
+
+        while let Some(item) = prepared.next().await {
+            if tx.send(item).is_err() {
+                break;
+            }
+        }
+    };
+
+    let writer = async {
+        while let Some((path, prepared)) = rx.recv().await {
+            let result = match prepared {
+                Ok(Some(prepared)) => commit_prepared_file(db, store, source, prepared).await,
+                Ok(None) => Ok((0, 0)),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok((created, updated)) => {
+                    stats.docs_processed += 1;
+                    stats.chunks_created += created;
+                    stats.chunks_updated += updated;
+                }
+                Err(e) => {
+                    let error_msg = format!("{}: {}", path.display(), e);
+                    warn!("{}", error_msg);
+                    stats.errors.push(error_msg);
+                    stats.docs_skipped += 1;
+                }
+            }
+
+            set_progress_chunks(
+                progress,
+                "Processing files",
+                (stats.chunks_created + stats.chunks_updated) as usize,
+            );
+            advance_progress(progress);
+        }
+    };
+
+    tokio::join!(workers, writer);
+    stats.errors.sort();
+}
+
+async fn prepare_file_entry<'a>(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    source: &Source,
+    path: &'a PathBuf,
+) -> (&'a PathBuf, Result<Option<PreparedFile>>) {
+    let result = prepare_file(config, embedding, db, store, embedder, source, path).await;
+    (path, result)
+}
+
+/// A file that has been read, parsed, chunked and embedded, ready to be written
+pub(crate) struct PreparedFile {
+    uri: String,
+    content_hash: String,
+    title: Option<String>,
+    content_type: ContentType,
+    chunk_count: usize,
+    embedded: EmbeddedChunks,
+}
+
+/// A new or changed file, parsed and chunked but not yet written
+struct FileChange {
+    content_hash: String,
+    content_type: ContentType,
+    existing_doc: Option<Document>,
+    parsed: ParsedDocument,
+    chunks: Vec<TextChunk>,
+}
+
+/// Read, parse and chunk a file, comparing it with the document stored for it
+/// in `source_id` (if the source exists yet)
+///
+/// Returns `None` for binary or unchanged files, which are left alone.
+async fn plan_file(
+    config: &Config,
+    db: &MetaDb,
+    source_id: Option<&str>,
+    path: &Path,
+) -> Result<Option<FileChange>> {
+    let file_uri = path.display().to_string();
+
+    // Read file content
+    let owned_path = path.to_path_buf();
+    let content = tokio::task::spawn_blocking(move || std::fs::read(owned_path))
+        .await
+        .map_err(|e| Error::Other(format!("File read task failed: {}", e)))??;
+
+    // Skip binary files
+    if is_binary_content(&content) {
+        debug!("Skipping binary file: {}", file_uri);
+        return Ok(None);
+    }
+
+    // Convert to string
+    let text = String::from_utf8_lossy(&content).to_string();
+    let content_hash = compute_content_hash(text.as_bytes());
+
+    // Unchanged content skips parsing, chunking and embedding entirely
+    let existing_doc = match source_id {
+        Some(source_id) => db.get_document_by_uri(source_id, &file_uri).await?,
+        None => None,
+    };
+    if let Some(existing_doc) = existing_doc.as_ref() {
+        if existing_doc.content_hash == content_hash {
+            debug!("File unchanged: {}", file_uri);
+            return Ok(None);
+        }
+    }
+
+    // Detect content type, parse and chunk off the async runtime
+    let content_type = ContentType::from_extension(path);
+    let chunk_config = config.chunk.clone();
+    let (parsed, chunks) =
+        tokio::task::spawn_blocking(move || parse_file(&text, content_type, &chunk_config))
+            .await
+            .map_err(|e| Error::Other(format!("Parse task failed: {}", e)))??;
+
+    Ok(Some(FileChange {
+        content_hash,
+        content_type,
+        existing_doc,
+        parsed,
+        chunks,
+    }))
+}
+
+/// Read, parse, chunk and embed a file without writing anything.
+///
+/// Returns `None` for binary or unchanged files.
+pub(crate) async fn prepare_file(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    source: &Source,
+    path: &Path,
+) -> Result<Option<PreparedFile>> {
+    let file_uri = path.display().to_string();
+    debug!("Processing file: {}", file_uri);
+
+    let Some(FileChange {
+        content_hash,
+        content_type,
+        existing_doc,
+        parsed,
+        chunks,
+    }) = plan_file(config, db, Some(&source.id), path).await?
+    else {
+        return Ok(None);
+    };
+
+    let chunk_count = chunks.len();
+    let embedded = if chunks.is_empty() {
+        debug!("No chunks generated for: {}", file_uri);
+        EmbeddedChunks::default()
+    } else {
+        embed_new_chunks(
+            config,
+            embedding,
+            db,
+            store,
+            embedder,
+            existing_doc.as_ref().map(|d| d.id.as_str()),
+            &file_uri,
+            chunks,
+        )
+        .await?
+    };
+
+    Ok(Some(PreparedFile {
+        uri: file_uri,
+        content_hash,
+        title: parsed.title,
+        content_type,
+        chunk_count,
+        embedded,
+    }))
+}
+
+/// Parse and chunk a file's text according to its content type
+fn parse_file(
+    text: &str,
+    content_type: ContentType,
+    chunk_config: &ChunkConfig,
+) -> Result<(ParsedDocument, Vec<TextChunk>)> {
+    let parsed = parse_text(text, content_type, None, chunk_config)?;
+    let chunks = chunk_document(&parsed, chunk_config)?;
+    Ok((parsed, chunks))
+}
+
+/// Parse text according to its content type, applying the chunk settings
+/// that decide what gets parsed (such as `chunk.notebook_outputs`)
+fn parse_text(
+    text: &str,
+    content_type: ContentType,
+    base_url: Option<&str>,
+    chunk_config: &ChunkConfig,
+) -> Result<ParsedDocument> {
+    match content_type {
+        ContentType::Notebook => parse_notebook(text, chunk_config.notebook_outputs),
+        _ => parse_content(text, content_type, base_url),
+    }
+}
+
+/// Write a prepared file's document, chunks and vectors
+pub(crate) async fn commit_prepared_file(
+    db: &MetaDb,
+    store: &QdrantStore,
+    source: &Source,
+    prepared: PreparedFile,
+) -> Result<(i32, i32)> {
+    // Create/update document
+    let mut doc = Document::new(
+        source.id.clone(),
+        prepared.uri.clone(),
+        prepared.content_hash.clone(),
+    );
+    doc.title = prepared.title;
+    doc.content_type = Some(format!("{:?}", prepared.content_type).to_lowercase());
+    let doc = db.upsert_document(&doc).await?;
+    debug!(
+        doc_id = %doc.id,
+        source_id = %doc.source_id,
+        uri = %doc.uri,
+        "Upserted document for file ingestion"
+    );
+
+    write_chunks(
+        db,
+        store,
         source,
         &doc,
-        &file_uri,
-        chunks,
+        &prepared.uri,
+        prepared.chunk_count,
+        prepared.embedded,
     )
-    .await?;
+    .await
+}
 
-    Ok((created, updated))
+/// Process a single file
+pub(crate) async fn process_file(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    source: &Source,
+    path: &Path,
+) -> Result<(i32, i32)> {
+    match prepare_file(config, embedding, db, store, embedder, source, path).await? {
+        Some(prepared) => commit_prepared_file(db, store, source, prepared).await,
+        None => Ok((0, 0)),
+    }
 }
 
 /// Process chunks for a document
+#[allow(clippy::too_many_arguments)]
 async fn process_chunks(
     config: &Config,
     embedding: &ResolvedEmbeddingConfig,
@@ -998,38 +1661,154 @@ async fn process_chunks(
     doc_uri: &str,
     chunks: Vec<TextChunk>,
 ) -> Result<(i32, i32)> {
-    let mut created = 0i32;
-    let mut updated = 0i32;
-    let mut chunks_to_embed: Vec<(usize, TextChunk)> = Vec::new();
-    let existing_chunks = db.get_chunks_by_modality(&doc.id, "text").await?;
-    let existing_hashes: HashSet<String> = existing_chunks
+    let chunk_count = chunks.len();
+    let embedded = embed_new_chunks(
+        config,
+        embedding,
+        db,
+        store,
+        embedder,
+        Some(&doc.id),
+        doc_uri,
+        chunks,
+    )
+    .await?;
+    write_chunks(db, store, source, doc, doc_uri, chunk_count, embedded).await
+}
+
+/// New or changed chunks paired with their embeddings
+#[derive(Default)]
+pub(crate) struct EmbeddedChunks {
+    items: Vec<(usize, TextChunk, Vec<f32>)>,
+    /// Indexes that already held a chunk, so writing there is an update
+    existing_indexes: HashSet<usize>,
+    /// Points of the stored chunks the items replace
+    replaced_points: Vec<String>,
+    /// Unchanged chunks whose offsets moved because earlier text was edited
+    shifted: Vec<Chunk>,
+    /// Model that produced the embeddings
+    model_id: String,
+}
+
+/// Whether `stored` already holds `chunk`'s text and heading trail
+fn chunk_unchanged(stored: Option<&Chunk>, chunk: &TextChunk) -> bool {
+    stored.is_some_and(|stored| {
+        stored.chunk_hash == chunk.hash && stored.headings() == chunk.headings
+    })
+}
+
+/// How a document's new chunks relate to the chunks stored for it
+struct ChunkPlan {
+    /// Chunks whose index now holds different text, with their index
+    to_embed: Vec<(usize, TextChunk)>,
+    /// Indexes that already held a chunk, so writing there is an update
+    existing_indexes: HashSet<usize>,
+    /// Points of the stored chunks `to_embed` replaces
+    replaced_points: Vec<String>,
+    /// Unchanged chunks whose offsets moved because earlier text was edited
+    shifted: Vec<Chunk>,
+    /// Stored chunks beyond the new chunk count, which are deleted
+    trimmed: usize,
+}
+
+impl ChunkPlan {
+    /// Chunks that would be (created, updated)
+    fn counts(&self) -> (i32, i32) {
+        let updated = self
+            .to_embed
+            .iter()
+            .filter(|(index, _)| self.existing_indexes.contains(index))
+            .count();
+        ((self.to_embed.len() - updated) as i32, updated as i32)
+    }
+}
+
+/// Compare `chunks` with the text chunks stored for `doc_id` without writing anything
+async fn plan_chunks(
+    db: &MetaDb,
+    doc_id: Option<&str>,
+    chunks: Vec<TextChunk>,
+) -> Result<ChunkPlan> {
+    let existing: HashMap<usize, Chunk> = match doc_id {
+        Some(doc_id) => db
+            .get_chunks_by_modality(doc_id, "text")
+            .await?
+            .into_iter()
+            .map(|c| (c.chunk_index as usize, c))
+            .collect(),
+        None => HashMap::new(),
+    };
+    let existing_indexes: HashSet<usize> = existing.keys().copied().collect();
+    let trimmed = existing_indexes
+        .iter()
+        .filter(|index| **index >= chunks.len())
+        .count();
+
+    // Only chunks whose index now holds different text need embedding
+    let (unchanged, to_embed): (Vec<_>, Vec<_>) = chunks
+        .into_iter()
+        .enumerate()
+        .partition(|(index, chunk)| chunk_unchanged(existing.get(index), chunk));
+    let replaced_points: Vec<String> = to_embed
         .iter()
-        .map(|c| c.chunk_hash.clone())
+        .filter_map(|(index, _)| existing.get(index))
+        .map(|c| c.qdrant_point_id.clone())
+        .collect();
+    let shifted: Vec<Chunk> = unchanged
+        .into_iter()
+        .filter_map(|(index, chunk)| {
+            let stored = existing.get(&index)?;
+            let (start, end) = (chunk.char_start as i32, chunk.char_end as i32);
+            (stored.char_start != start || stored.char_end != end).then(|| Chunk {
+                char_start: start,
+                char_end: end,
+                ..stored.clone()
+            })
+        })
         .collect();
 
-    // Find chunks that need embedding
-    for (i, chunk) in chunks.iter().enumerate() {
-        if !existing_hashes.contains(&chunk.hash) {
-            chunks_to_embed.push((i, chunk.clone()));
-        }
-    }
+    Ok(ChunkPlan {
+        to_embed,
+        existing_indexes,
+        replaced_points,
+        shifted,
+        trimmed,
+    })
+}
+
+/// Embed the chunks that differ from the chunk stored at the same index
+#[allow(clippy::too_many_arguments)]
+async fn embed_new_chunks(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    doc_id: Option<&str>,
+    doc_uri: &str,
+    chunks: Vec<TextChunk>,
+) -> Result<EmbeddedChunks> {
+    let ChunkPlan {
+        to_embed: chunks_to_embed,
+        existing_indexes,
+        replaced_points,
+        shifted,
+        ..
+    } = plan_chunks(db, doc_id, chunks).await?;
 
     if chunks_to_embed.is_empty() {
         debug!("All chunks unchanged for: {}", doc_uri);
-        return Ok((0, 0));
+        return Ok(EmbeddedChunks {
+            items: Vec::new(),
+            existing_indexes,
+            replaced_points,
+            shifted,
+            model_id: embedding.model_id.clone(),
+        });
     }
 
     let expected_dim = store.dimension();
-    if embedder.dimension() != expected_dim {
-        return Err(Error::Embedding(format!(
-            "Embedding dimension mismatch for model '{}' (family '{}', source {}): embedder {} != collection {}",
-            embedding.model_id,
-            embedding.family,
-            embedding.dimension_source,
-            embedder.dimension(),
-            expected_dim
-        )));
-    }
+    check_embedder_dimension(embedding, embedder, store)?;
 
     debug!(
         "Embedding {} new/changed chunks for: {}",
@@ -1045,28 +1824,53 @@ async fn process_chunks(
     let batch_size = embedding.effective_batch_size(config.embedding.batch_size);
     let embeddings = embed_in_batches(embedder, texts, batch_size).await?;
 
-    if embeddings
-        .iter()
-        .any(|embedding| embedding.len() != expected_dim)
-    {
-        let mismatched = embeddings
-            .iter()
-            .find(|embedding| embedding.len() != expected_dim)
-            .map(|embedding| embedding.len())
-            .unwrap_or(0);
-        return Err(Error::Embedding(format!(
-            "Embedding dimension mismatch: expected {}, got {}",
-            expected_dim, mismatched
-        )));
+    if let Some(mismatched) = embeddings.iter().find(|vec| vec.len() != expected_dim) {
+        return Err(dimension_mismatch(
+            embedding,
+            expected_dim,
+            mismatched.len(),
+            "text embedding",
+        ));
+    }
+
+    Ok(EmbeddedChunks {
+        items: chunks_to_embed
+            .into_iter()
+            .zip(embeddings)
+            .map(|((index, chunk), vector)| (index, chunk, vector))
+            .collect(),
+        existing_indexes,
+        replaced_points,
+        shifted,
+        model_id: embedding.model_id.clone(),
+    })
+}
+
+/// Write embedded chunks to SQLite and Qdrant, trimming chunks beyond `chunk_count`
+async fn write_chunks(
+    db: &MetaDb,
+    store: &QdrantStore,
+    source: &Source,
+    doc: &Document,
+    doc_uri: &str,
+    chunk_count: usize,
+    embedded: EmbeddedChunks,
+) -> Result<(i32, i32)> {
+    let mut created = 0i32;
+    let mut updated = 0i32;
+
+    for chunk in &embedded.shifted {
+        db.upsert_chunk(chunk).await?;
     }
 
     // Prepare points for Qdrant
     let mut points: Vec<ChunkPoint> = Vec::new();
+    let api_endpoint = doc.api_endpoint();
 
-    for ((chunk_index, chunk), embedding) in chunks_to_embed.iter().zip(embeddings.iter()) {
+    for (chunk_index, chunk, embedding) in embedded.items {
         let meta_chunk = Chunk::new(
             doc.id.clone(),
-            *chunk_index as i32,
+            chunk_index as i32,
             chunk.hash.clone(),
             chunk.text.clone(),
             chunk.char_start as i32,
@@ -1088,7 +1892,7 @@ async fn process_chunks(
             source.uri.clone(),
             doc.id.clone(),
             doc_uri.to_string(),
-            *chunk_index as i32,
+            chunk_index as i32,
             chunk.hash.clone(),
             Utc::now().to_rfc3339(),
         );
@@ -1099,6 +1903,10 @@ async fn process_chunks(
         } else {
             Some(chunk.headings.clone())
         };
+        if let Some(endpoint) = &api_endpoint {
+            payload.set_api_endpoint(endpoint);
+        }
+        payload.model_id = Some(embedded.model_id.clone());
 
         // Parse qdrant_point_id string to Uuid
         let point_id = Uuid::try_parse(&meta_chunk.qdrant_point_id).unwrap_or_else(|_| {
@@ -1107,23 +1915,35 @@ async fn process_chunks(
 
         points.push(ChunkPoint {
             id: point_id,
-            vector: embedding.clone(),
+            vector: embedding,
             payload,
         });
 
-        if existing_hashes.contains(&chunk.hash) {
+        if embedded.existing_indexes.contains(&chunk_index) {
             updated += 1;
         } else {
             created += 1;
         }
     }
 
-    // Upsert to Qdrant
-    store.upsert_points(points).await?;
+    // Upsert to Qdrant, then drop the points of the chunks they replaced
+    if !points.is_empty() {
+        let new_ids: HashSet<Uuid> = points.iter().map(|p| p.id).collect();
+        store.upsert_points(points).await?;
+        let replaced: Vec<Uuid> = embedded
+            .replaced_points
+            .iter()
+            .filter_map(|s| Uuid::try_parse(s).ok())
+            .filter(|id| !new_ids.contains(id))
+            .collect();
+        if !replaced.is_empty() {
+            store.delete_points(&replaced).await?;
+        }
+    }
 
     // Delete extra chunks if document shrunk
     let deleted_point_strings = db
-        .delete_chunks_from_index(&doc.id, chunks.len() as i32)
+        .delete_chunks_from_index(&doc.id, chunk_count as i32)
         .await?;
     if !deleted_point_strings.is_empty() {
         let deleted_uuids: Vec<Uuid> = deleted_point_strings
@@ -1150,6 +1970,7 @@ pub async fn cmd_ingest_url(
     overrides: CrawlOverrides,
     operation: RunOperation,
     interactive: bool,
+    cancel: Option<&CancellationToken>,
 ) -> Result<IngestStats> {
     info!("Ingesting URL: {}", url);
 
@@ -1169,57 +1990,54 @@ pub async fn cmd_ingest_url(
     // Resolve source interactively on conflicts
     let source = resolve_source(db, SourceType::Url, url, name.clone(), interactive).await?;
 
+    // Overrides given now replace the stored ones; the rest are reused
+    let overrides = persist_crawl_overrides(db, &source, overrides).await?;
+
     // Start ingestion run
     let run = db.start_ingestion_run(&source.id, operation).await?;
-
-    if embedder.dimension() != store.dimension() {
-        return Err(Error::Embedding(format!(
-            "Embedding dimension mismatch for model '{}' (family '{}', source {}): embedder {} != collection {}",
-            embedding.model_id,
-            embedding.family,
-            embedding.dimension_source,
-            embedder.dimension(),
-            store.dimension()
-        )));
+    let _heartbeat = db.start_run_heartbeat(&run.id);
+    if overrides.ignore_robots {
+        warn_robots_ignored(url);
+        db.mark_run_robots_ignored(&run.id).await?;
     }
 
-    // Build crawl config with CLI overrides
-    let mut crawl_config = config.crawl.clone();
-    if let Some(max_pages) = overrides.max_pages {
-        crawl_config.max_pages = max_pages;
-    }
-    if let Some(max_depth) = overrides.max_depth {
-        crawl_config.max_depth = max_depth;
-    }
-    if overrides.path_prefix.is_some() {
-        crawl_config.path_prefix = overrides.path_prefix;
-    }
+    check_embedder_dimension(embedding, embedder, store)?;
 
-    // Create crawler
-    let crawler = Crawler::new(crawl_config)?;
+    // Create crawler with CLI overrides
+    let crawler = Crawler::new(overrides.apply(&config.crawl))?;
 
     let mut current_uris: Vec<String> = Vec::new();
+    let mut redirects: Vec<(String, String)> = Vec::new();
 
     // Crawl and process pages
+    let crawl_progress = start_spinner("pages crawled");
     let pages = crawler
-        .crawl(url, |_page| {
+        .crawl(url, cancel, |_page| {
+            advance_progress(&crawl_progress);
             // Continue callback - return true to keep crawling
             true
         })
         .await?;
+    finish_progress(crawl_progress, "pages crawled");
+    record_crawl_report(&crawler, &mut stats).await;
+    let cancelled = record_cancellation(cancel, pages.len(), &mut stats);
 
     let page_progress = start_progress_bar(pages.len(), "Processing pages");
 
     for page in pages {
-        current_uris.push(page.url.clone());
-
         match process_page(config, embedding, db, store, embedder, &source, &page).await {
-            Ok((created, updated)) => {
+            Ok(Some((created, updated))) => {
+                current_uris.push(page.url.clone());
+                if let Some(requested) = &page.redirected_from {
+                    redirects.push((requested.clone(), page.url.clone()));
+                }
                 stats.docs_processed += 1;
                 stats.chunks_created += created;
                 stats.chunks_updated += updated;
             }
+            Ok(None) => stats.docs_skipped += 1,
             Err(e) => {
+                current_uris.push(page.url.clone());
                 let error_msg = format!("{}: {}", page.url, e);
                 warn!("{}", error_msg);
                 stats.errors.push(error_msg);
@@ -1227,51 +2045,24 @@ pub async fn cmd_ingest_url(
             }
         }
 
+        set_progress_chunks(
+            &page_progress,
+            "Processing pages",
+            (stats.chunks_created + stats.chunks_updated) as usize,
+        );
         advance_progress(&page_progress);
     }
 
     finish_progress(page_progress, "Pages processed");
+    current_uris.extend(db.record_redirect_aliases(&source.id, &redirects).await?);
 
-    // Delete stale documents
-    let stale_ids = db.delete_stale_documents(&source.id, &current_uris).await?;
-    if !stale_ids.is_empty() {
-        info!("Deleted {} stale documents", stale_ids.len());
-        for doc_id in &stale_ids {
-            if let Ok(chunks) = db.get_chunks(doc_id).await {
-                let point_ids: Vec<Uuid> = chunks
-                    .iter()
-                    .filter_map(|c| Uuid::try_parse(&c.qdrant_point_id).ok())
-                    .collect();
-                if !point_ids.is_empty() {
-                    if let Err(e) = store.delete_points(&point_ids).await {
-                        warn!("Failed to delete Qdrant points: {}", e);
-                    }
-                }
-            }
-        }
+    // Delete stale documents; a cancelled crawl did not see every page
+    if !cancelled {
+        remove_stale_documents(db, store, &source.id, &current_uris).await?;
     }
 
-    // Complete ingestion run
-    let errors = if stats.errors.is_empty() {
-        None
-    } else {
-        Some(stats.errors.clone())
-    };
-
-    db.complete_ingestion_run(
-        &run.id,
-        if stats.errors.is_empty() {
-            RunStatus::Completed
-        } else {
-            RunStatus::Failed
-        },
-        stats.docs_processed,
-        stats.chunks_created,
-        stats.chunks_updated,
-        stats.chunks_deleted,
-        errors,
-    )
-    .await?;
+    // Complete ingestion run, recording per-document errors
+    complete_run(db, &run.id, &stats).await?;
 
     info!(
         "Ingestion complete: {} docs, {} chunks created, {} chunks updated",
@@ -1291,8 +2082,10 @@ pub async fn cmd_ingest_sitemap(
     sitemap_url: &str,
     name: Option<String>,
     max_pages: Option<u32>,
+    ignore_robots: bool,
     operation: RunOperation,
     interactive: bool,
+    cancel: Option<&CancellationToken>,
 ) -> Result<IngestStats> {
     use crate::crawl::SitemapParser;
 
@@ -1302,9 +2095,14 @@ pub async fn cmd_ingest_sitemap(
 
     store.ensure_collection().await?;
 
+    let overrides = CrawlOverrides {
+        ignore_robots,
+        ..Default::default()
+    };
+    let crawler = Crawler::new(overrides.apply(&config.crawl))?;
+
     // Parse sitemap to get URLs
-    let parser = SitemapParser::new(&config.crawl.user_agent)?;
-    let entries = parser.parse(sitemap_url).await?;
+    let entries = SitemapParser::new(&crawler).parse(sitemap_url).await?;
 
     if entries.is_empty() {
         warn!("No URLs found in sitemap: {}", sitemap_url);
@@ -1342,37 +2140,221 @@ pub async fn cmd_ingest_sitemap(
 
     // Start ingestion run
     let run = db.start_ingestion_run(&source.id, operation).await?;
+    let _heartbeat = db.start_run_heartbeat(&run.id);
+    if ignore_robots {
+        warn_robots_ignored(sitemap_url);
+        db.mark_run_robots_ignored(&run.id).await?;
+    }
 
-    if embedder.dimension() != store.dimension() {
-        return Err(Error::Embedding(format!(
-            "Embedding dimension mismatch for model '{}' (family '{}', source {}): embedder {} != collection {}",
-            embedding.model_id,
-            embedding.family,
-            embedding.dimension_source,
-            embedder.dimension(),
-            store.dimension()
-        )));
+    check_embedder_dimension(embedding, embedder, store)?;
+
+    // Process each URL from sitemap
+    let urls: Vec<String> = entries.into_iter().map(|entry| entry.loc).collect();
+    let (current_uris, pages_fetched) = ingest_page_list(
+        config, embedding, embedder, db, store, &crawler, &source, &urls, cancel, &mut stats,
+    )
+    .await?;
+    let cancelled = record_cancellation(cancel, pages_fetched, &mut stats);
+
+    // Delete stale documents; a cancelled crawl did not see every page
+    if !cancelled {
+        remove_stale_documents(db, store, &source.id, &current_uris).await?;
+    }
+
+    // Complete ingestion run, recording per-document errors
+    complete_run(db, &run.id, &stats).await?;
+
+    info!(
+        "Sitemap ingestion complete: {} docs, {} chunks created, {} chunks updated",
+        stats.docs_processed, stats.chunks_created, stats.chunks_updated
+    );
+
+    Ok(stats)
+}
+
+/// URLs of a `urls` source, one page each
+///
+/// URL lists keep their URLs in `config_json`, so `update` fetches the same
+/// pages again.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlList {
+    pub urls: Vec<String>,
+}
+
+impl UrlList {
+    /// Read the URLs stored on a source, falling back to none
+    pub fn from_source(source: &Source) -> Self {
+        source
+            .config_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parse one URL per line, skipping blank lines, `#` comments and repeats
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut seen = HashSet::new();
+        let mut urls = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let url = Url::parse(line).map_err(|e| {
+                Error::Config(format!(
+                    "Invalid URL on line {}: {} ({})",
+                    index + 1,
+                    line,
+                    e
+                ))
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(Error::Config(format!(
+                    "Invalid URL on line {}: {} (only http and https are fetched)",
+                    index + 1,
+                    line
+                )));
+            }
+            if seen.insert(url.to_string()) {
+                urls.push(url.to_string());
+            }
+        }
+        Ok(Self { urls })
+    }
+
+    /// Read a URL list from a file, or from stdin when `path` is `-`
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = if path == Path::new("-") {
+            std::io::read_to_string(std::io::stdin())?
+        } else {
+            std::fs::read_to_string(path)?
+        };
+        Self::parse(&text)
+    }
+}
+
+/// Source URI of a URL list read from `path`
+pub fn url_list_uri(path: &Path) -> String {
+    if path == Path::new("-") {
+        "stdin".to_string()
+    } else {
+        path.canonicalize()
+            .unwrap_or_else(|_| path.to_path_buf())
+            .display()
+            .to_string()
     }
+}
+
+/// Ingest a list of URLs, fetching each page once without following links
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_ingest_urls(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &MetaDb,
+    store: &QdrantStore,
+    uri: &str,
+    list: &UrlList,
+    name: Option<String>,
+    operation: RunOperation,
+    interactive: bool,
+    cancel: Option<&CancellationToken>,
+) -> Result<IngestStats> {
+    info!("Ingesting {} URLs from {}", list.urls.len(), uri);
+
+    let mut stats = IngestStats::default();
+
+    if list.urls.is_empty() {
+        warn!("No URLs found in {}", uri);
+        return Ok(stats);
+    }
+
+    store.ensure_collection().await?;
+
+    // Resolve source interactively on conflicts
+    let source = resolve_source(db, SourceType::Urls, uri, name.clone(), interactive).await?;
+    db.update_source_config(&source.id, Some(serde_json::to_string(list)?))
+        .await?;
+
+    // Start ingestion run
+    let run = db.start_ingestion_run(&source.id, operation).await?;
+    let _heartbeat = db.start_run_heartbeat(&run.id);
+
+    check_embedder_dimension(embedding, embedder, store)?;
     let crawler = Crawler::new(config.crawl.clone())?;
 
-    let mut current_uris: Vec<String> = Vec::new();
-    let url_progress = start_progress_bar(entries.len(), "Processing URLs");
+    let (current_uris, pages_fetched) = ingest_page_list(
+        config, embedding, embedder, db, store, &crawler, &source, &list.urls, cancel, &mut stats,
+    )
+    .await?;
+    let cancelled = record_cancellation(cancel, pages_fetched, &mut stats);
 
-    // Process each URL from sitemap
-    for entry in entries {
-        current_uris.push(entry.loc.clone());
+    // Delete stale documents; a cancelled ingest did not see every page
+    if !cancelled {
+        remove_stale_documents(db, store, &source.id, &current_uris).await?;
+    }
 
-        // Fetch the page
-        match crawler.fetch(&entry.loc).await {
+    // Complete ingestion run, recording per-document errors
+    complete_run(db, &run.id, &stats).await?;
+
+    info!(
+        "URL list ingestion complete: {} docs, {} chunks created, {} chunks updated",
+        stats.docs_processed, stats.chunks_created, stats.chunks_updated
+    );
+
+    Ok(stats)
+}
+
+/// Fetch and process each of `urls` once, without following their links
+///
+/// Returns the URIs the source still covers and the number of pages fetched.
+#[allow(clippy::too_many_arguments)]
+async fn ingest_page_list(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &MetaDb,
+    store: &QdrantStore,
+    crawler: &Crawler,
+    source: &Source,
+    urls: &[String],
+    cancel: Option<&CancellationToken>,
+    stats: &mut IngestStats,
+) -> Result<(Vec<String>, usize)> {
+    let mut current_uris: Vec<String> = Vec::new();
+    let mut redirects: Vec<(String, String)> = Vec::new();
+    let url_progress = start_progress_bar(urls.len(), "Processing URLs");
+
+    let mut pages_fetched = 0;
+    for url in urls {
+        if cancel.is_some_and(|token| token.is_cancelled()) {
+            break;
+        }
+        // Fetch the page, abandoning it if the ingest is cancelled meanwhile
+        let fetch_result = match cancel {
+            Some(token) => match token.run_until_cancelled(crawler.fetch(url)).await {
+                Some(result) => result,
+                None => break,
+            },
+            None => crawler.fetch(url).await,
+        };
+        match fetch_result {
             Ok(page) => {
-                match process_page(config, embedding, db, store, embedder, &source, &page).await {
-                    Ok((created, updated)) => {
+                pages_fetched += 1;
+                match process_page(config, embedding, db, store, embedder, source, &page).await {
+                    Ok(Some((created, updated))) => {
+                        current_uris.push(page.url.clone());
+                        if page.redirected_from.is_some() {
+                            redirects.push((url.clone(), page.url.clone()));
+                        }
                         stats.docs_processed += 1;
                         stats.chunks_created += created;
                         stats.chunks_updated += updated;
                     }
+                    Ok(None) => stats.docs_skipped += 1,
                     Err(e) => {
-                        let error_msg = format!("{}: {}", entry.loc, e);
+                        current_uris.push(page.url.clone());
+                        let error_msg = format!("{}: {}", url, e);
                         warn!("{}", error_msg);
                         stats.errors.push(error_msg);
                         stats.docs_skipped += 1;
@@ -1380,68 +2362,104 @@ pub async fn cmd_ingest_sitemap(
                 }
             }
             Err(e) => {
-                let error_msg = format!("{}: {}", entry.loc, e);
+                current_uris.push(url.clone());
+                let error_msg = format!("{}: {}", url, e);
                 warn!("{}", error_msg);
                 stats.errors.push(error_msg);
                 stats.docs_skipped += 1;
             }
         }
 
+        set_progress_chunks(
+            &url_progress,
+            "Processing URLs",
+            (stats.chunks_created + stats.chunks_updated) as usize,
+        );
         advance_progress(&url_progress);
     }
 
     finish_progress(url_progress, "URLs processed");
+    current_uris.extend(db.record_redirect_aliases(&source.id, &redirects).await?);
+    Ok((current_uris, pages_fetched))
+}
 
-    // Delete stale documents
-    let stale_ids = db.delete_stale_documents(&source.id, &current_uris).await?;
-    if !stale_ids.is_empty() {
-        info!("Deleted {} stale documents", stale_ids.len());
-        for doc_id in &stale_ids {
-            if let Ok(chunks) = db.get_chunks(doc_id).await {
-                let point_ids: Vec<Uuid> = chunks
-                    .iter()
-                    .filter_map(|c| Uuid::try_parse(&c.qdrant_point_id).ok())
-                    .collect();
-                if !point_ids.is_empty() {
-                    if let Err(e) = store.delete_points(&point_ids).await {
-                        warn!("Failed to delete Qdrant points: {}", e);
-                    }
-                }
-            }
-        }
-    }
+/// What ingesting a crawled page would change, decided without writing anything
+enum PagePlan {
+    /// Same content as the stored document
+    Unchanged,
+    /// Identical to another document of the source, which it becomes an alias of
+    Alias(Document),
+    /// Too little text to be stored
+    TooShort,
+    /// New or changed content, parsed and chunked
+    Changed {
+        existing_doc: Option<Document>,
+        parsed: Box<ParsedDocument>,
+        chunks: Vec<TextChunk>,
+    },
+}
 
-    // Complete ingestion run
-    let errors = if stats.errors.is_empty() {
-        None
-    } else {
-        Some(stats.errors.clone())
+/// Parse and chunk a crawled page, comparing it with the documents stored in
+/// `source_id` (if the source exists yet)
+async fn plan_page(
+    config: &Config,
+    db: &MetaDb,
+    source_id: Option<&str>,
+    page: &CrawledPage,
+) -> Result<PagePlan> {
+    // Unchanged content skips parsing, chunking and embedding entirely; its
+    // links were already followed during the crawl
+    let existing_doc = match source_id {
+        Some(source_id) => db.get_document_by_uri(source_id, &page.url).await?,
+        None => None,
     };
+    if let Some(existing_doc) = existing_doc.as_ref() {
+        if existing_doc.content_hash == page.content_hash {
+            debug!("Page unchanged: {}", page.url);
+            return Ok(PagePlan::Unchanged);
+        }
+    }
 
-    db.complete_ingestion_run(
-        &run.id,
-        if stats.errors.is_empty() {
-            RunStatus::Completed
-        } else {
-            RunStatus::Failed
-        },
-        stats.docs_processed,
-        stats.chunks_created,
-        stats.chunks_updated,
-        stats.chunks_deleted,
-        errors,
-    )
-    .await?;
+    // Identical content at another URI (mirror, alias) is not chunked and
+    // embedded again
+    if let (Some(source_id), None) = (source_id, existing_doc.as_ref()) {
+        if let Some(canonical) = db
+            .find_document_by_hash(source_id, &page.content_hash, &page.url)
+            .await?
+        {
+            return Ok(PagePlan::Alias(canonical));
+        }
+    }
 
-    info!(
-        "Sitemap ingestion complete: {} docs, {} chunks created, {} chunks updated",
-        stats.docs_processed, stats.chunks_created, stats.chunks_updated
-    );
+    // Parse content
+    let parsed = parse_text(
+        &page.content,
+        page.content_type,
+        Some(&page.url),
+        &config.chunk,
+    )?;
+
+    // Skip near-empty pages (nav shells, "404" stubs); their links were
+    // already followed during the crawl
+    let text_chars = parsed.text.trim().chars().count();
+    if text_chars < config.crawl.min_document_chars {
+        debug!(
+            "Skipping {}: {} characters of text (minimum {})",
+            page.url, text_chars, config.crawl.min_document_chars
+        );
+        return Ok(PagePlan::TooShort);
+    }
 
-    Ok(stats)
+    let chunks = chunk_document(&parsed, &config.chunk)?;
+    Ok(PagePlan::Changed {
+        existing_doc,
+        parsed: Box::new(parsed),
+        chunks,
+    })
 }
 
-/// Process a crawled page
+/// Process a crawled page, returning `None` when it is skipped for having too
+/// little text
 async fn process_page(
     config: &Config,
     embedding: &ResolvedEmbeddingConfig,
@@ -1450,26 +2468,37 @@ async fn process_page(
     embedder: &dyn Embedder,
     source: &Source,
     page: &CrawledPage,
-) -> Result<(i32, i32)> {
+) -> Result<Option<(i32, i32)>> {
     debug!("Processing page: {}", page.url);
 
-    let content_hash = compute_content_hash(page.content.as_bytes());
-
-    // Check if content changed
-    let existing_doc = db.get_document_by_uri(&source.id, &page.url).await?;
-    if let Some(existing_doc) = existing_doc.as_ref() {
-        if existing_doc.content_hash == content_hash {
-            debug!("Page unchanged: {}", page.url);
-            return Ok((0, 0));
+    let (existing_doc, parsed, chunks) = match plan_page(config, db, Some(&source.id), page).await?
+    {
+        PagePlan::Unchanged => return Ok(Some((0, 0))),
+        PagePlan::TooShort => return Ok(None),
+        PagePlan::Alias(canonical) => {
+            debug!(
+                "Page {} duplicates {}, recording alias",
+                page.url, canonical.uri
+            );
+            db.upsert_document_alias(&source.id, &page.url, &canonical.id)
+                .await?;
+            return Ok(Some((0, 0)));
         }
-    }
+        PagePlan::Changed {
+            existing_doc,
+            parsed,
+            chunks,
+        } => (existing_doc, parsed, chunks),
+    };
     let was_existing = existing_doc.is_some();
 
-    // Parse content
-    let parsed = parse_content(&page.content, page.content_type, Some(&page.url))?;
-
-    // Create/update document
-    let mut doc = Document::new(source.id.clone(), page.url.clone(), content_hash.clone());
+    // Create/update document, replacing any alias the URI had before its content diverged
+    db.delete_document_alias(&source.id, &page.url).await?;
+    let mut doc = Document::new(
+        source.id.clone(),
+        page.url.clone(),
+        page.content_hash.clone(),
+    );
     doc.title = page.title.clone().or(parsed.title.clone());
     doc.content_type = Some(format!("{:?}", page.content_type).to_lowercase());
     let doc = db.upsert_document(&doc).await?;
@@ -1481,12 +2510,9 @@ async fn process_page(
         "Upserted document for page ingestion"
     );
 
-    // Chunk the document
-    let chunks = chunk_document(&parsed, &content_hash, &config.chunk)?;
-
     // Multimodal image selection + caching (optional)
     let images = select_image_candidates(config, embedding, &parsed);
-    let cached_images = if images.is_empty() {
+    let mut cached_images = if images.is_empty() {
         Vec::new()
     } else {
         debug!(count = images.len(), uri = %page.url, "Selected image candidates for ingestion");
@@ -1495,11 +2521,14 @@ async fn process_page(
         }
         fetch_and_cache_images(config, &images).await
     };
+    if let Some(screenshot) = cache_page_screenshot(config, embedding, page).await {
+        cached_images.push(screenshot);
+    }
 
     if chunks.is_empty() {
         debug!("No chunks generated for: {}", page.url);
         if cached_images.is_empty() {
-            return Ok((0, 0));
+            return Ok(Some((0, 0)));
         }
 
         let (image_created, image_updated) = match embed_cached_images(
@@ -1523,7 +2552,7 @@ async fn process_page(
             }
         };
 
-        return Ok((image_created, image_updated));
+        return Ok(Some((image_created, image_updated)));
     }
 
     // Process text chunks
@@ -1550,43 +2579,492 @@ async fn process_page(
         {
             Ok(counts) => counts,
             Err(e) => {
-                warn!(uri = %page.url, "Failed to embed images: {}", e);
-                (0, 0)
+                warn!(uri = %page.url, "Failed to embed images: {}", e);
+                (0, 0)
+            }
+        }
+    };
+
+    Ok(Some((created + image_created, updated + image_updated)))
+}
+
+/// Source URI of an OpenAPI spec: URLs as given, paths made absolute
+pub fn openapi_spec_uri(location: &str) -> String {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        location.to_string()
+    } else {
+        let path = Path::new(location);
+        path.canonicalize()
+            .unwrap_or_else(|_| path.to_path_buf())
+            .display()
+            .to_string()
+    }
+}
+
+/// Read an OpenAPI spec from a URL or a local file
+async fn load_openapi_spec(config: &Config, uri: &str) -> Result<String> {
+    if !(uri.starts_with("http://") || uri.starts_with("https://")) {
+        return Ok(std::fs::read_to_string(uri)?);
+    }
+    let client = reqwest::Client::builder()
+        .user_agent(&config.crawl.user_agent)
+        .timeout(Duration::from_secs(config.crawl.timeout_secs))
+        .build()
+        .map_err(|e| Error::Crawl(format!("Failed to create HTTP client: {}", e)))?;
+    let response = client.get(uri).send().await?;
+    if !response.status().is_success() {
+        return Err(Error::Crawl(format!("HTTP {}: {}", response.status(), uri)));
+    }
+    Ok(response.text().await?)
+}
+
+/// Ingest an OpenAPI / Swagger spec as one document per operation
+///
+/// Documents are keyed by the spec URI plus a JSON pointer to the operation
+/// (`<spec>#/paths/~1pets/get`), so re-ingesting only re-embeds operations
+/// whose synthesized text changed.
+#[allow(clippy::too_many_arguments)]
+pub async fn cmd_ingest_openapi(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &MetaDb,
+    store: &QdrantStore,
+    uri: &str,
+    name: Option<String>,
+    operation: RunOperation,
+    interactive: bool,
+) -> Result<IngestStats> {
+    info!("Ingesting OpenAPI spec: {}", uri);
+
+    let mut stats = IngestStats::default();
+    let operations = parse_openapi(&load_openapi_spec(config, uri).await?)?;
+    if operations.is_empty() {
+        warn!("No operations found in {}", uri);
+        return Ok(stats);
+    }
+
+    store.ensure_collection().await?;
+
+    // Resolve source interactively on conflicts
+    let source = resolve_source(db, SourceType::Openapi, uri, name.clone(), interactive).await?;
+
+    // Start ingestion run
+    let run = db.start_ingestion_run(&source.id, operation).await?;
+    let _heartbeat = db.start_run_heartbeat(&run.id);
+
+    check_embedder_dimension(embedding, embedder, store)?;
+
+    let mut current_uris = Vec::new();
+    let progress = start_progress_bar(operations.len(), "Processing operations");
+    for api_operation in &operations {
+        let doc_uri = api_operation.uri(uri);
+        current_uris.push(doc_uri.clone());
+        match process_api_operation(
+            config,
+            embedding,
+            db,
+            store,
+            embedder,
+            &source,
+            &doc_uri,
+            api_operation,
+        )
+        .await
+        {
+            Ok((created, updated)) => {
+                stats.docs_processed += 1;
+                stats.chunks_created += created;
+                stats.chunks_updated += updated;
+            }
+            Err(e) => {
+                let error_msg = format!("{} {}: {}", api_operation.method, api_operation.path, e);
+                warn!("{}", error_msg);
+                stats.errors.push(error_msg);
+                stats.docs_skipped += 1;
+            }
+        }
+        set_progress_chunks(
+            &progress,
+            "Processing operations",
+            (stats.chunks_created + stats.chunks_updated) as usize,
+        );
+        advance_progress(&progress);
+    }
+    finish_progress(progress, "Operations processed");
+
+    // Operations removed from the spec become stale documents
+    remove_stale_documents(db, store, &source.id, &current_uris).await?;
+
+    complete_run(db, &run.id, &stats).await?;
+
+    info!(
+        "OpenAPI ingestion complete: {} operations, {} chunks created, {} chunks updated",
+        stats.docs_processed, stats.chunks_created, stats.chunks_updated
+    );
+
+    Ok(stats)
+}
+
+/// Chunk and embed one API operation, skipping it when its text is unchanged
+#[allow(clippy::too_many_arguments)]
+async fn process_api_operation(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    source: &Source,
+    doc_uri: &str,
+    api_operation: &ApiOperation,
+) -> Result<(i32, i32)> {
+    let content_hash = compute_content_hash(api_operation.text.as_bytes());
+    let endpoint = api_operation.endpoint();
+    if let Some(existing) = db.get_document_by_uri(&source.id, doc_uri).await? {
+        // Operations stored before endpoints were recorded are rewritten once
+        if existing.content_hash == content_hash
+            && existing.api_endpoint().as_ref() == Some(&endpoint)
+        {
+            debug!("Operation unchanged: {}", doc_uri);
+            return Ok((0, 0));
+        }
+    }
+
+    let parsed = parse_content(&api_operation.text, ContentType::Markdown, None)?;
+    let mut doc = Document::new(source.id.clone(), doc_uri.to_string(), content_hash.clone());
+    doc.title = Some(api_operation.title());
+    doc.content_type = Some("openapi".to_string());
+    doc.api_endpoint_json = Some(serde_json::to_string(&endpoint)?);
+    let doc = db.upsert_document(&doc).await?;
+
+    let chunks = chunk_document(&parsed, &config.chunk)?;
+    process_chunks(
+        config, embedding, db, store, embedder, source, &doc, doc_uri, chunks,
+    )
+    .await
+}
+
+/// Preview a directory ingestion without writing anything.
+///
+/// Files go through the same change detection, parsing and chunking as
+/// `cmd_ingest_dir`, and the returned stats count the chunks that would be
+/// created, updated and deleted; unchanged and binary files count as skipped.
+/// Nothing is embedded and neither SQLite nor Qdrant is modified.
+pub async fn cmd_preview_ingest_dir(
+    config: &Config,
+    db: &MetaDb,
+    path: &Path,
+    options: DirIngestOptions,
+) -> Result<IngestStats> {
+    let canonical_path = path
+        .canonicalize()
+        .map_err(|e| Error::InvalidPath(format!("{}: {}", path.display(), e)))?;
+
+    let uri = canonical_path.display().to_string();
+    info!("Previewing directory ingestion: {}", uri);
+
+    if let Some(filter) = &options.filter {
+        filter.exclude_set()?;
+    }
+
+    let mut stats = IngestStats::default();
+
+    let overlaps = check_dir_overlap(db, &canonical_path).await?;
+    stats.overlap_warnings = format_overlap_warnings(&overlaps, &uri);
+
+    let source = db.get_source_by_uri(&uri).await?;
+    let filter = match options.filter {
+        Some(filter) => filter,
+        None => source
+            .as_ref()
+            .map(DirFilter::from_source)
+            .unwrap_or_default(),
+    };
+
+    let files = collect_dir_files(&canonical_path, &filter, config.ingest.max_file_bytes)?;
+    info!("Found {} files to process", files.len());
+
+    let current_uris: Vec<String> = files.iter().map(|p| p.display().to_string()).collect();
+    let file_progress = start_progress_bar(files.len(), "Previewing files");
+
+    for path in &files {
+        match preview_file(config, db, source.as_ref(), path, &mut stats).await {
+            Ok(true) => stats.docs_processed += 1,
+            Ok(false) => stats.docs_skipped += 1,
+            Err(e) => record_preview_error(&mut stats, &path.display().to_string(), e),
+        }
+        advance_progress(&file_progress);
+    }
+
+    finish_progress(file_progress, "Files previewed");
+
+    preview_stale_documents(db, source.as_ref(), &current_uris, &mut stats).await?;
+
+    Ok(stats)
+}
+
+/// Preview a URL ingestion without writing anything.
+///
+/// The site is crawled as `cmd_ingest_url` would crawl it; see
+/// [`cmd_preview_ingest_dir`] for how the stats are projected.
+pub async fn cmd_preview_ingest_url(
+    config: &Config,
+    db: &MetaDb,
+    url: &str,
+    overrides: CrawlOverrides,
+) -> Result<IngestStats> {
+    info!("Previewing URL ingestion: {}", url);
+
+    let mut stats = IngestStats::default();
+
+    let overlaps = check_url_overlap(db, url).await?;
+    stats.overlap_warnings = format_overlap_warnings(&overlaps, url);
+
+    let source = db.get_source_by_uri(url).await?;
+    let overrides = match &source {
+        Some(source) => overrides.or(CrawlOverrides::from_source(source)),
+        None => overrides,
+    };
+    if overrides.ignore_robots {
+        warn_robots_ignored(url);
+    }
+    let crawler = Crawler::new(overrides.apply(&config.crawl))?;
+
+    let crawl_progress = start_spinner("pages crawled");
+    let pages = crawler
+        .crawl(url, None, |_page| {
+            advance_progress(&crawl_progress);
+            true
+        })
+        .await?;
+    finish_progress(crawl_progress, "pages crawled");
+    record_crawl_report(&crawler, &mut stats).await;
+
+    let mut current_uris: Vec<String> = Vec::new();
+    for page in &pages {
+        preview_page_entry(
+            config,
+            db,
+            source.as_ref(),
+            page,
+            &mut current_uris,
+            &mut stats,
+        )
+        .await;
+    }
+
+    preview_stale_documents(db, source.as_ref(), &current_uris, &mut stats).await?;
+
+    Ok(stats)
+}
+
+/// Preview a sitemap ingestion without writing anything.
+///
+/// Sitemap URLs are fetched as `cmd_ingest_sitemap` would fetch them; see
+/// [`cmd_preview_ingest_dir`] for how the stats are projected.
+pub async fn cmd_preview_ingest_sitemap(
+    config: &Config,
+    db: &MetaDb,
+    sitemap_url: &str,
+    max_pages: Option<u32>,
+    ignore_robots: bool,
+) -> Result<IngestStats> {
+    use crate::crawl::SitemapParser;
+
+    info!("Previewing sitemap ingestion: {}", sitemap_url);
+
+    let mut stats = IngestStats::default();
+
+    let overrides = CrawlOverrides {
+        ignore_robots,
+        ..Default::default()
+    };
+    let crawler = Crawler::new(overrides.apply(&config.crawl))?;
+    let entries = SitemapParser::new(&crawler).parse(sitemap_url).await?;
+
+    if entries.is_empty() {
+        warn!("No URLs found in sitemap: {}", sitemap_url);
+        return Ok(stats);
+    }
+
+    let max = max_pages.unwrap_or(config.crawl.max_pages);
+    let entries: Vec<_> = entries.into_iter().take(max as usize).collect();
+
+    if let Some(first_entry) = entries.first() {
+        let overlaps = check_url_overlap(db, &first_entry.loc).await?;
+        stats.overlap_warnings = format_overlap_warnings(&overlaps, sitemap_url);
+    }
+
+    let source = db.get_source_by_uri(sitemap_url).await?;
+    if ignore_robots {
+        warn_robots_ignored(sitemap_url);
+    }
+
+    let mut current_uris: Vec<String> = Vec::new();
+    let url_progress = start_progress_bar(entries.len(), "Previewing URLs");
+
+    for entry in entries {
+        match crawler.fetch(&entry.loc).await {
+            Ok(page) => {
+                preview_page_entry(
+                    config,
+                    db,
+                    source.as_ref(),
+                    &page,
+                    &mut current_uris,
+                    &mut stats,
+                )
+                .await;
+            }
+            Err(e) => {
+                current_uris.push(entry.loc.clone());
+                record_preview_error(&mut stats, &entry.loc, e);
             }
         }
+        advance_progress(&url_progress);
+    }
+
+    finish_progress(url_progress, "URLs previewed");
+
+    preview_stale_documents(db, source.as_ref(), &current_uris, &mut stats).await?;
+
+    Ok(stats)
+}
+
+/// Print crawl outcome tallies, if the ingestion crawled
+pub fn print_crawl_report(stats: &IngestStats) {
+    let Some(report) = &stats.crawl else {
+        return;
     };
 
-    Ok((created + image_created, updated + image_updated))
+    say!(
+        "  Pages fetched: {} ({}, {} rendered)",
+        report.pages_fetched,
+        format_size(report.bytes_fetched),
+        report.pages_rendered
+    );
+    say!(
+        "  Pages skipped: {} by robots.txt, {} redirected out of scope, {} already visited",
+        report.pages_skipped_robots,
+        report.pages_skipped_redirect,
+        report.pages_deduplicated
+    );
+    say!("  Pages failed: {}", report.pages_failed);
 }
 
-fn start_progress_bar(len: usize, message: &str) -> Option<ProgressBar> {
-    if len == 0 {
-        return None;
+/// Print the projected stats of a dry-run ingestion
+pub fn print_ingest_preview(stats: &IngestStats, unit: &str) {
+    for warning in &stats.overlap_warnings {
+        say!("{}", warning);
     }
 
-    let pb = add_progress_bar(len as u64);
-    pb.set_style(
-        ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} {msg}",
-        )
-        .unwrap()
-        .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
-    );
-    pb.set_message(message.to_string());
-    pb.enable_steady_tick(Duration::from_millis(100));
-    Some(pb)
+    say!("\n🔍 Ingestion (Dry Run)\n");
+    say!("{} to process: {}", unit, stats.docs_processed);
+    if stats.docs_skipped > 0 {
+        say!("{} to skip: {}", unit, stats.docs_skipped);
+    }
+    say!("Chunks to create: {}", stats.chunks_created);
+    say!("Chunks to update: {}", stats.chunks_updated);
+    say!("Chunks to delete: {}", stats.chunks_deleted);
+    print_crawl_report(stats);
+    say!("\nNothing was embedded or written.");
+}
+
+/// Count the chunks a file would add, update or remove, using the same
+/// [`plan_file`] decision as `prepare_file`.
+///
+/// Returns `false` for binary or unchanged files, which would be left alone.
+async fn preview_file(
+    config: &Config,
+    db: &MetaDb,
+    source: Option<&Source>,
+    path: &Path,
+    stats: &mut IngestStats,
+) -> Result<bool> {
+    let Some(change) = plan_file(config, db, source.map(|s| s.id.as_str()), path).await? else {
+        return Ok(false);
+    };
+    preview_chunks(db, change.existing_doc.as_ref(), change.chunks, stats).await?;
+    Ok(true)
 }
 
-fn advance_progress(pb: &Option<ProgressBar>) {
-    if let Some(pb) = pb {
-        pb.inc(1);
+/// Preview a crawled page and record it the way the ingest loops do
+async fn preview_page_entry(
+    config: &Config,
+    db: &MetaDb,
+    source: Option<&Source>,
+    page: &CrawledPage,
+    current_uris: &mut Vec<String>,
+    stats: &mut IngestStats,
+) {
+    match plan_page(config, db, source.map(|s| s.id.as_str()), page).await {
+        Ok(PagePlan::Changed {
+            existing_doc,
+            chunks,
+            ..
+        }) => {
+            current_uris.push(page.url.clone());
+            match preview_chunks(db, existing_doc.as_ref(), chunks, stats).await {
+                Ok(()) => stats.docs_processed += 1,
+                Err(e) => record_preview_error(stats, &page.url, e),
+            }
+        }
+        // Kept but not re-embedded, so nothing to process
+        Ok(PagePlan::Unchanged | PagePlan::Alias(_)) => {
+            current_uris.push(page.url.clone());
+            stats.docs_skipped += 1;
+        }
+        Ok(PagePlan::TooShort) => stats.docs_skipped += 1,
+        Err(e) => {
+            current_uris.push(page.url.clone());
+            record_preview_error(stats, &page.url, e);
+        }
     }
 }
 
-fn finish_progress(pb: Option<ProgressBar>, message: &str) {
-    if let Some(pb) = pb {
-        pb.finish_with_message(message.to_string());
+fn record_preview_error(stats: &mut IngestStats, uri: &str, e: Error) {
+    let error_msg = format!("{}: {}", uri, e);
+    warn!("{}", error_msg);
+    stats.errors.push(error_msg);
+    stats.docs_skipped += 1;
+}
+
+/// Count the chunks that would be created, updated and deleted, using the
+/// same [`plan_chunks`] decision as `embed_new_chunks`. Image embedding is
+/// not projected.
+async fn preview_chunks(
+    db: &MetaDb,
+    existing_doc: Option<&Document>,
+    chunks: Vec<TextChunk>,
+    stats: &mut IngestStats,
+) -> Result<()> {
+    let plan = plan_chunks(db, existing_doc.map(|d| d.id.as_str()), chunks).await?;
+    let (created, updated) = plan.counts();
+    stats.chunks_created += created;
+    stats.chunks_updated += updated;
+    stats.chunks_deleted += plan.trimmed as i32;
+    Ok(())
+}
+
+/// Count the chunks of documents that would be removed as stale
+async fn preview_stale_documents(
+    db: &MetaDb,
+    source: Option<&Source>,
+    current_uris: &[String],
+    stats: &mut IngestStats,
+) -> Result<()> {
+    let Some(source) = source else {
+        return Ok(());
+    };
+
+    let current: HashSet<&String> = current_uris.iter().collect();
+    for doc in db.list_documents(&source.id).await? {
+        if !current.contains(&doc.uri) {
+            stats.chunks_deleted += db.get_chunks(&doc.id).await?.len() as i32;
+        }
     }
+
+    Ok(())
 }
 
 async fn resolve_source(
@@ -1604,7 +3082,11 @@ async fn resolve_source(
         return Ok(existing);
     }
 
-    let source = Source::new(source_type, uri.to_string(), desired_name);
+    let name = match desired_name {
+        Some(name) => Some(unique_source_name(db, &name, uri).await?),
+        None => None,
+    };
+    let source = Source::new(source_type, uri.to_string(), name);
     db.insert_source(&source).await?;
     Ok(source)
 }
@@ -1654,20 +3136,22 @@ async fn resolve_source_interactive(
                 "Another source already uses the name '{}': {} (URI: {})",
                 name, named.id, named.uri
             );
+            let suffixed = unique_source_name(db, name, uri).await?;
             println!("Choose an action:");
-            println!("  [1] Keep duplicate name");
+            println!("  [1] Use '{}'", suffixed);
             println!("  [2] Enter a new name for this source");
             println!("  [3] Rename the existing source");
             println!("  [4] Abort");
             let choice = prompt_choice(1, 4);
             match choice {
-                1 => {}
+                1 => final_name = Some(suffixed),
                 2 => {
                     let new_name = prompt_string("Enter new name", None);
-                    final_name = Some(new_name);
+                    final_name = Some(unique_source_name(db, &new_name, uri).await?);
                 }
                 3 => {
                     let new_name = prompt_string("Enter new name for existing source", None);
+                    let new_name = unique_source_name(db, &new_name, &named.uri).await?;
                     db.update_source_name(&named.id, Some(new_name)).await?;
                 }
                 _ => return Err(Error::Config("Ingestion aborted".to_string())),
@@ -1739,10 +3223,8 @@ fn prompt_string(prompt: &str, default: Option<&str>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{EmbeddingDimensionSource, ResolvedEmbeddingConfig};
-    use crate::embedding_backend::{EmbeddingBackendConfig, EmbeddingBackendKind};
-    use crate::models::MultimodalStrategy;
     use crate::parse::{ContentType, ExtractedMedia, Heading, ParsedDocument};
+    use crate::test_support::{test_embedding_config, FakeQdrant, NoopEmbedder, RecordingEmbedder};
 
     fn multimodal_config() -> Config {
         let mut config = Config::default();
@@ -1752,35 +3234,6 @@ mod tests {
         config
     }
 
-    fn test_embedding_config(supports_image: bool, multi_vector: bool) -> ResolvedEmbeddingConfig {
-        ResolvedEmbeddingConfig {
-            model_id: "test-model".to_string(),
-            family: "test".to_string(),
-            modalities: if supports_image {
-                vec!["text".to_string(), "image".to_string()]
-            } else {
-                vec!["text".to_string()]
-            },
-            dimension: 384,
-            dimension_source: EmbeddingDimensionSource::Config,
-            backend: EmbeddingBackendConfig {
-                kind: EmbeddingBackendKind::Http,
-                url: "http://localhost:7997".to_string(),
-            },
-            strategy: if multi_vector {
-                MultimodalStrategy::LateInteraction
-            } else {
-                MultimodalStrategy::DualEncoder
-            },
-            supports_text: true,
-            supports_image,
-            supports_joint_inputs: false,
-            supports_multi_vector: multi_vector,
-            supports_mrl: false,
-            max_batch: 32,
-        }
-    }
-
     #[test]
     fn test_select_image_candidates_dedupes_urls() {
         let config = multimodal_config();
@@ -1872,4 +3325,1234 @@ mod tests {
         assert!(is_perceptual_duplicate(near, &seen));
         assert!(!is_perceptual_duplicate(far, &seen));
     }
+
+    fn write_tree(root: &Path, files: &[&str]) {
+        for file in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "content").unwrap();
+        }
+    }
+
+    fn relative_names(root: &Path, files: &[PathBuf]) -> Vec<String> {
+        let mut names: Vec<String> = files
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_collect_dir_files_filters_extensions() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_tree(tmp.path(), &["a.md", "b.TXT", "c.rs", "nested/d.md"]);
+
+        let filter =
+            DirFilter::from_options(Some("md, .txt".to_string()), None, false, None).unwrap();
+        let files = collect_dir_files(tmp.path(), &filter, u64::MAX).unwrap();
+
+        assert_eq!(
+            relative_names(tmp.path(), &files),
+            vec!["a.md", "b.TXT", "nested/d.md"]
+        );
+    }
+
+    #[test]
+    fn test_collect_dir_files_applies_excludes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_tree(
+            tmp.path(),
+            &["a.md", "node_modules/pkg/readme.md", "docs/node_modules/x.md", "docs/b.md", "c.tmp"],
+        );
+
+        let filter = DirFilter::from_options(
+            None,
+            Some(vec!["**/node_modules/**".to_string(), "*.tmp".to_string()]),
+            false,
+            None,
+        )
+        .unwrap();
+        let files = collect_dir_files(tmp.path(), &filter, u64::MAX).unwrap();
+
+        assert_eq!(relative_names(tmp.path(), &files), vec!["a.md", "docs/b.md"]);
+    }
+
+    #[test]
+    fn test_collect_dir_files_honors_ignore_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_tree(
+            tmp.path(),
+            &[
+                "README.md",
+                "src/lib.rs",
+                "target/debug/build.log",
+                "web/node_modules/pkg/index.js",
+                "notes/draft.md",
+                "docs/guide.md",
+                "docs/old.md",
+            ],
+        );
+        write_tree(tmp.path(), &[".git/HEAD"]);
+        std::fs::write(tmp.path().join(".gitignore"), "target/\nnode_modules/\n").unwrap();
+        std::fs::write(tmp.path().join(".ignore"), "notes/\n").unwrap();
+        std::fs::write(tmp.path().join("src/.gitignore"), "*.tmp\n").unwrap();
+        let all: Vec<&str> = vec![
+            "README.md",
+            "docs/guide.md",
+            "docs/old.md",
+            "notes/draft.md",
+            "src/lib.rs",
+            "target/debug/build.log",
+            "web/node_modules/pkg/index.js",
+        ];
+
+        // Ignore files combine with explicit excludes
+        let filter =
+            DirFilter::from_options(None, Some(vec!["docs/old.md".to_string()]), false, None)
+                .unwrap();
+        let files = collect_dir_files(tmp.path(), &filter, u64::MAX).unwrap();
+        assert_eq!(
+            relative_names(tmp.path(), &files),
+            vec!["README.md", "docs/guide.md", "src/lib.rs"]
+        );
+
+        let filter = DirFilter::from_options(None, None, false, Some(false)).unwrap();
+        assert!(filter.include_ignored);
+        let files = collect_dir_files(tmp.path(), &filter, u64::MAX).unwrap();
+        assert_eq!(relative_names(tmp.path(), &files), all);
+
+        // Outside a git checkout .gitignore still applies
+        std::fs::remove_dir_all(tmp.path().join(".git")).unwrap();
+        let files = collect_dir_files(tmp.path(), &DirFilter::default(), u64::MAX).unwrap();
+        assert!(
+            !relative_names(tmp.path(), &files)
+                .iter()
+                .any(|name| name.starts_with("target/") || name.contains("node_modules")),
+            "{files:?}"
+        );
+    }
+
+    #[test]
+    fn test_collect_dir_files_skips_binary_and_oversized_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        write_tree(tmp.path(), &["a.md", "README"]);
+        let mut executable = b"\x7fELF\x02\x01\x01".to_vec();
+        executable.extend([0u8; 64]);
+        std::fs::write(tmp.path().join("tool"), executable).unwrap();
+        std::fs::write(tmp.path().join("big.txt"), "x".repeat(2048)).unwrap();
+
+        let files = collect_dir_files(tmp.path(), &DirFilter::default(), 1024).unwrap();
+        assert_eq!(relative_names(tmp.path(), &files), vec!["README", "a.md"]);
+
+        let files = collect_dir_files(tmp.path(), &DirFilter::default(), 2048).unwrap();
+        assert_eq!(
+            relative_names(tmp.path(), &files),
+            vec!["README", "a.md", "big.txt"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_dir_files_follows_symlinks_without_looping() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("docs");
+        write_tree(&root, &["a.md", "sub/b.md"]);
+        write_tree(tmp.path(), &["shared/c.md"]);
+        // A cycle back to the root, a second path to `sub`, and a link out of the tree
+        symlink(&root, root.join("sub/loop")).unwrap();
+        symlink(root.join("sub"), root.join("alias")).unwrap();
+        symlink(tmp.path().join("shared"), root.join("shared")).unwrap();
+
+        let files = collect_dir_files(&root, &DirFilter::default(), u64::MAX).unwrap();
+        assert_eq!(relative_names(&root, &files), vec!["a.md", "sub/b.md"]);
+
+        // `alias` sorts before `sub`, so b.md is always collected through the link
+        let filter = DirFilter::from_options(None, None, true, None).unwrap();
+        let files = collect_dir_files(&root, &filter, u64::MAX).unwrap();
+        assert_eq!(
+            relative_names(&root, &files),
+            vec!["a.md", "alias/b.md", "shared/c.md"]
+        );
+    }
+
+    #[test]
+    fn test_dir_filter_round_trips_through_source_config() {
+        assert!(DirFilter::from_options(None, None, false, None).is_none());
+        assert!(
+            DirFilter::from_options(Some(" , ".to_string()), Some(vec![]), false, None).is_none()
+        );
+
+        let filter = DirFilter::from_options(
+            Some("md".to_string()),
+            Some(vec!["drafts/**".to_string()]),
+            true,
+            Some(false),
+        )
+        .unwrap();
+        let mut source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        assert_eq!(DirFilter::from_source(&source), DirFilter::default());
+
+        source.config_json = Some(serde_json::to_string(&filter).unwrap());
+        assert_eq!(DirFilter::from_source(&source), filter);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_source_auto_suffixes_taken_names() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        let first = resolve_source(&db, SourceType::Dir, "/a", Some("docs".to_string()), false)
+            .await
+            .unwrap();
+        let second = resolve_source(&db, SourceType::Dir, "/b", Some("docs".to_string()), false)
+            .await
+            .unwrap();
+        assert_eq!(first.name.as_deref(), Some("docs"));
+        assert_eq!(second.name.as_deref(), Some("docs-2"));
+
+        // Re-ingesting an existing URI keeps its source and name
+        let again = resolve_source(&db, SourceType::Dir, "/b", Some("docs".to_string()), false)
+            .await
+            .unwrap();
+        assert_eq!(again.id, second.id);
+        assert_eq!(again.name.as_deref(), Some("docs-2"));
+    }
+
+    #[tokio::test]
+    async fn test_no_robots_fetches_disallowed_page_for_one_crawl() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/private.html"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("<p>Private notes</p>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        let crawl = CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            timeout_secs: 5,
+            ..CrawlConfig::default()
+        };
+        let url = format!("{}/private.html", server.uri());
+
+        let respecting = Crawler::new(CrawlOverrides::default().apply(&crawl)).unwrap();
+        let err = respecting.fetch(&url).await.unwrap_err();
+        assert!(matches!(err, Error::RobotsDisallowed(_)), "got {:?}", err);
+
+        let overrides = CrawlOverrides {
+            ignore_robots: true,
+            ..Default::default()
+        };
+        let ignoring = Crawler::new(overrides.apply(&crawl)).unwrap();
+        let page = ignoring.fetch(&url).await.unwrap();
+        assert!(page.content.contains("Private notes"));
+        assert!(crawl.respect_robots_txt);
+
+        // The override applies to this crawl only: it is recorded on the run,
+        // never stored on the source for later updates
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Url, server.uri(), None);
+        db.insert_source(&source).await.unwrap();
+        let merged = persist_crawl_overrides(&db, &source, overrides)
+            .await
+            .unwrap();
+        assert!(merged.ignore_robots);
+        let stored = db.get_source(&source.id).await.unwrap().unwrap();
+        assert!(!CrawlOverrides::from_source(&stored).ignore_robots);
+
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        assert!(!run.robots_ignored);
+        db.mark_run_robots_ignored(&run.id).await.unwrap();
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert!(run.robots_ignored);
+    }
+
+    #[test]
+    fn test_crawl_overrides_store_js_mode() {
+        let mut source = Source::new(SourceType::Url, "https://example.com".to_string(), None);
+        assert_eq!(CrawlOverrides::from_source(&source).js_mode, None);
+
+        let stored = CrawlOverrides {
+            js_mode: Some(JsMode::Always),
+            ..Default::default()
+        };
+        source.config_json = Some(serde_json::to_string(&stored).unwrap());
+        assert_eq!(
+            source.config_json.as_deref(),
+            Some(r#"{"js_mode":"always"}"#)
+        );
+        assert_eq!(CrawlOverrides::from_source(&source), stored);
+
+        // An explicit mode wins over auto_js_rendering = false
+        let crawl = CrawlConfig {
+            auto_js_rendering: false,
+            ..CrawlConfig::default()
+        };
+        let overrides = CrawlOverrides {
+            js_mode: stored.js_mode,
+            ..Default::default()
+        };
+        assert_eq!(overrides.apply(&crawl).effective_js_mode(), JsMode::Always);
+        assert_eq!(
+            CrawlOverrides::default().apply(&crawl).effective_js_mode(),
+            JsMode::Never
+        );
+    }
+
+    #[test]
+    fn test_dir_filter_rejects_invalid_glob() {
+        let filter =
+            DirFilter::from_options(None, Some(vec!["a[".to_string()]), false, None).unwrap();
+        assert!(matches!(filter.exclude_set(), Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_embedder_collection_mismatch_is_a_dimension_error() {
+        let store = QdrantStore::new("http://127.0.0.1:9", "test_collection", 4, None)
+            .await
+            .unwrap();
+        let err =
+            check_embedder_dimension(&test_embedding_config(false, false), &NoopEmbedder, &store)
+                .expect_err("3 dimensions should not fit a 4-dimension collection");
+        assert!(
+            matches!(
+                err,
+                Error::DimensionMismatch {
+                    expected: 4,
+                    actual: 3,
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+        assert_eq!(err.exit_code(), crate::error::exit_code::DIMENSION_MISMATCH);
+    }
+
+    fn screenshot_config(base_dir: &Path, include_page_screenshot: bool) -> Config {
+        let mut config = multimodal_config();
+        config.crawl.multimodal.include_page_screenshot = include_page_screenshot;
+        config.paths.base_dir = base_dir.to_path_buf();
+        config
+    }
+
+    /// Cache `page`'s screenshot and embed it, returning the asset and the embedded paths
+    async fn embed_page_screenshot(
+        config: &Config,
+        page: &CrawledPage,
+    ) -> Option<(CachedAsset, Vec<String>)> {
+        let embedding = test_embedding_config(true, false);
+        let asset = cache_page_screenshot(config, &embedding, page).await?;
+        let parsed = parse_content(&page.content, page.content_type, Some(&page.url)).unwrap();
+        let embedder = RecordingEmbedder::default();
+        let vectors = embed_asset_vectors(
+            config,
+            &embedding,
+            &embedder,
+            &parsed,
+            std::slice::from_ref(&asset),
+        )
+        .await
+        .unwrap();
+        assert_eq!(vectors.len(), 1);
+        let embedded = embedder.images();
+        Some((asset, embedded))
+    }
+
+    #[tokio::test]
+    async fn test_page_screenshot_is_cached_as_image_asset() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let page = CrawledPage {
+            url: "https://example.com/app".to_string(),
+            redirected_from: None,
+            content: "<html><body><h1>App</h1></body></html>".to_string(),
+            content_hash: compute_content_hash(b"<html><body><h1>App</h1></body></html>"),
+            content_type: ContentType::Html,
+            title: Some("App".to_string()),
+            links: Vec::new(),
+            depth: 0,
+            screenshot: Some(vec![0xFF; 64]),
+        };
+
+        let disabled = screenshot_config(tmp.path(), false);
+        assert!(embed_page_screenshot(&disabled, &page).await.is_none());
+
+        let config = screenshot_config(tmp.path(), true);
+        let (asset, embedded) = embed_page_screenshot(&config, &page).await.unwrap();
+        assert!(asset.path.exists());
+        assert_eq!(asset.media.url, page.url);
+        assert_eq!(asset.media.alt.as_deref(), Some("Screenshot of App"));
+        assert_eq!(embedded, vec![asset.path.to_string_lossy().to_string()]);
+    }
+
+    #[cfg(feature = "js-rendering")]
+    #[tokio::test]
+    #[ignore = "needs a local Chrome or Chromium"]
+    async fn test_rendered_page_screenshot_is_embedded() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let html = "<html><head><title>Dashboard</title></head>\
+            <body><h1>Dashboard</h1><p>Rendered content.</p></body></html>";
+        Mock::given(method("GET"))
+            .and(path("/app.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(html, "text/html"))
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = screenshot_config(tmp.path(), true);
+        config.crawl.js_mode = JsMode::Always;
+        config.crawl.rate_limit_per_host = 1000.0;
+        config.crawl.respect_robots_txt = false;
+        let crawler = Crawler::new(config.crawl.clone()).unwrap();
+        let page = crawler
+            .fetch(&format!("{}/app.html", server.uri()))
+            .await
+            .unwrap();
+        crawler.close().await.unwrap();
+
+        let screenshot = page
+            .screenshot
+            .as_deref()
+            .expect("rendered page has a screenshot");
+        // JPEG start-of-image marker
+        assert!(screenshot.starts_with(&[0xFF, 0xD8]));
+        let (asset, embedded) = embed_page_screenshot(&config, &page).await.unwrap();
+        assert_eq!(std::fs::read(&asset.path).unwrap(), screenshot);
+        assert_eq!(embedded, vec![asset.path.to_string_lossy().to_string()]);
+    }
+
+    /// What one ingest of `files` left behind
+    struct IngestOutcome {
+        stats: IngestStats,
+        /// URI and chunk count of each stored document, by URI
+        documents: Vec<(String, usize)>,
+        /// Texts sent to the embedder, sorted
+        embedded: Vec<String>,
+        points: usize,
+    }
+
+    async fn ingest_with_concurrency(files: &[PathBuf], concurrency: usize) -> IngestOutcome {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let qdrant = FakeQdrant::start().await;
+        let store = qdrant.store().await;
+        let embedder = RecordingEmbedder::default();
+
+        let mut stats = IngestStats::default();
+        ingest_files(
+            &Config::default(),
+            &test_embedding_config(false, false),
+            &embedder,
+            &db,
+            &store,
+            &source,
+            files,
+            concurrency,
+            &None,
+            &mut stats,
+        )
+        .await;
+
+        let mut documents = Vec::new();
+        for doc in db.list_documents(&source.id).await.unwrap() {
+            let chunks = db.get_chunks(&doc.id).await.unwrap().len();
+            documents.push((doc.uri, chunks));
+        }
+        documents.sort();
+        let mut embedded = embedder.texts();
+        embedded.sort();
+        IngestOutcome {
+            stats,
+            documents,
+            embedded,
+            points: qdrant.point_ids().len(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_file_is_not_rechunked_or_embedded() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("guide.md");
+        let text = "# Guide\n\nInstall the package, then run the setup command.";
+        std::fs::write(&path, text).unwrap();
+
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, tmp.path().display().to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        // As left by an earlier ingest of the same content
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                path.display().to_string(),
+                compute_content_hash(text.as_bytes()),
+            ))
+            .await
+            .unwrap();
+        let chunk = Chunk::new(
+            doc.id.clone(),
+            0,
+            "chunk-hash".to_string(),
+            text.to_string(),
+            0,
+            text.len() as i32,
+            None,
+        );
+        db.upsert_chunk(&chunk).await.unwrap();
+        // Nothing listens here, so any Qdrant call would surface as an error
+        let store = QdrantStore::new("http://127.0.0.1:9", "test_collection", 3, None)
+            .await
+            .unwrap();
+
+        let embedder = RecordingEmbedder::default();
+        let mut stats = IngestStats::default();
+        ingest_files(
+            &Config::default(),
+            &test_embedding_config(false, false),
+            &embedder,
+            &db,
+            &store,
+            &source,
+            std::slice::from_ref(&path),
+            1,
+            &None,
+            &mut stats,
+        )
+        .await;
+
+        assert!(stats.errors.is_empty(), "{:?}", stats.errors);
+        assert_eq!(stats.docs_processed, 1);
+        assert_eq!((stats.chunks_created, stats.chunks_updated), (0, 0));
+        assert_eq!(embedder.texts().len(), 0);
+        let chunks = db.get_chunks(&doc.id).await.unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].qdrant_point_id, chunk.qdrant_point_id);
+    }
+
+    #[test]
+    fn test_notebook_outputs_setting_applies_to_files_and_pages() {
+        let notebook = r#"{
+          "cells": [{
+            "cell_type": "code",
+            "metadata": {},
+            "source": "print(40 + 2)",
+            "outputs": [{ "output_type": "stream", "name": "stdout", "text": "42\n" }]
+          }],
+          "metadata": {},
+          "nbformat": 4,
+          "nbformat_minor": 5
+        }"#;
+        let url = Some("https://example.com/demo.ipynb");
+        let mut config = Config::default();
+
+        let (parsed, _) = parse_file(notebook, ContentType::Notebook, &config.chunk).unwrap();
+        assert!(!parsed.text.contains("42"));
+        let parsed = parse_text(notebook, ContentType::Notebook, url, &config.chunk).unwrap();
+        assert!(!parsed.text.contains("42"));
+
+        config.chunk.notebook_outputs = true;
+        let (parsed, _) = parse_file(notebook, ContentType::Notebook, &config.chunk).unwrap();
+        assert!(parsed.text.contains("42"));
+        let parsed = parse_text(notebook, ContentType::Notebook, url, &config.chunk).unwrap();
+        assert!(parsed.text.contains("42"));
+    }
+
+    #[tokio::test]
+    async fn test_editing_one_paragraph_reembeds_only_that_chunk() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, tmp.path().display().to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "guide.md".to_string(),
+                "old-hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:9", "test_collection", 3, None)
+            .await
+            .unwrap();
+        let mut config = Config::default();
+        config.chunk.max_chars = 60;
+        config.chunk.min_chars = 10;
+        config.chunk.overlap_chars = 0;
+
+        let section = |n: usize, body: &str| format!("## Step {n}\n\n{body}\n\n");
+        let original: String = (1..=3)
+            .map(|n| section(n, &format!("Run the command for step number {n} now.")))
+            .collect();
+        let edited = original.replace("step number 2", "step number two");
+        let (_, before) = parse_file(&original, ContentType::Markdown, &config.chunk).unwrap();
+        let (_, after) = parse_file(&edited, ContentType::Markdown, &config.chunk).unwrap();
+        assert_eq!(before.len(), 3);
+        assert_eq!(after.len(), 3);
+
+        // As left by ingesting the original text
+        for (index, chunk) in before.iter().enumerate() {
+            db.upsert_chunk(&Chunk::new(
+                doc.id.clone(),
+                index as i32,
+                chunk.hash.clone(),
+                chunk.text.clone(),
+                chunk.char_start as i32,
+                chunk.char_end as i32,
+                Some(chunk.headings.clone()),
+            ))
+            .await
+            .unwrap();
+        }
+        let stored = db.get_chunks(&doc.id).await.unwrap();
+
+        let embedder = RecordingEmbedder::default();
+        let embedded = embed_new_chunks(
+            &config,
+            &test_embedding_config(false, false),
+            &db,
+            &store,
+            &embedder,
+            Some(&doc.id),
+            "guide.md",
+            after,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(embedder.texts().len(), 1);
+        let indexes: Vec<usize> = embedded.items.iter().map(|(i, _, _)| *i).collect();
+        assert_eq!(indexes, vec![1]);
+        assert_eq!(
+            embedded.replaced_points,
+            vec![stored[1].qdrant_point_id.clone()]
+        );
+        // The edit lengthened step 2, moving step 3 without changing its text
+        assert_eq!(embedded.shifted.len(), 1);
+        assert_eq!(embedded.shifted[0].chunk_index, 2);
+        assert_eq!(
+            embedded.shifted[0].qdrant_point_id,
+            stored[2].qdrant_point_id
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parallel_ingest_matches_serial() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        // Several sections per file, each too long to share a chunk
+        let mut files: Vec<PathBuf> = (0..24)
+            .map(|i| {
+                let path = tmp.path().join(format!("file-{i:02}.md"));
+                let sections: Vec<String> = (0..=i % 3)
+                    .map(|s| {
+                        let sentence = format!("File {i} section {s} covers one topic in depth. ");
+                        format!("## Section {s}\n\n{}", sentence.repeat(30))
+                    })
+                    .collect();
+                std::fs::write(&path, format!("# File {i}\n\n{}", sections.join("\n\n"))).unwrap();
+                path
+            })
+            .collect();
+        files.push(tmp.path().join("missing-a.md"));
+        files.push(tmp.path().join("missing-b.md"));
+
+        let serial = ingest_with_concurrency(&files, 1).await;
+        let parallel = ingest_with_concurrency(&files, 8).await;
+
+        assert_eq!(serial.stats.docs_processed, 24);
+        assert_eq!(serial.stats.docs_skipped, 2);
+        assert_eq!(serial.documents.len(), 24);
+        assert!(serial.documents.iter().any(|(_, chunks)| *chunks > 1));
+        let stored_chunks: usize = serial.documents.iter().map(|(_, chunks)| chunks).sum();
+        assert_eq!(serial.stats.chunks_created as usize, stored_chunks);
+        assert_eq!(serial.embedded.len(), stored_chunks);
+        assert_eq!(serial.points, stored_chunks);
+
+        assert_eq!(parallel.stats.docs_processed, serial.stats.docs_processed);
+        assert_eq!(parallel.stats.docs_skipped, serial.stats.docs_skipped);
+        assert_eq!(parallel.stats.chunks_created, serial.stats.chunks_created);
+        assert_eq!(parallel.stats.chunks_updated, serial.stats.chunks_updated);
+        assert_eq!(parallel.stats.errors, serial.stats.errors);
+        assert_eq!(parallel.documents, serial.documents);
+        assert_eq!(parallel.embedded, serial.embedded);
+        assert_eq!(parallel.points, serial.points);
+    }
+
+    #[tokio::test]
+    async fn test_url_ingest_run_records_fetch_failures() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let html = "<html><body><h1>Docs</h1>\
+            <a href=\"/docs/missing-a.html\">a</a>\
+            <a href=\"/docs/missing-b.html\">b</a></body></html>";
+        Mock::given(method("GET"))
+            .and(path("/docs/index.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(html, "text/html"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let mut crawl_config = Config::default().crawl;
+        crawl_config.auto_js_rendering = false;
+        crawl_config.rate_limit_per_host = 1000.0;
+        let crawler = Crawler::new(crawl_config).unwrap();
+        let seed = format!("{}/docs/index.html", server.uri());
+        let pages = crawler.crawl(&seed, None, |_| true).await.unwrap();
+        assert_eq!(pages.len(), 1);
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Url, seed.clone(), None);
+        db.insert_source(&source).await.unwrap();
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+
+        let mut stats = IngestStats {
+            docs_processed: 1,
+            ..Default::default()
+        };
+        record_crawl_report(&crawler, &mut stats).await;
+        complete_run(&db, &run.id, &stats).await.unwrap();
+
+        assert_eq!(stats.docs_skipped, 2);
+        let report = stats.crawl.as_ref().unwrap();
+        assert_eq!((report.pages_fetched, report.pages_failed), (1, 2));
+        // The seed page made it in, so the run is a partial success with its errors kept
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert_eq!(run.status, "completed");
+        let errors: Vec<String> = serde_json::from_str(&run.errors_json.unwrap()).unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.contains("/docs/missing-a.html")));
+        assert!(errors.iter().any(|e| e.contains("/docs/missing-b.html")));
+        assert!(crawler.take_failures().await.is_empty());
+
+        // A run where every page failed is a failure
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        let stats = IngestStats {
+            docs_skipped: 2,
+            errors: vec!["a: 404".to_string(), "b: 404".to_string()],
+            ..Default::default()
+        };
+        complete_run(&db, &run.id, &stats).await.unwrap();
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert_eq!(run.status, "failed");
+        assert!(run.errors_json.is_some());
+
+        // So is one where the only page that did not fail was skipped as too short
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        let stats = IngestStats {
+            docs_skipped: 3,
+            errors: vec!["a: 404".to_string(), "b: 404".to_string()],
+            ..Default::default()
+        };
+        complete_run(&db, &run.id, &stats).await.unwrap();
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert_eq!(run.status, "failed");
+    }
+
+    #[tokio::test]
+    async fn test_update_reuses_stored_crawl_overrides() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let index = (0..5)
+            .map(|i| format!("<a href=\"/docs/page-{i}.html\">page {i}</a>"))
+            .collect::<String>();
+        Mock::given(method("GET"))
+            .and(path("/docs/index.html"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(format!("<html><body>{index}</body></html>"), "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>A page.</p>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let seed = format!("{}/docs/index.html", server.uri());
+        let source = Source::new(SourceType::Url, seed.clone(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let mut config = Config::default();
+        config.crawl.auto_js_rendering = false;
+        config.crawl.rate_limit_per_host = 1000.0;
+        let crawl_with = |overrides: CrawlOverrides| {
+            let crawl = overrides.apply(&config.crawl);
+            let seed = seed.clone();
+            async move {
+                let crawler = Crawler::new(crawl).unwrap();
+                crawler.crawl(&seed, None, |_| true).await.unwrap().len()
+            }
+        };
+
+        // Ingest with --max-pages 2, as cmd_ingest_url does
+        let ingest = CrawlOverrides {
+            max_pages: Some(2),
+            ..Default::default()
+        };
+        let ingested = persist_crawl_overrides(&db, &source, ingest).await.unwrap();
+        assert_eq!(crawl_with(ingested).await, 2);
+
+        // Update passes no overrides and gets the stored cap back
+        let source = db.get_source(&source.id).await.unwrap().unwrap();
+        let updated = persist_crawl_overrides(&db, &source, CrawlOverrides::default())
+            .await
+            .unwrap();
+        assert_eq!(updated.max_pages, Some(2));
+        assert_eq!(crawl_with(updated).await, 2);
+        assert_eq!(crawl_with(CrawlOverrides::default()).await, 6);
+    }
+
+    #[tokio::test]
+    async fn test_boilerplate_page_links_followed_without_document() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let shell = "<html><body><nav><a href=\"/docs/guide.html\">Guide</a></nav>\
+            <p>404</p></body></html>";
+        let guide = "<html><body><h1>Guide</h1><p>The guide explains how indexing \
+            works, from crawling pages to embedding their chunks.</p></body></html>";
+        Mock::given(method("GET"))
+            .and(path("/docs/index.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(shell, "text/html"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/guide.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(guide, "text/html"))
+            .mount(&server)
+            .await;
+
+        let mut config = Config::default();
+        config.crawl.auto_js_rendering = false;
+        config.crawl.rate_limit_per_host = 1000.0;
+        config.crawl.min_document_chars = 40;
+        let crawler = Crawler::new(config.crawl.clone()).unwrap();
+        let seed = format!("{}/docs/index.html", server.uri());
+        let pages = crawler.crawl(&seed, None, |_| true).await.unwrap();
+
+        // The shell page's link was still followed
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().any(|p| p.url.ends_with("/docs/guide.html")));
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Url, seed.clone(), None);
+        db.insert_source(&source).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:6334", "test_collection", 3, None)
+            .await
+            .unwrap();
+
+        let shell_page = pages.iter().find(|p| p.url == seed).unwrap();
+        let outcome = process_page(
+            &config,
+            &test_embedding_config(false, false),
+            &db,
+            &store,
+            &NoopEmbedder,
+            &source,
+            shell_page,
+        )
+        .await
+        .unwrap();
+
+        assert!(outcome.is_none());
+        assert!(db.list_documents(&source.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recrawl_of_unchanged_pages_skips_embedding() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Pages without text chunk to nothing, so ingesting them never writes to Qdrant
+        let server = MockServer::start().await;
+        let index = "<html><head><title>Docs</title></head><body>\
+            <a href=\"/docs/guide.html\"></a></body></html>";
+        let guide = "<html><head><title>Guide</title></head><body></body></html>";
+        for (route, html) in [("/docs/index.html", index), ("/docs/guide.html", guide)] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(html, "text/html"))
+                .mount(&server)
+                .await;
+        }
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let seed = format!("{}/docs/index.html", server.uri());
+        let source = Source::new(SourceType::Url, seed.clone(), None);
+        db.insert_source(&source).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:9", "test_collection", 3, None)
+            .await
+            .unwrap();
+
+        let mut config = Config::default();
+        config.crawl.auto_js_rendering = false;
+        config.crawl.rate_limit_per_host = 1000.0;
+        config.crawl.min_document_chars = 0;
+        let embedding = test_embedding_config(false, false);
+        let embedder = RecordingEmbedder::default();
+
+        // The first crawl parses both pages and stores their hashes
+        let crawler = Crawler::new(config.crawl.clone()).unwrap();
+        let pages = crawler.crawl(&seed, None, |_| true).await.unwrap();
+        assert_eq!(pages.len(), 2);
+        for page in &pages {
+            let plan = plan_page(&config, &db, Some(&source.id), page)
+                .await
+                .unwrap();
+            assert!(matches!(plan, PagePlan::Changed { .. }), "{}", page.url);
+            process_page(&config, &embedding, &db, &store, &embedder, &source, page)
+                .await
+                .unwrap();
+        }
+        assert_eq!(db.list_documents(&source.id).await.unwrap().len(), 2);
+
+        // The re-crawl still follows the unchanged index page's links, but
+        // neither page is parsed or embedded again
+        let crawler = Crawler::new(config.crawl.clone()).unwrap();
+        let pages = crawler.crawl(&seed, None, |_| true).await.unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].title.as_deref(), Some("Docs"));
+        for page in &pages {
+            let plan = plan_page(&config, &db, Some(&source.id), page)
+                .await
+                .unwrap();
+            assert!(matches!(plan, PagePlan::Unchanged), "{}", page.url);
+            let outcome = process_page(&config, &embedding, &db, &store, &embedder, &source, page)
+                .await
+                .unwrap();
+            assert_eq!(outcome, Some((0, 0)), "{}", page.url);
+        }
+        assert!(embedder.texts().is_empty());
+    }
+
+    #[test]
+    fn test_url_list_parse_skips_comments_and_repeats() {
+        let list = UrlList::parse(
+            "# docs to index\n\nhttps://example.com/a\n  https://example.com/b  \nhttps://example.com/a\n",
+        )
+        .unwrap();
+        assert_eq!(
+            list.urls,
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
+
+        let err = UrlList::parse("https://example.com/a\nftp://example.com/b\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+        assert!(UrlList::parse("not a url").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_url_list_fetches_each_page_without_crawling() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for page in ["a", "b", "c"] {
+            // Empty pages chunk to nothing, so Qdrant is never written
+            let html = format!(
+                "<html><head><title>{page}</title></head><body>\
+                <a href=\"/docs/linked-{page}.html\"></a></body></html>"
+            );
+            Mock::given(method("GET"))
+                .and(path(format!("/docs/{page}.html")))
+                .respond_with(ResponseTemplate::new(200).set_body_raw(html, "text/html"))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let mut config = Config::default();
+        config.crawl.auto_js_rendering = false;
+        config.crawl.respect_robots_txt = false;
+        config.crawl.rate_limit_per_host = 1000.0;
+        config.crawl.min_document_chars = 0;
+        let crawler = Crawler::new(config.crawl.clone()).unwrap();
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Urls, "stdin".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:6334", "test_collection", 3, None)
+            .await
+            .unwrap();
+
+        let list = UrlList::parse(
+            &["a", "b", "c"]
+                .map(|page| format!("{}/docs/{page}.html", server.uri()))
+                .join("\n"),
+        )
+        .unwrap();
+        let mut stats = IngestStats::default();
+        let (current_uris, pages_fetched) = ingest_page_list(
+            &config,
+            &test_embedding_config(false, false),
+            &NoopEmbedder,
+            &db,
+            &store,
+            &crawler,
+            &source,
+            &list.urls,
+            None,
+            &mut stats,
+        )
+        .await
+        .unwrap();
+
+        assert!(stats.errors.is_empty(), "{:?}", stats.errors);
+        assert_eq!((pages_fetched, stats.docs_processed), (3, 3));
+        assert_eq!(current_uris, list.urls);
+        assert_eq!(db.list_documents(&source.id).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_identical_pages_are_aliased_not_reembedded() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(
+            SourceType::Url,
+            "https://example.com/docs".to_string(),
+            None,
+        );
+        db.insert_source(&source).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:6334", "test_collection", 3, None)
+            .await
+            .unwrap();
+
+        let html = "<html><body><h1>Install</h1><p>Run the installer.</p></body></html>";
+        let page = |url: &str| CrawledPage {
+            url: url.to_string(),
+            redirected_from: None,
+            content: html.to_string(),
+            content_hash: compute_content_hash(html.as_bytes()),
+            content_type: ContentType::Html,
+            title: None,
+            links: Vec::new(),
+            depth: 0,
+            screenshot: None,
+        };
+
+        // The first URL was ingested earlier and owns the only chunk set
+        let canonical = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "https://example.com/docs/install".to_string(),
+                compute_content_hash(html.as_bytes()),
+            ))
+            .await
+            .unwrap();
+        let chunk = Chunk::new(
+            canonical.id.clone(),
+            0,
+            "hash".to_string(),
+            "Install\n\nRun the installer.".to_string(),
+            0,
+            27,
+            None,
+        );
+        db.upsert_chunk(&chunk).await.unwrap();
+
+        let embedder = RecordingEmbedder::default();
+        let mirror = page("https://example.com/docs/latest/install");
+        let outcome = process_page(
+            &Config::default(),
+            &test_embedding_config(false, false),
+            &db,
+            &store,
+            &embedder,
+            &source,
+            &mirror,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome, Some((0, 0)));
+        assert_eq!(embedder.texts().len(), 0);
+        let docs = db.list_documents(&source.id).await.unwrap();
+        assert_eq!(docs.len(), 1);
+        assert_eq!(db.get_chunks(&canonical.id).await.unwrap().len(), 1);
+        assert_eq!(
+            db.get_document_aliases(&canonical.id).await.unwrap(),
+            vec![mirror.url.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redirected_page_keeps_requested_url_as_alias() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(
+            SourceType::Url,
+            "https://example.com/docs".to_string(),
+            None,
+        );
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "https://example.com/docs/new".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let moved = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "https://example.com/docs/moved".to_string(),
+                "old-hash".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        // Until stale cleanup removes it, the old document keeps its URL; a
+        // redirect to a URL with no document records nothing
+        let redirects = vec![
+            ("https://example.com/docs/old".to_string(), doc.uri.clone()),
+            (moved.uri.clone(), doc.uri.clone()),
+            (
+                "https://example.com/docs/gone".to_string(),
+                "https://example.com/docs/missing".to_string(),
+            ),
+        ];
+        assert_eq!(
+            db.record_redirect_aliases(&source.id, &redirects)
+                .await
+                .unwrap(),
+            vec!["https://example.com/docs/old".to_string()]
+        );
+        assert_eq!(
+            db.get_document_aliases(&doc.id).await.unwrap(),
+            vec!["https://example.com/docs/old".to_string()]
+        );
+        assert!(db.get_document_aliases(&moved.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_projects_changes_without_writing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(tmp.path().join("docs")).unwrap();
+        let root = tmp.path().join("docs").canonicalize().unwrap();
+        std::fs::write(
+            root.join("changed.md"),
+            "# Guide\n\nThe guide was rewritten.",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("new.md"),
+            "# New\n\nA page added since the last run.",
+        )
+        .unwrap();
+        let same = "# Same\n\nNot edited since the last run.";
+        std::fs::write(root.join("same.md"), same).unwrap();
+
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, root.display().to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        // changed.md was ingested as two chunks; removed.md no longer exists
+        let changed = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                root.join("changed.md").display().to_string(),
+                "old-hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let removed = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                root.join("removed.md").display().to_string(),
+                "removed-hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        db.upsert_document(&Document::new(
+            source.id.clone(),
+            root.join("same.md").display().to_string(),
+            compute_content_hash(same.as_bytes()),
+        ))
+        .await
+        .unwrap();
+        for (doc_id, index) in [(&changed.id, 0), (&changed.id, 1), (&removed.id, 0)] {
+            let chunk = Chunk::new(
+                doc_id.clone(),
+                index,
+                format!("old-{index}"),
+                "Old text".to_string(),
+                0,
+                8,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+        }
+
+        let stats =
+            cmd_preview_ingest_dir(&Config::default(), &db, &root, DirIngestOptions::default())
+                .await
+                .unwrap();
+
+        // same.md is skipped; changed.md's first chunk is replaced and its
+        // second trimmed
+        assert_eq!(stats.docs_processed, 2);
+        assert_eq!(stats.docs_skipped, 1);
+        assert_eq!(stats.chunks_created, 1);
+        assert_eq!(stats.chunks_updated, 1);
+        assert_eq!(stats.chunks_deleted, 2);
+        assert!(stats.errors.is_empty());
+
+        // Nothing was written: no runs, same documents and chunks
+        assert_eq!(db.list_sources().await.unwrap().len(), 1);
+        assert!(db.list_runs(None, 10).await.unwrap().is_empty());
+        let docs = db.list_documents(&source.id).await.unwrap();
+        assert_eq!(docs.len(), 3);
+        assert_eq!(docs[0].content_hash, "old-hash");
+        assert_eq!(db.get_chunks(&changed.id).await.unwrap().len(), 2);
+        assert_eq!(db.get_chunks(&removed.id).await.unwrap().len(), 1);
+    }
 }
@@ -1,8 +1,9 @@
 //! Init command implementation
 
-use crate::config::{render_config_toml, Config};
+use crate::config::{render_config_toml, Config, Quantization};
 use crate::error::{Error, Result};
 use crate::meta::MetaDb;
+use crate::say;
 use crate::store::QdrantStore;
 use crossterm::cursor;
 use crossterm::event::{self, Event, KeyCode};
@@ -139,13 +140,13 @@ pub async fn cmd_init(options: InitOptions) -> Result<()> {
         }
     }
 
-    println!("✓ Initialized librarian at {:?}", config.paths.base_dir);
-    println!("\nConfiguration: {:?}", config.paths.config_file);
-    println!("Database: {:?}", config.paths.db_file);
-    println!("\nNext steps:");
-    println!("  librarian ingest dir ./path/to/docs    # Index local docs");
-    println!("  librarian ingest url https://docs.rs   # Index web docs");
-    println!("  librarian query \"how to use X\"         # Search the index");
+    say!("✓ Initialized librarian at {:?}", config.paths.base_dir);
+    say!("\nConfiguration: {:?}", config.paths.config_file);
+    say!("Database: {:?}", config.paths.db_file);
+    say!("\nNext steps:");
+    say!("  librarian ingest dir ./path/to/docs    # Index local docs");
+    say!("  librarian ingest url https://docs.rs   # Index web docs");
+    say!("  librarian query \"how to use X\"         # Search the index");
 
     Ok(())
 }
@@ -555,7 +556,7 @@ fn run_init_wizard(config: &mut Config) -> Result<()> {
     Ok(())
 }
 
-fn compute_irrelevant_paths(config: &Config) -> HashSet<String> {
+pub(crate) fn compute_irrelevant_paths(config: &Config) -> HashSet<String> {
     let mut irrelevant = HashSet::new();
 
     if !config.reranker.enabled {
@@ -567,9 +568,20 @@ fn compute_irrelevant_paths(config: &Config) -> HashSet<String> {
         irrelevant.insert("query.bm25_weight".to_string());
     }
 
+    if config.store.quantization == Quantization::None {
+        irrelevant.insert("store.quantization_quantile".to_string());
+        irrelevant.insert("store.quantization_always_ram".to_string());
+    }
+
+    if !config.crawl.respect_robots_txt {
+        irrelevant.insert("crawl.robots_cache_ttl_secs".to_string());
+    }
+
     if !config.crawl.auto_js_rendering {
         irrelevant.insert("crawl.js_page_load_timeout_ms".to_string());
         irrelevant.insert("crawl.js_render_wait_ms".to_string());
+        irrelevant.insert("crawl.js_wait_for_selector".to_string());
+        irrelevant.insert("crawl.js_renderer_pool_size".to_string());
         irrelevant.insert("crawl.js_no_sandbox".to_string());
     }
 
@@ -584,6 +596,7 @@ fn compute_irrelevant_paths(config: &Config) -> HashSet<String> {
             "crawl.multimodal.allowed_mime_prefixes",
             "crawl.multimodal.min_relevance_score",
             "crawl.multimodal.include_css_background_images",
+            "crawl.multimodal.include_page_screenshot",
         ] {
             irrelevant.insert(key.to_string());
         }
@@ -595,6 +608,7 @@ fn compute_irrelevant_paths(config: &Config) -> HashSet<String> {
             "crawl.multimodal.allowed_mime_prefixes",
             "crawl.multimodal.min_relevance_score",
             "crawl.multimodal.include_css_background_images",
+            "crawl.multimodal.include_page_screenshot",
         ] {
             irrelevant.insert(key.to_string());
         }
@@ -1,19 +1,27 @@
 //! CLI commands implementation
 
+pub mod config;
 pub mod ingest;
 pub mod init;
 pub mod prune;
 pub mod query;
 pub mod reindex;
+pub mod runs;
 pub mod sources;
 pub mod status;
 pub mod update;
+pub mod vacuum;
+pub mod watch;
 
+pub use config::*;
 pub use ingest::*;
 pub use init::*;
 pub use prune::*;
 pub use query::*;
 pub use reindex::*;
+pub use runs::*;
 pub use sources::*;
 pub use status::*;
 pub use update::*;
+pub use vacuum::*;
+pub use watch::*;
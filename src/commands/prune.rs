@@ -1,15 +1,26 @@
 //! Prune command - remove stale/deleted documents
 
+use crate::commands::sources::resolve_source;
 use crate::config::Config;
 use crate::error::Result;
 use crate::meta::{MetaDb, SourceType};
+use crate::progress::{finish_progress, start_progress_bar, start_spinner};
+use crate::say;
 use crate::store::QdrantStore;
+use futures::StreamExt;
+use indicatif::ProgressBar;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::future::Future;
 use std::path::Path;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+/// Number of orphan points removed per Qdrant delete request
+const ORPHAN_DELETE_BATCH: usize = 500;
+
+/// Number of database chunks looked up in Qdrant per request
+const CHUNK_CHECK_PAGE: usize = 1000;
+
 /// Prune statistics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PruneStats {
@@ -18,6 +29,14 @@ pub struct PruneStats {
     pub documents_removed: usize,
     pub chunks_removed: usize,
     pub orphan_points_removed: usize,
+    /// SQLite chunks whose Qdrant point is missing
+    #[serde(default)]
+    pub dangling_chunks: usize,
+    #[serde(default)]
+    pub dangling_chunks_removed: usize,
+    /// Sources owning dangling chunks, which `reindex` can re-embed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dangling_sources: Vec<String>,
 }
 
 /// Prune options
@@ -29,6 +48,10 @@ pub struct PruneOptions {
     pub dry_run: bool,
     /// Also remove orphaned Qdrant points not in DB
     pub remove_orphans: bool,
+    /// Delete SQLite chunks whose Qdrant point is missing (otherwise only reported)
+    pub remove_dangling: bool,
+    /// Number of orphan delete requests to run at once (0 is treated as 1)
+    pub concurrency: usize,
 }
 
 /// Execute prune command
@@ -74,7 +97,7 @@ pub async fn cmd_prune(
                 )
                 .await?;
             }
-            SourceType::Url | SourceType::Sitemap => {
+            SourceType::Url | SourceType::Sitemap | SourceType::Urls | SourceType::Openapi => {
                 // For URL sources, we can't easily check if pages still exist
                 // without re-crawling, so just log info
                 info!(
@@ -85,15 +108,54 @@ pub async fn cmd_prune(
         }
     }
 
-    // Remove orphaned Qdrant points
-    if options.remove_orphans {
-        let orphans = find_orphan_points(db, store).await?;
-        stats.orphan_points_removed = orphans.len();
+    // Cross-check SQLite chunks and Qdrant points in both directions, a page at
+    // a time so neither side is ever held in memory whole
+    if options.remove_orphans || options.remove_dangling {
+        if options.remove_orphans {
+            let mut orphans = Vec::new();
+            let spinner = start_spinner("Scanning Qdrant points");
+            let mut pages = store.point_id_pages();
+            while let Some(page) = pages.next_page().await? {
+                orphans.extend(find_orphans(db, &page).await?);
+                if let Some(pb) = &spinner {
+                    pb.inc(page.len() as u64);
+                }
+            }
+            finish_progress(spinner, "Qdrant points scanned");
+
+            stats.orphan_points_removed = orphans.len();
+
+            if !orphans.is_empty() {
+                warn!(
+                    count = orphans.len(),
+                    "Found orphaned Qdrant points not in database"
+                );
+            }
 
-        if !options.dry_run && !orphans.is_empty() {
-            store.delete_points(&orphans).await?;
-            info!(count = orphans.len(), "Removed orphaned Qdrant points");
+            if !options.dry_run && !orphans.is_empty() {
+                let count = orphans.len();
+                let progress = start_progress_bar(count, "Removing orphan points");
+                delete_in_batches(
+                    orphans,
+                    ORPHAN_DELETE_BATCH,
+                    options.concurrency,
+                    &progress,
+                    |batch| async move { store.delete_points(&batch).await },
+                )
+                .await?;
+                finish_progress(progress, "Orphan points removed");
+                info!(count, "Removed orphaned Qdrant points");
+            }
         }
+
+        let dangling = find_dangling_chunks(db, store).await?;
+        check_dangling_chunks(
+            db,
+            dangling,
+            options.remove_dangling && !options.dry_run,
+            &mut stats,
+        )
+        .await?;
     }
 
     Ok(stats)
@@ -145,37 +207,123 @@ async fn prune_directory_source(
     Ok(())
 }
 
-/// Find Qdrant points that are not in the database
-async fn find_orphan_points(db: &MetaDb, store: &QdrantStore) -> Result<Vec<Uuid>> {
-    // Get all chunk IDs from database
-    let db_chunks = db.list_all_chunk_ids().await?;
-    let db_ids: HashSet<String> = db_chunks.into_iter().collect();
+/// Points of a page of Qdrant point IDs that have no database chunk
+async fn find_orphans(db: &MetaDb, page: &[Uuid]) -> Result<Vec<Uuid>> {
+    let ids: Vec<String> = page.iter().map(Uuid::to_string).collect();
+    let known = db.existing_chunk_ids(&ids).await?;
+    Ok(page
+        .iter()
+        .filter(|id| !known.contains(&id.to_string()))
+        .copied()
+        .collect())
+}
 
-    // Get all point IDs from Qdrant
-    let qdrant_points = store.list_all_point_ids().await?;
+/// Point IDs of database chunks without a Qdrant point, checked a page of
+/// chunks at a time
+async fn find_dangling_chunks(db: &MetaDb, store: &QdrantStore) -> Result<Vec<String>> {
+    let spinner = start_spinner("Checking chunks against Qdrant");
+    let mut dangling = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let page = db
+            .list_chunk_ids_page(after.as_deref(), CHUNK_CHECK_PAGE)
+            .await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = Some(last.clone());
+
+        // IDs that are not UUIDs can never match a point
+        let uuids: Vec<Uuid> = page
+            .iter()
+            .filter_map(|id| Uuid::try_parse(id).ok())
+            .collect();
+        let present = store.existing_point_ids(&uuids).await?;
+        dangling.extend(
+            page.iter()
+                .filter(|id| Uuid::try_parse(id).map_or(true, |uuid| !present.contains(&uuid)))
+                .cloned(),
+        );
+        if let Some(pb) = &spinner {
+            pb.inc(page.len() as u64);
+        }
+    }
+    finish_progress(spinner, "Chunks checked");
+    Ok(dangling)
+}
 
-    // Find orphans (in Qdrant but not in DB)
-    let orphans: Vec<Uuid> = qdrant_points
-        .into_iter()
-        .filter(|id| !db_ids.contains(&id.to_string()))
+/// Delete `ids` in batches of `batch_size`, running up to `concurrency`
+/// delete requests at once and advancing `progress` as each one finishes
+async fn delete_in_batches<F, Fut>(
+    ids: Vec<Uuid>,
+    batch_size: usize,
+    concurrency: usize,
+    progress: &Option<ProgressBar>,
+    delete: F,
+) -> Result<()>
+where
+    F: Fn(Vec<Uuid>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let batches: Vec<Vec<Uuid>> = ids
+        .chunks(batch_size.max(1))
+        .map(<[Uuid]>::to_vec)
         .collect();
+    let requests = batches.into_iter().map(|batch| {
+        let count = batch.len() as u64;
+        let request = delete(batch);
+        async move { request.await.map(|_| count) }
+    });
+    let mut pending = futures::stream::iter(requests).buffer_unordered(concurrency.max(1));
+
+    while let Some(result) = pending.next().await {
+        let count = result?;
+        if let Some(pb) = progress {
+            pb.inc(count);
+        }
+    }
 
-    if !orphans.is_empty() {
-        warn!(
-            count = orphans.len(),
-            "Found orphaned Qdrant points not in database"
-        );
+    Ok(())
+}
+
+/// Record database chunks whose Qdrant point is missing in `stats`.
+///
+/// Such chunks can never be returned by a search. The owning sources are
+/// listed so they can be re-embedded with `reembed-missing`. With `remove`
+/// the chunks are deleted and their documents marked changed, so the next
+/// ingest re-embeds them.
+async fn check_dangling_chunks(
+    db: &MetaDb,
+    mut dangling: Vec<String>,
+    remove: bool,
+    stats: &mut PruneStats,
+) -> Result<()> {
+    dangling.sort();
+    stats.dangling_chunks = dangling.len();
+    if dangling.is_empty() {
+        return Ok(());
+    }
+
+    warn!(
+        count = dangling.len(),
+        "Found database chunks missing from Qdrant"
+    );
+
+    stats.dangling_sources = db.get_source_ids_by_point_ids(&dangling).await?;
+    if remove {
+        stats.dangling_chunks_removed = db.delete_chunks_by_point_ids(&dangling).await?;
     }
 
-    Ok(orphans)
+    Ok(())
 }
 
 /// Remove a specific source and all its data
 pub async fn cmd_remove_source(
     db: &MetaDb,
     store: &QdrantStore,
-    source_id: &str,
+    source: &str,
 ) -> Result<PruneStats> {
+    let source_id = &resolve_source(db, source).await?.id;
     info!(source_id = %source_id, "Removing source");
 
     let mut stats = PruneStats::default();
@@ -216,27 +364,175 @@ pub async fn cmd_remove_source(
 
 /// Print prune stats to console
 pub fn print_prune_stats(stats: &PruneStats, dry_run: bool) {
-    println!(
+    say!(
         "\n🧹 Prune {}\n",
         if dry_run { "(Dry Run)" } else { "Complete" }
     );
-    println!("Sources checked: {}", stats.sources_checked);
-    println!("Documents checked: {}", stats.documents_checked);
-    println!(
+    say!("Sources checked: {}", stats.sources_checked);
+    say!("Documents checked: {}", stats.documents_checked);
+    say!(
         "Documents {}: {}",
         if dry_run { "to remove" } else { "removed" },
         stats.documents_removed
     );
-    println!(
+    say!(
         "Chunks {}: {}",
         if dry_run { "to remove" } else { "removed" },
         stats.chunks_removed
     );
     if stats.orphan_points_removed > 0 {
-        println!(
+        say!(
             "Orphan points {}: {}",
             if dry_run { "to remove" } else { "removed" },
             stats.orphan_points_removed
         );
     }
+    if stats.dangling_chunks > 0 {
+        say!("Dangling chunks: {}", stats.dangling_chunks);
+        if stats.dangling_chunks_removed > 0 {
+            say!("Dangling chunks removed: {}", stats.dangling_chunks_removed);
+        } else {
+            let sources: Vec<String> = stats
+                .dangling_sources
+                .iter()
+                .map(|id| format!("--source {}", id))
+                .collect();
+            say!(
+                "  Re-embed with 'librarian reindex {}' or delete with --remove-dangling",
+                sources.join(" ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::{Chunk, Document, Source};
+
+    #[tokio::test]
+    async fn test_dangling_chunks_are_reported_and_removed() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "/docs/guide.md".to_string(),
+                "doc-hash".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        let indexed = Chunk::new(
+            doc.id.clone(),
+            0,
+            "a".to_string(),
+            "A".to_string(),
+            0,
+            1,
+            None,
+        );
+        let lost = Chunk::new(
+            doc.id.clone(),
+            1,
+            "b".to_string(),
+            "B".to_string(),
+            1,
+            2,
+            None,
+        );
+        db.upsert_chunk(&indexed).await.unwrap();
+        db.upsert_chunk(&lost).await.unwrap();
+
+        // Only the first chunk's point survived in Qdrant, next to one orphan
+        let orphan = Uuid::new_v4();
+        let points = vec![Uuid::parse_str(&indexed.qdrant_point_id).unwrap(), orphan];
+        assert_eq!(find_orphans(&db, &points).await.unwrap(), vec![orphan]);
+
+        // Chunk IDs are paged in ID order without repeats
+        let mut ids = [
+            indexed.qdrant_point_id.clone(),
+            lost.qdrant_point_id.clone(),
+        ];
+        ids.sort();
+        let first = db.list_chunk_ids_page(None, 1).await.unwrap();
+        assert_eq!(first, ids[..1]);
+        let second = db.list_chunk_ids_page(Some(&first[0]), 1).await.unwrap();
+        assert_eq!(second, ids[1..]);
+        assert!(db
+            .list_chunk_ids_page(Some(&second[0]), 1)
+            .await
+            .unwrap()
+            .is_empty());
+        let dangling = vec![lost.qdrant_point_id.clone()];
+
+        let mut stats = PruneStats::default();
+        check_dangling_chunks(&db, dangling.clone(), false, &mut stats)
+            .await
+            .unwrap();
+        assert_eq!(stats.dangling_chunks, 1);
+        assert_eq!(stats.dangling_chunks_removed, 0);
+        assert_eq!(stats.dangling_sources, vec![source.id.clone()]);
+        assert!(db
+            .get_chunk_by_point_id(&lost.qdrant_point_id)
+            .await
+            .unwrap()
+            .is_some());
+
+        let mut stats = PruneStats::default();
+        check_dangling_chunks(&db, dangling, true, &mut stats)
+            .await
+            .unwrap();
+        assert_eq!(stats.dangling_chunks_removed, 1);
+        assert_eq!(stats.dangling_sources, vec![source.id.clone()]);
+        assert!(db
+            .get_chunk_by_point_id(&lost.qdrant_point_id)
+            .await
+            .unwrap()
+            .is_none());
+        // The document no longer matches its file, so the next ingest re-embeds it
+        let doc = db.get_document(&doc.id).await.unwrap().unwrap();
+        assert_ne!(doc.content_hash, "doc-hash");
+        assert_eq!(
+            db.list_all_chunk_ids().await.unwrap(),
+            vec![indexed.qdrant_point_id.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_orphans_are_deleted_in_concurrent_batches() {
+        use std::collections::HashSet;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Mutex;
+
+        let orphans: Vec<Uuid> = (0..10_000).map(|_| Uuid::new_v4()).collect();
+
+        let deleted = Mutex::new(Vec::new());
+        let calls = AtomicUsize::new(0);
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        delete_in_batches(orphans.clone(), 500, 4, &None, |batch| {
+            let (deleted, calls, in_flight, max_in_flight) =
+                (&deleted, &calls, &in_flight, &max_in_flight);
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                deleted.lock().unwrap().extend(batch);
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 20);
+        let max = max_in_flight.load(Ordering::SeqCst);
+        assert!(max > 1 && max <= 4, "max in flight: {max}");
+        let deleted: HashSet<Uuid> = deleted.into_inner().unwrap().into_iter().collect();
+        assert_eq!(deleted, orphans.into_iter().collect::<HashSet<_>>());
+    }
 }
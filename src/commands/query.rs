@@ -1,15 +1,25 @@
 //! Query command implementation
 
+use crate::chunk::{heading_texts, normalize_section_path, parse_heading_trail};
+use crate::commands::sources::resolve_source;
 use crate::config::{Config, ResolvedEmbeddingConfig};
 use crate::embed::Embedder;
-use crate::error::Result;
-use crate::meta::MetaDb;
+use crate::error::{Error, Result};
+use crate::meta::{Chunk, KeywordFilter, KeywordMatch, MetaDb};
 use crate::models::is_multimodal_reranker_model;
-use crate::rank::{RankedResult, Ranker};
+use crate::rank::{
+    normalize_score, rerank_probability, Bm25Scorer, ContextWindow, RankedResult, Ranker,
+};
 use crate::rerank::{create_reranker, Reranker};
 use crate::store::{QdrantStore, SearchFilter};
+use futures::future;
+use futures::stream::{self, BoxStream, Stream, StreamExt, TryStreamExt};
 use serde::Serialize;
-use tracing::{debug, info};
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 /// Query options
 #[derive(Debug, Clone, Default)]
@@ -20,12 +30,30 @@ pub struct QueryOptions {
     pub min_score: Option<f32>,
     /// Filter by source IDs
     pub source_ids: Option<Vec<String>>,
+    /// Filter by source tags (matches sources carrying any of the tags)
+    pub tags: Option<Vec<String>>,
     /// Filter by source types
     pub source_types: Option<Vec<String>>,
     /// Filter by path prefix
     pub path_prefix: Option<String>,
+    /// Only match chunks under this heading path, e.g. `Configuration/Crawl`
+    pub section: Option<String>,
+    /// Only match chunks embedded with the query's model
+    pub strict_model: bool,
     /// Deduplicate by document
     pub dedupe_docs: bool,
+    /// Number of neighboring chunks to include on each side of a hit
+    pub context: usize,
+    /// Number of ranked results to skip, for fetching later pages
+    pub offset: usize,
+    /// Report the ranking settings and active filters with the results
+    pub explain: bool,
+    /// Count every candidate above `min_score`, not just the returned page
+    pub count: bool,
+    /// Abort after this many seconds (defaults to `query.timeout_secs`)
+    pub timeout_secs: Option<u64>,
+    /// Candidates handed to the reranker (defaults to `query.rerank_candidates`)
+    pub rerank_candidates: Option<usize>,
 }
 
 /// Query result for CLI display
@@ -33,9 +61,92 @@ pub struct QueryOptions {
 pub struct QueryResult {
     pub results: Vec<RankedResult>,
     pub query: String,
+    /// Query with synonyms appended, when `query.synonyms_file` expanded it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded_query: Option<String>,
     pub total_chunks_searched: usize,
+    /// Number of ranked results skipped before `results`
+    pub offset: usize,
+    /// Candidates above `min_score`, counted up to `query.max_results` (only set with `count`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_candidates: Option<usize>,
+    /// Whether candidates past `results` were left out (only set with `count`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
+    /// How the results were ranked and filtered (only set when explaining)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explain: Option<QueryExplanation>,
 }
 
+/// Ranking settings and filters that shaped a query's results
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryExplanation {
+    /// Minimum score results had to reach before reranking
+    pub min_score: f32,
+    /// Whether results were ranked by keywords alone, without the vector search
+    pub keyword_only: bool,
+    /// BM25 weight, when hybrid search fused keyword scores in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bm25_weight: Option<f32>,
+    /// Largest share of the gap to 1.0 a title match could close, when title boosting is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_boost: Option<f32>,
+    /// Likewise for a heading-trail match, when heading boosting is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_boost: Option<f32>,
+    /// Reranker model, when results were reranked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reranker: Option<String>,
+    /// Filters that restricted the search, e.g. `tag: rust`
+    pub filters: Vec<String>,
+}
+
+impl QueryExplanation {
+    fn new(config: &Config, options: &QueryOptions, min_score: f32, keyword_only: bool) -> Self {
+        let mut filters = Vec::new();
+        if let Some(sources) = &options.source_ids {
+            filters.push(format!("source: {}", sources.join(", ")));
+        }
+        if let Some(tags) = options.tags.as_ref().filter(|t| !t.is_empty()) {
+            filters.push(format!("tag: {}", tags.join(", ")));
+        }
+        if let Some(types) = &options.source_types {
+            filters.push(format!("type: {}", types.join(", ")));
+        }
+        if let Some(prefix) = &options.path_prefix {
+            filters.push(format!("path prefix: {}", prefix));
+        }
+        if let Some(section) = &options.section {
+            filters.push(format!("section: {}", section));
+        }
+        if options.strict_model {
+            filters.push("same embedding model".to_string());
+        }
+        if options.dedupe_docs {
+            filters.push("dedupe by document".to_string());
+        }
+
+        Self {
+            min_score,
+            keyword_only,
+            bm25_weight: (config.query.hybrid_search && !keyword_only)
+                .then_some(config.query.bm25_weight),
+            title_boost: (config.query.title_boost > 0.0).then_some(config.query.title_boost),
+            heading_boost: (config.query.heading_boost > 0.0).then_some(config.query.heading_boost),
+            reranker: (config.reranker.enabled && !keyword_only)
+                .then(|| config.reranker.model.clone()),
+            filters,
+        }
+    }
+}
+
+/// Candidate multiplier when deduping by document, since several chunks may
+/// share a document
+const DEDUPE_OVERFETCH: usize = 5;
+
+/// Results hydrated per SQLite lookup while streaming
+const HYDRATE_BATCH: usize = 10;
+
 /// Execute a query
 pub async fn cmd_query(
     config: &Config,
@@ -46,85 +157,740 @@ pub async fn cmd_query(
     query: &str,
     options: QueryOptions,
 ) -> Result<QueryResult> {
+    let timeout_secs = options.timeout_secs.or(config.query.timeout_secs);
+    let search = async {
+        collect_query(query_stream(config, embedding, embedder, db, store, query, options).await?)
+            .await
+    };
+    with_query_timeout(timeout_secs, search).await
+}
+
+/// Execute a keyword-only query: BM25 over the chunk text in SQLite, without
+/// embedding the query or contacting Qdrant, so it works fully offline
+pub async fn cmd_keyword_query(
+    config: &Config,
+    db: &MetaDb,
+    query: &str,
+    options: QueryOptions,
+) -> Result<QueryResult> {
+    let timeout_secs = options.timeout_secs.or(config.query.timeout_secs);
+    let search =
+        async { collect_query(keyword_query_stream(config, db, query, options).await?).await };
+    with_query_timeout(timeout_secs, search).await
+}
+
+/// Run a keyword-only query, returning its results as a stream
+pub async fn keyword_query_stream<'a>(
+    config: &Config,
+    db: &'a MetaDb,
+    query: &str,
+    options: QueryOptions,
+) -> Result<QueryStream<'a>> {
+    search_stream(config, None, db, query, options).await
+}
+
+/// Trim the query text, rejecting it if nothing is left: embedding an empty
+/// string returns arbitrary neighbours or a backend error
+pub fn validate_query(query: &str) -> Result<&str> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(Error::InvalidQuery(
+            "query text is empty; pass the words to search for".to_string(),
+        ));
+    }
+    Ok(query)
+}
+
+/// The `(offset, window)` of ranked results a page of `k` results spans.
+/// Rejects an offset past `query.max_results` and, when `reranking`, rerank
+/// candidates too few to fill the page, so callers can check before contacting
+/// any backend.
+pub fn query_page(
+    config: &Config,
+    k: Option<usize>,
+    offset: usize,
+    rerank_candidates: Option<usize>,
+    reranking: bool,
+) -> Result<(usize, usize)> {
+    let k = k.unwrap_or(config.query.default_k);
+    if offset >= config.query.max_results {
+        return Err(Error::InvalidQuery(format!(
+            "--offset {} is past the last rankable result; query.max_results is {}",
+            offset, config.query.max_results
+        )));
+    }
+    // Later pages are ranked together with the ones before them, up to query.max_results
+    let window = offset.saturating_add(k).min(config.query.max_results);
+    let rerank_candidates = rerank_candidates
+        .or(config.query.rerank_candidates)
+        .filter(|_| reranking);
+    if let Some(candidates) = rerank_candidates {
+        if candidates < window {
+            return Err(Error::InvalidQuery(format!(
+                "rerank candidates ({}) must be at least the number of results requested ({})",
+                candidates, window
+            )));
+        }
+    }
+    Ok((offset.min(window), window))
+}
+
+/// Fail a query with [`Error::Timeout`] once `timeout_secs` pass
+pub async fn with_query_timeout<T>(
+    timeout_secs: Option<u64>,
+    search: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(secs) = timeout_secs else {
+        return search.await;
+    };
+    // Dropping the search on timeout cancels any backend request still in flight
+    tokio::time::timeout(Duration::from_secs(secs), search)
+        .await
+        .unwrap_or_else(|_| {
+            Err(Error::Timeout(format!(
+                "query did not finish within {}s; raise --timeout or query.timeout_secs",
+                secs
+            )))
+        })
+}
+
+async fn collect_query(stream: QueryStream<'_>) -> Result<QueryResult> {
+    let QueryStream {
+        query,
+        expanded_query,
+        offset,
+        total_candidates,
+        explain,
+        results,
+    } = stream;
+    let results: Vec<RankedResult> = results.try_collect().await?;
+
+    let total = results.len();
+    info!("Returning {} results", total);
+
+    Ok(QueryResult {
+        results,
+        query,
+        expanded_query,
+        total_chunks_searched: total,
+        offset,
+        total_candidates,
+        truncated: total_candidates.map(|candidates| candidates > offset + total),
+        explain,
+    })
+}
+
+/// Results of a query, yielded in rank order.
+///
+/// With hybrid search, reranking and counting off, each result is read from
+/// SQLite and yielded as soon as it passes the score filter. Otherwise every
+/// candidate has to be scored before the first one is yielded.
+pub struct QueryStream<'a> {
+    pub query: String,
+    /// Query with synonyms appended, when `query.synonyms_file` expanded it
+    pub expanded_query: Option<String>,
+    /// Number of ranked results skipped before the first one yielded
+    pub offset: usize,
+    /// Candidates above `min_score` (only set with `QueryOptions::count`)
+    pub total_candidates: Option<usize>,
+    /// How the results were ranked and filtered (only set when explaining)
+    pub explain: Option<QueryExplanation>,
+    results: BoxStream<'a, Result<RankedResult>>,
+}
+
+impl QueryStream<'_> {
+    /// Fill in a highlighted snippet on each text result as it is yielded
+    pub fn with_highlights(mut self, style: HighlightStyle) -> Self {
+        let terms = query_terms(self.expanded_query.as_deref().unwrap_or(&self.query));
+        self.results = self
+            .results
+            .map_ok(move |mut r| {
+                if r.modality.as_deref() != Some("image") {
+                    r.snippet = Some(highlight_snippet(
+                        &r.chunk_text,
+                        &terms,
+                        SNIPPET_MAX_CHARS,
+                        style,
+                    ));
+                }
+                r
+            })
+            .boxed();
+        self
+    }
+}
+
+impl Stream for QueryStream<'_> {
+    type Item = Result<RankedResult>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.results.poll_next_unpin(cx)
+    }
+}
+
+/// Run a query, returning its results as a stream.
+///
+/// Embedding the query and searching Qdrant happen before this returns; the
+/// stream then ranks, hydrates and pages the candidates.
+pub async fn query_stream<'a>(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &'a MetaDb,
+    store: &QdrantStore,
+    query: &str,
+    options: QueryOptions,
+) -> Result<QueryStream<'a>> {
+    search_stream(
+        config,
+        Some((embedding, embedder, store)),
+        db,
+        query,
+        options,
+    )
+    .await
+}
+
+/// Embedding backend and vector store for a query; keyword-only queries go without
+type VectorSearch<'v> = (
+    &'v ResolvedEmbeddingConfig,
+    &'v dyn Embedder,
+    &'v QdrantStore,
+);
+
+/// Run a query through the vector search, or by keywords alone when `vector` is unset
+async fn search_stream<'a>(
+    config: &Config,
+    vector: Option<VectorSearch<'_>>,
+    db: &'a MetaDb,
+    query: &str,
+    options: QueryOptions,
+) -> Result<QueryStream<'a>> {
+    let query = validate_query(query)?;
     info!("Querying: {}", query);
 
-    let k = options.k.unwrap_or(config.query.default_k);
+    let (offset, window) = query_page(
+        config,
+        options.k,
+        options.offset,
+        options.rerank_candidates,
+        vector.is_some() && config.reranker.enabled,
+    )?;
     let min_score = options.min_score.unwrap_or(config.query.min_score);
+    let rerank_candidates = options.rerank_candidates.or(config.query.rerank_candidates);
+    let explain = options
+        .explain
+        .then(|| QueryExplanation::new(config, &options, min_score, vector.is_none()));
 
-    let query_embeddings = embedder.embed(vec![query.to_string()]).await?;
-    let query_vector = query_embeddings
-        .into_iter()
-        .next()
-        .ok_or_else(|| crate::error::Error::Embedding("No embedding returned".to_string()))?;
-
-    // Build search filter
-    let filter = if options.source_ids.is_some()
-        || options.source_types.is_some()
-        || options.path_prefix.is_some()
-    {
-        Some(SearchFilter {
-            source_ids: options.source_ids,
-            source_types: options.source_types,
-            path_prefix: options.path_prefix,
-        })
+    let expanded = config.synonyms()?.expand(query);
+    let expanded_query = (expanded != query).then(|| expanded.clone());
+    if let Some(expanded) = &expanded_query {
+        debug!("Expanded query to: {}", expanded);
+    }
+
+    let source_ids = match resolve_source_filter(db, options.source_ids, options.tags).await? {
+        Some(ids) if ids.is_empty() => {
+            info!("No sources match the requested filters");
+            return Ok(QueryStream {
+                query: query.to_string(),
+                expanded_query,
+                offset: options.offset,
+                total_candidates: options.count.then_some(0),
+                explain,
+                results: stream::empty().boxed(),
+            });
+        }
+        ids => ids,
+    };
+
+    let section = options
+        .section
+        .as_deref()
+        .map(normalize_section_path)
+        .filter(|section| !section.is_empty());
+
+    let ranker = Ranker::new(config.query.bm25_weight)
+        .with_boosts(config.query.title_boost, config.query.heading_boost);
+    let terms = Bm25Scorer::new().tokenize(&expanded);
+
+    // Over-fetch when deduping so k distinct documents can survive.
+    // Counting looks at every candidate query.max_results allows, not just this page.
+    let fetch_window = if options.count {
+        config.query.max_results.max(window)
     } else {
-        None
+        window
+    };
+    let fetch_window = match rerank_candidates {
+        Some(candidates) if vector.is_some() && config.reranker.enabled => {
+            fetch_window.max(candidates)
+        }
+        _ => fetch_window,
+    };
+    let fetch_limit = if options.dedupe_docs {
+        fetch_window * DEDUPE_OVERFETCH
+    } else {
+        fetch_window * 2
     };
 
-    // Search Qdrant
-    let search_results = store.search(query_vector, k * 2, filter).await?;
-    debug!("Got {} raw results from Qdrant", search_results.len());
+    let candidates = match vector {
+        Some((embedding, embedder, store)) => {
+            let query_embeddings = embedder.embed(vec![expanded.clone()]).await?;
+            let query_vector = query_embeddings
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::Embedding("No embedding returned".to_string()))?;
 
-    // Rank results
-    let ranker = Ranker::new(config.query.bm25_weight);
-    let mut ranked = ranker.rank_vector_only(search_results);
+            // Build search filter
+            let filter = if source_ids.is_some()
+                || options.source_types.is_some()
+                || options.path_prefix.is_some()
+                || options.strict_model
+                || section.is_some()
+            {
+                Some(SearchFilter {
+                    source_ids,
+                    source_types: options.source_types,
+                    path_prefix: options.path_prefix,
+                    model_id: options.strict_model.then(|| embedding.model_id.clone()),
+                    section: section.clone(),
+                })
+            } else {
+                None
+            };
 
-    // Enrich with chunk text from SQLite
-    for result in &mut ranked {
-        if let Ok(Some(chunk)) = db.get_chunk_by_point_id(&result.id).await {
-            result.chunk_text = chunk.chunk_text;
+            let search_results = store.search(query_vector, fetch_limit, filter).await?;
+            debug!("Got {} raw results from Qdrant", search_results.len());
+            let mut ranked = ranker.rank_vector_only(search_results);
+            let mismatched = flag_model_mismatches(&mut ranked, &embedding.model_id);
+            if mismatched > 0 {
+                warn!(
+                    "{} of {} candidates were embedded with a different model than '{}'; \
+                     their scores are not comparable. Reindex those sources or pass --strict-model",
+                    mismatched,
+                    ranked.len(),
+                    embedding.model_id
+                );
+            }
+            ranked
         }
+        None => {
+            let filter = KeywordFilter {
+                source_ids,
+                source_types: options.source_types,
+                path_prefix: options.path_prefix,
+                section,
+            };
+            let matches = db.keyword_matches(&terms, &filter, fetch_limit).await?;
+            debug!("Got {} keyword matches from SQLite", matches.len());
+            rank_keyword_matches(matches)
+        }
+    };
+    let candidates = ranker.boost_matches(candidates, &terms);
+    let plan = RankPlan {
+        query: query.to_string(),
+        expanded_query: expanded,
+        min_score,
+        offset,
+        window,
+        dedupe_docs: options.dedupe_docs,
+        context: options.context,
+        count: options.count,
+        rerank_candidates,
+    };
+
+    let (results, total_candidates) = match vector {
+        Some((embedding, _, _))
+            if config.query.hybrid_search || config.reranker.enabled || options.count =>
+        {
+            let (ranked, total_candidates) =
+                rank_candidates(config, &embedding.backend.url, db, candidates, &plan).await?;
+            (
+                stream::iter(ranked.into_iter().map(Ok)).boxed(),
+                total_candidates,
+            )
+        }
+        Some(_) => (stream_candidates(db, candidates, plan), None),
+        // Keyword scores are final, so there is nothing to fuse or rerank
+        None => {
+            let total_candidates = options.count.then(|| {
+                let kept = ranker.filter_by_score(candidates.clone(), min_score);
+                count_candidates(&kept, options.dedupe_docs, config.query.max_results)
+            });
+            (stream_candidates(db, candidates, plan), total_candidates)
+        }
+    };
+
+    Ok(QueryStream {
+        query: query.to_string(),
+        expanded_query,
+        offset,
+        total_candidates,
+        explain,
+        results,
+    })
+}
+
+/// How to turn Qdrant's candidates into the requested page of results
+struct RankPlan {
+    query: String,
+    expanded_query: String,
+    min_score: f32,
+    offset: usize,
+    window: usize,
+    dedupe_docs: bool,
+    context: usize,
+    count: bool,
+    rerank_candidates: Option<usize>,
+}
+
+/// Rank vector-only candidates lazily, hydrating results in batches of
+/// [`HYDRATE_BATCH`] once they make the requested page
+fn stream_candidates(
+    db: &MetaDb,
+    candidates: Vec<RankedResult>,
+    plan: RankPlan,
+) -> BoxStream<'_, Result<RankedResult>> {
+    let mut seen_docs = HashSet::new();
+    stream::iter(candidates)
+        .filter(move |r| {
+            let keep = r.base_score() >= plan.min_score
+                && (!plan.dedupe_docs || seen_docs.insert(r.doc_uri.clone()));
+            future::ready(keep)
+        })
+        .skip(plan.offset)
+        .take(plan.window - plan.offset)
+        .chunks(HYDRATE_BATCH)
+        .then(move |mut batch| async move {
+            hydrate_results(db, &mut batch).await?;
+            if plan.context > 0 {
+                expand_context(db, &mut batch, plan.context).await?;
+            }
+            Ok::<_, Error>(stream::iter(batch.into_iter().map(Ok)))
+        })
+        .try_flatten()
+        .boxed()
+}
+
+/// Score every candidate together, for hybrid search, reranking and counting,
+/// returning the requested page and the candidate count when asked for
+async fn rank_candidates(
+    config: &Config,
+    backend_url: &str,
+    db: &MetaDb,
+    candidates: Vec<RankedResult>,
+    plan: &RankPlan,
+) -> Result<(Vec<RankedResult>, Option<usize>)> {
+    let ranker = Ranker::new(config.query.bm25_weight);
+    let mut ranked = candidates;
+
+    // Enrich with chunk text and document aliases from SQLite
+    hydrate_results(db, &mut ranked).await?;
+
+    // Fuse in keyword scores for hybrid search
+    if config.query.hybrid_search {
+        let scores = bm25_scores(&plan.expanded_query, &ranked);
+        ranked = ranker.fuse_bm25(ranked, &scores);
     }
 
     // Filter by score
-    ranked = ranker.filter_by_score(ranked, min_score);
+    ranked = ranker.filter_by_score(ranked, plan.min_score);
+    let total_candidates = plan
+        .count
+        .then(|| count_candidates(&ranked, plan.dedupe_docs, config.query.max_results));
 
     // Optional reranking
     if config.reranker.enabled && !ranked.is_empty() {
-        let reranker = create_reranker(&config.reranker, &embedding.backend.url)?;
-        if is_multimodal_reranker_model(&config.reranker.model) {
-            ranked = apply_reranker(reranker.as_ref(), query, ranked, config.reranker.top_k).await?;
-        } else {
-            let (text_results, other_results): (Vec<_>, Vec<_>) = ranked
-                .into_iter()
-                .partition(|r| r.modality.as_deref().unwrap_or("text") == "text");
+        let reranker = create_reranker(&config.reranker, backend_url)?;
+        let top_k = config.reranker.top_k.max(plan.window);
+        ranked = rerank_top_candidates(
+            reranker.as_ref(),
+            &config.reranker.model,
+            &plan.query,
+            ranked,
+            plan.rerank_candidates,
+            top_k,
+        )
+        .await?;
+    }
 
-            let mut reranked_text =
-                apply_reranker(reranker.as_ref(), query, text_results, config.reranker.top_k)
-                    .await?;
-            reranked_text.extend(other_results);
-            ranked = reranked_text;
-        }
+    // Deduplicate if requested, then keep the requested page
+    ranked = ranker.page_results(
+        ranked,
+        plan.offset,
+        plan.window - plan.offset,
+        plan.dedupe_docs,
+    );
+
+    if plan.context > 0 {
+        expand_context(db, &mut ranked, plan.context).await?;
     }
 
-    // Deduplicate if requested
-    if options.dedupe_docs {
-        ranked = ranker.dedupe_by_doc(ranked);
+    Ok((ranked, total_candidates))
+}
+
+/// Flag results whose vectors were embedded with a model other than
+/// `model_id`, returning how many were flagged.
+///
+/// Points written before models were recorded carry no model and are not
+/// flagged.
+fn flag_model_mismatches(results: &mut [RankedResult], model_id: &str) -> usize {
+    let mut flagged = 0;
+    for result in results.iter_mut() {
+        result.model_mismatch = result
+            .model_id
+            .as_deref()
+            .is_some_and(|stored| stored != model_id);
+        flagged += usize::from(result.model_mismatch);
     }
+    flagged
+}
 
-    // Limit to k results
-    ranked.truncate(k);
+/// Fill in chunk text and document aliases from SQLite, looking up every
+/// result's chunk and every document's aliases in a single query each
+async fn hydrate_results(db: &MetaDb, results: &mut [RankedResult]) -> Result<()> {
+    let point_ids: Vec<String> = results.iter().map(|r| r.id.clone()).collect();
+    let mut chunks = db.get_chunks_by_point_ids(&point_ids).await?;
+    let doc_ids: Vec<String> = chunks
+        .values()
+        .map(|chunk| chunk.doc_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let aliases = db.get_aliases_by_doc_ids(&doc_ids).await?;
+    for result in results.iter_mut() {
+        let Some(chunk) = chunks.remove(&result.id) else {
+            continue;
+        };
+        result.aliases = aliases.get(&chunk.doc_id).cloned().unwrap_or_default();
+        result.chunk_text = chunk.chunk_text;
+    }
+    Ok(())
+}
 
-    let total = ranked.len();
-    info!("Returning {} results", total);
+/// Number of results that could be returned, counting each document once when
+/// deduping, capped at `max_results`
+fn count_candidates(results: &[RankedResult], dedupe_docs: bool, max_results: usize) -> usize {
+    let count = if dedupe_docs {
+        results
+            .iter()
+            .map(|r| r.doc_uri.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    } else {
+        results.len()
+    };
+    count.min(max_results)
+}
 
-    Ok(QueryResult {
-        results: ranked,
-        query: query.to_string(),
-        total_chunks_searched: total,
+/// Rank keyword matches by their full-text BM25 rank, scaled against the
+/// best match so scores stay within 0–1 like vector similarities
+fn rank_keyword_matches(matches: Vec<KeywordMatch>) -> Vec<RankedResult> {
+    let max_rank = matches.iter().map(|m| m.rank).fold(0.0f64, f64::max);
+
+    let mut ranked: Vec<RankedResult> = matches
+        .into_iter()
+        .map(|m| {
+            let bm25 = if max_rank > 0.0 {
+                normalize_score((m.rank / max_rank) as f32)
+            } else {
+                0.0
+            };
+            RankedResult {
+                id: m.point_id,
+                score: bm25,
+                vector_score: None,
+                bm25_score: Some(bm25),
+                fused_score: None,
+                boost_score: None,
+                rerank_score: None,
+                doc_uri: m.doc_uri,
+                title: m.title,
+                chunk_text: m.chunk_text,
+                headings: m
+                    .headings_json
+                    .as_deref()
+                    .map(|json| heading_texts(&parse_heading_trail(json))),
+                chunk_index: m.chunk_index,
+                source_id: m.source_id,
+                source_type: m.source_type,
+                source_uri: m.source_uri,
+                modality: Some("text".to_string()),
+                media_url: None,
+                media_hash: None,
+                snippet: None,
+                context: None,
+                aliases: Vec::new(),
+                model_id: None,
+                model_mismatch: false,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    ranked
+}
+
+/// BM25 score of each result's chunk text against the query, keyed by result ID
+fn bm25_scores(query: &str, results: &[RankedResult]) -> HashMap<String, f32> {
+    let scorer = Bm25Scorer::new();
+    let terms = scorer.tokenize(query);
+    let total_len: usize = results.iter().map(|r| r.chunk_text.len()).sum();
+    let avg_doc_len = (total_len as f32 / results.len().max(1) as f32).max(1.0);
+
+    results
+        .iter()
+        .map(|r| {
+            let score = scorer.score(&terms, &r.chunk_text, avg_doc_len);
+            (r.id.clone(), score)
+        })
+        .collect()
+}
+
+/// Combine explicit sources with sources resolved from tags.
+///
+/// Explicit sources may be given by ID, name, or unambiguous prefix. Returns
+/// `None` when no source filter applies, and an empty list when the filters
+/// cannot match any source.
+async fn resolve_source_filter(
+    db: &MetaDb,
+    sources: Option<Vec<String>>,
+    tags: Option<Vec<String>>,
+) -> Result<Option<Vec<String>>> {
+    let source_ids = match sources {
+        Some(sources) => {
+            let mut ids = Vec::with_capacity(sources.len());
+            for source in &sources {
+                ids.push(resolve_source(db, source).await?.id);
+            }
+            Some(ids)
+        }
+        None => None,
+    };
+
+    let Some(tags) = tags.filter(|t| !t.is_empty()) else {
+        return Ok(source_ids);
+    };
+
+    let tagged: Vec<String> = db
+        .list_sources_by_tags(&tags)
+        .await?
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    debug!("Tags {:?} matched {} sources", tags, tagged.len());
+
+    Ok(Some(match source_ids {
+        Some(ids) => ids.into_iter().filter(|id| tagged.contains(id)).collect(),
+        None => tagged,
+    }))
+}
+
+/// Attach the surrounding chunks of each text hit's document, fetching the
+/// hits and then all of their windows in one query each
+async fn expand_context(db: &MetaDb, results: &mut [RankedResult], context: usize) -> Result<()> {
+    let context = i32::try_from(context).unwrap_or(i32::MAX);
+    let point_ids: Vec<String> = results
+        .iter()
+        .filter(|r| r.modality.as_deref().unwrap_or("text") == "text")
+        .map(|r| r.id.clone())
+        .collect();
+    let hits = db.get_chunks_by_point_ids(&point_ids).await?;
+    let range = |index: i32| (index.saturating_sub(context), index.saturating_add(context));
+    let windows: Vec<(String, i32, i32)> = hits
+        .values()
+        .map(|chunk| {
+            let (from, to) = range(chunk.chunk_index);
+            (chunk.doc_id.clone(), from, to)
+        })
+        .collect();
+    let by_doc = db.get_chunk_windows(&windows).await?;
+
+    for result in results.iter_mut() {
+        let Some(chunk) = hits.get(&result.id) else {
+            continue;
+        };
+        let Some(doc_chunks) = by_doc.get(&chunk.doc_id) else {
+            continue;
+        };
+        let (from, to) = range(chunk.chunk_index);
+        let start = doc_chunks.partition_point(|c| c.chunk_index < from);
+        let end = doc_chunks.partition_point(|c| c.chunk_index <= to);
+        result.context = build_context_window(&doc_chunks[start..end], chunk.chunk_index);
+    }
+    Ok(())
+}
+
+/// Join consecutive chunks, dropping text repeated by chunk overlap
+fn build_context_window(chunks: &[Chunk], hit_index: i32) -> Option<ContextWindow> {
+    let first = chunks.first()?;
+    let last = chunks.last()?;
+    let mut text = String::new();
+    let mut match_range = None;
+
+    for chunk in chunks {
+        let overlap = overlap_len(&text, &chunk.chunk_text);
+        let start = if overlap > 0 {
+            text.len() - overlap
+        } else {
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.len()
+        };
+        text.push_str(&chunk.chunk_text[overlap..]);
+        if chunk.chunk_index == hit_index {
+            match_range = Some((start, text.len()));
+        }
+    }
+
+    let (match_start, match_end) = match_range?;
+    Some(ContextWindow {
+        text,
+        start_chunk: first.chunk_index,
+        end_chunk: last.chunk_index,
+        match_start,
+        match_end,
     })
 }
 
+/// Length of the longest suffix of `prev` that starts `next`, ignoring
+/// incidental matches shorter than a few characters
+fn overlap_len(prev: &str, next: &str) -> usize {
+    const MIN_OVERLAP: usize = 8;
+    (MIN_OVERLAP..=prev.len().min(next.len()))
+        .rev()
+        .find(|&len| next.is_char_boundary(len) && prev.ends_with(&next[..len]))
+        .unwrap_or(0)
+}
+
+/// Rerank the best `candidates` results (every result when unset), keeping
+/// `top_k`; text-only rerankers leave other modalities in their vector order
+async fn rerank_top_candidates(
+    reranker: &dyn Reranker,
+    model: &str,
+    query: &str,
+    mut ranked: Vec<RankedResult>,
+    candidates: Option<usize>,
+    top_k: usize,
+) -> Result<Vec<RankedResult>> {
+    if let Some(candidates) = candidates {
+        ranked.truncate(candidates);
+    }
+    if is_multimodal_reranker_model(model) {
+        return apply_reranker(reranker, query, ranked, top_k).await;
+    }
+
+    let (text_results, other_results): (Vec<_>, Vec<_>) = ranked
+        .into_iter()
+        .partition(|r| r.modality.as_deref().unwrap_or("text") == "text");
+    let mut reranked_text = apply_reranker(reranker, query, text_results, top_k).await?;
+    reranked_text.extend(other_results);
+    Ok(reranked_text)
+}
+
 async fn apply_reranker(
     reranker: &dyn Reranker,
     query: &str,
@@ -153,7 +919,8 @@ async fn apply_reranker(
     for r in reranked {
         if let Some(item) = results.get(r.index) {
             let mut updated = item.clone();
-            updated.score = r.score;
+            updated.score = rerank_probability(r.score);
+            updated.rerank_score = Some(updated.score);
             ordered.push(updated);
         }
     }
@@ -163,17 +930,58 @@ async fn apply_reranker(
 }
 
 /// Print query results to console
-pub fn print_query_results(result: &QueryResult) {
+pub fn print_query_results(result: &QueryResult, highlight: Option<HighlightStyle>) {
     println!("\n🔍 Query: {}\n", result.query);
-    println!("Found {} results:\n", result.results.len());
+    if let Some(expanded) = &result.expanded_query {
+        println!("Expanded to: {}\n", expanded);
+    }
+    match (result.total_candidates, result.truncated) {
+        (Some(candidates), Some(true)) => println!(
+            "Found {} results ({} matched; raise --limit or use --offset for more):\n",
+            result.results.len(),
+            candidates
+        ),
+        (Some(candidates), _) => println!(
+            "Found {} results ({} matched):\n",
+            result.results.len(),
+            candidates
+        ),
+        (None, _) => println!("Found {} results:\n", result.results.len()),
+    }
+
+    if let Some(explain) = &result.explain {
+        print_explanation(explain);
+    }
+
+    let terms = query_terms(result.search_text());
 
     for (i, r) in result.results.iter().enumerate() {
-        println!("{}. [score: {:.3}] {}", i + 1, r.score, r.doc_uri);
+        println!(
+            "{}. [score: {:.3}] {}",
+            result.offset + i + 1,
+            r.score,
+            r.doc_uri
+        );
+
+        if result.explain.is_some() {
+            println!("   Scores: {}", score_breakdown(r));
+        }
 
         if let Some(title) = &r.title {
             println!("   Title: {}", title);
         }
 
+        if !r.aliases.is_empty() {
+            println!("   Also at: {}", r.aliases.join(", "));
+        }
+
+        if r.model_mismatch {
+            println!(
+                "   ⚠ Embedded with {}; reindex to compare fairly",
+                r.model_id.as_deref().unwrap_or("another model")
+            );
+        }
+
         if let Some(headings) = &r.headings {
             if !headings.is_empty() {
                 println!("   Section: {}", headings.join(" > "));
@@ -186,6 +994,18 @@ pub fn print_query_results(result: &QueryResult) {
                 .as_deref()
                 .unwrap_or_else(|| r.doc_uri.as_str());
             println!("   [image] {}\n", label);
+        } else if let Some(context) = &r.context {
+            println!(
+                "   Context (chunks {}-{}):\n   {}\n",
+                context.start_chunk,
+                context.end_chunk,
+                context.text.replace('\n', "\n   ")
+            );
+        } else if let Some(style) = highlight {
+            println!(
+                "   {}\n",
+                highlight_snippet(&r.chunk_text, &terms, SNIPPET_MAX_CHARS, style)
+            );
         } else {
             let preview = if r.chunk_text.len() > 200 {
                 format!("{}...", &r.chunk_text[..200].trim())
@@ -196,3 +1016,1230 @@ pub fn print_query_results(result: &QueryResult) {
         }
     }
 }
+
+/// Print the ranking settings and filters behind a query's results
+fn print_explanation(explain: &QueryExplanation) {
+    let mut stages = vec![if explain.keyword_only {
+        "BM25 keyword".to_string()
+    } else {
+        "vector".to_string()
+    }];
+    if let Some(weight) = explain.bm25_weight {
+        stages.push(format!("BM25 (weight {:.2})", weight));
+    }
+    if explain.title_boost.is_some() || explain.heading_boost.is_some() {
+        stages.push(format!(
+            "title/heading boost (up to {:.2}/{:.2})",
+            explain.title_boost.unwrap_or(0.0),
+            explain.heading_boost.unwrap_or(0.0)
+        ));
+    }
+    if let Some(model) = &explain.reranker {
+        stages.push(format!("rerank ({})", model));
+    }
+    println!("Ranking: {}", stages.join(" → "));
+    println!("Min score: {:.3}", explain.min_score);
+    if explain.filters.is_empty() {
+        println!("Filters: none\n");
+    } else {
+        println!("Filters: {}\n", explain.filters.join("; "));
+    }
+}
+
+/// Every score that contributed to a result's rank, e.g. `vector 0.812, bm25 0.500`
+pub fn score_breakdown(result: &RankedResult) -> String {
+    let mut parts = Vec::new();
+    let optional = [
+        ("vector", result.vector_score),
+        ("bm25", result.bm25_score),
+        ("fused", result.fused_score),
+        ("boost", result.boost_score),
+        ("rerank", result.rerank_score),
+    ];
+    for (label, score) in optional {
+        if let Some(score) = score {
+            parts.push(format!("{} {:.3}", label, score));
+        }
+    }
+    parts.join(", ")
+}
+
+/// Maximum snippet length, in characters, when highlighting matches
+pub const SNIPPET_MAX_CHARS: usize = 200;
+
+/// How highlighted query terms are marked in snippets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightStyle {
+    /// Bold yellow ANSI escapes, for terminals
+    Ansi,
+    /// `**term**`, for markdown and JSON output
+    Markdown,
+}
+
+impl HighlightStyle {
+    fn markers(self) -> (&'static str, &'static str) {
+        match self {
+            HighlightStyle::Ansi => ("\x1b[1;33m", "\x1b[0m"),
+            HighlightStyle::Markdown => ("**", "**"),
+        }
+    }
+}
+
+impl QueryResult {
+    /// The text that was searched for: the expanded query when synonyms applied
+    pub fn search_text(&self) -> &str {
+        self.expanded_query.as_deref().unwrap_or(&self.query)
+    }
+
+    /// Fill in highlighted snippets on every text result
+    pub fn apply_highlights(&mut self, style: HighlightStyle) {
+        let terms = query_terms(self.search_text());
+        for r in &mut self.results {
+            if r.modality.as_deref() != Some("image") {
+                r.snippet = Some(highlight_snippet(
+                    &r.chunk_text,
+                    &terms,
+                    SNIPPET_MAX_CHARS,
+                    style,
+                ));
+            }
+        }
+    }
+}
+
+/// Keyword terms of a query, as matched by highlighting
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Bm25Scorer::new()
+        .tokenize(query)
+        .into_iter()
+        .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|t| t.len() >= 3)
+        .collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Byte ranges of non-overlapping, case-insensitive term matches, in order
+fn find_term_matches(text: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let longest = terms
+            .iter()
+            .filter(|t| {
+                text.get(i..i + t.len())
+                    .is_some_and(|s| s.eq_ignore_ascii_case(t))
+            })
+            .map(|t| t.len())
+            .max();
+        match longest {
+            Some(len) => {
+                matches.push((i, i + len));
+                i += len;
+            }
+            None => i += text[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    matches
+}
+
+/// Trim text to a window of at most `max_chars` characters around the first
+/// term match and mark every match inside it.
+pub fn highlight_snippet(
+    text: &str,
+    terms: &[String],
+    max_chars: usize,
+    style: HighlightStyle,
+) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let matches = find_term_matches(&text, terms);
+
+    let char_offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let total = char_offsets.len();
+    let byte_at = |char_idx: usize| char_offsets.get(char_idx).copied().unwrap_or(text.len());
+
+    // Start a little before the first match so it has some leading context
+    let start_char = match matches.first() {
+        Some(&(first, _)) if total > max_chars => {
+            let first_char = char_offsets.partition_point(|&b| b < first);
+            first_char
+                .saturating_sub(max_chars / 4)
+                .min(total - max_chars)
+        }
+        _ => 0,
+    };
+    let end_char = (start_char + max_chars).min(total);
+    let (start, end) = (byte_at(start_char), byte_at(end_char));
+
+    let (open, close) = style.markers();
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut cursor = start;
+    for &(m_start, m_end) in &matches {
+        if m_end <= start || m_start >= end {
+            continue;
+        }
+        let (m_start, m_end) = (m_start.max(start), m_end.min(end));
+        snippet.push_str(&text[cursor..m_start]);
+        snippet.push_str(open);
+        snippet.push_str(&text[m_start..m_end]);
+        snippet.push_str(close);
+        cursor = m_end;
+    }
+    snippet.push_str(&text[cursor..end]);
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_query_aborts_when_backend_stalls() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let backend = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/probe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "BAAI/bge-small-en-v1.5",
+                "embedding_dim": 4,
+                "text_embeddings": [[0.1, 0.2, 0.3, 0.4]]
+            })))
+            .mount(&backend)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embed/text"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(20)))
+            .mount(&backend)
+            .await;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.embedding.url = backend.uri();
+        config.query.timeout_secs = Some(1);
+        let embedding = config.resolve_embedding_config().await.unwrap();
+        let embedder = crate::embed::create_embedder(&embedding).unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:9", "test_collection", 4, None)
+            .await
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let err = cmd_query(
+            &config,
+            &embedding,
+            embedder.as_ref(),
+            &db,
+            &store,
+            "slow",
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)), "got {:?}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_blank_query_is_rejected_before_backend_call() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let backend = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/probe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "BAAI/bge-small-en-v1.5",
+                "embedding_dim": 4,
+                "text_embeddings": [[0.1, 0.2, 0.3, 0.4]]
+            })))
+            .mount(&backend)
+            .await;
+        // Only the valid query may reach the embedding endpoint
+        Mock::given(method("POST"))
+            .and(path("/v1/embed/text"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "embeddings": [[0.1, 0.2, 0.3, 0.4]]
+            })))
+            .expect(1)
+            .mount(&backend)
+            .await;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.embedding.url = backend.uri();
+        let embedding = config.resolve_embedding_config().await.unwrap();
+        let embedder = crate::embed::create_embedder(&embedding).unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:9", "test_collection", 4, None)
+            .await
+            .unwrap();
+
+        for query in ["", "   ", "\t\n"] {
+            let err = cmd_query(
+                &config,
+                &embedding,
+                embedder.as_ref(),
+                &db,
+                &store,
+                query,
+                QueryOptions::default(),
+            )
+            .await
+            .unwrap_err();
+            assert!(matches!(err, Error::InvalidQuery(_)), "got {:?}", err);
+            let err = cmd_keyword_query(&config, &db, query, QueryOptions::default())
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::InvalidQuery(_)), "got {:?}", err);
+        }
+
+        let err = cmd_query(
+            &config,
+            &embedding,
+            embedder.as_ref(),
+            &db,
+            &store,
+            "  crawl timeout ",
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(!matches!(err, Error::InvalidQuery(_)), "got {:?}", err);
+        let result = cmd_keyword_query(&config, &db, "  crawl timeout ", QueryOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.query, "crawl timeout");
+    }
+
+    #[tokio::test]
+    async fn test_keyword_only_query_works_without_backend() {
+        use crate::meta::{Document, Source, SourceType};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        // Nothing listens here; a keyword-only query must never try
+        config.embedding.url = "http://127.0.0.1:9".to_string();
+        config.reranker.enabled = true;
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = Document::new(
+            source.id.clone(),
+            "/docs/crawl.md".to_string(),
+            "Crawling".to_string(),
+        );
+        let doc = db.upsert_document(&doc).await.unwrap();
+        let texts = [
+            "The crawl timeout defaults to 30 seconds per request.",
+            "Retries use exponential backoff after a timeout.",
+            "Sitemaps seed the crawl frontier.",
+        ];
+        for (i, text) in texts.iter().enumerate() {
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                i as i32,
+                format!("hash-{}", i),
+                text.to_string(),
+                0,
+                text.len() as i32,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+        }
+
+        let options = QueryOptions {
+            explain: true,
+            count: true,
+            ..Default::default()
+        };
+        let result = cmd_keyword_query(&config, &db, "crawl timeout", options)
+            .await
+            .unwrap();
+
+        let texts: Vec<&str> = result
+            .results
+            .iter()
+            .map(|r| r.chunk_text.as_str())
+            .collect();
+        assert_eq!(
+            texts[0],
+            "The crawl timeout defaults to 30 seconds per request."
+        );
+        assert_eq!(texts.len(), 3);
+        assert_eq!(result.total_candidates, Some(3));
+        assert!(result.results.iter().all(|r| r.vector_score.is_none()));
+        assert_eq!(result.results[0].bm25_score, Some(1.0));
+        assert_eq!(result.results[0].score, 1.0);
+        assert!(result.results[1].score < 1.0);
+        let explain = result.explain.unwrap();
+        assert!(explain.keyword_only);
+        assert_eq!(explain.reranker, None);
+
+        // Chunks matching none of the terms are left out
+        let result = cmd_keyword_query(&config, &db, "backoff", QueryOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.results.len(), 1);
+        assert_eq!(result.results[0].chunk_index, 1);
+    }
+
+    #[tokio::test]
+    async fn test_tag_filter_limits_query_to_tagged_sources() {
+        use crate::meta::{Document, Source, SourceType};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        let mut source_ids = Vec::new();
+        for (name, tags) in [
+            ("tokio", vec!["rust", "async"]),
+            ("serde", vec!["rust"]),
+            ("django", vec!["python"]),
+        ] {
+            let source = Source::new(
+                SourceType::Dir,
+                format!("/docs/{}", name),
+                Some(name.to_string()),
+            );
+            db.insert_source(&source).await.unwrap();
+            let tags: Vec<String> = tags.into_iter().map(String::from).collect();
+            db.add_source_tags(&source.id, &tags).await.unwrap();
+
+            let doc = Document::new(
+                source.id.clone(),
+                format!("/docs/{}/guide.md", name),
+                name.into(),
+            );
+            let doc = db.upsert_document(&doc).await.unwrap();
+            let text = format!("{} configuration guide", name);
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                0,
+                name.into(),
+                text.clone(),
+                0,
+                text.len() as i32,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+            source_ids.push(source.id);
+        }
+
+        let tags = |tags: &[&str]| Some(tags.iter().map(|t| t.to_string()).collect::<Vec<_>>());
+        let resolved = resolve_source_filter(&db, None, tags(&["rust"]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert!(resolved.contains(&source_ids[0]) && resolved.contains(&source_ids[1]));
+        // Explicit sources narrow the tagged set rather than widening it
+        let resolved = resolve_source_filter(&db, Some(vec!["serde".to_string()]), tags(&["rust"]))
+            .await
+            .unwrap();
+        assert_eq!(resolved, Some(vec![source_ids[1].clone()]));
+        let resolved =
+            resolve_source_filter(&db, Some(vec!["django".to_string()]), tags(&["rust"]))
+                .await
+                .unwrap();
+        assert_eq!(resolved, Some(Vec::new()));
+
+        let uris = |result: QueryResult| {
+            let mut uris: Vec<String> = result.results.into_iter().map(|r| r.doc_uri).collect();
+            uris.sort();
+            uris
+        };
+        let options = QueryOptions {
+            tags: tags(&["rust"]),
+            ..Default::default()
+        };
+        let result = cmd_keyword_query(&config, &db, "configuration guide", options)
+            .await
+            .unwrap();
+        assert_eq!(
+            uris(result),
+            vec!["/docs/serde/guide.md", "/docs/tokio/guide.md"]
+        );
+
+        let options = QueryOptions {
+            tags: tags(&["python"]),
+            ..Default::default()
+        };
+        let result = cmd_keyword_query(&config, &db, "configuration guide", options)
+            .await
+            .unwrap();
+        assert_eq!(uris(result), vec!["/docs/django/guide.md"]);
+
+        // A tag no source carries matches nothing instead of everything
+        let options = QueryOptions {
+            tags: tags(&["go"]),
+            ..Default::default()
+        };
+        let result = cmd_keyword_query(&config, &db, "configuration guide", options)
+            .await
+            .unwrap();
+        assert!(result.results.is_empty());
+    }
+
+    fn strip_markers(snippet: &str) -> String {
+        snippet.replace("**", "").replace('…', "")
+    }
+
+    #[tokio::test]
+    async fn test_expand_context_includes_neighbors_within_document() {
+        use crate::meta::{Document, Source, SourceType};
+        use crate::store::ChunkPayload;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let mut hits = Vec::new();
+        for name in ["a", "b"] {
+            let doc = Document::new(source.id.clone(), format!("/docs/{}.md", name), name.into());
+            let doc = db.upsert_document(&doc).await.unwrap();
+            for i in 0..4 {
+                let text = format!("{} chunk {}", name, i);
+                let chunk = Chunk::new(
+                    doc.id.clone(),
+                    i,
+                    format!("{}-{}", name, i),
+                    text.clone(),
+                    0,
+                    text.len() as i32,
+                    None,
+                );
+                db.upsert_chunk(&chunk).await.unwrap();
+                if i == 0 || i == 2 {
+                    let payload = ChunkPayload::new(
+                        source.id.clone(),
+                        "dir".to_string(),
+                        "/docs".to_string(),
+                        doc.id.clone(),
+                        doc.uri.clone(),
+                        i,
+                        chunk.chunk_hash.clone(),
+                        chunk.updated_at.clone(),
+                    );
+                    hits.push(RankedResult::from(crate::store::SearchResult {
+                        id: chunk.qdrant_point_id.clone(),
+                        score: 0.9,
+                        payload,
+                    }));
+                }
+            }
+        }
+
+        expand_context(&db, &mut hits, 1).await.unwrap();
+
+        // a chunk 2 pulls in its neighbors 1 and 3
+        let window = hits[1].context.as_ref().unwrap();
+        assert_eq!(window.text, "a chunk 1\n\na chunk 2\n\na chunk 3");
+        assert_eq!((window.start_chunk, window.end_chunk), (1, 3));
+        assert_eq!(
+            &window.text[window.match_start..window.match_end],
+            "a chunk 2"
+        );
+
+        // b chunk 0 has no predecessor and never reaches into document a
+        let window = hits[2].context.as_ref().unwrap();
+        assert_eq!(window.text, "b chunk 0\n\nb chunk 1");
+        assert!(!window.text.contains("a chunk"));
+        assert_eq!(window.match_start, 0);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_results_match_collected_ranking() {
+        use crate::meta::{Document, Source, SourceType};
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        // Two chunks per document, with scores interleaved across documents
+        let mut hits = Vec::new();
+        for (d, name) in ["a", "b", "c"].iter().enumerate() {
+            let doc = Document::new(
+                source.id.clone(),
+                format!("/docs/{}.md", name),
+                name.to_string(),
+            );
+            let doc = db.upsert_document(&doc).await.unwrap();
+            for i in 0..2 {
+                let text = format!("{} chunk {}", name, i);
+                let chunk = Chunk::new(
+                    doc.id.clone(),
+                    i,
+                    format!("{}-{}", name, i),
+                    text.clone(),
+                    0,
+                    text.len() as i32,
+                    None,
+                );
+                db.upsert_chunk(&chunk).await.unwrap();
+                hits.push(SearchResult {
+                    id: chunk.qdrant_point_id.clone(),
+                    score: 0.9 - 0.1 * d as f32 - 0.35 * i as f32,
+                    payload: ChunkPayload::new(
+                        source.id.clone(),
+                        "dir".to_string(),
+                        "/docs".to_string(),
+                        doc.id.clone(),
+                        doc.uri.clone(),
+                        i,
+                        chunk.chunk_hash.clone(),
+                        chunk.updated_at.clone(),
+                    ),
+                });
+            }
+        }
+
+        let mut config = Config::default();
+        config.reranker.enabled = false;
+        for (dedupe_docs, offset) in [(false, 0), (false, 2), (true, 0), (true, 1)] {
+            let plan = || RankPlan {
+                query: "chunk".to_string(),
+                expanded_query: "chunk".to_string(),
+                min_score: 0.3,
+                offset,
+                window: offset + 3,
+                dedupe_docs,
+                context: 1,
+                count: false,
+                rerank_candidates: None,
+            };
+            let candidates = Ranker::new(0.5).rank_vector_only(hits.clone());
+
+            let streamed: Vec<RankedResult> = stream_candidates(&db, candidates.clone(), plan())
+                .try_collect()
+                .await
+                .unwrap();
+            let (collected, _) =
+                rank_candidates(&config, "http://unused", &db, candidates, &plan())
+                    .await
+                    .unwrap();
+
+            let ids = |results: &[RankedResult]| {
+                results
+                    .iter()
+                    .map(|r| (r.id.clone(), r.chunk_text.clone(), r.context.is_some()))
+                    .collect::<Vec<_>>()
+            };
+            assert!(!streamed.is_empty());
+            assert_eq!(
+                ids(&streamed),
+                ids(&collected),
+                "dedupe {} offset {}",
+                dedupe_docs,
+                offset
+            );
+            // Streamed results are hydrated from SQLite like collected ones
+            assert!(streamed
+                .iter()
+                .all(|r| r.chunk_text.ends_with(char::is_numeric)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hydrate_results_fills_text_in_rank_order() {
+        use crate::meta::{Document, Source, SourceType};
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = Document::new(source.id.clone(), "/docs/a.md".to_string(), "a".into());
+        let doc = db.upsert_document(&doc).await.unwrap();
+        db.upsert_document_alias(&source.id, "/docs/alias.md", &doc.id)
+            .await
+            .unwrap();
+
+        let mut results = Vec::new();
+        for i in [2, 0, 1] {
+            let text = format!("chunk {}", i);
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                i,
+                format!("hash-{}", i),
+                text.clone(),
+                0,
+                text.len() as i32,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+            results.push(RankedResult::from(SearchResult {
+                id: chunk.qdrant_point_id.clone(),
+                score: 0.5,
+                payload: ChunkPayload::new(
+                    source.id.clone(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    doc.id.clone(),
+                    doc.uri.clone(),
+                    i,
+                    chunk.chunk_hash.clone(),
+                    chunk.updated_at.clone(),
+                ),
+            }));
+        }
+
+        hydrate_results(&db, &mut results).await.unwrap();
+
+        let texts: Vec<&str> = results.iter().map(|r| r.chunk_text.as_str()).collect();
+        assert_eq!(texts, ["chunk 2", "chunk 0", "chunk 1"]);
+        assert!(results
+            .iter()
+            .all(|r| r.aliases == vec!["/docs/alias.md".to_string()]));
+    }
+
+    /// Scores documents by how often they mention "tokio"
+    struct KeywordReranker;
+
+    #[async_trait::async_trait]
+    impl Reranker for KeywordReranker {
+        async fn rerank(
+            &self,
+            _query: &str,
+            documents: Vec<String>,
+        ) -> Result<Vec<crate::rerank::RerankResult>> {
+            Ok(documents
+                .iter()
+                .enumerate()
+                .map(|(index, doc)| crate::rerank::RerankResult {
+                    index,
+                    // Logits, as cross-encoders return them
+                    score: doc.matches("tokio").count() as f32 * 2.0 - 1.0,
+                })
+                .collect())
+        }
+
+        fn model_name(&self) -> &str {
+            "keyword"
+        }
+    }
+
+    /// Records how many documents it is asked to score
+    #[derive(Default)]
+    struct CountingReranker(std::sync::Mutex<Vec<usize>>);
+
+    #[async_trait::async_trait]
+    impl Reranker for CountingReranker {
+        async fn rerank(
+            &self,
+            _query: &str,
+            documents: Vec<String>,
+        ) -> Result<Vec<crate::rerank::RerankResult>> {
+            self.0.lock().unwrap().push(documents.len());
+            Ok(documents
+                .iter()
+                .enumerate()
+                .map(|(index, _)| crate::rerank::RerankResult {
+                    index,
+                    score: index as f32,
+                })
+                .collect())
+        }
+
+        fn model_name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reranker_receives_configured_candidate_count() {
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let hits: Vec<SearchResult> = (0..20)
+            .map(|i| SearchResult {
+                id: format!("point-{i}"),
+                score: 1.0 - i as f32 / 100.0,
+                payload: ChunkPayload::new(
+                    "src".to_string(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    format!("doc-{i}"),
+                    format!("/docs/{i}.md"),
+                    0,
+                    format!("hash-{i}"),
+                    "2024-01-01".to_string(),
+                ),
+            })
+            .collect();
+        let ranked = Ranker::new(0.5).rank_vector_only(hits);
+        let model = "BAAI/bge-reranker-base";
+
+        let reranker = CountingReranker::default();
+        let results = rerank_top_candidates(&reranker, model, "chunk", ranked.clone(), Some(8), 5)
+            .await
+            .unwrap();
+        assert_eq!(*reranker.0.lock().unwrap(), vec![8]);
+        // Reranked among the best 8 vector hits, then cut to top_k
+        let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["point-7", "point-6", "point-5", "point-4", "point-3"]);
+
+        let reranker = CountingReranker::default();
+        rerank_top_candidates(&reranker, model, "chunk", ranked, None, 5)
+            .await
+            .unwrap();
+        assert_eq!(*reranker.0.lock().unwrap(), vec![20]);
+    }
+
+    #[test]
+    fn test_rerank_candidates_below_requested_results_are_rejected() {
+        let mut config = Config::default();
+        config.reranker.enabled = true;
+
+        let err = query_page(&config, Some(5), 0, Some(4), true).unwrap_err();
+        assert!(matches!(err, Error::InvalidQuery(_)), "got {:?}", err);
+        assert_eq!(
+            query_page(&config, Some(5), 0, Some(5), true).unwrap(),
+            (0, 5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rerank_candidates_ignored_without_reranking() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.reranker.enabled = false;
+        config.query.rerank_candidates = Some(4);
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        // No reranker runs, so the candidate count cannot cut the page short
+        assert_eq!(
+            query_page(&config, Some(5), 0, None, false).unwrap(),
+            (0, 5)
+        );
+
+        // Keyword-only queries never rerank, even with the reranker enabled
+        config.reranker.enabled = true;
+        let options = QueryOptions {
+            k: Some(5),
+            ..Default::default()
+        };
+        cmd_keyword_query(&config, &db, "crawl", options)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_offset_past_max_results_is_rejected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.query.max_results = 10;
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        let options = QueryOptions {
+            offset: 10,
+            ..Default::default()
+        };
+        let err = cmd_keyword_query(&config, &db, "crawl", options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidQuery(_)), "got {:?}", err);
+
+        let options = QueryOptions {
+            offset: 9,
+            ..Default::default()
+        };
+        assert!(cmd_keyword_query(&config, &db, "crawl", options)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_explain_reports_every_applied_score() {
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let hits: Vec<SearchResult> = [("a", 0.9, "runtime setup"), ("b", 0.6, "tokio tokio")]
+            .into_iter()
+            .map(|(id, score, _)| SearchResult {
+                id: id.to_string(),
+                score,
+                payload: ChunkPayload::new(
+                    "src".to_string(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    id.to_string(),
+                    format!("/docs/{}.md", id),
+                    0,
+                    id.to_string(),
+                    "2024-01-01".to_string(),
+                ),
+            })
+            .collect();
+        let ranker = Ranker::new(0.5);
+        let mut ranked = ranker.rank_vector_only(hits);
+        ranked[0].chunk_text = "runtime setup".to_string();
+        ranked[1].chunk_text = "tokio tokio".to_string();
+
+        let scores = bm25_scores("tokio runtime", &ranked);
+        let ranked = ranker.fuse_bm25(ranked, &scores);
+        let ranked = apply_reranker(&KeywordReranker, "tokio runtime", ranked, 5)
+            .await
+            .unwrap();
+
+        assert_eq!(ranked[0].id, "b");
+        for r in &ranked {
+            assert!(r.bm25_score.is_some());
+            assert!(r.fused_score.is_some());
+            assert_eq!(r.rerank_score, Some(r.score));
+        }
+        assert_eq!(ranked[0].vector_score, Some(0.6));
+        assert_eq!(
+            score_breakdown(&ranked[0]),
+            "vector 0.600, bm25 1.000, fused 0.800, rerank 0.953"
+        );
+        let json = serde_json::to_value(&ranked[0]).unwrap();
+        assert!(json["fused_score"].is_number());
+        assert!((json["rerank_score"].as_f64().unwrap() - 0.953).abs() < 1e-3);
+
+        let mut config = Config::default();
+        config.query.hybrid_search = true;
+        config.reranker.enabled = true;
+        let options = QueryOptions {
+            tags: Some(vec!["rust".to_string()]),
+            dedupe_docs: true,
+            explain: true,
+            ..Default::default()
+        };
+        let explain = QueryExplanation::new(&config, &options, 0.25, false);
+        assert_eq!(explain.bm25_weight, Some(config.query.bm25_weight));
+        assert_eq!(explain.reranker, Some(config.reranker.model.clone()));
+        assert_eq!(explain.filters, vec!["tag: rust", "dedupe by document"]);
+
+        config.query.hybrid_search = false;
+        config.reranker.enabled = false;
+        let explain = QueryExplanation::new(&config, &QueryOptions::default(), 0.25, false);
+        assert!(explain.bm25_weight.is_none() && explain.reranker.is_none());
+        assert!(explain.filters.is_empty());
+    }
+
+    #[test]
+    fn test_synonyms_let_alias_match_canonical_term() {
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("synonyms.txt"), "k8s => kubernetes\n").unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.query.synonyms_file = Some("synonyms.txt".into());
+        let synonyms = config.synonyms().unwrap();
+
+        // The file is read once per config; later edits need a restart
+        std::fs::write(tmp.path().join("synonyms.txt"), "").unwrap();
+        assert_eq!(config.synonyms().unwrap().expand("k8s"), "k8s kubernetes");
+
+        let texts = [
+            ("canonical", "Deploy the service to Kubernetes."),
+            ("other", "Deploy the service to a virtual machine."),
+        ];
+        let hits = texts
+            .iter()
+            .map(|(id, _)| SearchResult {
+                id: id.to_string(),
+                score: 0.5,
+                payload: ChunkPayload::new(
+                    "src".to_string(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    id.to_string(),
+                    format!("/docs/{}.md", id),
+                    0,
+                    id.to_string(),
+                    "2024-01-01".to_string(),
+                ),
+            })
+            .collect();
+        let ranker = Ranker::new(0.5);
+        let mut ranked = ranker.rank_vector_only(hits);
+        // Equal vector scores keep the input order
+        for (r, (_, text)) in ranked.iter_mut().zip(texts) {
+            r.chunk_text = text.to_string();
+        }
+
+        // Without expansion the alias matches nothing
+        let scores = bm25_scores("k8s", &ranked);
+        assert!(scores.values().all(|&score| score == 0.0));
+
+        let expanded = synonyms.expand("k8s");
+        assert_eq!(expanded, "k8s kubernetes");
+        let scores = bm25_scores(&expanded, &ranked);
+        let ranked = ranker.fuse_bm25(ranked, &scores);
+        assert_eq!(ranked[0].id, "canonical");
+        assert!(ranked[0].bm25_score.unwrap() > 0.0);
+        assert_eq!(ranked[1].bm25_score, Some(0.0));
+    }
+
+    #[test]
+    fn test_count_candidates_reflects_results_above_min_score() {
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let hits: Vec<SearchResult> = [0.9, 0.8, 0.7, 0.6, 0.4, 0.2]
+            .into_iter()
+            .enumerate()
+            .map(|(i, score)| SearchResult {
+                id: i.to_string(),
+                score,
+                payload: ChunkPayload::new(
+                    "src".to_string(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    "doc".to_string(),
+                    format!("/docs/{}.md", i / 2),
+                    0,
+                    i.to_string(),
+                    "2024-01-01".to_string(),
+                ),
+            })
+            .collect();
+        let ranker = Ranker::new(0.0);
+        let ranked = ranker.filter_by_score(ranker.rank_vector_only(hits), 0.5);
+
+        // 0.9, 0.8, 0.7, and 0.6 clear the threshold, from two documents
+        assert_eq!(count_candidates(&ranked, false, 100), 4);
+        assert_eq!(count_candidates(&ranked, true, 100), 2);
+        assert_eq!(count_candidates(&ranked, false, 3), 3);
+    }
+
+    #[tokio::test]
+    async fn test_section_filter_matches_heading_trail_prefixes() {
+        use crate::chunk::chunk_document;
+        use crate::config::{ChunkConfig, ChunkStrategy};
+        use crate::meta::{Document, Source, SourceType};
+        use crate::parse::{parse_content, ContentType};
+
+        let markdown = "# Guide\n\nIntro.\n\n\
+            ## Configuration\n\nSettings live in config.toml.\n\n\
+            ### Crawl\n\nThe crawl timeout is 30 seconds.\n\n\
+            #### Retries\n\nRetries back off after a timeout.\n\n\
+            ### Query\n\nThe query timeout is 10 seconds.\n\n\
+            ## Troubleshooting\n\nCrawl timeouts usually mean a slow host.\n";
+        let doc = parse_content(markdown, ContentType::Markdown, None).unwrap();
+        let config = ChunkConfig {
+            strategy: ChunkStrategy::Heading,
+            ..Default::default()
+        };
+        let chunks = chunk_document(&doc, &config).unwrap();
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let stored = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "/docs/guide.md".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        for (i, chunk) in chunks.iter().enumerate() {
+            // The same words in every chunk, so only the section decides
+            let row = Chunk::new(
+                stored.id.clone(),
+                i as i32,
+                format!("hash-{}", i),
+                "guide section".to_string(),
+                0,
+                13,
+                Some(chunk.headings.clone()),
+            );
+            db.upsert_chunk(&row).await.unwrap();
+        }
+
+        let terms = vec!["section".to_string()];
+        let matching = |section: &str| {
+            let filter = KeywordFilter {
+                section: Some(normalize_section_path(section)),
+                ..Default::default()
+            };
+            let db = db.clone();
+            let terms = terms.clone();
+            async move {
+                let mut results =
+                    rank_keyword_matches(db.keyword_matches(&terms, &filter, 100).await.unwrap());
+                results.sort_by_key(|r| r.chunk_index);
+                results
+                    .into_iter()
+                    .map(|r| r.headings.unwrap().last().cloned().unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // A path matches its own section and everything nested below it
+        assert_eq!(
+            matching("Guide/Configuration/Crawl").await,
+            vec!["Crawl", "Retries"]
+        );
+        // Case and spacing around parts do not matter
+        assert_eq!(
+            matching("guide / configuration/CRAWL").await,
+            vec!["Crawl", "Retries"]
+        );
+        assert_eq!(matching("guide").await.len(), chunks.len());
+        assert_eq!(
+            matching("guide/troubleshooting").await,
+            vec!["Troubleshooting"]
+        );
+        // Paths start at the top of the trail and name whole headings
+        assert!(matching("Configuration/Crawl").await.is_empty());
+        assert!(matching("guide/config").await.is_empty());
+        assert!(matching("Guide/Crawl").await.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_model_chunks_are_flagged() {
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let models = [
+            Some("BAAI/bge-small-en-v1.5"),
+            Some("sentence-transformers/all-MiniLM-L6-v2"),
+            None,
+        ];
+        let hits: Vec<SearchResult> = models
+            .iter()
+            .enumerate()
+            .map(|(i, model)| {
+                let mut payload = ChunkPayload::new(
+                    "src".to_string(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    "doc".to_string(),
+                    format!("/docs/{}.md", i),
+                    0,
+                    i.to_string(),
+                    "2024-01-01".to_string(),
+                );
+                payload.model_id = model.map(str::to_string);
+                SearchResult {
+                    id: i.to_string(),
+                    score: 0.9 - 0.1 * i as f32,
+                    payload,
+                }
+            })
+            .collect();
+        let mut ranked = Ranker::new(0.0).rank_vector_only(hits);
+
+        assert_eq!(
+            flag_model_mismatches(&mut ranked, "BAAI/bge-small-en-v1.5"),
+            1
+        );
+        let flags: Vec<bool> = ranked.iter().map(|r| r.model_mismatch).collect();
+        // Points from before models were recorded are not flagged
+        assert_eq!(flags, vec![false, true, false]);
+        assert_eq!(
+            ranked[1].model_id.as_deref(),
+            Some("sentence-transformers/all-MiniLM-L6-v2")
+        );
+
+        let json = serde_json::to_value(&ranked).unwrap();
+        assert_eq!(json[1]["model_mismatch"], true);
+        assert!(json[0].get("model_mismatch").is_none());
+        assert!(json[2].get("model_id").is_none());
+    }
+
+    #[test]
+    fn test_hybrid_fusion_promotes_keyword_matches() {
+        use crate::store::{ChunkPayload, SearchResult};
+
+        let hits: Vec<SearchResult> = [("a", 0.9), ("b", 0.6)]
+            .into_iter()
+            .map(|(id, score)| SearchResult {
+                id: id.to_string(),
+                score,
+                payload: ChunkPayload::new(
+                    "src".to_string(),
+                    "dir".to_string(),
+                    "/docs".to_string(),
+                    id.to_string(),
+                    format!("/docs/{}.md", id),
+                    0,
+                    id.to_string(),
+                    "2024-01-01".to_string(),
+                ),
+            })
+            .collect();
+        let ranker = Ranker::new(0.5);
+        let mut ranked = ranker.rank_vector_only(hits);
+        ranked[0].chunk_text = "runtime setup".to_string();
+        ranked[1].chunk_text = "tokio tokio".to_string();
+
+        let scores = bm25_scores("tokio", &ranked);
+        assert_eq!(scores["a"], 0.0);
+        let ranked = ranker.fuse_bm25(ranked, &scores);
+
+        assert_eq!(ranked[0].id, "b");
+        assert_eq!(ranked[0].bm25_score, Some(1.0));
+        assert_eq!(ranked[0].vector_score, Some(0.6));
+        assert_eq!(ranked[0].score, 0.8);
+    }
+
+    #[test]
+    fn test_context_window_removes_chunk_overlap() {
+        let chunk = |i: i32, text: &str| {
+            Chunk::new("doc".into(), i, format!("h{}", i), text.into(), 0, 0, None)
+        };
+        let chunks = vec![
+            chunk(0, "The runtime is configured with a builder."),
+            chunk(1, "with a builder. Call enable_all to turn on drivers."),
+        ];
+
+        let window = build_context_window(&chunks, 1).unwrap();
+        assert_eq!(
+            window.text,
+            "The runtime is configured with a builder. Call enable_all to turn on drivers."
+        );
+        assert_eq!(
+            &window.text[window.match_start..window.match_end],
+            "with a builder. Call enable_all to turn on drivers."
+        );
+    }
+
+    #[test]
+    fn test_highlight_snippet_windows_around_first_match() {
+        let filler = "lorem ipsum dolor sit amet ".repeat(20);
+        let text = format!(
+            "{}To configure the Tokio runtime, call builder. {}",
+            filler, filler
+        );
+        let terms = query_terms("How to configure tokio?");
+        assert_eq!(terms, vec!["configure", "how", "tokio"]);
+
+        let snippet = highlight_snippet(&text, &terms, 120, HighlightStyle::Markdown);
+        assert!(snippet.contains("**configure**"));
+        assert!(snippet.contains("**Tokio**"));
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(strip_markers(&snippet).chars().count() <= 120);
+    }
+
+    #[test]
+    fn test_highlight_snippet_handles_short_and_unmatched_text() {
+        let terms = query_terms("configure");
+
+        let snippet = highlight_snippet("Configure  it\nnow", &terms, 120, HighlightStyle::Ansi);
+        assert_eq!(snippet, "\x1b[1;33mConfigure\x1b[0m it now");
+
+        let long = "é".repeat(300);
+        let snippet = highlight_snippet(&long, &terms, 50, HighlightStyle::Markdown);
+        assert_eq!(strip_markers(&snippet).chars().count(), 50);
+        assert!(!snippet.starts_with('…'));
+    }
+}
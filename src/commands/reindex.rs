@@ -1,17 +1,26 @@
 //! Reindex command - re-embed all documents
 
+use crate::chunk::parse_heading_trail;
+use crate::commands::ingest::{check_embedder_dimension, dimension_mismatch};
 use crate::config::{Config, ResolvedEmbeddingConfig};
 use crate::embed::{
     embed_image_text_in_batches, embed_images_in_batches, embed_in_batches, Embedder,
     ImageEmbedInput, fuse_embeddings,
 };
 use crate::error::{Error, Result};
-use crate::meta::{MetaDb, RunOperation, RunStatus};
+use crate::meta::{ChunkRecord, Document, MetaDb, RunOperation, RunStatus, Source};
+use crate::progress::{advance_progress, finish_progress, set_progress_chunks, start_progress_bar};
+use crate::say;
 use crate::store::{ChunkPayload, ChunkPoint, QdrantStore};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::future::Future;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+/// Point IDs checked against Qdrant per request when looking for missing chunks
+const POINT_CHECK_PAGE: usize = 1000;
+
 /// Reindex statistics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReindexStats {
@@ -51,38 +60,27 @@ pub async fn cmd_reindex(
     info!("Starting reindex operation");
 
     store.ensure_collection().await?;
-
-    if embedder.dimension() != store.dimension() {
-        return Err(Error::Embedding(format!(
-            "Embedding dimension {} does not match Qdrant collection dimension {}",
-            embedder.dimension(),
-            store.dimension()
-        )));
-    }
+    check_embedder_dimension(embedding, embedder, store)?;
 
     let mut stats = ReindexStats::default();
 
     // Get sources to reindex
-    let sources = match &options.source_ids {
-        Some(ids) => {
-            let mut sources = Vec::new();
-            for id in ids {
-                if let Some(source) = db.get_source(id).await? {
-                    sources.push(source);
-                }
-            }
-            sources
-        }
-        None => db.list_sources().await?,
-    };
+    let sources = selected_sources(db, &options).await?;
 
     stats.sources_processed = sources.len();
 
+    let mut total_documents = 0;
+    for source in &sources {
+        total_documents += db.list_source_documents(&source.id).await?.len();
+    }
+    let progress = start_progress_bar(total_documents, "Reindexing documents");
+
     // Process each source
     for source in sources {
         let run = db
             .start_ingestion_run(&source.id, RunOperation::Reindex)
             .await?;
+        let _heartbeat = db.start_run_heartbeat(&run.id);
 
         let mut run_errors: Vec<String> = Vec::new();
         let mut run_docs_processed = 0usize;
@@ -121,31 +119,31 @@ pub async fn cmd_reindex(
                     run_errors.push(format!("{}: {}", doc.id, e));
                 }
             }
-        }
 
-        let status = if run_errors.is_empty() {
-            RunStatus::Completed
-        } else {
-            RunStatus::Failed
-        };
+            set_progress_chunks(&progress, "Reindexing documents", stats.chunks_reindexed);
+            advance_progress(&progress);
+        }
 
-        let _ = db
-            .complete_ingestion_run(
-                &run.id,
-                status,
-                run_docs_processed as i32,
-                0,
-                run_chunks_updated as i32,
-                0,
-                if run_errors.is_empty() {
-                    None
-                } else {
-                    Some(run_errors.clone())
-                },
-            )
-            .await;
+        let status = RunStatus::finished(run_docs_processed + run_errors.len(), run_errors.len());
+
+        db.complete_ingestion_run(
+            &run.id,
+            status,
+            run_docs_processed as i32,
+            0,
+            run_chunks_updated as i32,
+            0,
+            if run_errors.is_empty() {
+                None
+            } else {
+                Some(run_errors.clone())
+            },
+        )
+        .await?;
     }
 
+    finish_progress(progress, "Documents reindexed");
+
     info!(
         documents = stats.documents_processed,
         chunks = stats.chunks_reindexed,
@@ -180,6 +178,7 @@ async fn reindex_document(
         return Ok(0);
     }
 
+    let model_id = &embedding.model_id;
     let (text_chunks, image_chunks): (Vec<_>, Vec<_>) = chunks
         .into_iter()
         .partition(|c| c.modality == "text");
@@ -199,31 +198,17 @@ async fn reindex_document(
         }
 
         for (chunk, embedding) in text_chunks.iter().zip(all_embeddings.into_iter()) {
-            let point_id = Uuid::try_parse(&chunk.id)
-                .unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, chunk.id.as_bytes()));
-
-            let headings: Option<Vec<String>> = chunk
-                .headings
-                .as_ref()
-                .and_then(|h| serde_json::from_str(h).ok());
-
-            let mut payload = ChunkPayload::new(
-                source_id.to_string(),
-                source_type.to_string(),
-                source_uri.to_string(),
-                doc_id.to_string(),
-                doc.uri.clone(),
-                chunk.chunk_index,
-                chunk.content_hash.clone(),
-                chrono::Utc::now().to_rfc3339(),
-            );
-            payload.title = doc.title.clone();
-            payload.headings = headings;
-
             points.push(ChunkPoint {
-                id: point_id,
+                id: chunk_point_id(&chunk.id),
                 vector: embedding,
-                payload,
+                payload: text_chunk_payload(
+                    source_id,
+                    source_type,
+                    source_uri,
+                    &doc,
+                    chunk,
+                    model_id,
+                ),
             });
         }
 
@@ -269,16 +254,7 @@ async fn reindex_document(
 
         if !image_paths.is_empty() {
             let expected_dim = store.dimension();
-            if embedder.dimension() != expected_dim {
-                return Err(Error::Embedding(format!(
-                    "Embedding dimension mismatch for model '{}' (family '{}', source {}): embedder {} != collection {}",
-                    embedding.model_id,
-                    embedding.family,
-                    embedding.dimension_source,
-                    embedder.dimension(),
-                    expected_dim
-                )));
-            }
+            check_embedder_dimension(embedding, embedder, store)?;
 
             let batch_size = embedding.effective_batch_size(batch_size);
             let contexts: Vec<Option<String>> = image_meta
@@ -337,12 +313,12 @@ async fn reindex_document(
             if embeddings.is_empty() {
                 warn!(doc_id = %doc_id, "No image embeddings returned");
             } else if embeddings[0].len() != expected_dim {
-                return Err(Error::Embedding(format!(
-                    "Image embedding dimension mismatch for model '{}': expected {}, got {}",
-                    embedding.model_id,
+                return Err(dimension_mismatch(
+                    embedding,
                     expected_dim,
-                    embeddings[0].len()
-                )));
+                    embeddings[0].len(),
+                    "image embedding",
+                ));
             } else {
                 for (chunk, embedding_vec) in image_meta.iter().zip(embeddings.into_iter()) {
                     let point_id = Uuid::try_parse(&chunk.id).unwrap_or_else(|_| {
@@ -363,6 +339,7 @@ async fn reindex_document(
                     payload.modality = Some(chunk.modality.clone());
                     payload.media_url = chunk.media_url.clone();
                     payload.media_hash = chunk.media_hash.clone();
+                    payload.model_id = Some(model_id.clone());
 
                     points.push(ChunkPoint {
                         id: point_id,
@@ -396,13 +373,359 @@ fn find_cached_asset_path(base_dir: &std::path::Path, hash: &str) -> Option<std:
     None
 }
 
+/// The sources named in `options`, or every source when none are named
+async fn selected_sources(db: &MetaDb, options: &ReindexOptions) -> Result<Vec<Source>> {
+    match &options.source_ids {
+        Some(ids) => {
+            let mut sources = Vec::new();
+            for id in ids {
+                if let Some(source) = db.get_source(id).await? {
+                    sources.push(source);
+                }
+            }
+            Ok(sources)
+        }
+        None => db.list_sources().await,
+    }
+}
+
+/// Qdrant point ID for a stored chunk ID
+fn chunk_point_id(id: &str) -> Uuid {
+    Uuid::try_parse(id).unwrap_or_else(|_| Uuid::new_v5(&Uuid::NAMESPACE_OID, id.as_bytes()))
+}
+
+/// Payload for a text chunk rebuilt from SQLite
+fn text_chunk_payload(
+    source_id: &str,
+    source_type: &str,
+    source_uri: &str,
+    doc: &Document,
+    chunk: &ChunkRecord,
+    model_id: &str,
+) -> ChunkPayload {
+    let mut payload = ChunkPayload::new(
+        source_id.to_string(),
+        source_type.to_string(),
+        source_uri.to_string(),
+        doc.id.clone(),
+        doc.uri.clone(),
+        chunk.chunk_index,
+        chunk.content_hash.clone(),
+        chrono::Utc::now().to_rfc3339(),
+    );
+    payload.title = doc.title.clone();
+    payload.headings = chunk
+        .headings
+        .as_deref()
+        .map(parse_heading_trail)
+        .filter(|trail| !trail.is_empty());
+    if let Some(endpoint) = doc.api_endpoint() {
+        payload.set_api_endpoint(&endpoint);
+    }
+    payload.model_id = Some(model_id.to_string());
+    payload
+}
+
+/// Statistics for re-embedding chunks whose Qdrant point is missing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReembedStats {
+    pub sources_processed: usize,
+    /// Chunks recorded in SQLite without a point in Qdrant
+    pub chunks_missing: usize,
+    pub chunks_reembedded: usize,
+    /// Missing image chunks, which need `reindex` to fetch their media again
+    pub chunks_skipped: usize,
+    pub errors: usize,
+}
+
+/// Re-embed only the chunks whose Qdrant point is missing, e.g. after an
+/// ingest died between writing SQLite and upserting its vectors. Chunks that
+/// already have a point are left alone.
+pub async fn cmd_reembed_missing(
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    store: &QdrantStore,
+    embedder: &dyn Embedder,
+    options: ReindexOptions,
+) -> Result<ReembedStats> {
+    info!("Re-embedding chunks missing from Qdrant");
+
+    store.ensure_collection().await?;
+    check_embedder_dimension(embedding, embedder, store)?;
+
+    let mut stats = ReembedStats::default();
+    let sources = selected_sources(db, &options).await?;
+    stats.sources_processed = sources.len();
+
+    for source in sources {
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Reembed)
+            .await?;
+        let _heartbeat = db.start_run_heartbeat(&run.id);
+
+        let reembedded_before = stats.chunks_reembedded;
+        let run_errors = reembed_source(
+            embedding,
+            db,
+            embedder,
+            &source,
+            options.batch_size,
+            |ids| async move { store.existing_point_ids(&ids).await },
+            |points| store.upsert_points(points),
+            &mut stats,
+        )
+        .await?;
+
+        let reembedded = stats.chunks_reembedded - reembedded_before;
+        let status = RunStatus::finished(reembedded + run_errors.len(), run_errors.len());
+        db.complete_ingestion_run(
+            &run.id,
+            status,
+            0,
+            reembedded as i32,
+            0,
+            0,
+            (!run_errors.is_empty()).then_some(run_errors),
+        )
+        .await?;
+    }
+
+    info!(
+        missing = stats.chunks_missing,
+        reembedded = stats.chunks_reembedded,
+        errors = stats.errors,
+        "Re-embed complete"
+    );
+
+    Ok(stats)
+}
+
+/// Re-embed a source's text chunks that have no point, handing each
+/// document's new points to `upsert`. `existing` reports which of a page of
+/// point IDs are in the collection. Returns the per-document errors.
+#[allow(clippy::too_many_arguments)]
+async fn reembed_source<E, EFut, F, Fut>(
+    embedding: &ResolvedEmbeddingConfig,
+    db: &MetaDb,
+    embedder: &dyn Embedder,
+    source: &Source,
+    batch_size: usize,
+    mut existing: E,
+    mut upsert: F,
+    stats: &mut ReembedStats,
+) -> Result<Vec<String>>
+where
+    E: FnMut(Vec<Uuid>) -> EFut,
+    EFut: Future<Output = Result<HashSet<Uuid>>>,
+    F: FnMut(Vec<ChunkPoint>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let batch_size = embedding.effective_batch_size(batch_size);
+    let mut errors = Vec::new();
+
+    for doc in db.list_source_documents(&source.id).await? {
+        let mut missing = Vec::new();
+        for page in db
+            .list_document_chunks(&doc.id)
+            .await?
+            .chunks(POINT_CHECK_PAGE)
+        {
+            let present = existing(page.iter().map(|c| chunk_point_id(&c.id)).collect()).await?;
+            missing.extend(
+                page.iter()
+                    .filter(|chunk| !present.contains(&chunk_point_id(&chunk.id)))
+                    .cloned(),
+            );
+        }
+        let (text_chunks, image_chunks): (Vec<_>, Vec<_>) = missing
+            .into_iter()
+            .partition(|chunk| chunk.modality == "text");
+        stats.chunks_missing += text_chunks.len() + image_chunks.len();
+        if !image_chunks.is_empty() {
+            warn!(
+                doc_id = %doc.id,
+                count = image_chunks.len(),
+                "Skipping missing image chunks; run reindex to re-embed them"
+            );
+            stats.chunks_skipped += image_chunks.len();
+        }
+        if text_chunks.is_empty() {
+            continue;
+        }
+
+        let texts: Vec<String> = text_chunks.iter().map(|c| c.text.clone()).collect();
+        let result = match embed_in_batches(embedder, texts, batch_size).await {
+            Ok(vectors) => {
+                let points: Vec<ChunkPoint> = text_chunks
+                    .iter()
+                    .zip(vectors)
+                    .map(|(chunk, vector)| ChunkPoint {
+                        id: chunk_point_id(&chunk.id),
+                        vector,
+                        payload: text_chunk_payload(
+                            &source.id,
+                            &source.source_type,
+                            &source.uri,
+                            &doc,
+                            chunk,
+                            &embedding.model_id,
+                        ),
+                    })
+                    .collect();
+                upsert(points).await
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => stats.chunks_reembedded += text_chunks.len(),
+            Err(e) => {
+                warn!(doc_id = %doc.id, error = %e, "Failed to re-embed missing chunks");
+                stats.errors += 1;
+                errors.push(format!("{}: {}", doc.id, e));
+            }
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Print re-embed stats to console
+pub fn print_reembed_stats(stats: &ReembedStats) {
+    say!("\n🩹 Re-embed Complete\n");
+    say!("Sources processed: {}", stats.sources_processed);
+    say!("Chunks missing from Qdrant: {}", stats.chunks_missing);
+    say!("Chunks re-embedded: {}", stats.chunks_reembedded);
+    if stats.chunks_skipped > 0 {
+        say!(
+            "Image chunks skipped: {} (run 'librarian reindex' for those sources)",
+            stats.chunks_skipped
+        );
+    }
+    if stats.errors > 0 {
+        say!("Errors: {}", stats.errors);
+    }
+}
+
 /// Print reindex stats to console
 pub fn print_reindex_stats(stats: &ReindexStats) {
-    println!("\n🔄 Reindex Complete\n");
-    println!("Sources processed: {}", stats.sources_processed);
-    println!("Documents processed: {}", stats.documents_processed);
-    println!("Chunks reindexed: {}", stats.chunks_reindexed);
+    say!("\n🔄 Reindex Complete\n");
+    say!("Sources processed: {}", stats.sources_processed);
+    say!("Documents processed: {}", stats.documents_processed);
+    say!("Chunks reindexed: {}", stats.chunks_reindexed);
     if stats.errors > 0 {
-        println!("Errors: {}", stats.errors);
+        say!("Errors: {}", stats.errors);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::{Chunk, SourceType};
+    use crate::test_support::{test_embedding_config, RecordingEmbedder};
+
+    #[tokio::test]
+    async fn test_reembed_missing_only_embeds_chunks_without_points() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "/docs/guide.md".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let mut chunks = Vec::new();
+        for (i, text) in ["Already embedded.", "Lost before upsert."]
+            .iter()
+            .enumerate()
+        {
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                i as i32,
+                format!("hash-{}", i),
+                text.to_string(),
+                0,
+                text.len() as i32,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+            chunks.push(chunk);
+        }
+
+        // Only the first chunk made it into Qdrant
+        let present = HashSet::from([chunk_point_id(&chunks[0].qdrant_point_id)]);
+        let embedder = RecordingEmbedder::default();
+        let mut checked = Vec::new();
+        let mut upserted = Vec::new();
+        let mut stats = ReembedStats::default();
+        let errors = reembed_source(
+            &test_embedding_config(false, false),
+            &db,
+            &embedder,
+            &source,
+            32,
+            |ids| {
+                checked.extend(ids.iter().copied());
+                let found = ids.into_iter().filter(|id| present.contains(id)).collect();
+                async { Ok(found) }
+            },
+            |points| {
+                upserted.extend(points);
+                async { Ok(()) }
+            },
+            &mut stats,
+        )
+        .await
+        .unwrap();
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        // Only the document's own chunks are looked up, not the whole collection
+        assert_eq!(checked.len(), 2);
+        assert_eq!(embedder.texts(), vec!["Lost before upsert."]);
+        assert_eq!(upserted.len(), 1);
+        assert_eq!(upserted[0].id, chunk_point_id(&chunks[1].qdrant_point_id));
+        assert_eq!(upserted[0].payload.chunk_index, 1);
+        assert_eq!(upserted[0].payload.model_id.as_deref(), Some("test-model"));
+        assert_eq!((stats.chunks_missing, stats.chunks_reembedded), (1, 1));
+    }
+
+    #[test]
+    fn test_rebuilt_payload_keeps_api_endpoint() {
+        let mut doc = Document::new(
+            "source-1".to_string(),
+            "/specs/petstore.yaml#/paths/~1pets/get".to_string(),
+            "hash".to_string(),
+        );
+        doc.api_endpoint_json = Some(
+            r#"{"method":"GET","path":"/pets","operation_id":"listPets","tags":["pets"]}"#
+                .to_string(),
+        );
+        let chunk = ChunkRecord {
+            id: "chunk-1".to_string(),
+            text: "# GET /pets".to_string(),
+            chunk_index: 0,
+            content_hash: "chunk-hash".to_string(),
+            headings: None,
+            modality: "text".to_string(),
+            media_url: None,
+            media_hash: None,
+        };
+
+        let payload = text_chunk_payload(
+            "source-1",
+            "openapi",
+            "/specs/petstore.yaml",
+            &doc,
+            &chunk,
+            "test-model",
+        );
+        assert_eq!(payload.api_method.as_deref(), Some("GET"));
+        assert_eq!(payload.api_path.as_deref(), Some("/pets"));
+        assert_eq!(payload.api_operation_id.as_deref(), Some("listPets"));
+        assert_eq!(payload.api_tags, vec!["pets"]);
     }
 }
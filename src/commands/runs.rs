@@ -0,0 +1,141 @@
+//! Runs command - show ingestion history
+
+use crate::commands::sources::resolve_source;
+use crate::commands::status::{run_status_label, RunSummary};
+use crate::error::Result;
+use crate::meta::{IngestionRun, MetaDb};
+use crate::time_range::TimeRange;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Runs options
+#[derive(Debug, Clone)]
+pub struct RunsOptions {
+    /// Only show runs for these sources (ID, name, or unique prefix)
+    pub source_ids: Option<Vec<String>>,
+    /// Maximum number of runs to show
+    pub limit: usize,
+    /// Only show runs that started inside this window
+    pub range: TimeRange,
+}
+
+impl Default for RunsOptions {
+    fn default() -> Self {
+        Self {
+            source_ids: None,
+            limit: 20,
+            range: TimeRange::default(),
+        }
+    }
+}
+
+/// An ingestion run with its source and duration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunListing {
+    pub id: String,
+    pub source_id: String,
+    pub source: String,
+    #[serde(flatten)]
+    pub run: RunSummary,
+    /// Wall-clock duration, unset while the run is still going
+    pub duration_secs: Option<f64>,
+}
+
+/// List recent ingestion runs, newest first
+pub async fn cmd_list_runs(db: &MetaDb, options: RunsOptions) -> Result<Vec<RunListing>> {
+    info!("Listing ingestion runs");
+    db.reap_stale_runs().await?;
+
+    let runs = match &options.source_ids {
+        Some(sources) => {
+            let mut source_ids = Vec::with_capacity(sources.len());
+            for source in sources {
+                source_ids.push(resolve_source(db, source).await?.id);
+            }
+            source_ids.sort();
+            source_ids.dedup();
+
+            let mut runs = Vec::new();
+            for source_id in &source_ids {
+                runs.extend(
+                    db.list_runs_in_range(Some(source_id), &options.range, options.limit)
+                        .await?,
+                );
+            }
+            runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+            runs.truncate(options.limit);
+            runs
+        }
+        None => {
+            db.list_runs_in_range(None, &options.range, options.limit)
+                .await?
+        }
+    };
+
+    let names: HashMap<String, String> = db
+        .list_sources()
+        .await?
+        .into_iter()
+        .map(|s| (s.id, s.name.unwrap_or(s.uri)))
+        .collect();
+
+    Ok(runs
+        .into_iter()
+        .map(|run| RunListing {
+            id: run.id.clone(),
+            source: names
+                .get(&run.source_id)
+                .cloned()
+                .unwrap_or_else(|| run.source_id.clone()),
+            source_id: run.source_id.clone(),
+            duration_secs: run_duration_secs(&run),
+            run: RunSummary::from(run),
+        })
+        .collect())
+}
+
+fn run_duration_secs(run: &IngestionRun) -> Option<f64> {
+    let started = DateTime::parse_from_rfc3339(&run.started_at).ok()?;
+    let completed = DateTime::parse_from_rfc3339(run.completed_at.as_deref()?).ok()?;
+    Some((completed - started).num_milliseconds() as f64 / 1000.0)
+}
+
+/// Print ingestion runs to console
+pub fn print_runs(runs: &[RunListing]) {
+    println!("\n🕘 Ingestion Runs\n");
+
+    if runs.is_empty() {
+        println!("No ingestion runs recorded.");
+        return;
+    }
+
+    for listing in runs {
+        let run = &listing.run;
+        let duration = listing
+            .duration_secs
+            .map(|d| format!("{:.1}s", d))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{} {} {} — {}",
+            run.started_at,
+            run.operation,
+            listing.source,
+            run_status_label(Some(run))
+        );
+        println!(
+            "  Duration: {}, Docs: {}, Chunks: +{} ~{} -{}",
+            duration,
+            run.docs_processed,
+            run.chunks_created,
+            run.chunks_updated,
+            run.chunks_deleted
+        );
+        if run.robots_ignored {
+            println!("  ⚠ robots.txt ignored (--no-robots)");
+        }
+        println!("  Run: {}  Source: {}", listing.id, listing.source_id);
+        println!();
+    }
+}
@@ -1,10 +1,88 @@
-//! Source management commands (rename, etc.)
+//! Source management commands (rename, tags, etc.)
 
-use crate::error::Result;
-use crate::meta::{MetaDb, Source};
+use crate::commands::ingest::{persist_crawl_overrides, CrawlOverrides};
+use crate::error::{Error, Result};
+use crate::meta::{MetaDb, Source, SourceType};
 
-/// Rename a source's display name
-pub async fn cmd_rename_source(db: &MetaDb, source_id: &str, new_name: String) -> Result<Source> {
+/// Resolve a source from its ID, exact name, or an unambiguous prefix of either
+pub async fn resolve_source(db: &MetaDb, query: &str) -> Result<Source> {
+    // An empty query is a prefix of every ID, so it would pick the only source
+    if query.trim().is_empty() {
+        return Err(Error::InvalidQuery(
+            "source is empty; pass a source ID, name or prefix".to_string(),
+        ));
+    }
+    if let Some(source) = db.get_source(query).await? {
+        return Ok(source);
+    }
+    if let Some(source) = db.get_source_by_name(query).await? {
+        return Ok(source);
+    }
+
+    let mut matches: Vec<Source> = db
+        .list_sources()
+        .await?
+        .into_iter()
+        .filter(|s| {
+            s.id.starts_with(query) || s.name.as_deref().is_some_and(|n| n.starts_with(query))
+        })
+        .collect();
+
+    match matches.len() {
+        0 => Err(Error::SourceNotFound(query.to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let candidates = matches
+                .iter()
+                .map(|s| format!("{} ({})", s.id, s.name.as_deref().unwrap_or(&s.uri)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(Error::AmbiguousSource(query.to_string(), candidates))
+        }
+    }
+}
+
+/// Fail when a source other than the one at `uri` already uses `name`
+pub async fn ensure_name_available(db: &MetaDb, name: &str, uri: &str) -> Result<()> {
+    match db.get_source_by_name(name).await? {
+        Some(other) if other.uri != uri => Err(Error::NameTaken(name.to_string(), other.id)),
+        _ => Ok(()),
+    }
+}
+
+/// Pick a name no other source uses, appending `-2`, `-3`, ... to `name` on collision.
+/// The source at `uri` may keep its own name.
+pub async fn unique_source_name(db: &MetaDb, name: &str, uri: &str) -> Result<String> {
+    let mut candidate = name.to_string();
+    let mut suffix = 2;
+    while let Some(other) = db.get_source_by_name(&candidate).await? {
+        if other.uri == uri {
+            break;
+        }
+        candidate = format!("{}-{}", name, suffix);
+        suffix += 1;
+    }
+    Ok(candidate)
+}
+
+/// Rename a source's display name.
+///
+/// A name already used by another source is auto-suffixed (`docs-2`), or
+/// rejected with [`Error::NameTaken`] when `no_clobber` is set.
+pub async fn cmd_rename_source(
+    db: &MetaDb,
+    source: &str,
+    new_name: String,
+    no_clobber: bool,
+) -> Result<Source> {
+    let source = resolve_source(db, source).await?;
+    let new_name = if no_clobber {
+        ensure_name_available(db, &new_name, &source.uri).await?;
+        new_name
+    } else {
+        unique_source_name(db, &new_name, &source.uri).await?
+    };
+    let source_id = &source.id;
     db.update_source_name(source_id, Some(new_name)).await?;
     let updated = db
         .get_source(source_id)
@@ -22,3 +100,243 @@ pub async fn cmd_clear_source_name(db: &MetaDb, source_id: &str) -> Result<Sourc
         .ok_or_else(|| crate::error::Error::SourceNotFound(source_id.to_string()))?;
     Ok(updated)
 }
+
+/// Add tags to a source
+pub async fn cmd_tag_source(db: &MetaDb, source: &str, tags: &[String]) -> Result<Source> {
+    let source_id = &resolve_source(db, source).await?.id;
+    db.add_source_tags(source_id, tags).await?;
+    let updated = db
+        .get_source(source_id)
+        .await?
+        .ok_or_else(|| crate::error::Error::SourceNotFound(source_id.to_string()))?;
+    Ok(updated)
+}
+
+/// Remove tags from a source
+pub async fn cmd_untag_source(db: &MetaDb, source: &str, tags: &[String]) -> Result<Source> {
+    let source_id = &resolve_source(db, source).await?.id;
+    db.remove_source_tags(source_id, tags).await?;
+    let updated = db
+        .get_source(source_id)
+        .await?
+        .ok_or_else(|| crate::error::Error::SourceNotFound(source_id.to_string()))?;
+    Ok(updated)
+}
+
+/// Change the crawl overrides stored on a URL source for later updates
+///
+/// Given overrides replace the stored ones and the rest are kept, unless
+/// `reset` discards the stored overrides first.
+pub async fn cmd_reconfigure_source(
+    db: &MetaDb,
+    source: &str,
+    overrides: CrawlOverrides,
+    reset: bool,
+) -> Result<Source> {
+    let mut source = resolve_source(db, source).await?;
+    if source.source_type.parse::<SourceType>().ok() != Some(SourceType::Url) {
+        return Err(Error::Other(format!(
+            "Source '{}' is a {} source; crawl settings only apply to URL sources",
+            source.id, source.source_type
+        )));
+    }
+    if reset {
+        source.config_json = None;
+    }
+    persist_crawl_overrides(db, &source, overrides).await?;
+    db.get_source(&source.id)
+        .await?
+        .ok_or_else(|| Error::SourceNotFound(source.id.clone()))
+}
+
+/// Print the crawl overrides stored on a URL source
+pub fn print_crawl_overrides(source: &Source) {
+    let overrides = CrawlOverrides::from_source(source);
+    println!("✓ Crawl settings for source '{}':", source.id);
+    if overrides == CrawlOverrides::default() {
+        println!("  (none; crawl.* config values apply)");
+        return;
+    }
+    if let Some(max_pages) = overrides.max_pages {
+        println!("  max_pages: {}", max_pages);
+    }
+    if let Some(max_depth) = overrides.max_depth {
+        println!("  max_depth: {}", max_depth);
+    }
+    if let Some(path_prefix) = &overrides.path_prefix {
+        println!("  path_prefix: {}", path_prefix);
+    }
+    if let Some(allow_subdomains) = overrides.allow_subdomains {
+        println!("  allow_subdomains: {}", allow_subdomains);
+    }
+    if let Some(js_mode) = overrides.js_mode {
+        println!("  js_mode: {}", js_mode);
+    }
+    if let Some(selector) = &overrides.wait_for_selector {
+        println!("  wait_for_selector: {}", selector);
+    }
+    if let Some(use_sitemap) = overrides.use_sitemap {
+        println!("  use_sitemap: {}", use_sitemap);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::SourceType;
+    use tempfile::TempDir;
+
+    async fn add_source(db: &MetaDb, id: &str, name: Option<&str>) -> Source {
+        let mut source = Source::new(
+            SourceType::Dir,
+            format!("/docs/{}", id),
+            name.map(String::from),
+        );
+        source.id = id.to_string();
+        db.insert_source(&source).await.unwrap();
+        source
+    }
+
+    #[tokio::test]
+    async fn test_resolve_source_by_id_name_and_prefix() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        add_source(&db, "3f2a9c10", Some("tokio-docs")).await;
+        add_source(&db, "3f7b0e22", Some("tower")).await;
+        add_source(&db, "a81c44d0", None).await;
+
+        assert_eq!(
+            resolve_source(&db, "3f2a9c10").await.unwrap().id,
+            "3f2a9c10"
+        );
+        assert_eq!(resolve_source(&db, "tower").await.unwrap().id, "3f7b0e22");
+        assert_eq!(resolve_source(&db, "3f2").await.unwrap().id, "3f2a9c10");
+        assert_eq!(resolve_source(&db, "tok").await.unwrap().id, "3f2a9c10");
+        assert_eq!(resolve_source(&db, "a8").await.unwrap().id, "a81c44d0");
+        assert!(matches!(
+            resolve_source(&db, "zzz").await,
+            Err(Error::SourceNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_source_rejects_empty_query() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        add_source(&db, "3f2a9c10", Some("tokio-docs")).await;
+
+        for query in ["", "  ", "\t"] {
+            assert!(
+                matches!(
+                    resolve_source(&db, query).await,
+                    Err(Error::InvalidQuery(_))
+                ),
+                "{query:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_source_reports_ambiguous_candidates() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        add_source(&db, "3f2a9c10", Some("tokio-docs")).await;
+        add_source(&db, "3f7b0e22", Some("tower")).await;
+
+        match resolve_source(&db, "3f").await {
+            Err(Error::AmbiguousSource(query, candidates)) => {
+                assert_eq!(query, "3f");
+                assert!(candidates.contains("3f2a9c10 (tokio-docs)"));
+                assert!(candidates.contains("3f7b0e22 (tower)"));
+            }
+            other => panic!("expected ambiguous error, got {:?}", other),
+        }
+
+        // A name that is also a prefix of another name still resolves exactly
+        add_source(&db, "c0ffee00", Some("tower-http")).await;
+        assert_eq!(resolve_source(&db, "tower").await.unwrap().id, "3f7b0e22");
+    }
+    #[tokio::test]
+    async fn test_rename_source_name_collisions() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        add_source(&db, "aa000000", Some("docs")).await;
+        add_source(&db, "bb000000", Some("docs-2")).await;
+        add_source(&db, "cc000000", Some("guides")).await;
+
+        // Explicit error under --no-clobber, leaving the name unchanged
+        match cmd_rename_source(&db, "cc000000", "docs".to_string(), true).await {
+            Err(Error::NameTaken(name, owner)) => {
+                assert_eq!(name, "docs");
+                assert_eq!(owner, "aa000000");
+            }
+            other => panic!("expected name-taken error, got {:?}", other),
+        }
+        let unchanged = db.get_source("cc000000").await.unwrap().unwrap();
+        assert_eq!(unchanged.name.as_deref(), Some("guides"));
+
+        // Otherwise the first free suffix is used
+        let renamed = cmd_rename_source(&db, "cc000000", "docs".to_string(), false)
+            .await
+            .unwrap();
+        assert_eq!(renamed.name.as_deref(), Some("docs-3"));
+
+        // A source may keep its own name
+        let same = cmd_rename_source(&db, "aa000000", "docs".to_string(), true)
+            .await
+            .unwrap();
+        assert_eq!(same.name.as_deref(), Some("docs"));
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_source_merges_and_resets_overrides() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let mut site = Source::new(
+            SourceType::Url,
+            "https://example.com/docs/".to_string(),
+            None,
+        );
+        site.id = "5e1f0a00".to_string();
+        db.insert_source(&site).await.unwrap();
+        add_source(&db, "d1r00000", None).await;
+
+        let set = |overrides: CrawlOverrides, reset: bool| {
+            let db = &db;
+            async move {
+                let updated = cmd_reconfigure_source(db, "5e1f", overrides, reset)
+                    .await
+                    .unwrap();
+                CrawlOverrides::from_source(&updated)
+            }
+        };
+
+        let stored = set(
+            CrawlOverrides {
+                max_pages: Some(5),
+                ..Default::default()
+            },
+            false,
+        )
+        .await;
+        assert_eq!(stored.max_pages, Some(5));
+
+        let stored = set(
+            CrawlOverrides {
+                max_depth: Some(1),
+                ..Default::default()
+            },
+            false,
+        )
+        .await;
+        assert_eq!((stored.max_pages, stored.max_depth), (Some(5), Some(1)));
+
+        let stored = set(CrawlOverrides::default(), true).await;
+        assert_eq!(stored, CrawlOverrides::default());
+
+        assert!(matches!(
+            cmd_reconfigure_source(&db, "d1r", CrawlOverrides::default(), false).await,
+            Err(Error::Other(_))
+        ));
+    }
+}
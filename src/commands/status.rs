@@ -1,9 +1,15 @@
 //! Status command implementation
 
+use crate::commands::sources::resolve_source;
 use crate::config::Config;
+use crate::embedding_backend::EmbeddingBackendClient;
 use crate::error::Result;
-use crate::meta::{GlobalStats, IngestionRun, MetaDb, RunOperation, RunStatus, SourceStats};
+use crate::meta::{
+    GlobalStats, IngestionRun, MetaDb, RunOperation, RunStatus, Source, SourceStats,
+};
+use crate::say;
 use crate::store::QdrantStore;
+use crate::time_range::TimeRange;
 use clap_complete::Shell;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -21,13 +27,119 @@ pub struct StatusInfo {
     pub collection_exists: bool,
     pub qdrant_points: usize,
     pub db_stats: GlobalStats,
+    pub health: StatusHealth,
+    /// Errors from each source's latest run (only set when requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_errors: Option<Vec<SourceRunErrors>>,
+}
+
+/// Health checks reported by `status`, one flag per check; `None` marks a
+/// check that could not run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusHealth {
+    pub qdrant_reachable: bool,
+    /// Skipped when Qdrant is unreachable or the collection does not exist
+    pub collection_dimension: Option<bool>,
+    pub db_writable: bool,
+    /// Skipped with `--offline`
+    pub embedding_backend: Option<bool>,
+    /// Short reason behind each flag
+    pub details: HealthDetails,
+}
+
+/// Reasons shown next to the [`StatusHealth`] checks, under the same names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthDetails {
+    pub qdrant_reachable: String,
+    pub collection_dimension: Option<String>,
+    pub db_writable: String,
+    pub embedding_backend: Option<String>,
+}
+
+impl StatusHealth {
+    fn new(
+        qdrant_reachable: HealthCheck,
+        collection_dimension: Option<HealthCheck>,
+        db_writable: HealthCheck,
+        embedding_backend: Option<HealthCheck>,
+    ) -> Self {
+        Self {
+            qdrant_reachable: qdrant_reachable.ok,
+            collection_dimension: collection_dimension.as_ref().map(|check| check.ok),
+            db_writable: db_writable.ok,
+            embedding_backend: embedding_backend.as_ref().map(|check| check.ok),
+            details: HealthDetails {
+                qdrant_reachable: qdrant_reachable.detail,
+                collection_dimension: collection_dimension.map(|check| check.detail),
+                db_writable: db_writable.detail,
+                embedding_backend: embedding_backend.map(|check| check.detail),
+            },
+        }
+    }
+}
+
+/// Outcome of one health check
+#[derive(Debug, Clone)]
+struct HealthCheck {
+    ok: bool,
+    /// Short reason shown next to the check
+    detail: String,
+}
+
+impl HealthCheck {
+    fn pass(detail: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Options for the status command
+#[derive(Debug, Clone, Default)]
+pub struct StatusOptions {
+    /// Include the errors of each source's latest run
+    pub show_errors: bool,
+    /// Check that the embedding backend answers
+    pub check_backend: bool,
+    /// Only report latest runs that started inside this window
+    pub range: TimeRange,
+}
+
+/// Errors recorded by a source's latest ingestion run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRunErrors {
+    pub source_id: String,
+    pub source: String,
+    pub operation: String,
+    pub started_at: String,
+    pub errors: Vec<String>,
 }
 
 /// Get system status
-pub async fn cmd_status(config: &Config, db: &MetaDb, store: &QdrantStore) -> Result<StatusInfo> {
+pub async fn cmd_status(
+    config: &Config,
+    db: &MetaDb,
+    store: &QdrantStore,
+    options: StatusOptions,
+) -> Result<StatusInfo> {
     info!("Getting status");
 
+    // Runs a killed process left running would otherwise be reported as live
+    db.reap_stale_runs().await?;
     let db_stats = db.get_global_stats().await?;
+    let run_errors = if options.show_errors {
+        Some(collect_run_errors(db, &options.range).await?)
+    } else {
+        None
+    };
 
     // Check if we can connect to Qdrant and if collection exists
     let (qdrant_connected, collection_exists, qdrant_points) = match store.collection_exists().await
@@ -49,6 +161,26 @@ pub async fn cmd_status(config: &Config, db: &MetaDb, store: &QdrantStore) -> Re
         }
     };
 
+    let qdrant_reachable = if qdrant_connected {
+        HealthCheck::pass(format!("answered at {}", config.qdrant_url))
+    } else {
+        HealthCheck::fail(format!("not reachable at {}", config.qdrant_url))
+    };
+    let collection_dimension = if collection_exists {
+        Some(check_collection_dimension(store).await)
+    } else {
+        None
+    };
+    let db_writable = match db.check_writable().await {
+        Ok(()) => HealthCheck::pass("writable"),
+        Err(e) => HealthCheck::fail(e.to_string()),
+    };
+    let embedding_backend = if options.check_backend {
+        Some(check_embedding_backend(config.embedding_backend_url()).await)
+    } else {
+        None
+    };
+
     Ok(StatusInfo {
         config_path: config.paths.config_file.display().to_string(),
         db_path: config.paths.db_file.display().to_string(),
@@ -59,18 +191,80 @@ pub async fn cmd_status(config: &Config, db: &MetaDb, store: &QdrantStore) -> Re
         collection_exists,
         qdrant_points,
         db_stats,
+        health: StatusHealth::new(
+            qdrant_reachable,
+            collection_dimension,
+            db_writable,
+            embedding_backend,
+        ),
+        run_errors,
     })
 }
 
-/// Source information with stats
+async fn check_collection_dimension(store: &QdrantStore) -> HealthCheck {
+    let expected = store.dimension();
+    if expected == 0 {
+        return HealthCheck::fail("model dimension unknown; set embedding.dimension");
+    }
+    match store.collection_dimension().await {
+        Ok(Some(actual)) if actual == expected => {
+            HealthCheck::pass(format!("{} dimensions", actual))
+        }
+        Ok(Some(actual)) => HealthCheck::fail(format!(
+            "collection has {} dimensions, model expects {}",
+            actual, expected
+        )),
+        Ok(None) => HealthCheck::fail("collection uses named vectors"),
+        Err(e) => HealthCheck::fail(e.to_string()),
+    }
+}
+
+async fn check_embedding_backend(url: &str) -> HealthCheck {
+    let reachable = match EmbeddingBackendClient::new(url) {
+        Ok(client) => client.ping().await,
+        Err(e) => Err(e),
+    };
+    match reachable {
+        Ok(()) => HealthCheck::pass(format!("answered at {}", url)),
+        Err(_) => HealthCheck::fail(format!("not reachable at {}", url)),
+    }
+}
+
+/// Collect the errors of each source's latest run, skipping clean runs
+async fn collect_run_errors(db: &MetaDb, range: &TimeRange) -> Result<Vec<SourceRunErrors>> {
+    let mut result = Vec::new();
+    for source in db.list_sources().await? {
+        let Some(run) = db.get_latest_run(&source.id).await? else {
+            continue;
+        };
+        if !range.contains_rfc3339(&run.started_at) {
+            continue;
+        }
+        let errors = run.errors();
+        if errors.is_empty() {
+            continue;
+        }
+        result.push(SourceRunErrors {
+            source: source.name.unwrap_or(source.uri),
+            source_id: source.id,
+            operation: run.operation,
+            started_at: run.started_at,
+            errors,
+        });
+    }
+    Ok(result)
+}
+
+/// A source listing entry with stats and the outcome of its latest run
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SourceInfo {
+pub struct SourceListing {
     pub id: String,
     pub source_type: String,
     pub uri: String,
     pub name: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub tags: Vec<String>,
     pub stats: SourceStats,
     pub state: String,
     pub last_updated: Option<String>,
@@ -88,16 +282,14 @@ pub struct RunSummary {
     pub chunks_updated: i32,
     pub chunks_deleted: i32,
     pub error_count: usize,
+    /// The crawl bypassed robots.txt (`--no-robots`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub robots_ignored: bool,
 }
 
 impl From<IngestionRun> for RunSummary {
     fn from(run: IngestionRun) -> Self {
-        let error_count = run
-            .errors_json
-            .as_ref()
-            .and_then(|e| serde_json::from_str::<Vec<String>>(e).ok())
-            .map(|v| v.len())
-            .unwrap_or(0);
+        let error_count = run.errors().len();
 
         Self {
             operation: run.operation,
@@ -109,67 +301,187 @@ impl From<IngestionRun> for RunSummary {
             chunks_updated: run.chunks_updated,
             chunks_deleted: run.chunks_deleted,
             error_count,
+            robots_ignored: run.robots_ignored,
         }
     }
 }
 
-/// List all sources with their stats
-pub async fn cmd_list_sources(db: &MetaDb) -> Result<Vec<SourceInfo>> {
+/// List all sources with their stats and last ingestion run
+pub async fn cmd_list_sources(db: &MetaDb) -> Result<Vec<SourceListing>> {
     info!("Listing sources");
 
     let sources = db.list_sources().await?;
     let mut result = Vec::with_capacity(sources.len());
 
     for source in sources {
-        let stats = db.get_source_stats(&source.id).await?;
         let latest_run = db.get_latest_run(&source.id).await?;
-        let (state, last_updated) = derive_state(latest_run.as_ref());
-        result.push(SourceInfo {
-            id: source.id,
-            source_type: source.source_type,
-            uri: source.uri,
-            name: source.name,
-            created_at: source.created_at,
-            updated_at: source.updated_at,
-            stats,
-            state,
-            last_updated,
-            last_run: latest_run.map(RunSummary::from),
-        });
+        result.push(source_listing(db, source, latest_run).await?);
     }
 
     Ok(result)
 }
 
+/// Build the listing entry of a source from its stats and latest run
+async fn source_listing(
+    db: &MetaDb,
+    source: Source,
+    latest_run: Option<IngestionRun>,
+) -> Result<SourceListing> {
+    let stats = db.get_source_stats(&source.id).await?;
+    let (state, last_updated) = derive_state(latest_run.as_ref());
+    let tags = source.tags();
+    Ok(SourceListing {
+        id: source.id,
+        source_type: source.source_type,
+        uri: source.uri,
+        name: source.name,
+        created_at: source.created_at,
+        updated_at: source.updated_at,
+        tags,
+        stats,
+        state,
+        last_updated,
+        last_run: latest_run.map(RunSummary::from),
+    })
+}
+
+/// Everything recorded about a single source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceInfo {
+    #[serde(flatten)]
+    pub source: SourceListing,
+    /// Settings stored with the source (directory filters or crawl overrides)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+    /// Errors recorded by the latest run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Gather the details, stored settings, stats and latest run of one source
+pub async fn cmd_source_info(db: &MetaDb, source: &str) -> Result<SourceInfo> {
+    let source = resolve_source(db, source).await?;
+    let config = source
+        .config_json
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok());
+    let latest_run = db.get_latest_run(&source.id).await?;
+    let errors = latest_run
+        .as_ref()
+        .map(IngestionRun::errors)
+        .unwrap_or_default();
+
+    Ok(SourceInfo {
+        source: source_listing(db, source, latest_run).await?,
+        config,
+        errors,
+    })
+}
+
 /// Print status to console
-pub fn print_status(status: &StatusInfo) {
-    println!("\n📊 librarian Status\n");
-    println!("Configuration: {}", status.config_path);
-    println!("Database: {}", status.db_path);
-    println!("\nQdrant:");
-    println!("  URL: {}", status.qdrant_url);
-    println!("  Collection: {}", status.collection_name);
+pub fn print_status(status: &StatusInfo, color: bool) {
+    say!("\n📊 librarian Status\n");
+    say!("{:<15}{}", "Configuration:", status.config_path);
+    say!("{:<15}{}", "Database:", status.db_path);
+    say!("\nQdrant:");
+    say!("  {:<13}{}", "URL:", status.qdrant_url);
+    say!("  {:<13}{}", "Collection:", status.collection_name);
 
     let connection_status = if status.qdrant_connected {
         if status.collection_exists {
-            "✓ Connected"
+            paint("✓ Connected", GREEN, color)
         } else {
-            "⚠ Connected (collection not created - run 'librarian ingest' to create)"
+            paint(
+                "⚠ Connected (collection not created - run 'librarian ingest' to create)",
+                YELLOW,
+                color,
+            )
         }
     } else {
-        "✗ Not connected"
+        paint("✗ Not connected", RED, color)
     };
-    println!("  Status: {}", connection_status);
-    println!("  Points: {}", status.qdrant_points);
-    println!("\nEmbedding Model: {}", status.embedding_model);
-    println!("\nDatabase Stats:");
-    println!("  Sources: {}", status.db_stats.source_count);
-    println!("  Documents: {}", status.db_stats.document_count);
-    println!("  Chunks: {}", status.db_stats.chunk_count);
+    say!("  {:<13}{}", "Status:", connection_status);
+    say!("  {:<13}{}", "Points:", status.qdrant_points);
+    say!("\n{:<15}{}", "Embedding:", status.embedding_model);
+    say!("\nDatabase Stats:");
+    say!("  {:<13}{}", "Sources:", status.db_stats.source_count);
+    say!("  {:<13}{}", "Documents:", status.db_stats.document_count);
+    say!("  {:<13}{}", "Chunks:", status.db_stats.chunk_count);
+
+    let health = &status.health;
+    say!("\nHealth:");
+    let details = &health.details;
+    print_health_check(
+        "Qdrant",
+        Some(health.qdrant_reachable),
+        &details.qdrant_reachable,
+        color,
+    );
+    print_health_check(
+        "Dimension",
+        health.collection_dimension,
+        details.collection_dimension.as_deref().unwrap_or_default(),
+        color,
+    );
+    print_health_check(
+        "Database",
+        Some(health.db_writable),
+        &details.db_writable,
+        color,
+    );
+    print_health_check(
+        "Embeddings",
+        health.embedding_backend,
+        details.embedding_backend.as_deref().unwrap_or_default(),
+        color,
+    );
+
+    if let Some(run_errors) = &status.run_errors {
+        say!("\nRun Errors:");
+        if run_errors.is_empty() {
+            say!("  None - latest runs completed cleanly");
+        }
+        for entry in run_errors {
+            say!(
+                "  {} ({}) - {} at {}: {} errors",
+                entry.source,
+                entry.source_id,
+                entry.operation,
+                entry.started_at,
+                entry.errors.len()
+            );
+            for error in &entry.errors {
+                say!("    ✗ {}", error);
+            }
+        }
+    }
+}
+
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+const RED: &str = "31";
+const DIM: &str = "2";
+
+/// Wrap text in an ANSI color when color output is enabled
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn print_health_check(label: &str, ok: Option<bool>, detail: &str, color: bool) {
+    let line = match ok {
+        Some(true) => paint(&format!("✓ {}", detail), GREEN, color),
+        Some(false) => paint(&format!("✗ {}", detail), RED, color),
+        None => paint("– skipped", DIM, color),
+    };
+    say!("  {:<13}{}", format!("{}:", label), line);
 }
 
 /// Print sources list to console
-pub fn print_sources(sources: &[SourceInfo]) {
+pub fn print_sources(sources: &[SourceListing]) {
     println!("\n📚 Registered Sources\n");
 
     if sources.is_empty() {
@@ -179,12 +491,16 @@ pub fn print_sources(sources: &[SourceInfo]) {
 
     for source in sources {
         println!(
-            "• {} [{}]",
+            "• {} [{}]  {}",
             source.name.as_deref().unwrap_or(&source.uri),
-            source.source_type
+            source.source_type,
+            run_status_label(source.last_run.as_ref())
         );
         println!("  ID: {}", source.id);
         println!("  URI: {}", source.uri);
+        if !source.tags.is_empty() {
+            println!("  Tags: {}", source.tags.join(", "));
+        }
         println!(
             "  Documents: {}, Chunks: {}",
             source.stats.document_count, source.stats.chunk_count
@@ -194,13 +510,111 @@ pub fn print_sources(sources: &[SourceInfo]) {
             println!("  Last update: {}", last_updated);
         }
         if let Some(run) = &source.last_run {
-            println!("  Last operation: {} ({})", run.operation, run.status);
+            println!(
+                "  Last run: {} {} at {} ({} docs, {} chunks created, {} updated, {} deleted)",
+                run.operation,
+                run.status,
+                run.completed_at.as_deref().unwrap_or(&run.started_at),
+                run.docs_processed,
+                run.chunks_created,
+                run.chunks_updated,
+                run.chunks_deleted
+            );
         }
         println!("  Created: {}", source.created_at);
         println!();
     }
 }
 
+/// Errors listed by `print_source_info` before it summarizes the rest
+const INFO_MAX_ERRORS: usize = 10;
+
+/// Print the details of a single source
+pub fn print_source_info(info: &SourceInfo) {
+    let source = &info.source;
+    println!(
+        "\n📖 {} [{}]  {}\n",
+        source.name.as_deref().unwrap_or(&source.uri),
+        source.source_type,
+        run_status_label(source.last_run.as_ref())
+    );
+    println!("ID: {}", source.id);
+    println!("URI: {}", source.uri);
+    if let Some(name) = &source.name {
+        println!("Name: {}", name);
+    }
+    if !source.tags.is_empty() {
+        println!("Tags: {}", source.tags.join(", "));
+    }
+    println!("Created: {}", source.created_at);
+    println!("State: {}", source.state);
+    if let Some(last_updated) = &source.last_updated {
+        println!("Last update: {}", last_updated);
+    }
+
+    println!("\nContents:");
+    println!("  Documents: {}", source.stats.document_count);
+    println!("  Chunks: {}", source.stats.chunk_count);
+
+    println!("\nStored settings:");
+    match info.config.as_ref().and_then(|config| config.as_object()) {
+        Some(settings) if !settings.is_empty() => {
+            for (key, value) in settings {
+                match value.as_str() {
+                    Some(text) => println!("  {}: {}", key, text),
+                    None => println!("  {}: {}", key, value),
+                }
+            }
+        }
+        _ => println!("  None - config defaults apply"),
+    }
+
+    println!("\nLast run:");
+    match &source.last_run {
+        Some(run) => {
+            println!("  {} {} at {}", run.operation, run.status, run.started_at);
+            if let Some(completed_at) = &run.completed_at {
+                println!("  Completed: {}", completed_at);
+            }
+            println!(
+                "  {} docs, {} chunks created, {} updated, {} deleted",
+                run.docs_processed, run.chunks_created, run.chunks_updated, run.chunks_deleted
+            );
+        }
+        None => println!("  Never run"),
+    }
+
+    if !info.errors.is_empty() {
+        println!("\nErrors ({}):", info.errors.len());
+        for error in info.errors.iter().take(INFO_MAX_ERRORS) {
+            println!("  ✗ {}", error);
+        }
+        if info.errors.len() > INFO_MAX_ERRORS {
+            println!(
+                "  ... and {} more (use --json to see all)",
+                info.errors.len() - INFO_MAX_ERRORS
+            );
+        }
+    }
+}
+
+/// Short status column for the latest run of a source
+pub(crate) fn run_status_label(run: Option<&RunSummary>) -> String {
+    let Some(run) = run else {
+        return "– never run".to_string();
+    };
+
+    match RunStatus::from_str(&run.status) {
+        Ok(RunStatus::Completed) if run.error_count > 0 => {
+            format!("⚠ completed with {} errors", run.error_count)
+        }
+        Ok(RunStatus::Completed) => "✓ completed".to_string(),
+        Ok(RunStatus::Running) => "… running".to_string(),
+        Ok(RunStatus::Failed) => "✗ failed".to_string(),
+        Err(_) => run.status.clone(),
+    }
+}
+
 fn derive_state(run: Option<&IngestionRun>) -> (String, Option<String>) {
     let Some(run) = run else {
         return ("ready".to_string(), None);
@@ -212,7 +626,7 @@ fn derive_state(run: Option<&IngestionRun>) -> (String, Option<String>) {
     let state = match status {
         RunStatus::Running => match operation {
             RunOperation::Update => "updating",
-            RunOperation::Reindex | RunOperation::Ingest => "indexing",
+            RunOperation::Reindex | RunOperation::Reembed | RunOperation::Ingest => "indexing",
         },
         RunStatus::Completed => "ready",
         RunStatus::Failed => "error",
@@ -226,28 +640,271 @@ fn derive_state(run: Option<&IngestionRun>) -> (String, Option<String>) {
     (state.to_string(), last_updated)
 }
 
-/// Print source IDs with descriptions for shell completions
-pub fn print_source_completions(sources: &[SourceInfo], shell: Shell) {
-    for source in sources {
+/// Completion candidates for arguments that take a source: every ID, then
+/// every name, one per line in the shell's format.
+///
+/// Zsh lines are `value:description` with `:` escaped in both parts; fish lines
+/// are `value<TAB>description`. Bash gets bare values, so names containing
+/// whitespace are left out there.
+pub fn format_source_completions(sources: &[SourceListing], shell: Shell) -> Vec<String> {
+    let ids = sources.iter().map(|source| {
         let display_name = source.name.as_deref().unwrap_or(&source.uri);
-        let mut description = format!(
+        let description = format!(
             "{} (at {}), Created {}",
             display_name, source.uri, source.created_at
         );
-        description = description.replace('\n', " ");
+        (source.id.as_str(), description)
+    });
+    let names = sources.iter().filter_map(|source| {
+        let name = source.name.as_deref().filter(|name| *name != source.uri)?;
+        Some((name, format!("Source {} (at {})", source.id, source.uri)))
+    });
 
-        match shell {
-            Shell::Zsh => {
-                let sanitized = description.replace(':', "\\:");
-                println!("{}:{}", source.id, sanitized);
-            }
-            Shell::Fish => {
-                let sanitized = description.replace('\t', " ");
-                println!("{}\t{}", source.id, sanitized);
-            }
-            _ => {
-                println!("{}", source.id);
+    ids.chain(names)
+        .filter_map(|(value, description)| {
+            let value = value.replace('\n', " ");
+            let description = description.replace('\n', " ");
+            match shell {
+                Shell::Zsh => Some(format!(
+                    "{}:{}",
+                    value.replace(':', "\\:"),
+                    description.replace(':', "\\:")
+                )),
+                Shell::Fish => Some(format!(
+                    "{}\t{}",
+                    value.replace('\t', " "),
+                    description.replace('\t', " ")
+                )),
+                _ => (!value.contains(char::is_whitespace)).then_some(value),
             }
+        })
+        .collect()
+}
+
+/// Print source IDs and names with descriptions for shell completions
+pub fn print_source_completions(sources: &[SourceListing], shell: Shell) {
+    for line in format_source_completions(sources, shell) {
+        println!("{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::{Chunk, Document, SourceType};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_list_sources_reflects_latest_run() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let listing = cmd_list_sources(&db).await.unwrap();
+        assert_eq!(listing.len(), 1);
+        assert!(listing[0].last_run.is_none());
+        assert_eq!(run_status_label(None), "– never run");
+
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        db.complete_ingestion_run(
+            &run.id,
+            RunStatus::Failed,
+            3,
+            10,
+            2,
+            1,
+            Some(vec!["boom".to_string()]),
+        )
+        .await
+        .unwrap();
+
+        let listing = cmd_list_sources(&db).await.unwrap();
+        let last_run = listing[0].last_run.as_ref().expect("last run should be set");
+        assert_eq!(listing[0].state, "error");
+        assert_eq!(last_run.status, "failed");
+        assert_eq!(last_run.docs_processed, 3);
+        assert_eq!(last_run.chunks_created, 10);
+        assert_eq!(last_run.error_count, 1);
+        assert!(last_run.completed_at.is_some());
+        assert_eq!(run_status_label(Some(last_run)), "✗ failed");
+
+        let json = serde_json::to_value(&listing[0]).unwrap();
+        assert_eq!(json["last_run"]["status"], "failed");
+    }
+
+    #[tokio::test]
+    async fn test_source_completions_are_well_formed_per_shell() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        for (uri, name) in [
+            ("/docs", Some("docs")),
+            ("https://example.com/a:b", Some("My Docs: v2")),
+            ("/notes", None),
+        ] {
+            let source = Source::new(SourceType::Dir, uri.to_string(), name.map(String::from));
+            db.insert_source(&source).await.unwrap();
+        }
+        let sources = cmd_list_sources(&db).await.unwrap();
+
+        let bash = format_source_completions(&sources, Shell::Bash);
+        for source in &sources {
+            assert!(bash.contains(&source.id));
+        }
+        assert!(bash.contains(&"docs".to_string()));
+        assert!(bash.iter().all(|line| !line.contains(char::is_whitespace)));
+        assert_eq!(bash.len(), 4);
+
+        // Every line has exactly one unescaped separator
+        let zsh = format_source_completions(&sources, Shell::Zsh);
+        assert_eq!(zsh.len(), 5);
+        for line in &zsh {
+            assert_eq!(line.replace("\\:", "").matches(':').count(), 1, "{line}");
+        }
+        assert!(zsh.iter().any(|line| line.starts_with("My Docs\\: v2:")));
+
+        let fish = format_source_completions(&sources, Shell::Fish);
+        assert_eq!(fish.len(), 5);
+        for line in &fish {
+            assert_eq!(line.matches('\t').count(), 1, "{line}");
+            assert!(!line.contains('\n'));
         }
+        assert!(fish.iter().any(|line| line.starts_with("My Docs: v2\t")));
+    }
+
+    #[tokio::test]
+    async fn test_source_info_aggregates_source_details() {
+        let tmp = TempDir::new().unwrap();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        let mut source = Source::new(
+            SourceType::Url,
+            "https://docs.example.com/".to_string(),
+            Some("example-docs".to_string()),
+        );
+        source.config_json = Some(r#"{"max_pages":50,"path_prefix":"/guide"}"#.to_string());
+        db.insert_source(&source).await.unwrap();
+        db.update_source_tags(&source.id, &["rust".to_string()])
+            .await
+            .unwrap();
+
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "https://docs.example.com/guide".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let chunk = Chunk::new(
+            doc.id.clone(),
+            0,
+            "chunk-hash".to_string(),
+            "Guide text".to_string(),
+            0,
+            10,
+            None,
+        );
+        db.upsert_chunk(&chunk).await.unwrap();
+
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        let errors: Vec<String> = (0..12).map(|i| format!("page {} failed", i)).collect();
+        db.complete_ingestion_run(&run.id, RunStatus::Completed, 1, 1, 0, 0, Some(errors))
+            .await
+            .unwrap();
+
+        let info = cmd_source_info(&db, "example-docs").await.unwrap();
+        assert_eq!(info.source.id, source.id);
+        assert_eq!(info.source.tags, vec!["rust".to_string()]);
+        assert_eq!(info.source.stats.document_count, 1);
+        assert_eq!(info.source.stats.chunk_count, 1);
+        let config = info.config.clone().expect("stored config");
+        assert_eq!(config["max_pages"], 50);
+        assert_eq!(config["path_prefix"], "/guide");
+        let last_run = info.source.last_run.clone().expect("last run");
+        assert_eq!(last_run.status, "completed");
+        assert_eq!(info.errors.len(), 12);
+        assert_eq!(info.errors[0], "page 0 failed");
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["uri"], "https://docs.example.com/");
+        assert_eq!(json["errors"].as_array().unwrap().len(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_status_runs_with_embedding_backend_unreachable() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.embedding.url = "http://127.0.0.1:9".to_string();
+        config.qdrant_url = "http://127.0.0.1:9".to_string();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+
+        // Neither the backend nor Qdrant is reachable; the dimension comes from the registry
+        let store = QdrantStore::connect_offline(&config).await.unwrap();
+        assert_eq!(store.dimension(), 384);
+
+        let status = cmd_status(&config, &db, &store, StatusOptions::default())
+            .await
+            .unwrap();
+        assert!(!status.qdrant_connected);
+        assert_eq!(status.embedding_model, config.embedding.model);
+    }
+
+    #[tokio::test]
+    async fn test_status_json_reports_health_checks() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let backend = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/capabilities"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&backend)
+            .await;
+
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.embedding.url = backend.uri();
+        config.qdrant_url = "http://127.0.0.1:9".to_string();
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let store = QdrantStore::connect_offline(&config).await.unwrap();
+        let options = StatusOptions {
+            show_errors: false,
+            check_backend: true,
+            range: TimeRange::default(),
+        };
+
+        let status = cmd_status(&config, &db, &store, options.clone())
+            .await
+            .unwrap();
+        let json = serde_json::to_value(&status).unwrap();
+        let health = &json["health"];
+        assert_eq!(health["qdrant_reachable"], false);
+        assert!(health["collection_dimension"].is_null());
+        assert_eq!(health["db_writable"], true);
+        assert_eq!(health["embedding_backend"], true);
+        assert!(health["details"]["qdrant_reachable"]
+            .as_str()
+            .unwrap()
+            .contains("127.0.0.1:9"));
+
+        // Simulate the backend going away
+        config.embedding.url = "http://127.0.0.1:9".to_string();
+        let status = cmd_status(&config, &db, &store, options).await.unwrap();
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["health"]["embedding_backend"], false);
+        assert!(json["health"]["details"]["embedding_backend"]
+            .as_str()
+            .unwrap()
+            .contains("127.0.0.1:9"));
     }
 }
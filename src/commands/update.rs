@@ -1,13 +1,15 @@
 //! Update command - incrementally refresh sources and prune vectors
 
 use crate::commands::{
-    cmd_ingest_dir, cmd_ingest_sitemap, cmd_ingest_url, CrawlOverrides, IngestStats,
+    cmd_ingest_dir, cmd_ingest_openapi, cmd_ingest_sitemap, cmd_ingest_url, cmd_ingest_urls,
+    CrawlOverrides, DirIngestOptions, IngestStats, UrlList,
 };
 use crate::commands::{cmd_prune, PruneOptions, PruneStats};
 use crate::config::{Config, ResolvedEmbeddingConfig};
 use crate::embed::Embedder;
 use crate::error::Result;
 use crate::meta::{MetaDb, RunOperation, SourceType};
+use crate::say;
 use crate::store::QdrantStore;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -82,6 +84,7 @@ pub async fn cmd_update(
                     store,
                     path,
                     source.name.clone(),
+                    DirIngestOptions::default(),
                     RunOperation::Update,
                     false,
                 )
@@ -100,6 +103,7 @@ pub async fn cmd_update(
                     overrides,
                     RunOperation::Update,
                     false,
+                    None,
                 )
                 .await
             }
@@ -113,6 +117,38 @@ pub async fn cmd_update(
                     &source.uri,
                     source.name.clone(),
                     None,
+                    false,
+                    RunOperation::Update,
+                    false,
+                    None,
+                )
+                .await
+            }
+            SourceType::Urls => {
+                cmd_ingest_urls(
+                    config,
+                    embedding,
+                    embedder,
+                    db,
+                    store,
+                    &source.uri,
+                    &UrlList::from_source(&source),
+                    source.name.clone(),
+                    RunOperation::Update,
+                    false,
+                    None,
+                )
+                .await
+            }
+            SourceType::Openapi => {
+                cmd_ingest_openapi(
+                    config,
+                    embedding,
+                    embedder,
+                    db,
+                    store,
+                    &source.uri,
+                    source.name.clone(),
                     RunOperation::Update,
                     false,
                 )
@@ -147,6 +183,8 @@ pub async fn cmd_update(
             source_ids: options.source_ids.clone(),
             dry_run: false,
             remove_orphans: true,
+            remove_dangling: false,
+            concurrency: 4,
         };
 
         match cmd_prune(config, db, store, prune_options).await {
@@ -166,35 +204,35 @@ pub async fn cmd_update(
 
 /// Print update stats to console
 pub fn print_update_stats(stats: &UpdateStats) {
-    println!("\n♻️  Update Complete\n");
-    println!("Sources updated: {}", stats.sources_updated);
-    println!("Documents processed: {}", stats.ingest.docs_processed);
-    println!("Documents skipped: {}", stats.ingest.docs_skipped);
-    println!("Chunks created: {}", stats.ingest.chunks_created);
-    println!("Chunks updated: {}", stats.ingest.chunks_updated);
+    say!("\n♻️  Update Complete\n");
+    say!("Sources updated: {}", stats.sources_updated);
+    say!("Documents processed: {}", stats.ingest.docs_processed);
+    say!("Documents skipped: {}", stats.ingest.docs_skipped);
+    say!("Chunks created: {}", stats.ingest.chunks_created);
+    say!("Chunks updated: {}", stats.ingest.chunks_updated);
     if stats.ingest.chunks_deleted > 0 {
-        println!("Chunks deleted: {}", stats.ingest.chunks_deleted);
+        say!("Chunks deleted: {}", stats.ingest.chunks_deleted);
     }
     if let Some(prune) = &stats.prune {
-        println!("\nPrune:");
-        println!("  Documents removed: {}", prune.documents_removed);
-        println!("  Chunks removed: {}", prune.chunks_removed);
+        say!("\nPrune:");
+        say!("  Documents removed: {}", prune.documents_removed);
+        say!("  Chunks removed: {}", prune.chunks_removed);
         if prune.orphan_points_removed > 0 {
-            println!("  Orphan points removed: {}", prune.orphan_points_removed);
+            say!("  Orphan points removed: {}", prune.orphan_points_removed);
         }
     }
 
     if !stats.ingest.overlap_warnings.is_empty() {
-        println!("\nWarnings:");
+        say!("\nWarnings:");
         for warning in &stats.ingest.overlap_warnings {
-            println!("- {}", warning);
+            say!("- {}", warning);
         }
     }
 
     if !stats.ingest.errors.is_empty() {
-        println!("\nErrors:");
+        say!("\nErrors:");
         for error in &stats.ingest.errors {
-            println!("- {}", error);
+            say!("- {}", error);
         }
     }
 }
@@ -0,0 +1,98 @@
+//! Vacuum command - compact the metadata database
+
+use crate::error::Result;
+use crate::meta::MetaDb;
+use crate::output::format_size;
+use crate::say;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+/// Vacuum statistics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VacuumStats {
+    pub db_path: String,
+    /// Database size in bytes (including the WAL) before vacuuming
+    pub size_before: u64,
+    /// Database size in bytes (including the WAL) after vacuuming
+    pub size_after: u64,
+}
+
+impl VacuumStats {
+    pub fn bytes_reclaimed(&self) -> u64 {
+        self.size_before.saturating_sub(self.size_after)
+    }
+}
+
+/// Compact the metadata database and refresh its query planner statistics
+pub async fn cmd_vacuum(db: &MetaDb, db_path: &Path) -> Result<VacuumStats> {
+    info!("Vacuuming {}", db_path.display());
+
+    let size_before = database_size(db_path);
+    db.vacuum().await?;
+    let size_after = database_size(db_path);
+
+    Ok(VacuumStats {
+        db_path: db_path.display().to_string(),
+        size_before,
+        size_after,
+    })
+}
+
+/// Size of the database file plus its write-ahead log
+fn database_size(db_path: &Path) -> u64 {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    [db_path, Path::new(&wal)]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Print vacuum statistics
+pub fn print_vacuum_stats(stats: &VacuumStats) {
+    say!("\n🧹 Vacuum Complete\n");
+    say!("Database: {}", stats.db_path);
+    say!("Size before: {}", format_size(stats.size_before));
+    say!("Size after: {}", format_size(stats.size_after));
+    say!("Reclaimed: {}", format_size(stats.bytes_reclaimed()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::{Source, SourceType};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_vacuum_shrinks_database_after_deletes() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().join("metadata.db");
+        let db = MetaDb::new(&db_path).await.unwrap();
+
+        // Names are padded so the deleted rows span many pages
+        let padding = "x".repeat(4096);
+        let mut ids = Vec::new();
+        for i in 0..200 {
+            let source = Source::new(
+                SourceType::Dir,
+                format!("/docs/{}", i),
+                Some(format!("{}-{}", i, padding)),
+            );
+            db.insert_source(&source).await.unwrap();
+            ids.push(source.id);
+        }
+        for id in &ids {
+            db.delete_source(id).await.unwrap();
+        }
+
+        let stats = cmd_vacuum(&db, &db_path).await.unwrap();
+        assert!(stats.size_before > 200 * 4096);
+        assert!(stats.size_after < stats.size_before);
+        assert_eq!(
+            stats.bytes_reclaimed(),
+            stats.size_before - stats.size_after
+        );
+    }
+}
@@ -0,0 +1,420 @@
+//! Watch command - re-ingest a directory source when its files change
+
+use crate::commands::ingest::{collect_dir_files, process_file, remove_stale_documents, DirFilter};
+use crate::commands::prune::{cmd_prune, PruneOptions};
+use crate::config::{Config, ResolvedEmbeddingConfig};
+use crate::embed::Embedder;
+use crate::error::{Error, Result};
+use crate::meta::{MetaDb, RunOperation, RunStatus, Source, SourceType};
+use crate::say;
+use crate::store::QdrantStore;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Watch options
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Quiet period after the last file event before re-ingesting
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Statistics for one debounced batch of file changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchBatchStats {
+    pub files_changed: usize,
+    pub documents_removed: usize,
+    pub chunks_created: i32,
+    pub chunks_updated: i32,
+    pub chunks_deleted: i32,
+    pub orphan_points_removed: usize,
+    pub errors: Vec<String>,
+}
+
+/// Paths touched by file events, collected during a debounce window
+#[derive(Debug, Clone, Default)]
+pub struct PendingChanges {
+    paths: BTreeSet<PathBuf>,
+}
+
+impl PendingChanges {
+    /// Record the paths of a file event (access events are ignored)
+    pub fn record(&mut self, event: &Event) {
+        match event.kind {
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any => {
+                self.paths.extend(event.paths.iter().cloned());
+            }
+            EventKind::Access(_) | EventKind::Other => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+/// Watch a directory source and re-ingest changed files until interrupted
+pub async fn cmd_watch(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &MetaDb,
+    store: &QdrantStore,
+    source_id: &str,
+    options: WatchOptions,
+) -> Result<()> {
+    let source = db
+        .get_source(source_id)
+        .await?
+        .ok_or_else(|| Error::SourceNotFound(source_id.to_string()))?;
+
+    if source.get_type()? != SourceType::Dir {
+        return Err(Error::Config(format!(
+            "Source '{}' is a {} source; only directory sources can be watched",
+            source.id, source.source_type
+        )));
+    }
+
+    let root = PathBuf::from(&source.uri);
+    if !root.is_dir() {
+        return Err(Error::InvalidPath(format!(
+            "{}: directory no longer exists",
+            source.uri
+        )));
+    }
+
+    store.ensure_collection().await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let _ = tx.send(res);
+    })
+    .map_err(|e| Error::Other(format!("Failed to start file watcher: {}", e)))?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| Error::Other(format!("Failed to watch {}: {}", root.display(), e)))?;
+
+    info!("Watching {} (press Ctrl+C to stop)", root.display());
+
+    loop {
+        let first = tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+        };
+
+        let mut pending = PendingChanges::default();
+        record_result(&mut pending, first);
+
+        // Debounce: keep collecting until events go quiet
+        while let Ok(Some(event)) = tokio::time::timeout(options.debounce, rx.recv()).await {
+            record_result(&mut pending, event);
+        }
+
+        if pending.is_empty() {
+            continue;
+        }
+
+        match apply_changes(config, embedding, embedder, db, store, &source, &pending).await {
+            Ok(stats) => print_watch_batch(&stats),
+            Err(e) => warn!("Failed to apply file changes: {}", e),
+        }
+    }
+
+    info!("Stopped watching {}", root.display());
+    Ok(())
+}
+
+fn record_result(pending: &mut PendingChanges, result: notify::Result<Event>) {
+    match result {
+        Ok(event) => pending.record(&event),
+        Err(e) => warn!("File watcher error: {}", e),
+    }
+}
+
+/// Re-ingest changed files and drop documents for removed ones
+pub async fn apply_changes(
+    config: &Config,
+    embedding: &ResolvedEmbeddingConfig,
+    embedder: &dyn Embedder,
+    db: &MetaDb,
+    store: &QdrantStore,
+    source: &Source,
+    pending: &PendingChanges,
+) -> Result<WatchBatchStats> {
+    let mut stats = WatchBatchStats::default();
+    let root = Path::new(&source.uri);
+
+    // Walk the tree so changed paths go through the same .gitignore and filter rules as ingest
+    let filter = DirFilter::from_source(source);
+    let current_files = collect_dir_files(root, &filter, config.ingest.max_file_bytes)?;
+    let current: HashSet<&PathBuf> = current_files.iter().collect();
+
+    let changed: Vec<&PathBuf> = pending
+        .paths
+        .iter()
+        .filter(|p| current.contains(p))
+        .collect();
+    let removed = pending.paths.iter().any(|p| !p.exists());
+
+    if changed.is_empty() && !removed {
+        debug!("No relevant file changes in batch");
+        return Ok(stats);
+    }
+
+    let run = db
+        .start_ingestion_run(&source.id, RunOperation::Update)
+        .await?;
+    let _heartbeat = db.start_run_heartbeat(&run.id);
+
+    for path in changed {
+        match process_file(config, embedding, db, store, embedder, source, path).await {
+            Ok((created, updated)) => {
+                stats.files_changed += 1;
+                stats.chunks_created += created;
+                stats.chunks_updated += updated;
+            }
+            Err(e) => {
+                let error_msg = format!("{}: {}", path.display(), e);
+                warn!("{}", error_msg);
+                stats.errors.push(error_msg);
+            }
+        }
+    }
+
+    // Failures here are recorded like per-file errors so the run still completes
+    if removed {
+        let current_uris: Vec<String> = current_files
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        match remove_stale_documents(db, store, &source.id, &current_uris).await {
+            Ok(stale) => {
+                stats.documents_removed = stale.deleted.len();
+                stats.chunks_deleted = stale.deleted_point_ids.len() as i32;
+            }
+            Err(e) => {
+                let error_msg = format!("Failed to remove deleted files: {}", e);
+                warn!("{}", error_msg);
+                stats.errors.push(error_msg);
+            }
+        }
+
+        if stats.documents_removed > 0 {
+            let prune = cmd_prune(
+                config,
+                db,
+                store,
+                PruneOptions {
+                    source_ids: Some(vec![source.id.clone()]),
+                    dry_run: false,
+                    remove_orphans: true,
+                    remove_dangling: false,
+                    concurrency: 4,
+                },
+            )
+            .await;
+            match prune {
+                Ok(prune) => stats.orphan_points_removed = prune.orphan_points_removed,
+                Err(e) => {
+                    let error_msg = format!("Failed to prune orphaned points: {}", e);
+                    warn!("{}", error_msg);
+                    stats.errors.push(error_msg);
+                }
+            }
+        }
+    }
+
+    db.complete_ingestion_run(
+        &run.id,
+        RunStatus::finished(stats.files_changed + stats.errors.len(), stats.errors.len()),
+        stats.files_changed as i32,
+        stats.chunks_created,
+        stats.chunks_updated,
+        stats.chunks_deleted,
+        if stats.errors.is_empty() {
+            None
+        } else {
+            Some(stats.errors.clone())
+        },
+    )
+    .await?;
+
+    Ok(stats)
+}
+
+/// Print a one-line summary of a watch batch
+pub fn print_watch_batch(stats: &WatchBatchStats) {
+    if stats.files_changed == 0 && stats.documents_removed == 0 && stats.errors.is_empty() {
+        return;
+    }
+    say!(
+        "✓ {} files re-ingested ({} chunks created, {} updated), {} documents removed",
+        stats.files_changed,
+        stats.chunks_created,
+        stats.chunks_updated,
+        stats.documents_removed
+    );
+    for error in &stats.errors {
+        say!("  ✗ {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::compute_content_hash;
+    use crate::meta::Document;
+    use crate::test_support::{test_embedding_config, FakeQdrant, RecordingEmbedder};
+    use notify::event::{CreateKind, DataChange, ModifyKind, RemoveKind};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_pending_changes_ignores_access_events() {
+        let mut pending = PendingChanges::default();
+        pending.record(
+            &Event::new(EventKind::Access(notify::event::AccessKind::Any))
+                .add_path(PathBuf::from("/docs/a.md")),
+        );
+        assert!(pending.is_empty());
+
+        pending.record(
+            &Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from("/docs/a.md")),
+        );
+        pending.record(
+            &Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from("/docs/a.md")),
+        );
+        assert_eq!(pending.paths.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_modify_event_updates_document_hash() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("docs")).unwrap();
+        let docs_dir = tmp.path().join("docs").canonicalize().unwrap();
+        let file = docs_dir.join("notes.md");
+        std::fs::write(&file, "# Notes\n\nOriginal content.").unwrap();
+
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, docs_dir.display().to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = Document::new(
+            source.id.clone(),
+            file.display().to_string(),
+            "stale-hash".to_string(),
+        );
+        db.upsert_document(&doc).await.unwrap();
+
+        let updated_content = "# Notes\n\nThe watcher re-ingests this file after every \
+            edit, chunking the new text and embedding each chunk again.";
+        std::fs::write(&file, updated_content).unwrap();
+
+        let mut pending = PendingChanges::default();
+        pending.record(
+            &Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+                .add_path(file.clone()),
+        );
+
+        let config = Config::default();
+        let qdrant = FakeQdrant::start().await;
+        let embedder = RecordingEmbedder::default();
+        let stats = apply_changes(
+            &config,
+            &test_embedding_config(false, false),
+            &embedder,
+            &db,
+            &qdrant.store().await,
+            &source,
+            &pending,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stats.files_changed, 1);
+        assert!(stats.errors.is_empty());
+        assert!(stats.chunks_created > 0);
+        assert_eq!(embedder.texts().len(), stats.chunks_created as usize);
+        assert_eq!(qdrant.point_ids().len(), stats.chunks_created as usize);
+
+        let stored = db
+            .get_document_by_uri(&source.id, &file.display().to_string())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stored.content_hash,
+            compute_content_hash(updated_content.as_bytes())
+        );
+
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert_eq!(run.operation, "update");
+        assert_eq!(run.status, "completed");
+    }
+
+    #[tokio::test]
+    async fn test_removal_failure_still_completes_run() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("docs")).unwrap();
+        let docs_dir = tmp.path().join("docs").canonicalize().unwrap();
+        let file = docs_dir.join("notes.md");
+        std::fs::write(
+            &file,
+            "# Notes\n\nThese notes are ingested, then deleted while being watched.",
+        )
+        .unwrap();
+
+        let db = MetaDb::new(&tmp.path().join("test.db")).await.unwrap();
+        let source = Source::new(SourceType::Dir, docs_dir.display().to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let config = Config::default();
+        let embedding = test_embedding_config(false, false);
+        let embedder = RecordingEmbedder::default();
+        let qdrant = FakeQdrant::start().await;
+        let store = qdrant.store().await;
+
+        let mut pending = PendingChanges::default();
+        pending.record(&Event::new(EventKind::Create(CreateKind::File)).add_path(file.clone()));
+        let created = apply_changes(
+            &config, &embedding, &embedder, &db, &store, &source, &pending,
+        )
+        .await
+        .unwrap();
+        assert!(created.chunks_created > 0);
+
+        std::fs::remove_file(&file).unwrap();
+        let mut pending = PendingChanges::default();
+        pending.record(&Event::new(EventKind::Remove(RemoveKind::File)).add_path(file.clone()));
+        let removed = apply_changes(
+            &config, &embedding, &embedder, &db, &store, &source, &pending,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(removed.documents_removed, 1);
+        assert_eq!(removed.chunks_deleted, created.chunks_created);
+        assert!(qdrant.point_ids().is_empty());
+        // The fake Qdrant cannot scroll, so the orphan prune after the removal fails
+        assert_eq!(removed.errors.len(), 1, "{:?}", removed.errors);
+        assert!(removed.errors[0].contains("prune"), "{:?}", removed.errors);
+
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert_ne!(run.status, "running");
+        assert!(run.completed_at.is_some());
+        assert_eq!(run.chunks_deleted, created.chunks_created);
+        assert_eq!(run.errors(), removed.errors);
+    }
+}
@@ -15,6 +15,11 @@ pub fn default_collection_name() -> String {
     "librarian_docs".to_string()
 }
 
+/// Default number of points sent per Qdrant upsert request
+pub fn default_store_upsert_batch_size() -> usize {
+    256
+}
+
 /// Default embedding model (BAAI/bge-small-en-v1.5)
 pub fn default_embedding_model() -> String {
     "BAAI/bge-small-en-v1.5".to_string()
@@ -77,6 +82,11 @@ pub fn default_prefer_heading_boundaries() -> bool {
     true
 }
 
+/// Default size limit for files ingested from a directory (10 MB)
+pub fn default_ingest_max_file_bytes() -> u64 {
+    10_000_000
+}
+
 /// Default maximum crawl depth
 pub fn default_crawl_max_depth() -> u32 {
     3
@@ -105,11 +115,59 @@ pub fn default_crawl_timeout() -> u64 {
     30
 }
 
+/// Default idle connections kept per host
+pub fn default_crawl_pool_max_idle_per_host() -> usize {
+    8
+}
+
+/// Default idle connection lifetime in seconds
+pub fn default_crawl_pool_idle_timeout() -> u64 {
+    90
+}
+
+/// Default URL substrings that are never crawled (logins, admin pages, feeds, ...)
+pub fn default_crawl_skip_patterns() -> Vec<String> {
+    [
+        "/login",
+        "/logout",
+        "/signin",
+        "/signout",
+        "/register",
+        "/admin",
+        "/wp-admin",
+        "/api/",
+        "/cgi-bin/",
+        ".xml",
+        ".json",
+        ".rss",
+        ".atom",
+        "javascript:",
+        "mailto:",
+        "tel:",
+        "?page=",
+        "?sort=",
+        "?filter=",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// Default: skip calendar-like URLs containing /YYYY/MM/DD/
+pub fn default_crawl_skip_date_paths() -> bool {
+    true
+}
+
 /// Default: respect robots.txt
 pub fn default_respect_robots() -> bool {
     true
 }
 
+/// Default robots.txt cache lifetime (1 hour)
+pub fn default_robots_cache_ttl() -> u64 {
+    3600
+}
+
 /// Default number of query results
 pub fn default_query_k() -> usize {
     10
@@ -160,6 +218,11 @@ pub fn default_js_render_wait() -> u64 {
     2000
 }
 
+/// Default number of headless browsers that may render at once
+pub fn default_js_renderer_pool_size() -> usize {
+    2
+}
+
 /// Default: multimodal crawling disabled
 pub fn default_multimodal_enabled() -> bool { false }
 
@@ -16,10 +16,12 @@ use crate::models::{
     embedding_model_spec, reranker_model_spec, supported_multimodal_embedding_models,
     MultimodalStrategy,
 };
+use crate::rank::Synonyms;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tracing::{debug, info, warn};
 
 const PROBE_TEXT: &str = "ping";
@@ -49,6 +51,10 @@ pub struct Config {
     #[serde(default)]
     pub chunk: ChunkConfig,
 
+    /// Directory ingestion configuration
+    #[serde(default)]
+    pub ingest: IngestConfig,
+
     /// Web crawling configuration
     #[serde(default)]
     pub crawl: CrawlConfig,
@@ -61,9 +67,45 @@ pub struct Config {
     #[serde(default)]
     pub reranker: RerankerConfig,
 
+    /// Qdrant collection configuration
+    #[serde(default)]
+    pub store: StoreConfig,
+
+    /// Named overrides selected with `--profile`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+
+    /// Profile merged over the base settings, if any
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+
+    /// Collection chosen with `--collection` in place of the configured one
+    #[serde(skip)]
+    pub collection_override: Option<String>,
+
     /// Paths configuration (internal, not user-editable)
     #[serde(skip)]
     pub paths: PathsConfig,
+
+    /// `query.synonyms_file`, parsed on first use so a long-running server
+    /// reads it once
+    #[serde(skip)]
+    synonyms: OnceLock<Synonyms>,
+}
+
+/// Settings a `[profiles.<name>]` section may override
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qdrant_url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_name: Option<String>,
+
+    /// Partial `[embedding]` table merged key by key over the base one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<toml::Table>,
 }
 
 /// Custom embedding model configuration
@@ -129,7 +171,8 @@ pub struct EmbeddingConfig {
     #[serde(default = "default_embedding_multimodal")]
     pub multimodal: bool,
 
-    /// Optional embedding dimension override
+    /// Optional embedding dimension override; MRL models may request fewer
+    /// dimensions than they produce and have their vectors truncated
     #[serde(default)]
     pub dimension: Option<usize>,
 
@@ -137,10 +180,44 @@ pub struct EmbeddingConfig {
     #[serde(default = "default_embedding_batch_size")]
     pub batch_size: usize,
 
+    /// Distance metric the Qdrant collection is created with
+    #[serde(default)]
+    pub distance: VectorDistance,
+
     /// Custom model and backend metadata
     #[serde(default)]
     pub custom: CustomEmbeddingConfig,
 }
+
+/// How vector similarity is measured in the Qdrant collection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VectorDistance {
+    /// Angle between vectors; scores are the cosine similarity
+    #[default]
+    Cosine,
+    /// Raw dot product; scores are mapped through the logistic function
+    Dot,
+    /// Straight-line distance; scores are mapped to `1 / (1 + distance)`
+    Euclidean,
+}
+
+impl VectorDistance {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VectorDistance::Cosine => "cosine",
+            VectorDistance::Dot => "dot",
+            VectorDistance::Euclidean => "euclidean",
+        }
+    }
+}
+
+impl fmt::Display for VectorDistance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EmbeddingDimensionSource {
     Config,
@@ -166,12 +243,14 @@ impl fmt::Display for EmbeddingDimensionSource {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedEmbeddingConfig {
     pub model_id: String,
     pub family: String,
     pub modalities: Vec<String>,
     pub dimension: usize,
+    /// Dimension the model produces; larger than `dimension` when MRL vectors are truncated
+    pub native_dimension: usize,
     pub dimension_source: EmbeddingDimensionSource,
     pub backend: EmbeddingBackendConfig,
     pub strategy: MultimodalStrategy,
@@ -196,9 +275,90 @@ impl ResolvedEmbeddingConfig {
     }
 }
 
+/// How documents are split into chunks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkStrategy {
+    /// Fixed-size windows that prefer heading, paragraph, and sentence breaks
+    #[default]
+    Fixed,
+    /// Whole sentences packed up to the size budget
+    Sentence,
+    /// A new chunk at every heading, splitting oversized sections by sentence
+    Heading,
+    /// Embedding-similarity boundaries (not yet implemented)
+    Semantic,
+}
+
+impl ChunkStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkStrategy::Fixed => "fixed",
+            ChunkStrategy::Sentence => "sentence",
+            ChunkStrategy::Heading => "heading",
+            ChunkStrategy::Semantic => "semantic",
+        }
+    }
+}
+
+impl fmt::Display for ChunkStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// When crawled HTML pages are rendered with the headless browser
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsMode {
+    /// Render only pages detected as single-page apps
+    #[default]
+    Auto,
+    /// Render every HTML page
+    Always,
+    /// Never render, even when a single-page app is detected
+    Never,
+}
+
+impl JsMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JsMode::Auto => "auto",
+            JsMode::Always => "always",
+            JsMode::Never => "never",
+        }
+    }
+}
+
+impl fmt::Display for JsMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for JsMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(JsMode::Auto),
+            "always" => Ok(JsMode::Always),
+            "never" => Ok(JsMode::Never),
+            other => Err(format!(
+                "invalid JS mode '{}' (expected auto, always or never)",
+                other
+            )),
+        }
+    }
+}
+
 /// Chunking configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkConfig {
+    /// How chunk boundaries are chosen
+    #[serde(default)]
+    pub strategy: ChunkStrategy,
+
     /// Maximum characters per chunk
     #[serde(default = "default_chunk_max_chars")]
     pub max_chars: usize,
@@ -214,6 +374,18 @@ pub struct ChunkConfig {
     /// Minimum chunk size (don't create tiny chunks)
     #[serde(default = "default_chunk_min_chars")]
     pub min_chars: usize,
+
+    /// Include code cell outputs when ingesting Jupyter notebooks
+    #[serde(default)]
+    pub notebook_outputs: bool,
+}
+
+/// Directory ingestion configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestConfig {
+    /// Files larger than this many bytes are skipped
+    #[serde(default = "default_ingest_max_file_bytes")]
+    pub max_file_bytes: u64,
 }
 
 /// Web crawling configuration
@@ -231,6 +403,18 @@ pub struct CrawlConfig {
     #[serde(default)]
     pub allowed_domains: Vec<String>,
 
+    /// Also follow other subdomains of the allowed hosts' registrable domain
+    #[serde(default)]
+    pub allow_subdomains: bool,
+
+    /// URL regexes a link must match to be crawled (empty = no restriction)
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+
+    /// URL regexes that stop a link from being crawled (wins over include)
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+
     /// Path prefix to restrict crawling to (e.g., /docs/)
     /// Empty means no path restriction (entire domain allowed)
     #[serde(default)]
@@ -248,14 +432,35 @@ pub struct CrawlConfig {
     #[serde(default = "default_crawl_timeout")]
     pub timeout_secs: u64,
 
+    /// Idle keep-alive connections kept open per host (0 disables reuse)
+    #[serde(default = "default_crawl_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+
+    /// Seconds an idle keep-alive connection stays open
+    #[serde(default = "default_crawl_pool_idle_timeout")]
+    pub pool_idle_timeout_secs: u64,
+
     /// Whether to respect robots.txt
     #[serde(default = "default_respect_robots")]
     pub respect_robots_txt: bool,
 
+    /// Seconds a host's robots.txt is cached before it is fetched again
+    #[serde(default = "default_robots_cache_ttl")]
+    pub robots_cache_ttl_secs: u64,
+
+    /// Also seed crawls with the pages of sitemaps listed in the seed host's robots.txt
+    #[serde(default)]
+    pub use_sitemap: bool,
+
     /// Auto-detect SPAs and use JavaScript rendering when needed
     #[serde(default = "default_auto_js_rendering")]
     pub auto_js_rendering: bool,
 
+    /// When to render pages with the headless browser; `auto` defers to
+    /// `auto_js_rendering`
+    #[serde(default)]
+    pub js_mode: JsMode,
+
     /// Time to wait for page load when JS rendering (milliseconds)
     #[serde(default = "default_js_page_load_timeout")]
     pub js_page_load_timeout_ms: u64,
@@ -264,10 +469,34 @@ pub struct CrawlConfig {
     #[serde(default = "default_js_render_wait")]
     pub js_render_wait_ms: u64,
 
+    /// CSS selector to wait for before capturing rendered HTML (bounded by
+    /// `js_page_load_timeout_ms`)
+    #[serde(default)]
+    pub js_wait_for_selector: Option<String>,
+
+    /// Headless browsers available for concurrent rendering; each is launched
+    /// on first use
+    #[serde(default = "default_js_renderer_pool_size")]
+    pub js_renderer_pool_size: usize,
+
     /// Disable browser sandbox (required in some Docker/CI environments)
     #[serde(default)]
     pub js_no_sandbox: bool,
 
+    /// Skip pages whose extracted text is shorter than this many characters
+    /// (0 = keep every page). Links on skipped pages are still followed.
+    #[serde(default)]
+    pub min_document_chars: usize,
+
+    /// Case-insensitive URL substrings that are never crawled. Replacing the
+    /// list (e.g. without "/api/") re-enables those URLs.
+    #[serde(default = "default_crawl_skip_patterns")]
+    pub default_skip_patterns: Vec<String>,
+
+    /// Skip calendar-like URLs containing a /YYYY/MM/DD/ path
+    #[serde(default = "default_crawl_skip_date_paths")]
+    pub skip_date_paths: bool,
+
     /// Multimodal crawling configuration
     #[serde(default)]
     pub multimodal: MultimodalCrawlConfig,
@@ -295,6 +524,30 @@ pub struct QueryConfig {
     /// BM25 weight when hybrid is enabled (0.0 - 1.0)
     #[serde(default = "default_bm25_weight")]
     pub bm25_weight: f32,
+
+    /// Share of the gap to 1.0 a title match closes (0.0 - 1.0), scaled by
+    /// the share of query terms found in the title; 0 disables it
+    #[serde(default)]
+    pub title_boost: f32,
+
+    /// Share of the gap to 1.0 a heading-trail match closes (0.0 - 1.0),
+    /// scaled by the share of query terms found in the headings; 0 disables it
+    #[serde(default)]
+    pub heading_boost: f32,
+
+    /// File of `term => expansion` lines used to expand queries; relative
+    /// paths are resolved against the config directory
+    #[serde(default)]
+    pub synonyms_file: Option<PathBuf>,
+
+    /// Abort a query that takes longer than this many seconds; unset waits indefinitely
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// How many of the best candidates the reranker scores before results are
+    /// cut to `reranker.top_k`; unset reranks every candidate
+    #[serde(default)]
+    pub rerank_candidates: Option<usize>,
 }
 
 /// Reranker configuration (cross-encoder model for result reranking)
@@ -313,6 +566,89 @@ pub struct RerankerConfig {
     pub top_k: usize,
 }
 
+/// Qdrant collection configuration; everything but the upsert batch size is
+/// applied when the collection is created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreConfig {
+    /// Maximum number of points sent in a single Qdrant upsert request
+    #[serde(default = "default_store_upsert_batch_size")]
+    pub upsert_batch_size: usize,
+
+    /// Vector quantization (scalar or none)
+    #[serde(default)]
+    pub quantization: Quantization,
+
+    /// Quantile used to calibrate scalar quantization (0.5 - 1.0); unset uses Qdrant's default
+    #[serde(default)]
+    pub quantization_quantile: Option<f32>,
+
+    /// Keep quantized vectors in RAM even when the collection is stored on disk
+    #[serde(default)]
+    pub quantization_always_ram: bool,
+
+    /// HNSW index tuning
+    #[serde(default)]
+    pub hnsw: HnswConfig,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        Self {
+            upsert_batch_size: default_store_upsert_batch_size(),
+            quantization: Quantization::default(),
+            quantization_quantile: None,
+            quantization_always_ram: false,
+            hnsw: HnswConfig::default(),
+        }
+    }
+}
+
+/// HNSW index tuning; unset values use Qdrant's defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HnswConfig {
+    /// Edges per node in the graph (4 - 128); higher improves recall and uses more memory
+    #[serde(default)]
+    pub m: Option<u64>,
+
+    /// Neighbors considered while building the graph (4 - 4096); higher is slower to index
+    #[serde(default)]
+    pub ef_construct: Option<u64>,
+
+    /// Segment size in KB below which searches skip the graph and scan every vector
+    #[serde(default)]
+    pub full_scan_threshold: Option<u64>,
+
+    /// Neighbors considered per query (1 - 4096); higher improves recall and slows search
+    #[serde(default)]
+    pub search_ef: Option<u64>,
+}
+
+/// How vectors are compressed in the Qdrant collection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quantization {
+    /// int8 scalar quantization: about 4x less memory for a small recall loss
+    #[default]
+    Scalar,
+    /// Full-precision vectors only
+    None,
+}
+
+impl Quantization {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Quantization::Scalar => "scalar",
+            Quantization::None => "none",
+        }
+    }
+}
+
+impl fmt::Display for Quantization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Multimodal crawling configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultimodalCrawlConfig {
@@ -355,6 +691,10 @@ pub struct MultimodalCrawlConfig {
     /// Include CSS background images if detected
     #[serde(default = "default_multimodal_include_css_background_images")]
     pub include_css_background_images: bool,
+
+    /// Embed a full-page screenshot of each JS-rendered page as an image asset
+    #[serde(default)]
+    pub include_page_screenshot: bool,
 }
 
 /// Internal paths configuration
@@ -378,10 +718,16 @@ impl Default for Config {
             collection_name: default_collection_name(),
             embedding: EmbeddingConfig::default(),
             chunk: ChunkConfig::default(),
+            ingest: IngestConfig::default(),
             crawl: CrawlConfig::default(),
             query: QueryConfig::default(),
             reranker: RerankerConfig::default(),
+            store: StoreConfig::default(),
+            profiles: BTreeMap::new(),
+            active_profile: None,
+            collection_override: None,
             paths: PathsConfig::default(),
+            synonyms: OnceLock::new(),
         }
     }
 }
@@ -396,6 +742,7 @@ impl Default for EmbeddingConfig {
             multimodal: default_embedding_multimodal(),
             dimension: None,
             batch_size: default_embedding_batch_size(),
+            distance: VectorDistance::default(),
             custom: CustomEmbeddingConfig::default(),
         }
     }
@@ -420,10 +767,20 @@ impl Default for CustomEmbeddingConfig {
 impl Default for ChunkConfig {
     fn default() -> Self {
         Self {
+            strategy: ChunkStrategy::default(),
             max_chars: default_chunk_max_chars(),
             overlap_chars: default_chunk_overlap(),
             prefer_heading_boundaries: default_prefer_heading_boundaries(),
             min_chars: default_chunk_min_chars(),
+            notebook_outputs: false,
+        }
+    }
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: default_ingest_max_file_bytes(),
         }
     }
 }
@@ -434,20 +791,44 @@ impl Default for CrawlConfig {
             max_depth: default_crawl_max_depth(),
             max_pages: default_crawl_max_pages(),
             allowed_domains: Vec::new(),
+            allow_subdomains: false,
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
             path_prefix: None,
             rate_limit_per_host: default_crawl_rate_limit(),
             user_agent: default_crawl_user_agent(),
             timeout_secs: default_crawl_timeout(),
+            pool_max_idle_per_host: default_crawl_pool_max_idle_per_host(),
+            pool_idle_timeout_secs: default_crawl_pool_idle_timeout(),
             respect_robots_txt: default_respect_robots(),
+            robots_cache_ttl_secs: default_robots_cache_ttl(),
+            use_sitemap: false,
             auto_js_rendering: default_auto_js_rendering(),
+            js_mode: JsMode::default(),
             js_page_load_timeout_ms: default_js_page_load_timeout(),
             js_render_wait_ms: default_js_render_wait(),
+            js_wait_for_selector: None,
+            js_renderer_pool_size: default_js_renderer_pool_size(),
             js_no_sandbox: false,
+            min_document_chars: 0,
+            default_skip_patterns: default_crawl_skip_patterns(),
+            skip_date_paths: default_crawl_skip_date_paths(),
             multimodal: MultimodalCrawlConfig::default(),
         }
     }
 }
 
+impl CrawlConfig {
+    /// The JS mode to crawl with, treating `auto` as `never` when
+    /// `auto_js_rendering` is off
+    pub fn effective_js_mode(&self) -> JsMode {
+        match self.js_mode {
+            JsMode::Auto if !self.auto_js_rendering => JsMode::Never,
+            mode => mode,
+        }
+    }
+}
+
 impl Default for QueryConfig {
     fn default() -> Self {
         Self {
@@ -456,6 +837,11 @@ impl Default for QueryConfig {
             min_score: default_query_min_score(),
             hybrid_search: false,
             bm25_weight: default_bm25_weight(),
+            title_boost: 0.0,
+            heading_boost: 0.0,
+            synonyms_file: None,
+            timeout_secs: None,
+            rerank_candidates: None,
         }
     }
 }
@@ -483,10 +869,18 @@ impl Default for MultimodalCrawlConfig {
             allowed_mime_prefixes: default_multimodal_allowed_mime_prefixes(),
             min_relevance_score: default_multimodal_min_relevance_score(),
             include_css_background_images: default_multimodal_include_css_background_images(),
+            include_page_screenshot: false,
         }
     }
 }
 
+impl MultimodalCrawlConfig {
+    /// Whether rendered pages should be screenshotted for image embedding
+    pub fn captures_page_screenshots(&self) -> bool {
+        self.enabled && self.include_images && self.include_page_screenshot
+    }
+}
+
 impl Config {
     /// Get the default base directory for librarian (~/.librarian)
     pub fn default_base_dir() -> PathBuf {
@@ -500,6 +894,28 @@ impl Config {
         Self::default_base_dir().join("config.toml")
     }
 
+    /// Location of `query.synonyms_file`, resolved against the config directory
+    pub fn synonyms_path(&self) -> Option<PathBuf> {
+        self.query
+            .synonyms_file
+            .as_ref()
+            .map(|path| self.paths.base_dir.join(path))
+    }
+
+    /// Synonyms used to expand queries, empty when no synonyms file is set
+    ///
+    /// The file is read the first time this is called and reused afterwards.
+    pub fn synonyms(&self) -> Result<&Synonyms> {
+        if let Some(synonyms) = self.synonyms.get() {
+            return Ok(synonyms);
+        }
+        let synonyms = match self.synonyms_path() {
+            Some(path) => Synonyms::load(&path)?,
+            None => Synonyms::default(),
+        };
+        Ok(self.synonyms.get_or_init(|| synonyms))
+    }
+
     /// Initialize paths configuration
     fn init_paths(&mut self, base_dir: Option<PathBuf>) {
         let base = base_dir.unwrap_or_else(Self::default_base_dir);
@@ -512,6 +928,89 @@ impl Config {
 
     /// Load configuration from a specific file path
     pub fn load(config_path: &Path) -> Result<Self> {
+        Self::load_profile(config_path, None)
+    }
+
+    /// Load configuration with the named profile merged over the base settings
+    pub fn load_profile(config_path: &Path, profile: Option<&str>) -> Result<Self> {
+        let mut config = Self::read(config_path)?;
+        if let Some(profile) = profile {
+            config.apply_profile(profile)?;
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Merge a `[profiles.<name>]` section over the base settings
+    ///
+    /// Each profile keeps its own metadata database, since sources belong to
+    /// the collection they were indexed into.
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let defined = if self.profiles.is_empty() {
+                "none".to_string()
+            } else {
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            };
+            return Err(Error::Config(format!(
+                "Unknown profile '{}' (defined profiles: {})",
+                name, defined
+            )));
+        };
+
+        if let Some(qdrant_url) = profile.qdrant_url {
+            self.qdrant_url = qdrant_url;
+        }
+        if let Some(collection_name) = profile.collection_name {
+            self.collection_name = collection_name;
+        }
+        if let Some(overrides) = profile.embedding {
+            let mut embedding = toml::Table::try_from(&self.embedding)?;
+            merge_tables(&mut embedding, overrides);
+            self.embedding = embedding
+                .try_into()
+                .map_err(|e| Error::Config(format!("profiles.{}.embedding: {}", name, e)))?;
+        }
+
+        self.paths.db_file = self.paths.base_dir.join(format!("metadata.{}.db", name));
+        self.active_profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Point this invocation at another Qdrant collection, as `--collection` does
+    ///
+    /// Applied after any profile, so it wins over the profile's collection and
+    /// keeps its other settings. Like a profile, a different collection gets
+    /// its own metadata database, namespaced by the profile so two profiles
+    /// overriding to the same collection name keep separate sources; naming the
+    /// collection already in use changes nothing.
+    pub fn apply_collection_override(&mut self, name: &str) -> Result<()> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(Error::Config(format!(
+                "Invalid collection name '{}': use letters, digits, '-' and '_'",
+                name
+            )));
+        }
+        if name == self.collection_name {
+            return Ok(());
+        }
+
+        self.collection_name = name.to_string();
+        let db_name = match &self.active_profile {
+            Some(profile) => format!("metadata.{}.collection.{}.db", profile, name),
+            None => format!("metadata.collection.{}.db", name),
+        };
+        self.paths.db_file = self.paths.base_dir.join(db_name);
+        self.collection_override = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Parse a config file without validating its settings
+    pub fn read(config_path: &Path) -> Result<Self> {
         debug!("Loading config from {:?}", config_path);
 
         if !config_path.exists() {
@@ -532,7 +1031,6 @@ impl Config {
             base_dir: base,
         };
 
-        config.validate()?;
         Ok(config)
     }
 
@@ -596,6 +1094,20 @@ impl Config {
             ));
         }
 
+        // Caught here so ingest does not fail on the first document it chunks
+        if self.chunk.strategy == ChunkStrategy::Semantic {
+            return Err(Error::Config(
+                "chunk.strategy \"semantic\" is not yet implemented; use fixed, sentence, or heading"
+                    .to_string(),
+            ));
+        }
+
+        if self.ingest.max_file_bytes == 0 {
+            return Err(Error::Config(
+                "ingest.max_file_bytes must be greater than 0".to_string(),
+            ));
+        }
+
         if self.query.min_score < 0.0 || self.query.min_score > 1.0 {
             return Err(Error::Config(
                 "query.min_score must be between 0.0 and 1.0".to_string(),
@@ -608,12 +1120,96 @@ impl Config {
             ));
         }
 
+        for (key, boost) in [
+            ("title_boost", self.query.title_boost),
+            ("heading_boost", self.query.heading_boost),
+        ] {
+            if !(0.0..=1.0).contains(&boost) {
+                return Err(Error::Config(format!(
+                    "query.{} must be between 0.0 and 1.0",
+                    key
+                )));
+            }
+        }
+
+        if self.query.timeout_secs == Some(0) {
+            return Err(Error::Config(
+                "query.timeout_secs must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some(candidates) = self.query.rerank_candidates {
+            if candidates < self.reranker.top_k {
+                return Err(Error::Config(format!(
+                    "query.rerank_candidates ({}) must be at least reranker.top_k ({})",
+                    candidates, self.reranker.top_k
+                )));
+            }
+        }
+
         if self.crawl.rate_limit_per_host <= 0.0 {
             return Err(Error::Config(
                 "crawl.rate_limit_per_host must be positive".to_string(),
             ));
         }
 
+        if self.crawl.js_renderer_pool_size == 0 {
+            return Err(Error::Config(
+                "crawl.js_renderer_pool_size must be > 0".to_string(),
+            ));
+        }
+
+        for pattern in self
+            .crawl
+            .include_patterns
+            .iter()
+            .chain(&self.crawl.exclude_patterns)
+        {
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(Error::Config(format!(
+                    "Invalid crawl URL pattern '{}': {}",
+                    pattern, e
+                )));
+            }
+        }
+
+        if let Some(quantile) = self.store.quantization_quantile {
+            if !(0.5..=1.0).contains(&quantile) {
+                return Err(Error::Config(
+                    "store.quantization_quantile must be between 0.5 and 1.0".to_string(),
+                ));
+            }
+        }
+
+        let hnsw = &self.store.hnsw;
+        for (key, value, range) in [
+            ("m", hnsw.m, 4..=128),
+            ("ef_construct", hnsw.ef_construct, 4..=4096),
+            ("search_ef", hnsw.search_ef, 1..=4096),
+        ] {
+            if let Some(value) = value.filter(|value| !range.contains(value)) {
+                return Err(Error::Config(format!(
+                    "store.hnsw.{} must be between {} and {} (got {})",
+                    key,
+                    range.start(),
+                    range.end(),
+                    value
+                )));
+            }
+        }
+
+        if hnsw.full_scan_threshold.is_some_and(|kb| kb < 10) {
+            return Err(Error::Config(
+                "store.hnsw.full_scan_threshold must be >= 10".to_string(),
+            ));
+        }
+
+        if self.store.upsert_batch_size == 0 {
+            return Err(Error::Config(
+                "store.upsert_batch_size must be > 0".to_string(),
+            ));
+        }
+
         if self.embedding.batch_size == 0 {
             return Err(Error::Config(
                 "embedding.batch_size must be > 0".to_string(),
@@ -739,6 +1335,96 @@ impl Config {
         Ok(())
     }
 
+    /// File holding the last resolved embedding configuration
+    pub fn embedding_cache_path(&self) -> PathBuf {
+        match &self.active_profile {
+            Some(profile) => self
+                .paths
+                .base_dir
+                .join(format!("embedding-cache.{}.json", profile)),
+            None => self.paths.base_dir.join("embedding-cache.json"),
+        }
+    }
+
+    /// Resolve embedding configuration, reusing the cached result while the
+    /// `[embedding]` section is unchanged.
+    ///
+    /// `refresh` always probes the backend. A missing, unreadable, or stale
+    /// cache falls back to probing, and failing to write the cache only logs a
+    /// warning.
+    pub async fn resolve_embedding_config_cached(
+        &self,
+        refresh: bool,
+    ) -> Result<ResolvedEmbeddingConfig> {
+        let path = self.embedding_cache_path();
+        let key = self.embedding_cache_key()?;
+
+        if !refresh {
+            if let Some(resolved) = self.cached_embedding_config() {
+                debug!("Using cached embedding config from {}", path.display());
+                return Ok(resolved);
+            }
+        }
+
+        let resolved = self.resolve_embedding_config().await?;
+        let cache = EmbeddingCache {
+            key,
+            resolved: resolved.clone(),
+        };
+        let written = serde_json::to_string_pretty(&cache)
+            .map_err(Error::from)
+            .and_then(|json| std::fs::write(&path, json).map_err(Error::from));
+        if let Err(e) = written {
+            warn!(
+                "Failed to cache embedding config at {}: {}",
+                path.display(),
+                e
+            );
+        }
+        Ok(resolved)
+    }
+
+    /// Cached embedding resolution, if it still matches the `[embedding]` section
+    pub fn cached_embedding_config(&self) -> Option<ResolvedEmbeddingConfig> {
+        let key = self.embedding_cache_key().ok()?;
+        let content = std::fs::read_to_string(self.embedding_cache_path()).ok()?;
+        serde_json::from_str::<EmbeddingCache>(&content)
+            .ok()
+            .filter(|cache| cache.key == key)
+            .map(|cache| cache.resolved)
+    }
+
+    /// Best-known vector dimension without contacting the embedding backend
+    ///
+    /// Prefers the cached resolution, then `embedding.dimension`, then the
+    /// model registry and the custom model's dimension.
+    pub fn offline_embedding_dimension(&self) -> Option<usize> {
+        self.cached_embedding_config()
+            .map(|resolved| resolved.dimension)
+            .or(self.embedding.dimension)
+            .or_else(|| {
+                embedding_model_spec(self.embedding.model.trim())
+                    .and_then(|spec| spec.default_dimension)
+            })
+            .or(self.embedding.custom.dimension)
+    }
+
+    /// Hash of everything that feeds embedding resolution
+    fn embedding_cache_key(&self) -> Result<String> {
+        let embedding = serde_json::to_vec(&self.embedding)?;
+        Ok(blake3::hash(&embedding).to_hex().to_string())
+    }
+
+    /// URL of the backend serving the configured model
+    pub fn embedding_backend_url(&self) -> &str {
+        let model = self.embedding.model.trim();
+        if model == "custom" || embedding_model_spec(model).is_none() {
+            self.embedding.custom.url.trim()
+        } else {
+            self.embedding.url.trim()
+        }
+    }
+
     /// Resolve embedding configuration against allowlist and backend probe
     pub async fn resolve_embedding_config(&self) -> Result<ResolvedEmbeddingConfig> {
         let raw_model = self.embedding.model.trim();
@@ -815,10 +1501,10 @@ impl Config {
                     .iter()
                     .any(|model| model.id == model_id)
             {
-                return Err(Error::Embedding(format!(
-                    "Embedding backend does not advertise model '{}' in /capabilities",
-                    model_id
-                )));
+                return Err(Error::ModelNotAdvertised {
+                    url: backend.url.clone(),
+                    model: model_id.to_string(),
+                });
             }
         }
 
@@ -842,10 +1528,10 @@ impl Config {
             .await?;
 
         if probe.id != model_id {
-            return Err(Error::Embedding(format!(
-                "Embedding backend probe returned model '{}' but expected '{}'",
-                probe.id, model_id
-            )));
+            return Err(Error::ProbeFailed {
+                model: model_id.to_string(),
+                reason: format!("backend answered for model '{}'", probe.id),
+            });
         }
 
         let probe_family = probe.family.clone();
@@ -903,16 +1589,16 @@ impl Config {
                 .map(|v| !v.is_empty())
                 .unwrap_or(false);
             if supports_joint_inputs && !has_joint {
-                return Err(Error::Embedding(format!(
-                    "Embedding backend probe for '{}' did not return joint embeddings",
-                    model_id
-                )));
+                return Err(Error::ProbeFailed {
+                    model: model_id.to_string(),
+                    reason: "did not return joint embeddings".to_string(),
+                });
             }
             if !supports_joint_inputs && supports_image && !has_image {
-                return Err(Error::Embedding(format!(
-                    "Embedding backend probe for '{}' did not return image embeddings",
-                    model_id
-                )));
+                return Err(Error::ProbeFailed {
+                    model: model_id.to_string(),
+                    reason: "did not return image embeddings".to_string(),
+                });
             }
         }
 
@@ -922,10 +1608,10 @@ impl Config {
             .map(|v| !v.is_empty())
             .unwrap_or(false);
         if !has_text && !supports_joint_inputs {
-            return Err(Error::Embedding(format!(
-                "Embedding backend probe for '{}' did not return text embeddings",
-                model_id
-            )));
+            return Err(Error::ProbeFailed {
+                model: model_id.to_string(),
+                reason: "did not return text embeddings".to_string(),
+            });
         }
 
         let probe_dimension = probe.embedding_dim.or_else(|| {
@@ -962,10 +1648,13 @@ impl Config {
             ] {
                 if let Some(values) = embeddings {
                     if values.iter().any(|vec| vec.len() != probe_dim) {
-                        return Err(Error::Embedding(format!(
-                            "Embedding backend probe returned {} embeddings with inconsistent dimension for model '{}' (expected {})",
-                            label, model_id, probe_dim
-                        )));
+                        return Err(Error::ProbeFailed {
+                            model: model_id.to_string(),
+                            reason: format!(
+                                "{} embeddings do not all have the advertised dimension {}",
+                                label, probe_dim
+                            ),
+                        });
                     }
                 }
             }
@@ -983,22 +1672,37 @@ impl Config {
                 .map(|v| !v.is_empty())
                 .unwrap_or(false))
         {
-            return Err(Error::Embedding(format!(
-                "Embedding backend probe returned embeddings for '{}' but dimension could not be determined",
-                model_id
-            )));
+            return Err(Error::ProbeFailed {
+                model: model_id.to_string(),
+                reason: "returned embeddings of inconsistent dimensions".to_string(),
+            });
         }
         let registry_dimension = allowlisted.and_then(|spec| spec.default_dimension);
         let custom_dimension = self.embedding.custom.dimension;
 
+        let supports_mrl = probe
+            .supports_mrl
+            .or_else(|| allowlisted.map(|spec| spec.supports_mrl))
+            .or(self.embedding.custom.supports_mrl)
+            .unwrap_or(false);
+
         if let (Some(config_dimension), Some(probe_dimension)) =
             (self.embedding.dimension, probe_dimension)
         {
-            if config_dimension != probe_dimension {
-                return Err(Error::Embedding(format!(
-                    "Embedding dimension mismatch for model '{}' (family '{}', source config): config {} != probe {}",
-                    model_id, family, config_dimension, probe_dimension
-                )));
+            // MRL models can be truncated to a smaller prefix of their output
+            let truncatable = supports_mrl && config_dimension < probe_dimension;
+            if config_dimension != probe_dimension && !truncatable {
+                let hint = if config_dimension < probe_dimension {
+                    "; only Matryoshka/MRL models can be truncated to a smaller dimension"
+                } else {
+                    ""
+                };
+                return Err(Error::DimensionMismatch {
+                    model: model_id.to_string(),
+                    expected: config_dimension,
+                    actual: probe_dimension,
+                    context: format!("family '{}', config vs probe{}", family, hint),
+                });
             }
         }
 
@@ -1017,10 +1721,16 @@ impl Config {
             ));
         };
 
+        // Vectors are truncated from the model's native size down to `dimension`
+        let native_dimension = match probe_dimension.or(registry_dimension) {
+            Some(native) if supports_mrl && native > dimension => native,
+            _ => dimension,
+        };
+
         if let (Some(config_dimension), Some(registry_dimension)) =
             (self.embedding.dimension, registry_dimension)
         {
-            if config_dimension != registry_dimension {
+            if config_dimension != registry_dimension && native_dimension == dimension {
                 warn!(
                     "Embedding dimension {} does not match registry default {} for model '{}'",
                     config_dimension, registry_dimension, model_id
@@ -1040,12 +1750,6 @@ impl Config {
             strategy
         };
 
-        let supports_mrl = probe
-            .supports_mrl
-            .or_else(|| allowlisted.map(|spec| spec.supports_mrl))
-            .or(self.embedding.custom.supports_mrl)
-            .unwrap_or(false);
-
         let max_batch = probe
             .max_batch
             .or_else(|| allowlisted.map(|spec| spec.max_batch))
@@ -1071,6 +1775,7 @@ impl Config {
             family,
             modalities,
             dimension,
+            native_dimension,
             dimension_source,
             backend,
             strategy,
@@ -1084,6 +1789,14 @@ impl Config {
     }
 }
 
+/// Resolved embedding configuration saved between runs
+#[derive(Debug, Serialize, Deserialize)]
+struct EmbeddingCache {
+    /// `Config::embedding_cache_key` of the config it was resolved from
+    key: String,
+    resolved: ResolvedEmbeddingConfig,
+}
+
 /// Get the database URL for sqlx
 pub fn database_url(config: &Config) -> String {
     format!("sqlite://{}?mode=rwc", config.paths.db_file.display())
@@ -1184,6 +1897,13 @@ pub fn render_config_toml(
         config.embedding.batch_size == defaults.embedding.batch_size,
         irrelevant.contains("embedding.batch_size"),
     );
+    push_kv(
+        &mut lines,
+        "distance",
+        toml_string(config.embedding.distance.as_str()),
+        config.embedding.distance == defaults.embedding.distance,
+        irrelevant.contains("embedding.distance"),
+    );
 
     lines.push("".to_string());
     lines.push("[embedding.custom]".to_string());
@@ -1271,6 +1991,13 @@ pub fn render_config_toml(
 
     lines.push("".to_string());
     lines.push("[chunk]".to_string());
+    push_kv(
+        &mut lines,
+        "strategy",
+        toml_string(config.chunk.strategy.as_str()),
+        config.chunk.strategy == defaults.chunk.strategy,
+        irrelevant.contains("chunk.strategy"),
+    );
     push_kv(
         &mut lines,
         "max_chars",
@@ -1299,6 +2026,23 @@ pub fn render_config_toml(
         config.chunk.prefer_heading_boundaries == defaults.chunk.prefer_heading_boundaries,
         irrelevant.contains("chunk.prefer_heading_boundaries"),
     );
+    push_kv(
+        &mut lines,
+        "notebook_outputs",
+        toml_bool(config.chunk.notebook_outputs),
+        config.chunk.notebook_outputs == defaults.chunk.notebook_outputs,
+        irrelevant.contains("chunk.notebook_outputs"),
+    );
+
+    lines.push("".to_string());
+    lines.push("[ingest]".to_string());
+    push_kv(
+        &mut lines,
+        "max_file_bytes",
+        toml_integer(config.ingest.max_file_bytes as i64),
+        config.ingest.max_file_bytes == defaults.ingest.max_file_bytes,
+        irrelevant.contains("ingest.max_file_bytes"),
+    );
 
     lines.push("".to_string());
     lines.push("[query]".to_string());
@@ -1337,6 +2081,50 @@ pub fn render_config_toml(
         config.query.bm25_weight == defaults.query.bm25_weight,
         irrelevant.contains("query.bm25_weight"),
     );
+    push_kv(
+        &mut lines,
+        "title_boost",
+        toml_float(config.query.title_boost as f64),
+        config.query.title_boost == defaults.query.title_boost,
+        irrelevant.contains("query.title_boost"),
+    );
+    push_kv(
+        &mut lines,
+        "heading_boost",
+        toml_float(config.query.heading_boost as f64),
+        config.query.heading_boost == defaults.query.heading_boost,
+        irrelevant.contains("query.heading_boost"),
+    );
+    match &config.query.synonyms_file {
+        Some(path) => push_kv(
+            &mut lines,
+            "synonyms_file",
+            toml_string(&path.to_string_lossy()),
+            false,
+            irrelevant.contains("query.synonyms_file"),
+        ),
+        None => lines.push("# synonyms_file = \"synonyms.txt\"".to_string()),
+    }
+    match config.query.timeout_secs {
+        Some(secs) => push_kv(
+            &mut lines,
+            "timeout_secs",
+            toml_integer(secs as i64),
+            false,
+            irrelevant.contains("query.timeout_secs"),
+        ),
+        None => lines.push("# timeout_secs = 30".to_string()),
+    }
+    match config.query.rerank_candidates {
+        Some(candidates) => push_kv(
+            &mut lines,
+            "rerank_candidates",
+            toml_integer(candidates as i64),
+            false,
+            irrelevant.contains("query.rerank_candidates"),
+        ),
+        None => lines.push("# rerank_candidates = 50".to_string()),
+    }
 
     lines.push("".to_string());
     lines.push("[reranker]".to_string());
@@ -1362,6 +2150,62 @@ pub fn render_config_toml(
         irrelevant.contains("reranker.top_k"),
     );
 
+    lines.push("".to_string());
+    lines.push("[store]".to_string());
+    push_kv(
+        &mut lines,
+        "upsert_batch_size",
+        toml_integer(config.store.upsert_batch_size as i64),
+        config.store.upsert_batch_size == defaults.store.upsert_batch_size,
+        irrelevant.contains("store.upsert_batch_size"),
+    );
+    push_kv(
+        &mut lines,
+        "quantization",
+        toml_string(config.store.quantization.as_str()),
+        config.store.quantization == defaults.store.quantization,
+        irrelevant.contains("store.quantization"),
+    );
+    if let Some(quantile) = config.store.quantization_quantile {
+        push_kv(
+            &mut lines,
+            "quantization_quantile",
+            toml_float(quantile as f64),
+            config.store.quantization_quantile == defaults.store.quantization_quantile,
+            irrelevant.contains("store.quantization_quantile"),
+        );
+    } else {
+        lines.push("# quantization_quantile = 0.99".to_string());
+    }
+    push_kv(
+        &mut lines,
+        "quantization_always_ram",
+        toml_bool(config.store.quantization_always_ram),
+        config.store.quantization_always_ram == defaults.store.quantization_always_ram,
+        irrelevant.contains("store.quantization_always_ram"),
+    );
+
+    lines.push("".to_string());
+    lines.push("[store.hnsw]".to_string());
+    let hnsw = &config.store.hnsw;
+    for (key, value, placeholder) in [
+        ("m", hnsw.m, 16),
+        ("ef_construct", hnsw.ef_construct, 100),
+        ("full_scan_threshold", hnsw.full_scan_threshold, 10000),
+        ("search_ef", hnsw.search_ef, 128),
+    ] {
+        match value {
+            Some(value) => push_kv(
+                &mut lines,
+                key,
+                toml_integer(value as i64),
+                false,
+                irrelevant.contains(&format!("store.hnsw.{}", key)),
+            ),
+            None => lines.push(format!("# {} = {}", key, placeholder)),
+        }
+    }
+
     lines.push("".to_string());
     lines.push("[crawl]".to_string());
     push_kv(
@@ -1385,6 +2229,27 @@ pub fn render_config_toml(
         config.crawl.allowed_domains == defaults.crawl.allowed_domains,
         irrelevant.contains("crawl.allowed_domains"),
     );
+    push_kv(
+        &mut lines,
+        "allow_subdomains",
+        toml_bool(config.crawl.allow_subdomains),
+        config.crawl.allow_subdomains == defaults.crawl.allow_subdomains,
+        irrelevant.contains("crawl.allow_subdomains"),
+    );
+    push_kv(
+        &mut lines,
+        "include_patterns",
+        toml_array(&config.crawl.include_patterns),
+        config.crawl.include_patterns == defaults.crawl.include_patterns,
+        irrelevant.contains("crawl.include_patterns"),
+    );
+    push_kv(
+        &mut lines,
+        "exclude_patterns",
+        toml_array(&config.crawl.exclude_patterns),
+        config.crawl.exclude_patterns == defaults.crawl.exclude_patterns,
+        irrelevant.contains("crawl.exclude_patterns"),
+    );
     push_kv(
         &mut lines,
         "path_prefix",
@@ -1413,6 +2278,20 @@ pub fn render_config_toml(
         config.crawl.timeout_secs == defaults.crawl.timeout_secs,
         irrelevant.contains("crawl.timeout_secs"),
     );
+    push_kv(
+        &mut lines,
+        "pool_max_idle_per_host",
+        toml_integer(config.crawl.pool_max_idle_per_host as i64),
+        config.crawl.pool_max_idle_per_host == defaults.crawl.pool_max_idle_per_host,
+        irrelevant.contains("crawl.pool_max_idle_per_host"),
+    );
+    push_kv(
+        &mut lines,
+        "pool_idle_timeout_secs",
+        toml_integer(config.crawl.pool_idle_timeout_secs as i64),
+        config.crawl.pool_idle_timeout_secs == defaults.crawl.pool_idle_timeout_secs,
+        irrelevant.contains("crawl.pool_idle_timeout_secs"),
+    );
     push_kv(
         &mut lines,
         "respect_robots_txt",
@@ -1420,6 +2299,20 @@ pub fn render_config_toml(
         config.crawl.respect_robots_txt == defaults.crawl.respect_robots_txt,
         irrelevant.contains("crawl.respect_robots_txt"),
     );
+    push_kv(
+        &mut lines,
+        "robots_cache_ttl_secs",
+        toml_integer(config.crawl.robots_cache_ttl_secs as i64),
+        config.crawl.robots_cache_ttl_secs == defaults.crawl.robots_cache_ttl_secs,
+        irrelevant.contains("crawl.robots_cache_ttl_secs"),
+    );
+    push_kv(
+        &mut lines,
+        "use_sitemap",
+        toml_bool(config.crawl.use_sitemap),
+        config.crawl.use_sitemap == defaults.crawl.use_sitemap,
+        irrelevant.contains("crawl.use_sitemap"),
+    );
     push_kv(
         &mut lines,
         "auto_js_rendering",
@@ -1427,6 +2320,13 @@ pub fn render_config_toml(
         config.crawl.auto_js_rendering == defaults.crawl.auto_js_rendering,
         irrelevant.contains("crawl.auto_js_rendering"),
     );
+    push_kv(
+        &mut lines,
+        "js_mode",
+        toml_string(config.crawl.js_mode.as_str()),
+        config.crawl.js_mode == defaults.crawl.js_mode,
+        irrelevant.contains("crawl.js_mode"),
+    );
     push_kv(
         &mut lines,
         "js_page_load_timeout_ms",
@@ -1441,6 +2341,20 @@ pub fn render_config_toml(
         config.crawl.js_render_wait_ms == defaults.crawl.js_render_wait_ms,
         irrelevant.contains("crawl.js_render_wait_ms"),
     );
+    push_kv(
+        &mut lines,
+        "js_wait_for_selector",
+        toml_string(config.crawl.js_wait_for_selector.as_deref().unwrap_or("")),
+        config.crawl.js_wait_for_selector == defaults.crawl.js_wait_for_selector,
+        irrelevant.contains("crawl.js_wait_for_selector"),
+    );
+    push_kv(
+        &mut lines,
+        "js_renderer_pool_size",
+        toml_integer(config.crawl.js_renderer_pool_size as i64),
+        config.crawl.js_renderer_pool_size == defaults.crawl.js_renderer_pool_size,
+        irrelevant.contains("crawl.js_renderer_pool_size"),
+    );
     push_kv(
         &mut lines,
         "js_no_sandbox",
@@ -1448,6 +2362,27 @@ pub fn render_config_toml(
         config.crawl.js_no_sandbox == defaults.crawl.js_no_sandbox,
         irrelevant.contains("crawl.js_no_sandbox"),
     );
+    push_kv(
+        &mut lines,
+        "min_document_chars",
+        toml_integer(config.crawl.min_document_chars as i64),
+        config.crawl.min_document_chars == defaults.crawl.min_document_chars,
+        irrelevant.contains("crawl.min_document_chars"),
+    );
+    push_kv(
+        &mut lines,
+        "default_skip_patterns",
+        toml_array(&config.crawl.default_skip_patterns),
+        config.crawl.default_skip_patterns == defaults.crawl.default_skip_patterns,
+        irrelevant.contains("crawl.default_skip_patterns"),
+    );
+    push_kv(
+        &mut lines,
+        "skip_date_paths",
+        toml_bool(config.crawl.skip_date_paths),
+        config.crawl.skip_date_paths == defaults.crawl.skip_date_paths,
+        irrelevant.contains("crawl.skip_date_paths"),
+    );
 
     lines.push("".to_string());
     lines.push("[crawl.multimodal]".to_string());
@@ -1525,10 +2460,43 @@ pub fn render_config_toml(
             == defaults.crawl.multimodal.include_css_background_images,
         irrelevant.contains("crawl.multimodal.include_css_background_images"),
     );
+    push_kv(
+        &mut lines,
+        "include_page_screenshot",
+        toml_bool(config.crawl.multimodal.include_page_screenshot),
+        config.crawl.multimodal.include_page_screenshot
+            == defaults.crawl.multimodal.include_page_screenshot,
+        irrelevant.contains("crawl.multimodal.include_page_screenshot"),
+    );
+
+    if !config.profiles.is_empty() {
+        let mut profiles = toml::Table::new();
+        profiles.insert(
+            "profiles".to_string(),
+            toml::Value::try_from(&config.profiles).expect("profiles serialize to TOML"),
+        );
+        lines.push("".to_string());
+        let rendered = toml::to_string(&profiles).expect("profiles serialize to TOML");
+        lines.push(rendered.trim_end().to_string());
+    }
 
     lines.join("\n") + "\n"
 }
 
+/// Recursively overlay `overrides` onto `base`
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge_tables(base, overrides)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 fn push_kv(lines: &mut Vec<String>, key: &str, value: String, is_default: bool, is_irrelevant: bool) {
     let prefix = if is_default || is_irrelevant { "# " } else { "" };
     lines.push(format!("{}{} = {}", prefix, key, value));
@@ -1543,7 +2511,17 @@ fn toml_integer(value: i64) -> String {
 }
 
 fn toml_float(value: f64) -> String {
-    toml::Value::Float(value).to_string()
+    toml::Value::Float(shorten_float(value)).to_string()
+}
+
+/// Drop the digits an `f32` setting picks up when widened, so 0.3 stays 0.3
+pub(crate) fn shorten_float(value: f64) -> f64 {
+    let narrow = value as f32;
+    if f64::from(narrow) == value {
+        narrow.to_string().parse().unwrap_or(value)
+    } else {
+        value
+    }
 }
 
 fn toml_bool(value: bool) -> String {
@@ -1590,6 +2568,118 @@ mod tests {
         assert_eq!(loaded.collection_name, "test_collection");
     }
 
+    const PROFILES_TOML: &str = r#"
+collection_name = "base_docs"
+
+[embedding]
+model = "BAAI/bge-small-en-v1.5"
+batch_size = 16
+
+[profiles.wiki]
+collection_name = "company_wiki"
+qdrant_url = "http://wiki-qdrant:6334"
+
+[profiles.wiki.embedding]
+model = "BAAI/bge-base-en-v1.5"
+
+[profiles.wiki.embedding.custom]
+family = "bge"
+"#;
+
+    #[test]
+    fn test_profile_overrides_base_settings() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        std::fs::write(&config_path, PROFILES_TOML).unwrap();
+
+        let base = Config::load(&config_path).unwrap();
+        assert_eq!(base.collection_name, "base_docs");
+        assert_eq!(base.active_profile, None);
+        assert_eq!(base.paths.db_file, tmp.path().join("metadata.db"));
+
+        let wiki = Config::load_profile(&config_path, Some("wiki")).unwrap();
+        assert_eq!(wiki.active_profile.as_deref(), Some("wiki"));
+        assert_eq!(wiki.collection_name, "company_wiki");
+        assert_eq!(wiki.qdrant_url, "http://wiki-qdrant:6334");
+        assert_eq!(wiki.embedding.model, "BAAI/bge-base-en-v1.5");
+        assert_eq!(wiki.embedding.custom.family.as_deref(), Some("bge"));
+        // Keys the profile leaves alone come from the base config
+        assert_eq!(wiki.embedding.batch_size, 16);
+        assert_eq!(wiki.embedding.custom.backend, base.embedding.custom.backend);
+        assert_eq!(wiki.paths.db_file, tmp.path().join("metadata.wiki.db"));
+        assert_ne!(wiki.embedding_cache_path(), base.embedding_cache_path());
+
+        // Rewriting the file keeps the profiles
+        let defaults = Config::default();
+        let rendered = render_config_toml(&base, &defaults, &HashSet::new());
+        std::fs::write(&config_path, rendered).unwrap();
+        let reloaded = Config::load_profile(&config_path, Some("wiki")).unwrap();
+        assert_eq!(reloaded.collection_name, "company_wiki");
+        assert_eq!(reloaded.embedding.model, "BAAI/bge-base-en-v1.5");
+    }
+
+    #[test]
+    fn test_collection_override_applies_after_profile() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        std::fs::write(&config_path, PROFILES_TOML).unwrap();
+
+        let mut base = Config::load(&config_path).unwrap();
+        base.apply_collection_override("scratch").unwrap();
+        assert_eq!(base.collection_name, "scratch");
+        assert_eq!(base.collection_override.as_deref(), Some("scratch"));
+        assert_eq!(
+            base.paths.db_file,
+            tmp.path().join("metadata.collection.scratch.db")
+        );
+
+        // The override wins over the profile's collection, not its other settings
+        let mut wiki = Config::load_profile(&config_path, Some("wiki")).unwrap();
+        wiki.apply_collection_override("wiki_drafts").unwrap();
+        assert_eq!(wiki.collection_name, "wiki_drafts");
+        assert_eq!(wiki.qdrant_url, "http://wiki-qdrant:6334");
+        assert_eq!(wiki.embedding.model, "BAAI/bge-base-en-v1.5");
+        assert_eq!(
+            wiki.paths.db_file,
+            tmp.path().join("metadata.wiki.collection.wiki_drafts.db")
+        );
+
+        // The same override under another profile keeps its own database
+        let mut wiki_scratch = Config::load_profile(&config_path, Some("wiki")).unwrap();
+        wiki_scratch.apply_collection_override("scratch").unwrap();
+        assert_ne!(wiki_scratch.paths.db_file, base.paths.db_file);
+
+        // Naming the collection already in use keeps its database
+        let mut same = Config::load_profile(&config_path, Some("wiki")).unwrap();
+        same.apply_collection_override("company_wiki").unwrap();
+        assert_eq!(same.collection_override, None);
+        assert_eq!(same.paths.db_file, tmp.path().join("metadata.wiki.db"));
+
+        for bad in ["", "../docs", "two words"] {
+            assert!(base.apply_collection_override(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_profile_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        std::fs::write(&config_path, PROFILES_TOML).unwrap();
+
+        let err = Config::load_profile(&config_path, Some("rust-docs")).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Unknown profile 'rust-docs'"), "{message}");
+        assert!(message.contains("wiki"), "{message}");
+
+        // Profiles may only override the documented settings
+        let invalid = format!(
+            "{}\n[profiles.chunky.chunk]\nmax_chars = 10\n",
+            PROFILES_TOML
+        );
+        std::fs::write(&config_path, invalid).unwrap();
+        assert!(Config::load(&config_path).is_err());
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = Config::default();
@@ -1605,6 +2695,47 @@ mod tests {
         // Invalid: min > max
         config.chunk.min_chars = config.chunk.max_chars + 1;
         assert!(config.validate().is_err());
+
+        // Invalid: a chunking strategy that cannot run yet
+        let mut config = Config::default();
+        config.chunk.strategy = ChunkStrategy::Semantic;
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("chunk.strategy"), "{}", message);
+        config.chunk.strategy = ChunkStrategy::Heading;
+        assert!(config.validate().is_ok());
+
+        // Invalid: quantile outside the range Qdrant accepts
+        let mut config = Config::default();
+        config.store.quantization_quantile = Some(0.3);
+        assert!(config.validate().is_err());
+        config.store.quantization_quantile = Some(0.99);
+        assert!(config.validate().is_ok());
+
+        // Invalid: empty upsert batches
+        let mut config: Config = toml::from_str("[store]\nupsert_batch_size = 0").unwrap();
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("store.upsert_batch_size"), "{}", message);
+        config.store.upsert_batch_size = 64;
+        assert!(config.validate().is_ok());
+
+        // Invalid: HNSW settings outside their ranges
+        config.store.hnsw.m = Some(512);
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("store.hnsw.m must be between 4 and 128"));
+        config.store.hnsw.m = Some(32);
+        config.store.hnsw.search_ef = Some(0);
+        assert!(config.validate().is_err());
+        config.store.hnsw.search_ef = Some(256);
+        config.store.hnsw.full_scan_threshold = Some(5);
+        assert!(config.validate().is_err());
+        config.store.hnsw.full_scan_threshold = Some(20000);
+        assert!(config.validate().is_ok());
+
+        // Invalid: fewer rerank candidates than results kept after reranking
+        config.query.rerank_candidates = Some(config.reranker.top_k - 1);
+        assert!(config.validate().is_err());
+        config.query.rerank_candidates = Some(config.reranker.top_k);
+        assert!(config.validate().is_ok());
     }
 
     #[test]
@@ -1625,6 +2756,22 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_allowlist_accepts_registry_text_models_without_custom() {
+        for model in [
+            "nomic-ai/nomic-embed-text-v1.5",
+            "Snowflake/snowflake-arctic-embed-m",
+            "mixedbread-ai/mxbai-embed-large-v1",
+        ] {
+            let mut config = Config::default();
+            config.embedding.model = model.to_string();
+            config.embedding.allow_custom = false;
+            config.reranker.enabled = true;
+            config.reranker.model = "mixedbread-ai/mxbai-rerank-base-v1".to_string();
+            assert!(config.validate().is_ok(), "{} should be allowlisted", model);
+        }
+    }
+
     #[test]
     fn test_multimodal_validation_requires_embedding_support() {
         let mut config = Config::default();
@@ -1742,9 +2889,125 @@ mod tests {
         config.embedding.dimension = Some(384);
 
         let err = config.resolve_embedding_config().await.unwrap_err();
-        assert!(err
-            .to_string()
-            .contains("config 384 != probe 768"));
+        assert!(
+            matches!(
+                err,
+                Error::DimensionMismatch {
+                    expected: 384,
+                    actual: 768,
+                    ..
+                }
+            ),
+            "got {:?}",
+            err
+        );
+        assert_eq!(err.exit_code(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_embedding_config_reports_unreachable_backend() {
+        let mut config = Config::default();
+        config.embedding.url = "http://127.0.0.1:9".to_string();
+
+        let err = config.resolve_embedding_config().await.unwrap_err();
+        match &err {
+            Error::BackendUnreachable { url, .. } => assert!(url.contains("127.0.0.1:9")),
+            other => panic!("expected BackendUnreachable, got {:?}", other),
+        }
+        assert!(err.is_backend_unavailable());
+        assert_eq!(err.exit_code(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resolved_embedding_config_is_cached_until_config_changes() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/probe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "BAAI/bge-small-en-v1.5",
+                "embedding_dim": 384,
+                "text_embeddings": [vec![0.0_f32; 384]]
+            })))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.embedding.url = mock_server.uri();
+
+        // The first run probes and caches; the second reuses the cache
+        let first = config.resolve_embedding_config_cached(false).await.unwrap();
+        let second = config.resolve_embedding_config_cached(false).await.unwrap();
+        assert!(config.embedding_cache_path().exists());
+        assert_eq!(first.dimension, 384);
+        assert_eq!(second.dimension, first.dimension);
+        assert_eq!(second.model_id, first.model_id);
+        assert_eq!(second.backend.url, mock_server.uri());
+
+        // Refreshing and changing the config both probe again
+        config.resolve_embedding_config_cached(true).await.unwrap();
+        config.embedding.batch_size += 1;
+        config.resolve_embedding_config_cached(false).await.unwrap();
+        config.resolve_embedding_config_cached(false).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mrl_model_truncates_to_configured_dimension() {
+        let mock_server = MockServer::start().await;
+        let native: Vec<f32> = (0..768).map(|i| (i % 7) as f32 + 1.0).collect();
+
+        Mock::given(method("POST"))
+            .and(path("/probe"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "nomic-ai/nomic-embed-text-v1.5",
+                "embedding_dim": 768,
+                "text_embeddings": [native.clone()]
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embed/text"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "embeddings": [native.clone(), native]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut config = Config::default();
+        config.embedding.model = "nomic-ai/nomic-embed-text-v1.5".to_string();
+        config.embedding.url = mock_server.uri();
+        config.embedding.dimension = Some(256);
+
+        let resolved = config.resolve_embedding_config().await.unwrap();
+        assert_eq!(resolved.dimension, 256);
+        assert_eq!(resolved.native_dimension, 768);
+
+        let embedder = crate::embed::create_embedder(&resolved).unwrap();
+        assert_eq!(embedder.dimension(), 256);
+        let vectors = embedder
+            .embed(vec!["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        for vector in vectors {
+            assert_eq!(vector.len(), 256);
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-5);
+        }
+
+        // Requesting more dimensions than the model produces is still rejected
+        config.embedding.dimension = Some(1024);
+        let err = config.resolve_embedding_config().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DimensionMismatch {
+                expected: 1024,
+                actual: 768,
+                ..
+            }
+        ));
     }
 
     #[tokio::test]
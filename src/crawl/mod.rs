@@ -20,72 +20,169 @@ pub use renderer::*;
 pub use robots::*;
 pub use sitemap::*;
 
-use crate::config::CrawlConfig;
+use crate::chunk::compute_content_hash;
+use crate::config::{CrawlConfig, JsMode};
 use crate::error::{Error, Result};
-use crate::parse::{parse_html, ContentType, ExtractedLink};
+use crate::parse::{parse_html_links, ContentType, ExtractedLink};
+use regex::Regex;
+use reqwest::redirect::Policy;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 use url::Url;
 
 /// A crawled page
 #[derive(Debug, Clone)]
 pub struct CrawledPage {
+    /// URL that served the page, after any redirects
     pub url: String,
+    /// URL originally requested, when it redirected to `url`
+    pub redirected_from: Option<String>,
     pub content: String,
+    /// Hash of `content`; ingestion skips the page when it matches the stored document
+    pub content_hash: String,
     pub content_type: ContentType,
     pub title: Option<String>,
     pub links: Vec<ExtractedLink>,
     pub depth: u32,
+    /// Full-page screenshot when the page was rendered with screenshots enabled
+    pub screenshot: Option<Vec<u8>>,
+}
+
+/// Per-outcome tallies for the crawls run by a [`Crawler`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlReport {
+    /// Pages fetched successfully
+    pub pages_fetched: u32,
+    /// Pages that could not be fetched (HTTP errors, timeouts, ...)
+    pub pages_failed: u32,
+    /// URLs skipped because robots.txt disallows them
+    pub pages_skipped_robots: u32,
+    /// Pages that redirected outside the crawl's hosts or path prefix
+    #[serde(default)]
+    pub pages_skipped_redirect: u32,
+    /// Queued URLs skipped because they had already been visited
+    pub pages_deduplicated: u32,
+    /// Pages rendered with the headless browser
+    pub pages_rendered: u32,
+    /// Total size of the fetched page content
+    pub bytes_fetched: u64,
+}
+
+/// Redirects followed before a fetch gives up
+const MAX_REDIRECTS: usize = 5;
+
+/// Hosts and path prefix a crawl's pages may redirect into
+#[derive(Debug, Clone)]
+struct CrawlScope {
+    allowed_hosts: HashSet<String>,
+    allow_subdomains: bool,
+    path_prefix: String,
+}
+
+impl CrawlScope {
+    fn contains(&self, url: &Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| host_allowed(host, &self.allowed_hosts, self.allow_subdomains))
+            && url.path().starts_with(&self.path_prefix)
+    }
+}
+
+/// Raised by the page client's redirect policy for a hop out of the crawl scope
+#[derive(Debug)]
+struct OutOfScopeRedirect(Url);
+
+impl std::fmt::Display for OutOfScopeRedirect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "redirect to {} leaves the crawl scope", self.0)
+    }
+}
+
+impl std::error::Error for OutOfScopeRedirect {}
+
+/// The out-of-scope redirect target behind a failed page request, if any
+fn out_of_scope_target(err: &reqwest::Error) -> Option<&Url> {
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if let Some(redirect) = err.downcast_ref::<OutOfScopeRedirect>() {
+            return Some(&redirect.0);
+        }
+        source = err.source();
+    }
+    None
 }
 
 /// Web crawler state
 pub struct Crawler {
+    /// Client for robots.txt and sitemaps, which may redirect anywhere
     client: Client,
+    /// Client for pages, whose redirects are checked hop by hop against `scope`
+    page_client: Client,
+    /// Scope of the crawl in progress; `None` follows any redirect
+    scope: Arc<std::sync::RwLock<Option<CrawlScope>>>,
     config: CrawlConfig,
-    robots_cache: Arc<RwLock<HashMap<String, RobotsRules>>>,
+    url_patterns: UrlPatterns,
+    robots_cache: Arc<RwLock<RobotsCache>>,
+    /// Per-host locks held while fetching robots.txt, so concurrent fetches of
+    /// one host share a request without holding up other hosts
+    robots_fetch: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
     rate_limiters: Arc<RwLock<HashMap<String, HostRateLimiter>>>,
     visited: Arc<RwLock<HashSet<String>>>,
-    renderer: Option<Arc<tokio::sync::Mutex<HeadlessRenderer>>>,
+    /// Pages that could not be fetched during a crawl, as "url: error"
+    failures: Arc<RwLock<Vec<String>>>,
+    report: Arc<RwLock<CrawlReport>>,
+    renderer: Option<RendererPool>,
 }
 
 impl Crawler {
     /// Create a new crawler
     pub fn new(config: CrawlConfig) -> Result<Self> {
-        let client = Client::builder()
-            .user_agent(&config.user_agent)
-            .timeout(Duration::from_secs(config.timeout_secs))
-            .gzip(true)
-            .brotli(true)
-            .redirect(reqwest::redirect::Policy::limited(5))
-            .build()
-            .map_err(|e| Error::Crawl(format!("Failed to create HTTP client: {}", e)))?;
-
-        // Initialize renderer if auto JS rendering is enabled and feature is available
-        let renderer = if config.auto_js_rendering && is_js_rendering_available() {
+        let client = http_client(&config, Policy::limited(MAX_REDIRECTS))?;
+        let scope: Arc<std::sync::RwLock<Option<CrawlScope>>> = Arc::default();
+        let page_client = http_client(&config, scoped_redirect_policy(scope.clone()))?;
+
+        let url_patterns = UrlPatterns::new(&config.include_patterns, &config.exclude_patterns)?;
+
+        // Initialize renderer if JS rendering may be used and the feature is available
+        let renderer = if config.effective_js_mode() != JsMode::Never && is_js_rendering_available()
+        {
             let renderer_config = RendererConfig {
                 page_load_timeout_ms: config.js_page_load_timeout_ms,
                 render_wait_ms: config.js_render_wait_ms,
                 headless: true,
-                wait_for_selector: None,
+                wait_for_selector: config
+                    .js_wait_for_selector
+                    .clone()
+                    .filter(|selector| !selector.trim().is_empty()),
                 sandbox: !config.js_no_sandbox,
+                capture_screenshot: config.multimodal.captures_page_screenshots(),
             };
-            Some(Arc::new(tokio::sync::Mutex::new(HeadlessRenderer::new(
+            Some(RendererPool::new(
                 renderer_config,
-            ))))
+                config.js_renderer_pool_size,
+            ))
         } else {
             None
         };
 
+        let robots_ttl = Duration::from_secs(config.robots_cache_ttl_secs);
         Ok(Self {
             client,
+            page_client,
+            scope,
             config,
-            robots_cache: Arc::new(RwLock::new(HashMap::new())),
+            url_patterns,
+            robots_cache: Arc::new(RwLock::new(RobotsCache::new(robots_ttl))),
+            robots_fetch: Arc::new(Mutex::new(HashMap::new())),
             rate_limiters: Arc::new(RwLock::new(HashMap::new())),
             visited: Arc::new(RwLock::new(HashSet::new())),
+            failures: Arc::new(RwLock::new(Vec::new())),
+            report: Arc::new(RwLock::new(CrawlReport::default())),
             renderer,
         })
     }
@@ -100,12 +197,9 @@ impl Crawler {
 
         // Check robots.txt
         if self.config.respect_robots_txt {
-            self.ensure_robots_loaded(&host, &parsed_url).await?;
-            let rules = self.robots_cache.read().await;
-            if let Some(r) = rules.get(&host) {
-                if !r.is_allowed(parsed_url.path(), &self.config.user_agent) {
-                    return Err(Error::RobotsDisallowed(url.to_string()));
-                }
+            let rules = self.robots_rules(&host, &parsed_url).await?;
+            if !rules.is_allowed(parsed_url.path(), &self.config.user_agent) {
+                return Err(Error::RobotsDisallowed(url.to_string()));
             }
         }
 
@@ -115,7 +209,34 @@ impl Crawler {
         debug!("Fetching: {}", url);
 
         // Initial fetch with plain HTTP
-        let response = self.client.get(url).send().await?;
+        let response = match self.page_client.get(url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return Err(match out_of_scope_target(&e) {
+                    Some(target) => Error::RedirectOutOfScope {
+                        from: url.to_string(),
+                        to: target.to_string(),
+                    },
+                    None => e.into(),
+                })
+            }
+        };
+
+        // Redirects are followed, so the page belongs to the URL that served it
+        let final_url = response.url().clone();
+        let redirected_from = (without_fragment(&final_url) != without_fragment(&parsed_url))
+            .then(|| url.to_string());
+        if redirected_from.is_some() {
+            debug!("{} redirected to {}", url, final_url);
+            if self.config.respect_robots_txt {
+                let final_host = final_url.host_str().unwrap_or_default();
+                let rules = self.robots_rules(final_host, &final_url).await?;
+                if !rules.is_allowed(final_url.path(), &self.config.user_agent) {
+                    return Err(Error::RobotsDisallowed(final_url.to_string()));
+                }
+            }
+        }
+        let url = final_url.as_str();
 
         let status = response.status();
         if !status.is_success() {
@@ -132,34 +253,36 @@ impl Crawler {
 
         // Detect content type
         let ct = ContentType::detect(
-            Some(std::path::Path::new(parsed_url.path())),
+            Some(std::path::Path::new(final_url.path())),
             content_type_header.as_deref(),
         );
 
         // For HTML pages, check if SPA rendering is needed
-        if ct == ContentType::Html && self.config.auto_js_rendering {
+        let js_mode = self.config.effective_js_mode();
+        if ct == ContentType::Html && js_mode != JsMode::Never {
             let analysis = analyze_page(&content, url);
 
-            if analysis.needs_js_rendering {
-                info!(
-                    "SPA detected ({}, confidence: {:.0}%): {}",
-                    match &analysis.technology {
-                        PageTechnology::Spa(fw) => fw.to_string(),
-                        _ => "Dynamic".to_string(),
-                    },
-                    analysis.confidence * 100.0,
-                    url
-                );
+            if should_render(js_mode, &analysis) {
+                if analysis.needs_js_rendering {
+                    info!(
+                        "SPA detected ({}, confidence: {:.0}%): {}",
+                        match &analysis.technology {
+                            PageTechnology::Spa(fw) => fw.to_string(),
+                            _ => "Dynamic".to_string(),
+                        },
+                        analysis.confidence * 100.0,
+                        url
+                    );
 
-                for indicator in &analysis.indicators {
-                    debug!("  - {}", indicator);
+                    for indicator in &analysis.indicators {
+                        debug!("  - {}", indicator);
+                    }
                 }
 
                 // Try JS rendering if available
                 if let Some(renderer) = &self.renderer {
                     info!("Rendering with headless browser...");
-                    let renderer_guard = renderer.lock().await;
-                    match renderer_guard.render(url).await {
+                    match renderer.render(url).await {
                         Ok(rendered) => {
                             info!(
                                 "Rendered in {}ms: {} ({} bytes)",
@@ -167,9 +290,11 @@ impl Crawler {
                                 url,
                                 rendered.html.len()
                             );
+                            self.report.write().await.pages_rendered += 1;
 
-                            // Parse the rendered HTML and extract hash routes
-                            let parsed = parse_html(&rendered.html, Some(&rendered.url))?;
+                            // Extract links from the rendered HTML, plus hash routes
+                            let (title, mut links) =
+                                parse_html_links(&rendered.html, Some(&rendered.url));
 
                             // Check for hash routes in rendered content
                             let hash_routes =
@@ -185,7 +310,6 @@ impl Crawler {
                             }
 
                             // Convert hash routes to full URLs as links
-                            let mut links = parsed.links;
                             for route in hash_routes {
                                 let hash_url = build_hash_route_url(&rendered.url, &route);
                                 links.push(ExtractedLink {
@@ -197,11 +321,14 @@ impl Crawler {
 
                             return Ok(CrawledPage {
                                 url: rendered.url,
+                                redirected_from,
+                                content_hash: compute_content_hash(rendered.html.as_bytes()),
                                 content: rendered.html,
                                 content_type: ContentType::Html,
-                                title: rendered.title.or(parsed.title),
+                                title: rendered.title.or(title),
                                 links,
                                 depth: 0,
+                                screenshot: rendered.screenshot,
                             });
                         }
                         Err(e) => {
@@ -211,8 +338,8 @@ impl Crawler {
                     }
                 } else {
                     warn!(
-                        "SPA detected but JS rendering not available. \
-                         Compile with --features js-rendering or disable auto_js_rendering. \
+                        "JS rendering requested but not available. \
+                         Compile with --features js-rendering or set crawl.js_mode = \"never\". \
                          URL: {}",
                         url
                     );
@@ -220,21 +347,24 @@ impl Crawler {
             }
         }
 
-        // Parse HTML (either static content or non-SPA)
+        // Only the title and links are needed here; the full parse happens at
+        // ingestion, and only for pages whose content hash changed
         let (title, links) = if ct == ContentType::Html {
-            let parsed = parse_html(&content, Some(url))?;
-            (parsed.title, parsed.links)
+            parse_html_links(&content, Some(url))
         } else {
             (None, Vec::new())
         };
 
         Ok(CrawledPage {
             url: url.to_string(),
+            redirected_from,
+            content_hash: compute_content_hash(content.as_bytes()),
             content,
             content_type: ct,
             title,
             links,
             depth: 0,
+            screenshot: None,
         })
     }
 
@@ -245,15 +375,14 @@ impl Crawler {
         debug!("Fetching hash route: {}", full_url);
 
         if let Some(renderer) = &self.renderer {
-            let renderer_guard = renderer.lock().await;
-            match renderer_guard.render(&full_url).await {
+            match renderer.render(&full_url).await {
                 Ok(rendered) => {
-                    let parsed = parse_html(&rendered.html, Some(&rendered.url))?;
+                    self.report.write().await.pages_rendered += 1;
+                    let (title, mut links) = parse_html_links(&rendered.html, Some(&rendered.url));
 
                     // Extract more hash routes from this page
                     let hash_routes =
                         extract_hash_routes_from_rendered(&rendered.html, &rendered.url);
-                    let mut links = parsed.links;
                     for route in hash_routes {
                         let hash_url = build_hash_route_url(&rendered.url, &route);
                         links.push(ExtractedLink {
@@ -265,11 +394,14 @@ impl Crawler {
 
                     return Ok(CrawledPage {
                         url: full_url,
+                        redirected_from: None,
+                        content_hash: compute_content_hash(rendered.html.as_bytes()),
                         content: rendered.html,
                         content_type: ContentType::Html,
-                        title: rendered.title.or(parsed.title),
+                        title: rendered.title.or(title),
                         links,
                         depth: 0,
+                        screenshot: rendered.screenshot,
                     });
                 }
                 Err(e) => {
@@ -286,19 +418,22 @@ impl Crawler {
         ))
     }
 
-    /// Close the renderer (call this when done crawling)
+    /// Close the renderers (call this when done crawling)
     pub async fn close(&self) -> Result<()> {
         if let Some(renderer) = &self.renderer {
-            let renderer_guard = renderer.lock().await;
-            renderer_guard.close().await?;
+            renderer.close().await?;
         }
         Ok(())
     }
 
     /// Crawl from a seed URL
+    ///
+    /// Cancelling `cancel` stops the crawl before the next page, abandoning any
+    /// fetch in flight, and returns the pages crawled so far.
     pub async fn crawl(
         &self,
         seed_url: &str,
+        cancel: Option<&CancellationToken>,
         callback: impl Fn(CrawledPage) -> bool + Send + Sync,
     ) -> Result<Vec<CrawledPage>> {
         let seed = Url::parse(seed_url)?;
@@ -313,24 +448,15 @@ impl Crawler {
         if allowed_hosts.is_empty() {
             allowed_hosts.insert(seed_host.clone());
         }
+        let allow_subdomains = self.config.allow_subdomains;
 
         // Determine path prefix restriction
         // If not explicitly set, use the seed URL's path
-        let path_prefix = self.config.path_prefix.clone().unwrap_or_else(|| {
-            let seed_path = seed.path();
-            // Get the parent directory path from the seed URL
-            // e.g., /docs/intro/getting-started -> /docs/intro/
-            // e.g., /docs/ -> /docs/
-            if seed_path.ends_with('/') {
-                seed_path.to_string()
-            } else {
-                // Get directory part
-                match seed_path.rfind('/') {
-                    Some(idx) => seed_path[..=idx].to_string(),
-                    None => "/".to_string(),
-                }
-            }
-        });
+        let mut path_prefix = self
+            .config
+            .path_prefix
+            .clone()
+            .unwrap_or_else(|| seed_path_prefix(seed.path()));
 
         if path_prefix != "/" {
             info!("Restricting crawl to path prefix: {}", path_prefix);
@@ -339,6 +465,26 @@ impl Crawler {
         let mut queue: VecDeque<(String, u32)> = VecDeque::new();
         queue.push_back((seed_url.to_string(), 0));
 
+        if self.config.use_sitemap {
+            for url in self.robots_sitemap_urls(&seed, &seed_host).await {
+                if !should_crawl_url(&url, &self.config) || !self.url_patterns.allows(&url) {
+                    continue;
+                }
+                let in_scope = Url::parse(&url).is_ok_and(|parsed| {
+                    parsed
+                        .host_str()
+                        .is_some_and(|host| host_allowed(host, &allowed_hosts, allow_subdomains))
+                        && parsed.path().starts_with(&path_prefix)
+                });
+                if in_scope {
+                    queue.push_back((url, 0));
+                } else {
+                    debug!("Skipping sitemap URL {} - outside crawl scope", url);
+                }
+            }
+            info!("Queued {} sitemap URLs", queue.len() - 1);
+        }
+
         let mut results = Vec::new();
         let mut pages_crawled = 0u32;
         let mut attempts = 0u32;
@@ -360,6 +506,11 @@ impl Crawler {
         let mut is_hash_routed_spa = false;
 
         while let Some((url, depth)) = queue.pop_front() {
+            if cancel.is_some_and(|token| token.is_cancelled()) {
+                info!("Crawl cancelled after {} pages", results.len());
+                break;
+            }
+
             // Check limits
             if depth > self.config.max_depth {
                 continue;
@@ -394,6 +545,7 @@ impl Crawler {
             {
                 let mut visited = self.visited.write().await;
                 if visited.contains(&normalized) {
+                    self.report.write().await.pages_deduplicated += 1;
                     continue;
                 }
                 visited.insert(normalized.clone());
@@ -402,23 +554,88 @@ impl Crawler {
             // Determine if this is a hash route URL
             let is_hash_route = url.contains("#/");
 
+            // Redirects from the seed may move the crawl; any other page must stay in scope
+            self.set_scope((url != seed_url).then(|| CrawlScope {
+                allowed_hosts: allowed_hosts.clone(),
+                allow_subdomains,
+                path_prefix: path_prefix.clone(),
+            }));
+
             // Fetch the page
-            let fetch_result = if is_hash_route {
-                // Extract base URL and hash route
-                if let Some(hash_idx) = url.find('#') {
-                    let base = &url[..hash_idx];
-                    let route = &url[hash_idx + 1..];
-                    self.fetch_hash_route(base, route).await
+            let fetch = async {
+                if is_hash_route {
+                    // Extract base URL and hash route
+                    if let Some(hash_idx) = url.find('#') {
+                        let base = &url[..hash_idx];
+                        let route = &url[hash_idx + 1..];
+                        self.fetch_hash_route(base, route).await
+                    } else {
+                        self.fetch(&url).await
+                    }
                 } else {
                     self.fetch(&url).await
                 }
-            } else {
-                self.fetch(&url).await
+            };
+            let fetch_result = match cancel {
+                Some(token) => match token.run_until_cancelled(fetch).await {
+                    Some(result) => result,
+                    None => {
+                        info!("Crawl cancelled while fetching {}", url);
+                        break;
+                    }
+                },
+                None => fetch.await,
             };
 
             match fetch_result {
                 Ok(mut page) => {
                     page.depth = depth;
+                    if page.redirected_from.is_some() && url == seed_url {
+                        // A redirected seed (http -> https, apex -> www) moves the
+                        // crawl to where it landed
+                        if let Ok(landed) = Url::parse(&page.url) {
+                            if let Some(host) = landed.host_str() {
+                                if self.config.allowed_domains.is_empty() {
+                                    allowed_hosts.insert(host.to_string());
+                                }
+                            }
+                            if self.config.path_prefix.is_none() {
+                                path_prefix = seed_path_prefix(landed.path());
+                            }
+                        }
+                    }
+                    if page.redirected_from.is_some() {
+                        let in_scope = Url::parse(&page.url).is_ok_and(|final_url| {
+                            final_url.host_str().is_some_and(|host| {
+                                host_allowed(host, &allowed_hosts, allow_subdomains)
+                            }) && final_url.path().starts_with(&path_prefix)
+                        });
+                        if !in_scope {
+                            debug!(
+                                "Skipping {} - redirected to {} outside crawl scope",
+                                url, page.url
+                            );
+                            self.report.write().await.pages_skipped_redirect += 1;
+                            continue;
+                        }
+                        // Another queued URL may already have reached the same page
+                        let final_normalized = if is_hash_routed_spa {
+                            normalize_url_with_hash(&page.url)
+                        } else {
+                            normalize_url(&page.url)
+                        };
+                        if final_normalized != normalized
+                            && !self.visited.write().await.insert(final_normalized)
+                        {
+                            self.report.write().await.pages_deduplicated += 1;
+                            continue;
+                        }
+                    }
+                    {
+                        let mut report = self.report.write().await;
+                        report.pages_fetched += 1;
+                        report.bytes_fetched += page.content.len() as u64;
+                    }
 
                     // Check if we discovered hash routes - if so, switch to hash-aware mode
                     let has_hash_routes = page.links.iter().any(|l| l.url.contains("#/"));
@@ -428,19 +645,22 @@ impl Crawler {
                     }
 
                     // Queue internal links
+                    // Links are followed by host policy rather than `is_internal`,
+                    // which only marks links to the page's own host
                     for link in &page.links {
-                        if !link.is_internal {
-                            continue;
-                        }
-
                         // Skip URLs that shouldn't be crawled
-                        if !should_crawl_url(&link.url) {
+                        if !should_crawl_url(&link.url, &self.config) {
                             continue;
                         }
 
                         if let Ok(link_url) = Url::parse(&link.url) {
                             if let Some(host) = link_url.host_str() {
-                                if allowed_hosts.contains(host) {
+                                if host_allowed(host, &allowed_hosts, allow_subdomains) {
+                                    if !self.url_patterns.allows(&link.url) {
+                                        debug!("Skipping {} - excluded by URL patterns", link.url);
+                                        continue;
+                                    }
+
                                     // Check path prefix restriction
                                     let link_path = link_url.path();
                                     if !link_path.starts_with(&path_prefix) {
@@ -488,41 +708,131 @@ impl Crawler {
                         break;
                     }
                 }
+                Err(Error::RobotsDisallowed(_)) => {
+                    debug!("Skipping {} - disallowed by robots.txt", url);
+                    self.report.write().await.pages_skipped_robots += 1;
+                }
+                Err(Error::RedirectOutOfScope { to, .. }) => {
+                    debug!(
+                        "Skipping {} - redirected to {} outside crawl scope",
+                        url, to
+                    );
+                    self.report.write().await.pages_skipped_redirect += 1;
+                }
                 Err(e) => {
                     warn!("Failed to fetch {}: {}", url, e);
+                    self.report.write().await.pages_failed += 1;
+                    self.failures.write().await.push(format!("{}: {}", url, e));
                 }
             }
         }
+        self.set_scope(None);
 
         info!("Crawled {} pages from {}", results.len(), seed_url);
         Ok(results)
     }
 
-    async fn ensure_robots_loaded(&self, host: &str, url: &Url) -> Result<()> {
-        {
-            let cache = self.robots_cache.read().await;
-            if cache.contains_key(host) {
-                return Ok(());
+    fn set_scope(&self, scope: Option<CrawlScope>) {
+        *self.scope.write().unwrap_or_else(|e| e.into_inner()) = scope;
+    }
+
+    /// Take the fetch failures recorded by previous crawls
+    pub async fn take_failures(&self) -> Vec<String> {
+        std::mem::take(&mut *self.failures.write().await)
+    }
+
+    /// Take the outcome tallies recorded by previous crawls
+    pub async fn take_report(&self) -> CrawlReport {
+        std::mem::take(&mut *self.report.write().await)
+    }
+
+    /// Page URLs from the sitemaps listed in the seed host's robots.txt
+    ///
+    /// Failures are logged and yield no URLs, so the crawl still starts from
+    /// the seed.
+    async fn robots_sitemap_urls(&self, seed: &Url, seed_host: &str) -> Vec<String> {
+        let sitemaps = match self.robots_rules(seed_host, seed).await {
+            Ok(rules) => rules.sitemaps().to_vec(),
+            Err(e) => {
+                warn!("Could not read robots.txt for sitemaps: {}", e);
+                return Vec::new();
+            }
+        };
+        if sitemaps.is_empty() {
+            info!("robots.txt for {} lists no sitemaps", seed_host);
+            return Vec::new();
+        }
+
+        let parser = SitemapParser::new(self);
+        let mut urls = Vec::new();
+        for sitemap in &sitemaps {
+            match parser.parse(sitemap).await {
+                Ok(entries) => urls.extend(entries.into_iter().map(|entry| entry.loc)),
+                Err(e) => warn!("Could not read sitemap {}: {}", sitemap, e),
             }
         }
+        urls
+    }
+
+    /// Rules for `host`, fetching its robots.txt when not cached or expired
+    async fn robots_rules(&self, host: &str, url: &Url) -> Result<RobotsRules> {
+        if let Some(fetch) = self.robots_cache.read().await.get(host) {
+            return Ok(fetch.rules());
+        }
 
-        let robots_url = format!("{}://{}/robots.txt", url.scheme(), host);
+        // Another task may have fetched it while we waited for the host's lock
+        let host_lock = {
+            let mut locks = self.robots_fetch.lock().await;
+            locks.entry(host.to_string()).or_default().clone()
+        };
+        let _fetching = host_lock.lock().await;
+        if let Some(fetch) = self.robots_cache.read().await.get(host) {
+            return Ok(fetch.rules());
+        }
+
+        // Joining keeps the port, which the host alone would drop
+        let robots_url = url.join("/robots.txt")?;
         debug!("Fetching robots.txt from {}", robots_url);
 
-        let rules = match self.client.get(&robots_url).send().await {
-            Ok(response) if response.status().is_success() => {
-                let text = response.text().await.unwrap_or_default();
-                RobotsRules::parse(&text)
-            }
-            _ => {
-                // No robots.txt or error - allow all
-                RobotsRules::allow_all()
-            }
+        let fetch = match self.client.get(robots_url.as_str()).send().await {
+            Ok(response) if response.status().is_success() => match response.text().await {
+                Ok(text) => RobotsFetch::Fetched(RobotsRules::parse(&text)),
+                Err(e) => RobotsFetch::Failed(e.to_string()),
+            },
+            Ok(response) => RobotsFetch::from_error_status(response.status()),
+            Err(e) => RobotsFetch::Failed(e.to_string()),
         };
+        match &fetch {
+            RobotsFetch::Failed(reason) => warn!(
+                "Could not fetch {} ({}); allowing all paths until retry",
+                robots_url, reason
+            ),
+            RobotsFetch::Unavailable(reason) => warn!(
+                "{} is unavailable ({}); skipping the host's pages until retry",
+                robots_url, reason
+            ),
+            RobotsFetch::Fetched(_) | RobotsFetch::Missing => {}
+        }
 
-        let mut cache = self.robots_cache.write().await;
-        cache.insert(host.to_string(), rules);
-        Ok(())
+        let rules = fetch.rules();
+        self.robots_cache.write().await.insert(host, fetch);
+        Ok(rules)
+    }
+
+    /// Fetch the raw body of `url`, waiting for the host's rate limit but
+    /// without robots.txt checks or content handling (used for sitemaps)
+    pub(crate) async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let parsed_url = Url::parse(url)?;
+        let host = parsed_url
+            .host_str()
+            .ok_or_else(|| Error::Crawl("URL has no host".to_string()))?;
+        self.rate_limit(host).await?;
+
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::Crawl(format!("HTTP {}: {}", response.status(), url)));
+        }
+        Ok(response.bytes().await?.to_vec())
     }
 
     async fn rate_limit(&self, host: &str) -> Result<()> {
@@ -539,6 +849,61 @@ impl Crawler {
     }
 }
 
+/// HTTP client for crawl requests, following redirects under `policy`
+fn http_client(config: &CrawlConfig, policy: Policy) -> Result<Client> {
+    Client::builder()
+        .user_agent(&config.user_agent)
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .pool_max_idle_per_host(config.pool_max_idle_per_host)
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+        .gzip(true)
+        .brotli(true)
+        .redirect(policy)
+        .build()
+        .map_err(|e| Error::Crawl(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Follow up to [`MAX_REDIRECTS`] redirects, refusing any hop that leaves the
+/// crawl scope in effect, so nothing out of scope is requested
+fn scoped_redirect_policy(scope: Arc<std::sync::RwLock<Option<CrawlScope>>>) -> Policy {
+    Policy::custom(move |attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        let in_scope = scope
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .is_none_or(|scope| scope.contains(attempt.url()));
+        if in_scope {
+            attempt.follow()
+        } else {
+            let target = attempt.url().clone();
+            attempt.error(OutOfScopeRedirect(target))
+        }
+    })
+}
+
+/// Directory of a seed URL's path, e.g. `/docs/intro/` for
+/// `/docs/intro/getting-started` and `/docs/` for `/docs/`
+fn seed_path_prefix(seed_path: &str) -> String {
+    if seed_path.ends_with('/') {
+        seed_path.to_string()
+    } else {
+        match seed_path.rfind('/') {
+            Some(idx) => seed_path[..=idx].to_string(),
+            None => "/".to_string(),
+        }
+    }
+}
+
+/// `url` without its fragment, which never reaches the server
+fn without_fragment(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url
+}
+
 /// Normalize a URL for deduplication
 pub fn normalize_url(url: &str) -> String {
     normalize_url_impl(url, false)
@@ -592,37 +957,153 @@ fn build_hash_route_url(base_url: &str, hash_route: &str) -> String {
     }
 }
 
-/// Check if a URL should be crawled based on patterns
-pub fn should_crawl_url(url: &str) -> bool {
-    let lower = url.to_lowercase();
+/// Whether a fetched HTML page should be rendered with the headless browser
+pub fn should_render(mode: JsMode, analysis: &PageAnalysis) -> bool {
+    match mode {
+        JsMode::Always => true,
+        JsMode::Auto => analysis.needs_js_rendering,
+        JsMode::Never => false,
+    }
+}
 
-    // Skip common non-document URLs
-    let skip_patterns = [
-        "/login",
-        "/logout",
-        "/signin",
-        "/signout",
-        "/register",
-        "/admin",
-        "/wp-admin",
-        "/api/",
-        "/cgi-bin/",
-        ".xml",
-        ".json",
-        ".rss",
-        ".atom",
-        "javascript:",
-        "mailto:",
-        "tel:",
-        "?page=",
-        "?sort=",
-        "?filter=",
-    ];
-
-    for pattern in skip_patterns {
-        if lower.contains(pattern) {
+/// User-configured include/exclude regexes for crawled URLs
+#[derive(Debug, Clone, Default)]
+pub struct UrlPatterns {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl UrlPatterns {
+    /// Compile the include and exclude patterns
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let compile = |patterns: &[String], kind: &str| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|e| {
+                        Error::Crawl(format!(
+                            "Invalid crawl {} pattern '{}': {}",
+                            kind, pattern, e
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        Ok(Self {
+            include: compile(include, "include")?,
+            exclude: compile(exclude, "exclude")?,
+        })
+    }
+
+    /// Whether a URL may be crawled: any exclude match rejects it, and when
+    /// include patterns are set at least one must match
+    pub fn allows(&self, url: &str) -> bool {
+        if self.exclude.iter().any(|re| re.is_match(url)) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|re| re.is_match(url))
+    }
+}
+
+/// Whether a link's host may be crawled.
+///
+/// Hosts always match exactly. With `allow_subdomains`, a host also matches
+/// when it shares the registrable domain of an allowed host, so a crawl of
+/// `docs.example.com` can follow `api.example.com` but not `example.org`.
+pub fn host_allowed(host: &str, allowed_hosts: &HashSet<String>, allow_subdomains: bool) -> bool {
+    let host = host.to_ascii_lowercase();
+    allowed_hosts.iter().any(|allowed| {
+        let allowed = allowed.to_ascii_lowercase();
+        if host == allowed {
+            return true;
+        }
+        if !allow_subdomains {
             return false;
         }
+        let domain = registrable_domain(&allowed);
+        host == domain || host.ends_with(&format!(".{}", domain))
+    })
+}
+
+/// Hosting suffixes where every subdomain belongs to a different tenant, so
+/// `project.readthedocs.io` must never reach `other.readthedocs.io`
+const MULTI_TENANT_SUFFIXES: &[&str] = &[
+    "readthedocs.io",
+    "readthedocs-hosted.com",
+    "github.io",
+    "gitlab.io",
+    "gitbook.io",
+    "netlify.app",
+    "vercel.app",
+    "pages.dev",
+    "web.app",
+    "firebaseapp.com",
+    "herokuapp.com",
+    "azurewebsites.net",
+    "azurestaticapps.net",
+    "cloudfront.net",
+    "s3.amazonaws.com",
+    "appspot.com",
+    "blogspot.com",
+    "wordpress.com",
+    "surge.sh",
+    "fly.dev",
+    "onrender.com",
+];
+
+/// Approximate the registrable domain of a host (`docs.example.com` ->
+/// `example.com`, `docs.example.co.uk` -> `example.co.uk`).
+///
+/// This is a heuristic rather than a public suffix list lookup: two-letter
+/// country TLDs with a common second-level label keep three labels, and
+/// hosts under a [`MULTI_TENANT_SUFFIXES`] entry keep the tenant label. IP
+/// addresses and single-label hosts are returned unchanged.
+fn registrable_domain(host: &str) -> &str {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return host;
+    }
+
+    for suffix in MULTI_TENANT_SUFFIXES {
+        if let Some(tenants) = host.strip_suffix(suffix).and_then(|p| p.strip_suffix('.')) {
+            let start = tenants.rfind('.').map_or(0, |i| i + 1);
+            return &host[start..];
+        }
+    }
+
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() <= 2 {
+        return host;
+    }
+
+    let tld = labels[labels.len() - 1];
+    let second = labels[labels.len() - 2];
+    let keep = if tld.len() == 2
+        && matches!(second, "co" | "com" | "org" | "net" | "ac" | "gov" | "edu")
+    {
+        3
+    } else {
+        2
+    };
+
+    let skip: usize = labels[..labels.len() - keep]
+        .iter()
+        .map(|label| label.len() + 1)
+        .sum();
+    &host[skip..]
+}
+
+/// Check if a URL should be crawled, given the configured skip patterns
+pub fn should_crawl_url(url: &str, config: &CrawlConfig) -> bool {
+    let lower = url.to_lowercase();
+
+    // Skip common non-document URLs
+    if config
+        .default_skip_patterns
+        .iter()
+        .any(|pattern| lower.contains(&pattern.to_lowercase()))
+    {
+        return false;
     }
 
     // Skip plain anchor fragments (but NOT hash routes like #/path)
@@ -636,8 +1117,9 @@ pub fn should_crawl_url(url: &str) -> bool {
     }
 
     // Skip calendar-like URLs with dates
-    let date_pattern = regex::Regex::new(r"/\d{4}/\d{2}/\d{2}/").ok();
-    if let Some(re) = date_pattern {
+    if config.skip_date_paths {
+        static DATE_PATH: OnceLock<Regex> = OnceLock::new();
+        let re = DATE_PATH.get_or_init(|| Regex::new(r"/\d{4}/\d{2}/\d{2}/").unwrap());
         if re.is_match(&lower) {
             return false;
         }
@@ -693,15 +1175,185 @@ mod tests {
 
     #[test]
     fn test_should_crawl_url() {
-        assert!(should_crawl_url("https://example.com/docs/intro"));
-        assert!(!should_crawl_url("https://example.com/login"));
-        assert!(!should_crawl_url("https://example.com/api/users"));
-        assert!(!should_crawl_url("javascript:void(0)"));
+        let config = CrawlConfig::default();
+        assert!(should_crawl_url("https://example.com/docs/intro", &config));
+        assert!(!should_crawl_url("https://example.com/login", &config));
+        assert!(!should_crawl_url("https://example.com/api/users", &config));
+        assert!(!should_crawl_url("javascript:void(0)", &config));
         // Hash routes should be crawled
-        assert!(should_crawl_url("https://example.com/#/api"));
-        assert!(should_crawl_url("https://example.com/#/service/Lightbulb"));
+        assert!(should_crawl_url("https://example.com/#/api", &config));
+        assert!(should_crawl_url(
+            "https://example.com/#/service/Lightbulb",
+            &config
+        ));
         // Plain anchors should not
-        assert!(!should_crawl_url("https://example.com/page#section"));
+        assert!(!should_crawl_url(
+            "https://example.com/page#section",
+            &config
+        ));
+    }
+
+    #[test]
+    fn test_skip_patterns_can_be_overridden() {
+        let api_reference = "https://example.com/api/reference";
+        let schema = "https://example.com/docs/schema.json";
+        let dated = "https://example.com/blog/2024/01/31/release";
+
+        let defaults = CrawlConfig::default();
+        assert!(!should_crawl_url(api_reference, &defaults));
+        assert!(!should_crawl_url(schema, &defaults));
+        assert!(!should_crawl_url(dated, &defaults));
+
+        let config = CrawlConfig {
+            default_skip_patterns: vec!["/login".to_string()],
+            skip_date_paths: false,
+            ..CrawlConfig::default()
+        };
+        assert!(should_crawl_url(api_reference, &config));
+        assert!(should_crawl_url(schema, &config));
+        assert!(should_crawl_url(dated, &config));
+        assert!(!should_crawl_url("https://example.com/LOGIN", &config));
+    }
+
+    const STATIC_PAGE: &str = "<html><head><title>Guide</title></head><body><main>\
+        <h1>Install</h1><p>Download the archive, unpack it and run the installer. \
+        The installer asks for a target directory and sets up the command line tools.</p>\
+        </main></body></html>";
+    const SPA_PAGE: &str = "<html><head><title>React App</title></head><body>\
+        <div id=\"root\"></div><script src=\"bundle.js\"></script></body></html>";
+
+    #[test]
+    fn test_should_render_follows_js_mode() {
+        let static_page = analyze_page(STATIC_PAGE, "https://example.com/guide");
+        let spa = analyze_page(SPA_PAGE, "https://example.com/app");
+        assert!(!static_page.needs_js_rendering);
+        assert!(spa.needs_js_rendering);
+
+        assert!(should_render(JsMode::Always, &static_page));
+        assert!(!should_render(JsMode::Auto, &static_page));
+        assert!(should_render(JsMode::Auto, &spa));
+        assert!(!should_render(JsMode::Never, &spa));
+
+        // `auto` defers to the older auto_js_rendering switch
+        let config = CrawlConfig {
+            auto_js_rendering: false,
+            ..CrawlConfig::default()
+        };
+        assert_eq!(config.effective_js_mode(), JsMode::Never);
+        let config = CrawlConfig {
+            auto_js_rendering: false,
+            js_mode: JsMode::Always,
+            ..CrawlConfig::default()
+        };
+        assert_eq!(config.effective_js_mode(), JsMode::Always);
+    }
+
+    #[cfg(feature = "js-rendering")]
+    #[tokio::test]
+    #[ignore = "needs a local Chrome or Chromium"]
+    async fn test_js_mode_controls_rendering() {
+        let mock_server = MockServer::start().await;
+        for (route, html) in [("/guide.html", STATIC_PAGE), ("/app.html", SPA_PAGE)] {
+            Mock::given(method("GET"))
+                .and(path(route))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_raw(html.as_bytes().to_vec(), "text/html"),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+
+        let rendered = |js_mode: JsMode, route: &'static str| {
+            let uri = mock_server.uri();
+            async move {
+                let crawler = Crawler::new(CrawlConfig {
+                    js_mode,
+                    respect_robots_txt: false,
+                    rate_limit_per_host: 1000.0,
+                    ..CrawlConfig::default()
+                })
+                .expect("crawler should build");
+                crawler
+                    .fetch(&format!("{}{}", uri, route))
+                    .await
+                    .expect("fetch should succeed");
+                crawler.close().await.expect("renderer should close");
+                crawler.take_report().await.pages_rendered
+            }
+        };
+
+        assert_eq!(rendered(JsMode::Always, "/guide.html").await, 1);
+        assert_eq!(rendered(JsMode::Never, "/app.html").await, 0);
+    }
+
+    #[test]
+    fn test_url_patterns_include_and_exclude() {
+        let patterns = |include: &[&str], exclude: &[&str]| {
+            let to_vec = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            UrlPatterns::new(&to_vec(include), &to_vec(exclude)).unwrap()
+        };
+        let reference = "https://example.com/docs/reference/api.html";
+        let changelog = "https://example.com/docs/changelog/v2.html";
+        let guide = "https://example.com/docs/guide/intro.html";
+
+        // No patterns allow everything
+        let none = patterns(&[], &[]);
+        assert!(none.allows(reference) && none.allows(changelog) && none.allows(guide));
+
+        // Include-only requires a match
+        let include = patterns(&["/reference/", r"/guide/.*\.html$"], &[]);
+        assert!(include.allows(reference));
+        assert!(include.allows(guide));
+        assert!(!include.allows(changelog));
+
+        // Exclude-only rejects matches
+        let exclude = patterns(&[], &["/changelog/"]);
+        assert!(exclude.allows(reference));
+        assert!(!exclude.allows(changelog));
+
+        // Exclude wins over include
+        let combined = patterns(&["/docs/"], &["/changelog/"]);
+        assert!(combined.allows(reference));
+        assert!(!combined.allows(changelog));
+        assert!(!combined.allows("https://example.com/blog/post.html"));
+
+        let err = UrlPatterns::new(&["(".to_string()], &[]).unwrap_err();
+        assert!(err.to_string().contains("Invalid crawl include pattern"));
+    }
+
+    #[test]
+    fn test_host_allowed_same_host_and_subdomains() {
+        let allowed: HashSet<String> = ["docs.example.com".to_string()].into_iter().collect();
+
+        // Same host is always allowed, siblings only with allow_subdomains
+        assert!(host_allowed("docs.example.com", &allowed, false));
+        assert!(host_allowed("DOCS.example.com", &allowed, false));
+        assert!(!host_allowed("api.example.com", &allowed, false));
+        assert!(host_allowed("api.example.com", &allowed, true));
+        assert!(host_allowed("example.com", &allowed, true));
+        assert!(host_allowed("v2.api.example.com", &allowed, true));
+
+        // Other domains stay blocked, including lookalike suffixes
+        assert!(!host_allowed("example.org", &allowed, true));
+        assert!(!host_allowed("badexample.com", &allowed, true));
+        assert!(!host_allowed("docs.example.com.evil.net", &allowed, true));
+
+        // Country-code second-level domains keep three labels
+        let allowed: HashSet<String> = ["docs.example.co.uk".to_string()].into_iter().collect();
+        assert!(host_allowed("api.example.co.uk", &allowed, true));
+        assert!(!host_allowed("other.co.uk", &allowed, true));
+        assert_eq!(registrable_domain("127.0.0.1"), "127.0.0.1");
+
+        // Tenants of shared hosting suffixes are separate sites
+        let allowed: HashSet<String> = ["docs.project.readthedocs.io".to_string()]
+            .into_iter()
+            .collect();
+        assert!(host_allowed("api.project.readthedocs.io", &allowed, true));
+        assert!(!host_allowed("evil.readthedocs.io", &allowed, true));
+        assert!(!host_allowed("readthedocs.io", &allowed, true));
+        let allowed: HashSet<String> = ["alice.github.io".to_string()].into_iter().collect();
+        assert!(!host_allowed("bob.github.io", &allowed, true));
+        assert_eq!(registrable_domain("github.io"), "github.io");
     }
 
     #[test]
@@ -751,7 +1403,7 @@ mod tests {
         let crawler = Crawler::new(crawl_config).expect("crawler should build");
         let seed = format!("{}/index.html", mock_server.uri());
         let results = crawler
-            .crawl(&seed, |_page| true)
+            .crawl(&seed, None, |_page| true)
             .await
             .expect("crawl should complete");
         let expected_missing = max_attempts.saturating_sub(1) as usize;
@@ -766,4 +1418,397 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(missing_requests.len(), expected_missing);
     }
+
+    #[tokio::test]
+    async fn test_crawl_report_tallies_outcomes() {
+        let mock_server = MockServer::start().await;
+
+        let index = "<html><body>\
+            <a href=\"/docs/a.html\">a</a>\
+            <a href=\"/docs/b.html\">b</a>\
+            <a href=\"/docs/missing.html\">missing</a>\
+            <a href=\"/docs/private/secret.html\">secret</a>\
+            </body></html>";
+        // a.html and b.html both queue c.html before it is visited
+        let to_c = "<html><body><a href=\"/docs/c.html\">c</a></body></html>";
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("User-agent: *\nDisallow: /docs/private/\n"),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/index.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(index, "text/html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/docs/[ab]\.html$"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(to_c, "text/html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/c.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>c</p>", "text/html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let crawl_config = CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            timeout_secs: 5,
+            ..Default::default()
+        };
+
+        let crawler = Crawler::new(crawl_config).expect("crawler should build");
+        let seed = format!("{}/docs/index.html", mock_server.uri());
+        let pages = crawler
+            .crawl(&seed, None, |_page| true)
+            .await
+            .expect("crawl should complete");
+
+        let report = crawler.take_report().await;
+        assert_eq!(pages.len(), 4);
+        assert_eq!(report.pages_fetched, 4);
+        assert_eq!(report.pages_failed, 1);
+        assert_eq!(report.pages_skipped_robots, 1);
+        assert_eq!(report.pages_deduplicated, 1);
+        assert_eq!(report.pages_rendered, 0);
+        let bytes: usize = pages.iter().map(|p| p.content.len()).sum();
+        assert_eq!(report.bytes_fetched, bytes as u64);
+        assert_eq!(crawler.take_report().await, CrawlReport::default());
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_checked_against_crawl_scope() {
+        let mock_server = MockServer::start().await;
+        let other_host = MockServer::start().await;
+
+        let index = "<html><body>\
+            <a href=\"/docs/away.html\">away</a>\
+            <a href=\"/docs/old.html\">old</a>\
+            <a href=\"/docs/outside.html\">outside</a>\
+            <a href=\"/docs/new.html\">new</a>\
+            </body></html>";
+        Mock::given(method("GET"))
+            .and(path("/docs/index.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(index, "text/html"))
+            .mount(&mock_server)
+            .await;
+        // Same address under another host name, so not an allowed host
+        let away = format!(
+            "http://localhost:{}/docs/page.html",
+            other_host.address().port()
+        );
+        Mock::given(method("GET"))
+            .and(path("/docs/away.html"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", away.as_str()))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/old.html"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", "/docs/new.html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/outside.html"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", "/blog/post.html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/new.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>new</p>", "text/html"))
+            .mount(&mock_server)
+            .await;
+        // Out-of-scope hops are refused before they are requested, robots.txt included
+        Mock::given(method("GET"))
+            .and(path("/docs/page.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>away</p>", "text/html"))
+            .expect(0)
+            .mount(&other_host)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nAllow: /\n"))
+            .expect(0)
+            .mount(&other_host)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/blog/post.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>blog</p>", "text/html"))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let crawler = Crawler::new(CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            timeout_secs: 5,
+            ..Default::default()
+        })
+        .unwrap();
+        let seed = format!("{}/docs/index.html", mock_server.uri());
+        let pages = crawler.crawl(&seed, None, |_page| true).await.unwrap();
+
+        let urls: Vec<&str> = pages.iter().map(|p| p.url.as_str()).collect();
+        let new_url = format!("{}/docs/new.html", mock_server.uri());
+        assert_eq!(urls, vec![seed.as_str(), new_url.as_str()]);
+        // The page is stored under the URL that served it
+        assert_eq!(
+            pages[1].redirected_from,
+            Some(format!("{}/docs/old.html", mock_server.uri()))
+        );
+        assert!(pages.iter().all(|p| !p.content.contains("<p>away</p>")));
+
+        let report = crawler.take_report().await;
+        assert_eq!(report.pages_skipped_redirect, 2);
+        assert_eq!(report.pages_deduplicated, 1);
+        assert!(crawler.take_failures().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_robots_refetched_after_ttl() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nAllow: /\n"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/page.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>page</p>", "text/html"))
+            .mount(&mock_server)
+            .await;
+
+        let crawler = Crawler::new(CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            timeout_secs: 5,
+            robots_cache_ttl_secs: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        let robots_fetches = || async {
+            mock_server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .filter(|r| r.url.path() == "/robots.txt")
+                .count()
+        };
+
+        let url = format!("{}/page.html", mock_server.uri());
+        crawler.fetch(&url).await.unwrap();
+        crawler.fetch(&url).await.unwrap();
+        assert_eq!(robots_fetches().await, 1);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        crawler.fetch(&url).await.unwrap();
+        assert_eq!(robots_fetches().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_robots_disallows_until_retry() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/page.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>page</p>", "text/html"))
+            .mount(&mock_server)
+            .await;
+
+        let crawler = Crawler::new(CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            timeout_secs: 5,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let url = format!("{}/page.html", mock_server.uri());
+        let result = crawler.fetch(&url).await;
+        assert!(matches!(result, Err(Error::RobotsDisallowed(_))));
+        let page_fetches = mock_server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| r.url.path() == "/page.html")
+            .count();
+        assert_eq!(page_fetches, 0);
+    }
+
+    #[tokio::test]
+    async fn test_crawler_builds_with_pool_settings() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/page.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>page</p>", "text/html"))
+            .mount(&mock_server)
+            .await;
+
+        let url = format!("{}/page.html", mock_server.uri());
+        for (max_idle, idle_timeout) in [(4, 5), (0, 0)] {
+            let crawler = Crawler::new(CrawlConfig {
+                auto_js_rendering: false,
+                respect_robots_txt: false,
+                rate_limit_per_host: 1000.0,
+                timeout_secs: 5,
+                pool_max_idle_per_host: max_idle,
+                pool_idle_timeout_secs: idle_timeout,
+                ..Default::default()
+            })
+            .unwrap();
+            crawler.fetch(&url).await.unwrap();
+            crawler.fetch(&url).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_use_sitemap_seeds_crawl_from_robots() {
+        let mock_server = MockServer::start().await;
+        let robots = format!(
+            "User-agent: *\nAllow: /\nSitemap: {}/sitemap.xml\n",
+            mock_server.uri()
+        );
+        let sitemap = format!(
+            "<?xml version=\"1.0\"?>\
+            <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\
+            <url><loc>{0}/docs/orphan.html</loc></url>\
+            <url><loc>{0}/blog/outside.html</loc></url>\
+            </urlset>",
+            mock_server.uri()
+        );
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(robots))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/sitemap.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(sitemap, "application/xml"))
+            .mount(&mock_server)
+            .await;
+        for page in [
+            "/docs/index.html",
+            "/docs/orphan.html",
+            "/blog/outside.html",
+        ] {
+            Mock::given(method("GET"))
+                .and(path(page))
+                .respond_with(ResponseTemplate::new(200).set_body_raw("<p>page</p>", "text/html"))
+                .mount(&mock_server)
+                .await;
+        }
+
+        let seed = format!("{}/docs/index.html", mock_server.uri());
+        let crawl_with = |use_sitemap| {
+            let seed = seed.clone();
+            async move {
+                let crawler = Crawler::new(CrawlConfig {
+                    auto_js_rendering: false,
+                    rate_limit_per_host: 1000.0,
+                    timeout_secs: 5,
+                    use_sitemap,
+                    ..Default::default()
+                })
+                .unwrap();
+                let pages = crawler.crawl(&seed, None, |_| true).await.unwrap();
+                pages
+                    .into_iter()
+                    .map(|page| Url::parse(&page.url).unwrap().path().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(crawl_with(false).await, vec!["/docs/index.html"]);
+        assert_eq!(
+            crawl_with(true).await,
+            vec!["/docs/index.html", "/docs/orphan.html"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_crawl_returns_partial_results() {
+        let mock_server = MockServer::start().await;
+        let index = "<html><body>\
+            <a href=\"/docs/a.html\">a</a>\
+            <a href=\"/docs/slow.html\">slow</a>\
+            </body></html>";
+        Mock::given(method("GET"))
+            .and(path("/docs/index.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(index, "text/html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/a.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>a</p>", "text/html"))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/docs/slow.html"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<p>slow</p>", "text/html")
+                    .set_delay(Duration::from_secs(30)),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let crawler = Crawler::new(CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            ..Default::default()
+        })
+        .expect("crawler should build");
+        let seed = format!("{}/docs/index.html", mock_server.uri());
+
+        // A token cancelled up front stops the crawl before the seed
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let pages = crawler
+            .crawl(&seed, Some(&cancelled), |_page| true)
+            .await
+            .unwrap();
+        assert!(pages.is_empty());
+
+        // Cancelling while slow.html is in flight abandons it
+        let crawler = Crawler::new(crawler.config.clone()).unwrap();
+        let cancel = CancellationToken::new();
+        let start = Instant::now();
+        let pages = crawler
+            .crawl(&seed, Some(&cancel), |page| {
+                if page.url.ends_with("/a.html") {
+                    let cancel = cancel.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        cancel.cancel();
+                    });
+                }
+                true
+            })
+            .await
+            .expect("cancelled crawl should still succeed");
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        let urls: Vec<&str> = pages.iter().map(|page| page.url.as_str()).collect();
+        let a_url = format!("{}/docs/a.html", mock_server.uri());
+        assert_eq!(urls, vec![seed.as_str(), a_url.as_str()]);
+        assert_eq!(crawler.take_report().await.pages_failed, 0);
+    }
 }
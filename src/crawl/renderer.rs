@@ -4,6 +4,8 @@
 //! that require JavaScript execution (SPAs, dynamic content, etc.)
 
 use crate::error::{Error, Result};
+use std::sync::Mutex;
+use tokio::sync::Semaphore;
 
 /// Configuration for the headless browser renderer
 #[derive(Debug, Clone)]
@@ -14,10 +16,12 @@ pub struct RendererConfig {
     pub render_wait_ms: u64,
     /// Run browser in headless mode
     pub headless: bool,
-    /// Additional wait for specific selector (optional)
+    /// Selector to wait for before capturing HTML, bounded by the page-load timeout
     pub wait_for_selector: Option<String>,
     /// Enable sandbox (disable for Docker/CI environments)
     pub sandbox: bool,
+    /// Capture a full-page screenshot of each rendered page
+    pub capture_screenshot: bool,
 }
 
 impl Default for RendererConfig {
@@ -28,6 +32,7 @@ impl Default for RendererConfig {
             headless: true,
             wait_for_selector: None,
             sandbox: true,
+            capture_screenshot: false,
         }
     }
 }
@@ -43,12 +48,17 @@ pub struct RenderedPage {
     pub title: Option<String>,
     /// Time taken to render (milliseconds)
     pub render_time_ms: u64,
+    /// Full-page JPEG screenshot, when `capture_screenshot` is set
+    pub screenshot: Option<Vec<u8>>,
 }
 
 #[cfg(feature = "js-rendering")]
 mod browser_impl {
     use super::*;
     use chromiumoxide::browser::{Browser, BrowserConfig};
+    use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+    use chromiumoxide::page::ScreenshotParams;
+    use chromiumoxide::Page;
     use futures::StreamExt;
     use std::sync::Arc;
     use std::time::Duration;
@@ -56,6 +66,19 @@ mod browser_impl {
     use tokio::time::{timeout, Instant};
     use tracing::{debug, info, warn};
 
+    /// JPEG quality for page screenshots
+    const SCREENSHOT_JPEG_QUALITY: i64 = 80;
+
+    /// How often to check for `wait_for_selector` while waiting
+    const SELECTOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Poll until `selector` matches an element; `find_element` does not wait
+    async fn poll_for_selector(page: &Page, selector: &str) {
+        while page.find_element(selector).await.is_err() {
+            tokio::time::sleep(SELECTOR_POLL_INTERVAL).await;
+        }
+    }
+
     /// Headless browser renderer
     pub struct HeadlessRenderer {
         config: RendererConfig,
@@ -173,10 +196,8 @@ mod browser_impl {
 
             // Wait for specific selector if configured
             if let Some(selector) = &self.config.wait_for_selector {
-                let selector_timeout = Duration::from_secs(10);
-                match timeout(selector_timeout, page.find_element(selector)).await {
-                    Ok(Ok(_)) => debug!("Found selector: {}", selector),
-                    Ok(Err(e)) => warn!("Selector {} not found: {}", selector, e),
+                match timeout(load_timeout, poll_for_selector(&page, selector)).await {
+                    Ok(()) => debug!("Found selector: {}", selector),
                     Err(_) => warn!("Timeout waiting for selector: {}", selector),
                 }
             }
@@ -203,6 +224,23 @@ mod browser_impl {
                 .and_then(|v| v.into_value::<String>().ok())
                 .filter(|t| !t.is_empty());
 
+            let screenshot = if self.config.capture_screenshot {
+                let params = ScreenshotParams::builder()
+                    .format(CaptureScreenshotFormat::Jpeg)
+                    .quality(SCREENSHOT_JPEG_QUALITY)
+                    .full_page(true)
+                    .build();
+                match page.screenshot(params).await {
+                    Ok(bytes) => Some(bytes),
+                    Err(e) => {
+                        warn!("Failed to capture screenshot of {}: {}", url, e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
             // Close the page/tab
             if let Err(e) = page.close().await {
                 warn!("Failed to close page: {}", e);
@@ -216,6 +254,7 @@ mod browser_impl {
                 html,
                 title,
                 render_time_ms,
+                screenshot,
             })
         }
 
@@ -239,8 +278,15 @@ mod browser_impl {
     }
 
     impl Drop for HeadlessRenderer {
+        /// Cleanup for a renderer dropped without [`Self::close`], as when its
+        /// crawl is aborted: stop the CDP handler task, and let the dropped
+        /// `Browser` kill its Chrome child process
         fn drop(&mut self) {
-            // Note: async drop not possible, browser cleanup happens in close()
+            if let Ok(mut handle_guard) = self.handler_handle.try_lock() {
+                if let Some(handle) = handle_guard.take() {
+                    handle.abort();
+                }
+            }
         }
     }
 }
@@ -273,6 +319,83 @@ impl HeadlessRenderer {
     }
 }
 
+/// A fixed set of renderers, each checked out for one render at a time.
+///
+/// Up to `size` pages render concurrently; further renders wait for a free
+/// renderer. Renderers launch their browser on first use and keep it for later
+/// renders, and the most recently returned one is reused first, so a sequential
+/// crawl only ever launches one browser.
+pub struct RendererPool {
+    renderers: Vec<HeadlessRenderer>,
+    idle: Mutex<Vec<usize>>,
+    permits: Semaphore,
+}
+
+/// A renderer index checked out of a [`RendererPool`]; returned on drop
+struct Checkout<'a> {
+    idle: &'a Mutex<Vec<usize>>,
+    index: usize,
+}
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.push(self.index);
+        }
+    }
+}
+
+impl RendererPool {
+    /// Create a pool of `size` renderers (at least one) sharing `config`
+    pub fn new(config: RendererConfig, size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            renderers: (0..size)
+                .map(|_| HeadlessRenderer::new(config.clone()))
+                .collect(),
+            idle: Mutex::new((0..size).rev().collect()),
+            permits: Semaphore::new(size),
+        }
+    }
+
+    /// Number of renderers in the pool
+    pub fn size(&self) -> usize {
+        self.renderers.len()
+    }
+
+    /// Render a page on the next free renderer, waiting for one if all are busy
+    pub async fn render(&self, url: &str) -> Result<RenderedPage> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| Error::Crawl("Renderer pool is closed".to_string()))?;
+        // Holding a permit guarantees an idle renderer
+        let index = self
+            .idle
+            .lock()
+            .map_err(|_| Error::Crawl("Renderer pool lock poisoned".to_string()))?
+            .pop()
+            .ok_or_else(|| Error::Crawl("No idle renderer available".to_string()))?;
+        let checkout = Checkout {
+            idle: &self.idle,
+            index,
+        };
+        self.renderers[checkout.index].render(url).await
+    }
+
+    /// Close every renderer, reporting the first failure
+    pub async fn close(&self) -> Result<()> {
+        let mut first_error = None;
+        for renderer in &self.renderers {
+            if let Err(e) = renderer.close().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
 /// Check if JS rendering feature is available
 pub fn is_js_rendering_available() -> bool {
     cfg!(feature = "js-rendering")
@@ -290,6 +413,114 @@ mod tests {
         assert_eq!(config.render_wait_ms, 2000);
     }
 
+    #[cfg(feature = "js-rendering")]
+    #[tokio::test]
+    #[ignore = "needs a local Chrome or Chromium"]
+    async fn test_render_waits_for_selector() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let page = r#"<html><body><div id="app"></div><script>
+            setTimeout(function () {
+                document.getElementById("app").innerHTML = '<p id="late">Loaded later</p>';
+            }, 1500);
+        </script></body></html>"#;
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/app.html"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(page, "text/html"))
+            .mount(&mock_server)
+            .await;
+
+        let renderer = HeadlessRenderer::new(RendererConfig {
+            page_load_timeout_ms: 10_000,
+            render_wait_ms: 0,
+            wait_for_selector: Some("#late".to_string()),
+            ..RendererConfig::default()
+        });
+        let rendered = renderer
+            .render(&format!("{}/app.html", mock_server.uri()))
+            .await
+            .expect("render should succeed");
+        renderer.close().await.expect("renderer should close");
+
+        assert!(rendered.html.contains("Loaded later"));
+        assert!(rendered.render_time_ms >= 1500);
+    }
+
+    #[cfg(not(feature = "js-rendering"))]
+    #[tokio::test]
+    async fn test_pool_returns_renderers_after_failed_renders() {
+        let pool = RendererPool::new(RendererConfig::default(), 2);
+        assert_eq!(pool.size(), 2);
+
+        // The stub renderer always fails; each failure must hand its renderer back
+        let renders = (0..5).map(|i| {
+            pool.render(if i % 2 == 0 {
+                "https://a.test"
+            } else {
+                "https://b.test"
+            })
+        });
+        for result in futures::future::join_all(renders).await {
+            let err = result.unwrap_err().to_string();
+            assert!(err.contains("not available"), "unexpected error: {err}");
+        }
+        assert_eq!(pool.idle.lock().unwrap().len(), 2);
+        assert_eq!(RendererPool::new(RendererConfig::default(), 0).size(), 1);
+    }
+
+    #[cfg(feature = "js-rendering")]
+    #[tokio::test]
+    #[ignore = "needs a local Chrome or Chromium"]
+    async fn test_pool_renders_concurrently_up_to_its_size() {
+        use std::time::{Duration, Instant};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let delay = Duration::from_secs(2);
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><body><p>Slow page</p></body></html>", "text/html")
+                    .set_delay(delay),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let pool = RendererPool::new(
+            RendererConfig {
+                render_wait_ms: 0,
+                ..RendererConfig::default()
+            },
+            2,
+        );
+        let render_all = |count: usize| {
+            let pool = &pool;
+            let urls: Vec<String> = (0..count)
+                .map(|i| format!("{}/page-{i}.html", mock_server.uri()))
+                .collect();
+            async move {
+                let start = Instant::now();
+                let renders = urls.iter().map(|url| pool.render(url));
+                for result in futures::future::join_all(renders).await {
+                    result.expect("render should succeed");
+                }
+                start.elapsed()
+            }
+        };
+
+        // Launch both browsers so the timings below only measure rendering
+        render_all(2).await;
+        // Two renders overlap on two renderers...
+        assert!(render_all(2).await < delay * 2);
+        // ...while a third has to wait for a free one
+        assert!(render_all(3).await >= delay * 2);
+
+        pool.close().await.expect("pool should close");
+    }
+
     #[test]
     fn test_js_rendering_availability() {
         // This test passes regardless of feature flag
@@ -1,27 +1,59 @@
 //! robots.txt parsing and handling
 
 use robotstxt::DefaultMatcher;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::debug;
 
-/// Parsed robots.txt rules
+/// How long a failed or unavailable robots.txt fetch is remembered before
+/// retrying, at most
+pub const ROBOTS_FAILURE_RETRY: Duration = Duration::from_secs(60);
+
+/// Parsed robots.txt rules, shared rather than copied between cache and callers
 #[derive(Debug, Clone)]
 pub struct RobotsRules {
-    content: String,
+    content: Arc<str>,
+    sitemaps: Arc<[String]>,
 }
 
 impl RobotsRules {
     /// Parse robots.txt content
     pub fn parse(content: &str) -> Self {
+        // `Sitemap:` lines apply to every agent, wherever they appear
+        let mut sitemaps: Vec<String> = Vec::new();
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.split('#').next().unwrap_or_default().trim();
+            if key.trim().eq_ignore_ascii_case("sitemap")
+                && !value.is_empty()
+                && !sitemaps.iter().any(|s| s == value)
+            {
+                sitemaps.push(value.to_string());
+            }
+        }
+
         Self {
-            content: content.to_string(),
+            content: content.into(),
+            sitemaps: sitemaps.into(),
         }
     }
 
     /// Create rules that allow everything
     pub fn allow_all() -> Self {
-        Self {
-            content: String::new(),
-        }
+        Self::parse("")
+    }
+
+    /// Create rules that disallow everything
+    pub fn disallow_all() -> Self {
+        Self::parse("User-agent: *\nDisallow: /\n")
+    }
+
+    /// Sitemap URLs advertised with `Sitemap:` directives, in file order
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
     }
 
     /// Check if a path is allowed for a user agent
@@ -75,10 +107,151 @@ impl RobotsRules {
     }
 }
 
+/// Outcome of fetching a host's robots.txt
+#[derive(Debug, Clone)]
+pub enum RobotsFetch {
+    /// robots.txt was served; an empty file allows everything
+    Fetched(RobotsRules),
+    /// The host has no robots.txt (4xx other than 429), which allows everything
+    Missing,
+    /// The server is overloaded or failing (429 or 5xx); everything is
+    /// disallowed until the entry expires and the fetch is retried
+    Unavailable(String),
+    /// The fetch failed (network error); crawling proceeds as allow-all
+    /// until the entry expires and the fetch is retried
+    Failed(String),
+}
+
+impl RobotsFetch {
+    /// Outcome for a robots.txt response status other than success
+    pub fn from_error_status(status: reqwest::StatusCode) -> Self {
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            RobotsFetch::Unavailable(format!("HTTP {}", status))
+        } else if status.is_client_error() {
+            RobotsFetch::Missing
+        } else {
+            RobotsFetch::Failed(format!("HTTP {}", status))
+        }
+    }
+
+    /// Rules to apply for this outcome
+    pub fn rules(&self) -> RobotsRules {
+        match self {
+            RobotsFetch::Fetched(rules) => rules.clone(),
+            RobotsFetch::Unavailable(_) => RobotsRules::disallow_all(),
+            RobotsFetch::Missing | RobotsFetch::Failed(_) => RobotsRules::allow_all(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedRobots {
+    fetch: RobotsFetch,
+    fetched_at: Instant,
+}
+
+/// Per-host robots.txt results that expire after a TTL, so long-running
+/// sessions pick up changes. Failed and unavailable fetches expire sooner,
+/// after at most [`ROBOTS_FAILURE_RETRY`].
+#[derive(Debug)]
+pub struct RobotsCache {
+    ttl: Duration,
+    entries: HashMap<String, CachedRobots>,
+}
+
+impl RobotsCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The cached outcome for `host`, unless it has expired
+    pub fn get(&self, host: &str) -> Option<&RobotsFetch> {
+        self.get_at(host, Instant::now())
+    }
+
+    fn get_at(&self, host: &str, now: Instant) -> Option<&RobotsFetch> {
+        let entry = self.entries.get(host)?;
+        let ttl = match entry.fetch {
+            RobotsFetch::Failed(_) | RobotsFetch::Unavailable(_) => {
+                self.ttl.min(ROBOTS_FAILURE_RETRY)
+            }
+            _ => self.ttl,
+        };
+        (now.saturating_duration_since(entry.fetched_at) < ttl).then_some(&entry.fetch)
+    }
+
+    /// Record the outcome of fetching `host`'s robots.txt
+    pub fn insert(&mut self, host: &str, fetch: RobotsFetch) {
+        self.insert_at(host, fetch, Instant::now());
+    }
+
+    fn insert_at(&mut self, host: &str, fetch: RobotsFetch, now: Instant) {
+        self.entries.insert(
+            host.to_string(),
+            CachedRobots {
+                fetch,
+                fetched_at: now,
+            },
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_robots_cache_expires_entries() {
+        let mut cache = RobotsCache::new(Duration::from_secs(3600));
+        let start = Instant::now();
+        cache.insert_at("a.example", RobotsFetch::Missing, start);
+        cache.insert_at(
+            "b.example",
+            RobotsFetch::Failed("timeout".to_string()),
+            start,
+        );
+
+        assert!(matches!(
+            cache.get_at("a.example", start),
+            Some(RobotsFetch::Missing)
+        ));
+        assert!(matches!(
+            cache.get_at("b.example", start),
+            Some(RobotsFetch::Failed(_))
+        ));
+
+        // Failures are retried long before the TTL
+        let later = start + ROBOTS_FAILURE_RETRY;
+        assert!(cache.get_at("a.example", later).is_some());
+        assert!(cache.get_at("b.example", later).is_none());
+
+        let expired = start + Duration::from_secs(3600);
+        assert!(cache.get_at("a.example", expired).is_none());
+        assert!(cache.get_at("c.example", start).is_none());
+    }
+
+    #[test]
+    fn test_robots_error_statuses() {
+        use reqwest::StatusCode;
+
+        for status in [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            let fetch = RobotsFetch::from_error_status(status);
+            assert!(matches!(fetch, RobotsFetch::Unavailable(_)));
+            assert!(!fetch.rules().is_allowed("/docs/", "MyBot"));
+        }
+        for status in [StatusCode::NOT_FOUND, StatusCode::FORBIDDEN] {
+            let fetch = RobotsFetch::from_error_status(status);
+            assert!(matches!(fetch, RobotsFetch::Missing));
+            assert!(fetch.rules().is_allowed("/docs/", "MyBot"));
+        }
+    }
+
     #[test]
     fn test_robots_allow_all() {
         let rules = RobotsRules::allow_all();
@@ -102,6 +275,32 @@ Disallow: /
         assert!(!rules.is_allowed("/anything", "BadBot"));
     }
 
+    #[test]
+    fn test_robots_sitemap_directives() {
+        let content = r#"
+Sitemap: https://example.com/sitemap.xml
+User-agent: *
+Disallow: /admin/
+
+sitemap: https://example.com/docs/sitemap.xml.gz # docs only
+SITEMAP:https://cdn.example.com/sitemap-index.xml
+Sitemap: https://example.com/sitemap.xml
+Sitemap:
+"#;
+        let rules = RobotsRules::parse(content);
+
+        assert_eq!(
+            rules.sitemaps(),
+            [
+                "https://example.com/sitemap.xml",
+                "https://example.com/docs/sitemap.xml.gz",
+                "https://cdn.example.com/sitemap-index.xml",
+            ]
+        );
+        assert!(!rules.is_allowed("/admin/", "MyBot"));
+        assert!(RobotsRules::allow_all().sitemaps().is_empty());
+    }
+
     #[test]
     fn test_crawl_delay() {
         let content = r#"
@@ -4,13 +4,18 @@
 //! - Standard sitemap.xml format
 //! - Sitemap index files (sitemapindex)
 //! - Recursive sitemap index resolution
+//! - Gzipped sitemaps (`sitemap.xml.gz`)
 
+use super::Crawler;
 use crate::error::{Error, Result};
-use reqwest::Client;
-use std::time::Duration;
+use flate2::read::GzDecoder;
+use std::io::Read;
 use tracing::{debug, info, warn};
 use url::Url;
 
+/// Largest uncompressed sitemap accepted (the sitemaps.org limit)
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
 /// A URL entry from a sitemap
 #[derive(Debug, Clone)]
 pub struct SitemapEntry {
@@ -25,28 +30,21 @@ pub struct SitemapEntry {
 }
 
 /// Sitemap parser
-pub struct SitemapParser {
-    client: Client,
-    #[allow(dead_code)]
-    user_agent: String,
+///
+/// Sitemaps are fetched through the crawler, so they share its HTTP client
+/// and per-host rate limit with the pages they list.
+pub struct SitemapParser<'a> {
+    crawler: &'a Crawler,
     max_sitemaps: usize,
 }
 
-impl SitemapParser {
-    /// Create a new sitemap parser
-    pub fn new(user_agent: &str) -> Result<Self> {
-        let client = Client::builder()
-            .user_agent(user_agent)
-            .timeout(Duration::from_secs(30))
-            .gzip(true)
-            .build()
-            .map_err(|e| Error::Crawl(format!("Failed to create HTTP client: {}", e)))?;
-
-        Ok(Self {
-            client,
-            user_agent: user_agent.to_string(),
+impl<'a> SitemapParser<'a> {
+    /// Create a sitemap parser that fetches with `crawler`
+    pub fn new(crawler: &'a Crawler) -> Self {
+        Self {
+            crawler,
             max_sitemaps: 50, // Limit recursion for sitemap indexes
-        })
+        }
     }
 
     /// Parse a sitemap URL and return all page URLs
@@ -99,13 +97,9 @@ impl SitemapParser {
 
     /// Fetch and parse a single sitemap
     async fn fetch_and_parse(&self, url: &str) -> Result<ParseResult> {
-        let response = self.client.get(url).send().await?;
-
-        if !response.status().is_success() {
-            return Err(Error::Crawl(format!("HTTP {}: {}", response.status(), url)));
-        }
-
-        let content = response.text().await?;
+        let body = self.crawler.fetch_bytes(url).await?;
+        let content = decode_sitemap_body(&body)
+            .map_err(|e| Error::Crawl(format!("Failed to decompress sitemap {}: {}", url, e)))?;
 
         // Detect sitemap type and parse
         if content.contains("<sitemapindex") {
@@ -195,6 +189,29 @@ enum ParseResult {
     SitemapIndex(Vec<String>),
 }
 
+/// Sitemap text from a response body, gunzipping `.xml.gz` payloads
+///
+/// Servers that send `Content-Encoding: gzip` are already decoded by the
+/// client, and some serve `.gz` URLs uncompressed, so the gzip magic bytes
+/// decide rather than the URL or content type.
+fn decode_sitemap_body(body: &[u8]) -> std::io::Result<String> {
+    if !body.starts_with(&[0x1f, 0x8b]) {
+        return Ok(String::from_utf8_lossy(body).into_owned());
+    }
+
+    let mut decoded = Vec::new();
+    GzDecoder::new(body)
+        .take(MAX_SITEMAP_BYTES + 1)
+        .read_to_end(&mut decoded)?;
+    if decoded.len() as u64 > MAX_SITEMAP_BYTES {
+        return Err(std::io::Error::other(format!(
+            "larger than {} bytes uncompressed",
+            MAX_SITEMAP_BYTES
+        )));
+    }
+    Ok(String::from_utf8_lossy(&decoded).into_owned())
+}
+
 /// Extract text content from an XML tag
 fn extract_tag(content: &str, tag: &str) -> Option<String> {
     let start_tag = format!("<{}>", tag);
@@ -211,6 +228,27 @@ fn extract_tag(content: &str, tag: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::CrawlConfig;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_crawler() -> Crawler {
+        Crawler::new(CrawlConfig {
+            auto_js_rendering: false,
+            rate_limit_per_host: 1000.0,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
 
     #[test]
     fn test_extract_tag() {
@@ -223,7 +261,8 @@ mod tests {
 
     #[test]
     fn test_parse_urlset() {
-        let parser = SitemapParser::new("test-agent").unwrap();
+        let crawler = test_crawler();
+        let parser = SitemapParser::new(&crawler);
         let xml = r#"
         <?xml version="1.0" encoding="UTF-8"?>
         <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
@@ -247,4 +286,65 @@ mod tests {
             panic!("Expected UrlSet");
         }
     }
+
+    #[tokio::test]
+    async fn test_parse_gzipped_sitemaps() {
+        let mock_server = MockServer::start().await;
+        let xml = format!(
+            "<urlset><url><loc>{0}/a</loc></url><url><loc>{0}/b</loc></url></urlset>",
+            mock_server.uri()
+        );
+        let compressed = gzip(xml.as_bytes());
+
+        // A .gz file served as-is
+        Mock::given(method("GET"))
+            .and(path("/sitemap.xml.gz"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(compressed.clone(), "application/gzip"),
+            )
+            .mount(&mock_server)
+            .await;
+        // Transfer-encoded, which the client decodes before we see it
+        Mock::given(method("GET"))
+            .and(path("/encoded.xml"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", "gzip")
+                    .set_body_raw(compressed, "application/xml"),
+            )
+            .mount(&mock_server)
+            .await;
+        // A .gz URL whose body is plain XML
+        Mock::given(method("GET"))
+            .and(path("/plain.xml.gz"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(xml.clone(), "application/xml"))
+            .mount(&mock_server)
+            .await;
+
+        let crawler = test_crawler();
+        let parser = SitemapParser::new(&crawler);
+        for sitemap in ["/sitemap.xml.gz", "/encoded.xml", "/plain.xml.gz"] {
+            let entries = parser
+                .parse(&format!("{}{}", mock_server.uri(), sitemap))
+                .await
+                .unwrap();
+            let locs: Vec<_> = entries.iter().map(|e| e.loc.clone()).collect();
+            assert_eq!(
+                locs,
+                vec![
+                    format!("{}/a", mock_server.uri()),
+                    format!("{}/b", mock_server.uri())
+                ],
+                "{sitemap}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_sitemap_body_rejects_corrupt_gzip() {
+        let mut corrupt = gzip(b"<urlset></urlset>");
+        corrupt.truncate(12);
+        assert!(decode_sitemap_body(&corrupt).is_err());
+        assert_eq!(decode_sitemap_body(b"<urlset/>").unwrap(), "<urlset/>");
+    }
 }
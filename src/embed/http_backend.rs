@@ -1,4 +1,4 @@
-use super::{Embedder, ImageEmbedInput};
+use super::{truncate_embedding, Embedder, ImageEmbedInput};
 use crate::config::{EmbeddingDimensionSource, ResolvedEmbeddingConfig};
 use crate::embedding_backend::{EmbeddingBackendClient, ImageTextInput};
 use crate::error::{Error, Result};
@@ -12,6 +12,7 @@ pub struct HttpEmbedder {
     model_id: String,
     family: String,
     dimension: usize,
+    native_dimension: usize,
     dimension_source: EmbeddingDimensionSource,
     supports_image_inputs: bool,
 }
@@ -24,25 +25,39 @@ impl HttpEmbedder {
             model_id: config.model_id.clone(),
             family: config.family.clone(),
             dimension: config.dimension,
+            native_dimension: config.native_dimension,
             dimension_source: config.dimension_source,
             supports_image_inputs: config.supports_image_inputs(),
         })
     }
 
     fn validate_dimensions(&self, embeddings: &[Vec<f32>]) -> Result<()> {
-        if let Some(mismatch) = embeddings.iter().find(|vec| vec.len() != self.dimension) {
-            return Err(Error::Embedding(format!(
-                "Embedding dimension mismatch for model '{}' (family '{}', source {}): expected {}, got {}",
-                self.model_id,
-                self.family,
-                self.dimension_source,
-                self.dimension,
-                mismatch.len()
-            )));
+        if let Some(mismatch) = embeddings
+            .iter()
+            .find(|vec| vec.len() != self.native_dimension)
+        {
+            return Err(Error::DimensionMismatch {
+                model: self.model_id.clone(),
+                expected: self.native_dimension,
+                actual: mismatch.len(),
+                context: format!("family '{}', source {}", self.family, self.dimension_source),
+            });
         }
         Ok(())
     }
 
+    /// Validate backend vectors and truncate MRL embeddings to the configured dimension
+    fn finish(&self, embeddings: Vec<Vec<f32>>) -> Result<Vec<Vec<f32>>> {
+        self.validate_dimensions(&embeddings)?;
+        if self.native_dimension == self.dimension {
+            return Ok(embeddings);
+        }
+        Ok(embeddings
+            .iter()
+            .map(|vec| truncate_embedding(vec, self.dimension))
+            .collect())
+    }
+
     fn encode_image_base64(path: &str) -> Result<String> {
         let bytes = fs::read(path).map_err(|e| {
             Error::Embedding(format!("Failed to read image '{}': {}", path, e))
@@ -59,8 +74,7 @@ impl Embedder for HttpEmbedder {
         }
 
         let embeddings = self.client.embed_text(&self.model_id, texts).await?;
-        self.validate_dimensions(&embeddings)?;
-        Ok(embeddings)
+        self.finish(embeddings)
     }
 
     async fn embed_images(&self, images: Vec<String>) -> Result<Vec<Vec<f32>>> {
@@ -87,8 +101,7 @@ impl Embedder for HttpEmbedder {
             })
             .collect::<Result<Vec<_>>>()?;
         let embeddings = self.client.embed_image_text(&self.model_id, inputs).await?;
-        self.validate_dimensions(&embeddings)?;
-        Ok(embeddings)
+        self.finish(embeddings)
     }
 
     async fn embed_image_text(&self, inputs: Vec<ImageEmbedInput>) -> Result<Vec<Vec<f32>>> {
@@ -119,8 +132,7 @@ impl Embedder for HttpEmbedder {
             .client
             .embed_image_text(&self.model_id, request_inputs)
             .await?;
-        self.validate_dimensions(&embeddings)?;
-        Ok(embeddings)
+        self.finish(embeddings)
     }
 
     fn dimension(&self) -> usize {
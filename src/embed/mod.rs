@@ -27,6 +27,12 @@ pub fn normalize_embedding(vector: &[f32]) -> Vec<f32> {
     vector.iter().map(|v| v / norm).collect()
 }
 
+/// Keep the first `dimension` components of a Matryoshka (MRL) embedding and
+/// rescale them to unit length
+pub fn truncate_embedding(vector: &[f32], dimension: usize) -> Vec<f32> {
+    normalize_embedding(&vector[..dimension.min(vector.len())])
+}
+
 pub fn fuse_embeddings(image: &[f32], text: &[f32]) -> Vec<f32> {
     let image_norm = normalize_embedding(image);
     let text_norm = normalize_embedding(text);
@@ -5,7 +5,8 @@ use std::str::FromStr;
 use std::time::Duration;
 use url::Url;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum EmbeddingBackendKind {
     Http,
 }
@@ -27,7 +28,7 @@ impl FromStr for EmbeddingBackendKind {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingBackendConfig {
     pub kind: EmbeddingBackendKind,
     pub url: String,
@@ -161,6 +162,19 @@ impl EmbeddingBackendClient {
             .map_err(|e| Error::Config(format!("Invalid embedding backend URL: {}", e)))
     }
 
+    /// A request that got no HTTP response means the backend is down or unreachable
+    fn unreachable(&self, err: reqwest::Error) -> Error {
+        // The innermost cause says why, e.g. "Connection refused"
+        let mut cause: &dyn std::error::Error = &err;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        Error::BackendUnreachable {
+            url: self.base_url.to_string(),
+            reason: cause.to_string(),
+        }
+    }
+
     async fn send_with_retry<T: for<'de> Deserialize<'de>>(
         &self,
         request: reqwest::RequestBuilder,
@@ -175,7 +189,7 @@ impl EmbeddingBackendClient {
                     Ok(ok) => return Ok(ok.json::<T>().await?),
                     Err(e) => last_err = Some(Error::Embedding(e.to_string())),
                 },
-                Err(e) => last_err = Some(Error::Embedding(e.to_string())),
+                Err(e) => last_err = Some(self.unreachable(e)),
             }
 
             if attempt < self.retries {
@@ -188,6 +202,18 @@ impl EmbeddingBackendClient {
         }))
     }
 
+    /// Check that the backend answers HTTP at all, without retrying
+    pub async fn ping(&self) -> Result<()> {
+        let url = self.endpoint("/capabilities")?;
+        self.client
+            .get(url)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+        Ok(())
+    }
+
     pub async fn capabilities(&self) -> Result<BackendCapabilities> {
         let url = self.endpoint("/capabilities")?;
         let request = self.client.get(url);
@@ -11,12 +11,39 @@ pub enum Error {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Metadata database is locked: {0}")]
+    DatabaseLocked(String),
+
     #[error("Qdrant error: {0}")]
     Qdrant(String),
 
+    #[error("Qdrant at {url} unreachable; is it running? ({reason})")]
+    QdrantUnreachable { url: String, reason: String },
+
     #[error("Embedding error: {0}")]
     Embedding(String),
 
+    #[error("Embedding backend at {url} unreachable: {reason}")]
+    BackendUnreachable { url: String, reason: String },
+
+    #[error("Embedding backend at {url} does not advertise model '{model}' in /capabilities")]
+    ModelNotAdvertised { url: String, model: String },
+
+    #[error("Embedding dimension mismatch for model '{model}' ({context}): expected {expected}, got {actual}")]
+    DimensionMismatch {
+        model: String,
+        expected: usize,
+        actual: usize,
+        /// Where the two dimensions came from
+        context: String,
+    },
+
+    #[error("Embedding backend probe for '{model}' failed: {reason}")]
+    ProbeFailed { model: String, reason: String },
+
     #[error("Crawl error: {0}")]
     Crawl(String),
 
@@ -44,6 +71,12 @@ pub enum Error {
     #[error("Source not found: {0}")]
     SourceNotFound(String),
 
+    #[error("Ambiguous source '{0}', matches: {1}")]
+    AmbiguousSource(String, String),
+
+    #[error("Source name '{0}' is already used by source {1}")]
+    NameTaken(String, String),
+
     #[error("Document not found: {0}")]
     DocumentNotFound(String),
 
@@ -56,12 +89,18 @@ pub enum Error {
     #[error("Invalid path: {0}")]
     InvalidPath(String),
 
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
     #[error("Rate limited: {0}")]
     RateLimited(String),
 
     #[error("Robots.txt disallowed: {0}")]
     RobotsDisallowed(String),
 
+    #[error("{from} redirected to {to}, outside the crawl scope")]
+    RedirectOutOfScope { from: String, to: String },
+
     #[error("Max depth exceeded")]
     MaxDepthExceeded,
 
@@ -84,6 +123,69 @@ impl From<anyhow::Error> for Error {
     }
 }
 
+impl Error {
+    /// Whether the embedding backend could not serve the configured model
+    pub fn is_backend_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Error::BackendUnreachable { .. }
+                | Error::ModelNotAdvertised { .. }
+                | Error::DimensionMismatch { .. }
+                | Error::ProbeFailed { .. }
+        )
+    }
+
+    /// Process exit code for this error, grouped by category so scripts can
+    /// tell a bad config from an unreachable service or a missing source
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Config(_)
+            | Error::TomlParse(_)
+            | Error::NotInitialized
+            | Error::AlreadyInitialized(_) => exit_code::CONFIG,
+            Error::QdrantUnreachable { .. } | Error::BackendUnreachable { .. } | Error::Http(_) => {
+                exit_code::UNREACHABLE
+            }
+            Error::SourceNotFound(_) | Error::DocumentNotFound(_) => exit_code::NOT_FOUND,
+            Error::ModelNotAdvertised { .. } => exit_code::MODEL_NOT_ADVERTISED,
+            Error::DimensionMismatch { .. } => exit_code::DIMENSION_MISMATCH,
+            Error::ProbeFailed { .. } => exit_code::PROBE_FAILED,
+            Error::AmbiguousSource(..)
+            | Error::NameTaken(..)
+            | Error::InvalidPath(_)
+            | Error::InvalidQuery(_) => exit_code::USAGE,
+            Error::DatabaseLocked(_) | Error::Timeout(_) => exit_code::TEMPORARY,
+            _ => exit_code::FAILURE,
+        }
+    }
+}
+
+/// Process exit codes, documented in the README
+pub mod exit_code {
+    /// Any error without a more specific code
+    pub const FAILURE: i32 = 1;
+    /// Missing, unreadable or invalid configuration
+    pub const CONFIG: i32 = 2;
+    /// Qdrant or the embedding backend could not be reached
+    pub const UNREACHABLE: i32 = 3;
+    /// The named source, document or collection does not exist
+    pub const NOT_FOUND: i32 = 4;
+    /// The embedding backend does not advertise the configured model
+    pub const MODEL_NOT_ADVERTISED: i32 = 5;
+    /// The model's embeddings differ in size from the configured dimension
+    pub const DIMENSION_MISMATCH: i32 = 6;
+    /// The embedding backend's probe returned unusable output
+    pub const PROBE_FAILED: i32 = 7;
+    /// A query ran but matched nothing
+    pub const NO_RESULTS: i32 = 8;
+    /// Invalid command line arguments (EX_USAGE)
+    pub const USAGE: i32 = 64;
+    /// A transient condition such as a locked database or a timeout; retrying may succeed (EX_TEMPFAIL)
+    pub const TEMPORARY: i32 = 75;
+    /// Interrupted with Ctrl-C
+    pub const INTERRUPTED: i32 = 130;
+}
+
 /// Result type alias for librarian
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -93,3 +195,25 @@ impl From<qdrant_client::QdrantError> for Error {
         Error::Qdrant(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_follow_error_category() {
+        assert_eq!(Error::Config("bad".to_string()).exit_code(), 2);
+        assert_eq!(Error::NotInitialized.exit_code(), 2);
+        let unreachable = Error::QdrantUnreachable {
+            url: "http://127.0.0.1:6334".to_string(),
+            reason: "connection refused".to_string(),
+        };
+        assert_eq!(unreachable.exit_code(), 3);
+        assert_eq!(Error::SourceNotFound("docs".to_string()).exit_code(), 4);
+        let ambiguous = Error::AmbiguousSource("d".to_string(), "d1, d2".to_string());
+        assert_eq!(ambiguous.exit_code(), 64);
+        assert_eq!(Error::InvalidQuery("empty".to_string()).exit_code(), 64);
+        assert_eq!(Error::DatabaseLocked("busy".to_string()).exit_code(), 75);
+        assert_eq!(Error::Other("boom".to_string()).exit_code(), 1);
+    }
+}
@@ -15,11 +15,16 @@ pub mod error;
 pub mod mcp;
 pub mod models;
 pub mod meta;
+pub mod output;
 pub mod parse;
 pub mod progress;
 pub mod rank;
 pub mod rerank;
 pub mod store;
+pub mod time_range;
+
+#[cfg(test)]
+mod test_support;
 
 pub use config::Config;
 pub use error::{Error, Result};
@@ -1,25 +1,41 @@
 //! librarian CLI entry point
 
+use chrono::{DateTime, Utc};
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use librarian::{
     commands::{
-        cmd_ingest_dir, cmd_ingest_sitemap, cmd_ingest_url, cmd_init, cmd_list_sources, cmd_prune,
-        cmd_query, cmd_reindex, cmd_remove_source, cmd_rename_source, cmd_status, cmd_update,
-        print_prune_stats, print_query_results, print_reindex_stats, print_source_completions,
-        print_sources, print_status, print_update_stats, PruneOptions, QueryOptions,
-        ReindexOptions, UpdateOptions,
+        cmd_config_get, cmd_config_set, cmd_config_validate, cmd_ingest_dir, cmd_ingest_openapi,
+        cmd_ingest_sitemap, cmd_ingest_url, cmd_ingest_urls, cmd_init, cmd_keyword_query,
+        cmd_list_runs, cmd_list_sources, cmd_preview_ingest_dir, cmd_preview_ingest_sitemap,
+        cmd_preview_ingest_url, cmd_prune, cmd_query, cmd_reconfigure_source, cmd_reembed_missing,
+        cmd_reindex, cmd_remove_source, cmd_rename_source, cmd_source_info, cmd_status,
+        cmd_tag_source, cmd_untag_source, cmd_update, cmd_vacuum, cmd_watch, ensure_name_available,
+        keyword_query_stream, openapi_spec_uri, print_config_setting, print_crawl_overrides,
+        print_crawl_report, print_ingest_preview, print_prune_stats, print_query_results,
+        print_reembed_stats, print_reindex_stats, print_runs, print_source_completions,
+        print_source_info, print_sources, print_status, print_update_stats, print_vacuum_stats,
+        print_validation_report, query_page, query_stream, url_list_uri, validate_query,
+        with_query_timeout, DirFilter, DirIngestOptions, HighlightStyle, PruneOptions,
+        QueryOptions, ReindexOptions, RunsOptions, StatusOptions, UpdateOptions, UrlList,
+        ValidateOptions, WatchOptions,
     },
-    config::Config,
-    embed::create_embedder,
-    error::Result,
+    config::{Config, ResolvedEmbeddingConfig},
+    embed::{create_embedder, Embedder},
+    error::{exit_code, Error, Result},
     mcp::McpServer,
     meta::{MetaDb, RunOperation},
-    progress::LogWriterFactory,
+    output::{color_enabled, JsonOutput},
+    progress::{self, LogWriterFactory},
+    say,
     store::QdrantStore,
+    time_range::{parse_time_arg, TimeRange},
 };
+use serde_json::json;
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
-use tracing::error;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 #[derive(Parser)]
@@ -30,14 +46,51 @@ struct Cli {
     #[arg(short, long, global = true)]
     config: Option<PathBuf>,
 
+    /// Merge the named [profiles.<name>] section over the base config
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Use this Qdrant collection instead of the configured one (after any
+    /// --profile); a different collection also gets its own metadata database
+    #[arg(long, global = true, value_name = "NAME")]
+    collection: Option<String>,
+
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Silence progress and status messages; command results are still printed
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log filter (e.g. warn, or librarian=debug); overrides --quiet, --verbose and RUST_LOG
+    #[arg(long, global = true, value_name = "LEVEL", value_parser = parse_log_level)]
+    log_level: Option<String>,
+
+    /// Log line format: text, or json (one object per line) for log aggregators; logs always go to stderr
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "text")]
+    log_format: LogFormat,
+
     /// Output as JSON
     #[arg(long, global = true)]
     json: bool,
 
+    /// Output newline-delimited JSON, one object per line (query, sources, and runs)
+    #[arg(long, global = true, conflicts_with = "json")]
+    ndjson: bool,
+
+    /// Write --json or --ndjson output to this file instead of stdout
+    #[arg(long, global = true, value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// Probe the embedding backend again instead of using the cached model details
+    #[arg(long, global = true)]
+    refresh_model: bool,
+
+    /// Never contact the embedding backend; commands that embed fail instead
+    #[arg(long, global = true, conflicts_with = "refresh_model")]
+    offline: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -68,27 +121,84 @@ enum Commands {
     /// Query the RAG index
     Query {
         /// The search query
+        #[arg(value_parser = parse_query_text)]
         query: String,
 
         /// Maximum number of results
         #[arg(short, long, default_value = "5")]
         limit: usize,
 
+        /// Skip this many ranked results (e.g. --offset 5 for results 6-10)
+        #[arg(long, default_value = "0")]
+        offset: usize,
+
         /// Minimum similarity score (0-1)
         #[arg(short, long)]
         min_score: Option<f32>,
 
-        /// Filter to specific source IDs
+        /// Filter to specific sources (ID, name, or unique prefix)
         #[arg(long)]
         source: Option<Vec<String>>,
 
+        /// Filter to sources carrying any of these tags (comma-separated or repeated)
+        #[arg(long, value_delimiter = ',')]
+        tag: Option<Vec<String>>,
+
+        /// Only match chunks under this heading path, from the top-level heading (e.g. "Guide/Configuration")
+        #[arg(long, value_name = "PATH")]
+        section: Option<String>,
+
+        /// Rank by keywords in the stored chunk text alone, without the embedding backend or Qdrant
+        #[arg(long)]
+        keyword_only: bool,
+
+        /// Only match chunks embedded with the current embedding model
+        #[arg(long, conflicts_with = "keyword_only")]
+        strict_model: bool,
+
         /// Deduplicate results by document
         #[arg(long)]
         dedupe: bool,
+
+        /// Highlight query terms and trim snippets to a window around the first match
+        #[arg(long)]
+        highlight: bool,
+
+        /// Include N neighboring chunks before and after each hit
+        #[arg(long, value_name = "N", default_value = "0")]
+        context: usize,
+
+        /// Show the vector, BM25, fused, and reranker scores behind each result
+        #[arg(long)]
+        explain: bool,
+
+        /// Count every match above the minimum score, not just the returned results
+        #[arg(long)]
+        count: bool,
+
+        /// Abort the query after this many seconds (default: query.timeout_secs)
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+
+        /// Hand only the top N vector hits to the reranker (default: query.rerank_candidates, or all)
+        #[arg(long, value_name = "N", conflicts_with = "keyword_only")]
+        rerank_candidates: Option<usize>,
     },
 
     /// Show system status
-    Status,
+    Status {
+        /// List the errors recorded by each source's latest ingestion run
+        #[arg(long)]
+        show_errors: bool,
+
+        /// Only report latest runs started at or after this time (RFC3339, date, or age like 7d)
+        #[arg(long, value_name = "TIME", value_parser = parse_time_arg, requires = "show_errors")]
+        since: Option<DateTime<Utc>>,
+
+        /// Only report latest runs started before this time (RFC3339, date, or age like 7d)
+        #[arg(long, value_name = "TIME", value_parser = parse_time_arg, requires = "show_errors")]
+        until: Option<DateTime<Utc>>,
+    },
 
     /// List registered sources
     Sources {
@@ -101,6 +211,34 @@ enum Commands {
         completion: Option<Shell>,
     },
 
+    /// Show everything recorded about one source
+    Info {
+        /// Source ID, name, or unique prefix
+        source_id: String,
+    },
+
+    /// Compact the metadata database and refresh query planner statistics
+    Vacuum,
+
+    /// Show recent ingestion runs
+    Runs {
+        /// Only show runs for these sources (ID, name, or unique prefix)
+        #[arg(long)]
+        source: Option<Vec<String>>,
+
+        /// Maximum number of runs to show
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Only show runs started at or after this time (RFC3339, date, or age like 7d)
+        #[arg(long, value_name = "TIME", value_parser = parse_time_arg)]
+        since: Option<DateTime<Utc>>,
+
+        /// Only show runs started before this time (RFC3339, date, or age like 7d)
+        #[arg(long, value_name = "TIME", value_parser = parse_time_arg)]
+        until: Option<DateTime<Utc>>,
+    },
+
     /// Remove stale documents and orphan points
     Prune {
         /// Dry run - show what would be removed
@@ -111,6 +249,14 @@ enum Commands {
         #[arg(long)]
         remove_orphans: bool,
 
+        /// Delete indexed chunks whose Qdrant point is missing
+        #[arg(long)]
+        remove_dangling: bool,
+
+        /// Number of orphan delete requests to run in parallel
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+
         /// Only prune specific source IDs
         #[arg(long)]
         source: Option<Vec<String>>,
@@ -127,6 +273,17 @@ enum Commands {
         batch_size: usize,
     },
 
+    /// Re-embed only chunks whose Qdrant point is missing (e.g. after an interrupted ingest)
+    ReembedMissing {
+        /// Only check specific source IDs
+        #[arg(long)]
+        source: Option<Vec<String>>,
+
+        /// Batch size for embedding
+        #[arg(long, default_value = "32")]
+        batch_size: usize,
+    },
+
     /// Incrementally update sources and prune embeddings
     Update {
         /// Only update specific source IDs
@@ -138,20 +295,100 @@ enum Commands {
         skip_prune: bool,
     },
 
+    /// Watch a directory source and re-ingest files as they change
+    Watch {
+        /// Directory source ID to watch
+        source_id: String,
+
+        /// Quiet period in milliseconds before re-ingesting a batch of changes
+        #[arg(long, default_value = "500")]
+        debounce_ms: u64,
+    },
+
     /// Remove a source and all its data
     ///
     /// Use 'librarian sources --ids-only' to list available source IDs
     Remove {
-        /// Source ID to remove (use 'librarian sources' to list)
+        /// Source ID, name, or unique prefix to remove (use 'librarian sources' to list)
         source_id: String,
     },
 
     /// Rename an existing source
     Rename {
-        /// Source ID to rename
+        /// Source ID, name, or unique prefix to rename
         source_id: String,
         /// New name to set
         name: String,
+        /// Fail instead of auto-suffixing when another source has this name
+        #[arg(long)]
+        no_clobber: bool,
+    },
+
+    /// Add tags to a source
+    Tag {
+        /// Source ID, name, or unique prefix to tag
+        source_id: String,
+        /// Tags to add
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from a source
+    Untag {
+        /// Source ID, name, or unique prefix to untag
+        source_id: String,
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Change the crawl settings a URL source is updated with
+    ///
+    /// Settings given here replace the stored ones; the rest are kept.
+    /// Run without options to show the stored settings.
+    Reconfigure {
+        /// Source ID, name, or unique prefix of a URL source
+        source_id: String,
+
+        /// Maximum pages to crawl
+        #[arg(long)]
+        max_pages: Option<u32>,
+
+        /// Maximum crawl depth
+        #[arg(long)]
+        max_depth: Option<u32>,
+
+        /// Restrict crawling to this path prefix (e.g., /docs/)
+        #[arg(long)]
+        path_prefix: Option<String>,
+
+        /// Only follow links on the seed host
+        #[arg(long, conflicts_with = "allow_subdomains")]
+        same_domain: bool,
+
+        /// Also follow other subdomains of the seed's domain
+        #[arg(long)]
+        allow_subdomains: bool,
+
+        /// Render pages with a headless browser: auto, always or never
+        #[arg(long, value_name = "MODE")]
+        js: Option<librarian::config::JsMode>,
+
+        /// CSS selector to wait for before capturing a rendered page
+        #[arg(long, value_name = "SELECTOR")]
+        wait_for: Option<String>,
+
+        /// Also crawl pages from sitemaps listed in the site's robots.txt
+        #[arg(long, conflicts_with = "no_use_sitemap")]
+        use_sitemap: bool,
+
+        /// Stop crawling pages from robots.txt sitemaps
+        #[arg(long)]
+        no_use_sitemap: bool,
+
+        /// Discard the stored settings before applying the given ones
+        #[arg(long)]
+        reset: bool,
     },
 
     /// Start MCP server on stdio
@@ -169,18 +406,58 @@ enum Commands {
         #[command(subcommand)]
         action: DbAction,
     },
+
+    /// Read or change settings in config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+/// Config file actions
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print a setting by dotted key (e.g. query.min_score)
+    Get {
+        /// Dotted setting key
+        key: String,
+    },
+
+    /// Change a setting and rewrite config.toml
+    Set {
+        /// Dotted setting key
+        key: String,
+
+        /// New value, parsed as the setting's type
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+
+    /// Check config.toml and report each problem; exits non-zero on failure
+    Validate {
+        /// Also resolve the embedding model against the backend
+        #[arg(long)]
+        check_backend: bool,
+
+        /// Also check that Qdrant is reachable
+        #[arg(long)]
+        check_qdrant: bool,
+    },
 }
 
 /// Database management actions
 #[derive(Subcommand)]
 enum DbAction {
-    /// Initialize/create the Qdrant collection
+    /// Initialize/create the Qdrant collection (pick another with --collection)
     Init,
 
     /// Show Qdrant collection status
     Status,
 
-    /// Reset the collection (delete all vectors and recreate)
+    /// Create missing payload indexes on filtered fields of an existing collection
+    ReindexPayload,
+
+    /// Reset the collection (delete all vectors and recreate); pick another with --collection
     Reset {
         /// Skip confirmation prompt
         #[arg(long)]
@@ -199,13 +476,39 @@ enum IngestSource {
         #[arg(short, long)]
         name: Option<String>,
 
-        /// File extensions to include (e.g., md,txt,html)
+        /// Fail instead of auto-suffixing (e.g. docs-2) when another source has this name
         #[arg(long)]
+        no_clobber: bool,
+
+        /// File extensions to include (e.g., md,txt,html)
+        #[arg(short, long)]
         extensions: Option<String>,
 
-        /// Exclude patterns (glob)
+        /// Exclude glob patterns relative to the directory (e.g., "**/node_modules/**")
         #[arg(long)]
         exclude: Option<Vec<String>>,
+
+        /// Follow symlinked files and directories; by default symlinks are skipped
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Skip paths excluded by .gitignore and .ignore files (default: true)
+        #[arg(
+            long,
+            value_name = "BOOL",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "true"
+        )]
+        respect_gitignore: Option<bool>,
+
+        /// Number of files to process in parallel
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+
+        /// Preview what would be ingested without embedding or writing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Ingest a URL (with crawling)
@@ -217,18 +520,50 @@ enum IngestSource {
         #[arg(short, long)]
         name: Option<String>,
 
-        /// Maximum pages to crawl
-        #[arg(long, default_value = "100")]
-        max_pages: u32,
+        /// Fail instead of auto-suffixing (e.g. docs-2) when another source has this name
+        #[arg(long)]
+        no_clobber: bool,
 
-        /// Maximum crawl depth
-        #[arg(long, default_value = "3")]
-        max_depth: u32,
+        /// Maximum pages to crawl (saved for updates)
+        #[arg(long)]
+        max_pages: Option<u32>,
+
+        /// Maximum crawl depth (saved for updates)
+        #[arg(long)]
+        max_depth: Option<u32>,
 
         /// Restrict crawling to this path prefix (e.g., /docs/)
         /// If not specified, defaults to the seed URL's directory path
         #[arg(long)]
         path_prefix: Option<String>,
+
+        /// Only follow links on the seed host (overrides crawl.allow_subdomains)
+        #[arg(long, conflicts_with = "allow_subdomains")]
+        same_domain: bool,
+
+        /// Also follow other subdomains of the seed's domain (e.g. api.example.com from docs.example.com)
+        #[arg(long)]
+        allow_subdomains: bool,
+
+        /// Render pages with a headless browser: auto, always or never (saved for updates)
+        #[arg(long, value_name = "MODE")]
+        js: Option<librarian::config::JsMode>,
+
+        /// CSS selector to wait for before capturing a rendered page
+        #[arg(long, value_name = "SELECTOR")]
+        wait_for: Option<String>,
+
+        /// Also crawl pages from sitemaps listed in the site's robots.txt (saved for updates)
+        #[arg(long)]
+        use_sitemap: bool,
+
+        /// Ignore robots.txt for this crawl only (not saved for updates)
+        #[arg(long)]
+        no_robots: bool,
+
+        /// Preview what would be ingested without embedding or writing anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Ingest URLs from a sitemap
@@ -240,34 +575,242 @@ enum IngestSource {
         #[arg(short, long)]
         name: Option<String>,
 
+        /// Fail instead of auto-suffixing (e.g. docs-2) when another source has this name
+        #[arg(long)]
+        no_clobber: bool,
+
         /// Maximum pages to fetch
         #[arg(long)]
         max_pages: Option<u32>,
+
+        /// Ignore robots.txt for this crawl only (not saved for updates)
+        #[arg(long)]
+        no_robots: bool,
+
+        /// Preview what would be ingested without embedding or writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Ingest a list of URLs, one page each, without crawling
+    Urls {
+        /// File with one URL per line, or - to read stdin
+        #[arg(long, value_name = "PATH")]
+        from_file: PathBuf,
+
+        /// Source name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Fail instead of auto-suffixing (e.g. docs-2) when another source has this name
+        #[arg(long)]
+        no_clobber: bool,
     },
+
+    /// Ingest an OpenAPI / Swagger spec, one document per operation
+    Openapi {
+        /// Spec URL or file path (JSON or YAML)
+        location: String,
+
+        /// Source name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Fail instead of auto-suffixing (e.g. docs-2) when another source has this name
+        #[arg(long)]
+        no_clobber: bool,
+    },
+}
+
+impl IngestSource {
+    /// Whether this is a `--dry-run` preview, which embeds and writes nothing
+    fn is_dry_run(&self) -> bool {
+        matches!(
+            self,
+            IngestSource::Dir { dry_run: true, .. }
+                | IngestSource::Url { dry_run: true, .. }
+                | IngestSource::Sitemap { dry_run: true, .. }
+        )
+    }
+}
+
+impl Commands {
+    /// Whether the command embeds text and so needs the embedding backend
+    fn needs_embeddings(&self) -> bool {
+        match self {
+            Commands::Query { keyword_only, .. } => !keyword_only,
+            Commands::Ingest { source } => !source.is_dry_run(),
+            Commands::Reindex { .. }
+            | Commands::ReembedMissing { .. }
+            | Commands::Update { .. }
+            | Commands::Watch { .. }
+            | Commands::Mcp => true,
+            // Creating the collection needs the model's vector dimension
+            Commands::Db { action } => matches!(action, DbAction::Init | DbAction::Reset { .. }),
+            Commands::Init { .. }
+            | Commands::Status { .. }
+            | Commands::Sources { .. }
+            | Commands::Info { .. }
+            | Commands::Vacuum
+            | Commands::Runs { .. }
+            | Commands::Prune { .. }
+            | Commands::Remove { .. }
+            | Commands::Rename { .. }
+            | Commands::Tag { .. }
+            | Commands::Untag { .. }
+            | Commands::Reconfigure { .. }
+            | Commands::Config { .. }
+            | Commands::Completions { .. } => false,
+        }
+    }
+}
+
+/// Model and embedder resolved up front for commands that need embeddings
+type Embedding = (ResolvedEmbeddingConfig, Box<dyn Embedder>);
+
+fn resolved(embedding: &Option<Embedding>) -> (&ResolvedEmbeddingConfig, &dyn Embedder) {
+    let (config, embedder) = embedding
+        .as_ref()
+        .expect("embedding is resolved for commands that need it");
+    (config, embedder.as_ref())
+}
+
+/// Build the Qdrant client; no request is sent until the store is used
+async fn open_store(config: &Config, embedding: &Option<Embedding>) -> Result<QdrantStore> {
+    match embedding {
+        Some((embedding_config, _)) => QdrantStore::connect(config, embedding_config).await,
+        None => QdrantStore::connect_offline(config).await,
+    }
+}
+
+/// Open the store for a command that reads or writes vectors, failing early
+/// with an actionable error when Qdrant is down
+async fn connect_store(config: &Config, embedding: &Option<Embedding>) -> Result<QdrantStore> {
+    let store = open_store(config, embedding).await?;
+    store.ensure_reachable().await?;
+    Ok(store)
 }
 
 #[tokio::main]
 async fn main() {
     if let Err(e) = run().await {
         error!("{}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
 
+/// Parse arguments, exiting with [`exit_code::USAGE`] instead of clap's 2 on bad input
+fn parse_cli() -> Cli {
+    let parsed = Cli::try_parse().and_then(|cli| {
+        if cli.output_file.is_some() && !(cli.json || cli.ndjson) {
+            Err(Cli::command().error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--output-file requires --json or --ndjson",
+            ))
+        } else {
+            Ok(cli)
+        }
+    });
+    parsed.unwrap_or_else(|e| {
+        let code = if e.use_stderr() { exit_code::USAGE } else { 0 };
+        let _ = e.print();
+        std::process::exit(code);
+    })
+}
+
 async fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = parse_cli();
+
+    // The MCP server speaks JSON-RPC on stdout, so nothing else may reach it
+    let mcp = matches!(cli.command, Commands::Mcp);
+    let _stdout = mcp.then(progress::StdoutGuard::reserve);
+
+    // Initialize logging; stdout stays free for command output and the MCP protocol
+    match cli.log_format {
+        // No progress bars run under MCP, and their hidden draw target would
+        // swallow logs when stderr is piped to the client
+        LogFormat::Text if mcp => tracing_subscriber::registry()
+            .with(
+                fmt::layer()
+                    .with_ansi(std::io::stderr().is_terminal())
+                    .with_writer(std::io::stderr),
+            )
+            .with(log_filter(&cli))
+            .init(),
+        LogFormat::Text => tracing_subscriber::registry()
+            .with(fmt::layer().with_writer(LogWriterFactory::default()))
+            .with(log_filter(&cli))
+            .init(),
+        LogFormat::Json => tracing_subscriber::registry()
+            .with(
+                fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .with_writer(std::io::stderr),
+            )
+            .with(log_filter(&cli))
+            .init(),
+    }
 
-    // Initialize logging
-    let filter = if cli.verbose {
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"))
+    progress::init_progress(cli.json || cli.ndjson || cli.quiet || mcp);
+    progress::set_quiet(cli.quiet);
+
+    execute(cli).await
+}
+
+/// Log filter from `--log-level`, then `--quiet`/`--verbose`, then `RUST_LOG`
+fn log_filter(cli: &Cli) -> EnvFilter {
+    if let Some(level) = &cli.log_level {
+        EnvFilter::new(level)
+    } else if cli.quiet {
+        EnvFilter::new("error")
+    } else if cli.verbose {
+        EnvFilter::new("debug")
     } else {
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
-    };
+    }
+}
+
+/// Trim the query text, rejecting it at parse time if nothing is left
+fn parse_query_text(query: &str) -> std::result::Result<String, String> {
+    validate_query(query)
+        .map(ToString::to_string)
+        .map_err(|e| e.to_string())
+}
 
-    tracing_subscriber::registry()
-        .with(fmt::layer().with_writer(LogWriterFactory::default()))
-        .with(filter)
-        .init();
+fn parse_log_level(level: &str) -> std::result::Result<String, String> {
+    EnvFilter::try_new(level)
+        .map(|_| level.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// How log lines are written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    /// Human-readable lines, kept clear of progress bars
+    Text,
+    /// One JSON object per event, with its fields and enclosing spans
+    Json,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!(
+                "invalid log format '{}' (expected text or json)",
+                other
+            )),
+        }
+    }
+}
+
+async fn execute(cli: Cli) -> Result<()> {
+    let output = JsonOutput::new(cli.output_file.clone());
 
     // Handle init command specially (doesn't need existing config)
     if matches!(cli.command, Commands::Init { .. }) {
@@ -282,64 +825,227 @@ async fn run() -> Result<()> {
         return Ok(());
     }
 
+    // Validation reports on configs that would fail to load
+    if let Commands::Config {
+        action:
+            ConfigAction::Validate {
+                check_backend,
+                check_qdrant,
+            },
+    } = cli.command
+    {
+        let config_path = cli.config.unwrap_or_else(Config::default_config_path);
+        let options = ValidateOptions {
+            profile: cli.profile,
+            collection: cli.collection,
+            check_backend,
+            check_qdrant,
+        };
+        let report = cmd_config_validate(&config_path, options).await;
+        if cli.json {
+            output.write(&report)?;
+        } else {
+            print_validation_report(&report);
+        }
+        if !report.passed() {
+            std::process::exit(exit_code::CONFIG);
+        }
+        return Ok(());
+    }
+
     // Load configuration
-    let config = load_config(cli.config.as_deref()).await?;
+    let config = load_config(
+        cli.config.as_deref(),
+        cli.profile.as_deref(),
+        cli.collection.as_deref(),
+    )
+    .await?;
 
-    // Resolve embedding config and create embedder to get dimension
-    let embedding_config = config.resolve_embedding_config().await?;
-    let embedder = create_embedder(&embedding_config)?;
+    // Config edits need neither the database nor the backends
+    if let Commands::Config { action } = cli.command {
+        return handle_config_action(&config, action, cli.json.then_some(&output));
+    }
+
+    // Reject a page of results that can never be served before contacting any backend
+    if let Commands::Query {
+        limit,
+        offset,
+        rerank_candidates,
+        keyword_only,
+        ..
+    } = &cli.command
+    {
+        query_page(
+            &config,
+            Some(*limit),
+            *offset,
+            *rerank_candidates,
+            config.reranker.enabled && !keyword_only,
+        )?;
+    }
+
+    // Only commands that embed need the backend; the rest run offline
+    let needs_embeddings = cli.command.needs_embeddings();
+    if cli.offline && needs_embeddings {
+        return Err(Error::Config(
+            "This command needs the embedding backend and cannot run with --offline".to_string(),
+        ));
+    }
+    let embedding = if needs_embeddings {
+        // Resolve embedding config (cached between runs) and create embedder to get dimension
+        let embedding_config = config
+            .resolve_embedding_config_cached(cli.refresh_model)
+            .await?;
+        let embedder = create_embedder(&embedding_config)?;
+        Some((embedding_config, embedder))
+    } else {
+        None
+    };
 
     // Initialize components
     let db = MetaDb::new(&config.paths.db_file).await?;
-    let store = QdrantStore::new(
-        &config.qdrant_url,
-        &config.collection_name,
-        embedder.dimension(),
-        Some(&embedding_config),
-    )
-    .await?;
 
     // Handle commands
     match cli.command {
         Commands::Init { .. } => unreachable!(),
 
         Commands::Ingest { source } => {
-            handle_ingest(&config, &embedding_config, embedder.as_ref(), &db, &store, source).await?;
+            // Previews neither embed nor write vectors, so they need neither backend
+            let store = if source.is_dry_run() {
+                None
+            } else {
+                Some(connect_store(&config, &embedding).await?)
+            };
+            let json = cli.json.then_some(&output);
+            handle_ingest(&config, &embedding, &db, store.as_ref(), source, json).await?;
         }
 
         Commands::Query {
             query,
             limit,
+            offset,
             min_score,
             source,
+            tag,
+            section,
+            keyword_only,
+            strict_model,
             dedupe,
+            highlight,
+            context,
+            explain,
+            count,
+            timeout,
+            rerank_candidates,
         } => {
             let options = QueryOptions {
                 k: Some(limit),
+                offset,
                 min_score,
                 source_ids: source,
+                tags: tag,
+                section,
+                strict_model,
                 dedupe_docs: dedupe,
+                context,
+                explain,
+                count,
+                timeout_secs: timeout,
+                rerank_candidates,
                 ..Default::default()
             };
 
-            let results =
-                cmd_query(&config, &embedding_config, embedder.as_ref(), &db, &store, &query, options)
-                    .await?;
+            if cli.ndjson {
+                // Write each result as soon as it is ranked instead of collecting them first
+                let store = if keyword_only {
+                    None
+                } else {
+                    Some(connect_store(&config, &embedding).await?)
+                };
+                let timeout_secs = options.timeout_secs.or(config.query.timeout_secs);
+                let search = async {
+                    let stream = match &store {
+                        None => keyword_query_stream(&config, &db, &query, options).await?,
+                        Some(store) => {
+                            let (embedding_config, embedder) = resolved(&embedding);
+                            query_stream(
+                                &config,
+                                embedding_config,
+                                embedder,
+                                &db,
+                                store,
+                                &query,
+                                options,
+                            )
+                            .await?
+                        }
+                    };
+                    let stream = if highlight {
+                        stream.with_highlights(HighlightStyle::Markdown)
+                    } else {
+                        stream
+                    };
+                    output.write_ndjson_stream(stream).await
+                };
+                if with_query_timeout(timeout_secs, search).await? == 0 {
+                    exit_no_results();
+                }
+                return Ok(());
+            }
+
+            let mut results = if keyword_only {
+                cmd_keyword_query(&config, &db, &query, options).await?
+            } else {
+                let (embedding_config, embedder) = resolved(&embedding);
+                let store = connect_store(&config, &embedding).await?;
+                cmd_query(
+                    &config,
+                    embedding_config,
+                    embedder,
+                    &db,
+                    &store,
+                    &query,
+                    options,
+                )
+                .await?
+            };
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&results)?);
+                if highlight {
+                    results.apply_highlights(HighlightStyle::Markdown);
+                }
+                output.write(&results)?;
             } else {
-                print_query_results(&results);
+                let style = if std::io::stdout().is_terminal() {
+                    HighlightStyle::Ansi
+                } else {
+                    HighlightStyle::Markdown
+                };
+                print_query_results(&results, highlight.then_some(style));
+            }
+            if results.results.is_empty() {
+                exit_no_results();
             }
         }
 
-        Commands::Status => {
-            let status = cmd_status(&config, &db, &store).await?;
+        Commands::Status {
+            show_errors,
+            since,
+            until,
+        } => {
+            // Status reports an unreachable Qdrant instead of failing on it
+            let store = open_store(&config, &embedding).await?;
+            let options = StatusOptions {
+                show_errors,
+                check_backend: !cli.offline,
+                range: TimeRange::new(since, until),
+            };
+            let status = cmd_status(&config, &db, &store, options).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&status)?);
+                output.write(&status)?;
             } else {
-                print_status(&status);
+                print_status(&status, color_enabled());
             }
         }
 
@@ -356,82 +1062,179 @@ async fn run() -> Result<()> {
                 for source in &sources {
                     println!("{}", source.id);
                 }
+            } else if cli.ndjson {
+                output.write_ndjson(&sources)?;
             } else if cli.json {
-                println!("{}", serde_json::to_string_pretty(&sources)?);
+                output.write(&sources)?;
             } else {
                 print_sources(&sources);
             }
         }
 
+        Commands::Info { source_id } => {
+            let info = cmd_source_info(&db, &source_id).await?;
+
+            if cli.json {
+                output.write(&info)?;
+            } else {
+                print_source_info(&info);
+            }
+        }
+
+        Commands::Runs {
+            source,
+            limit,
+            since,
+            until,
+        } => {
+            let options = RunsOptions {
+                source_ids: source,
+                limit,
+                range: TimeRange::new(since, until),
+            };
+            let runs = cmd_list_runs(&db, options).await?;
+
+            if cli.ndjson {
+                output.write_ndjson(&runs)?;
+            } else if cli.json {
+                output.write(&runs)?;
+            } else {
+                print_runs(&runs);
+            }
+        }
+
         Commands::Prune {
             dry_run,
             remove_orphans,
+            remove_dangling,
+            concurrency,
             source,
         } => {
             let options = PruneOptions {
                 source_ids: source,
                 dry_run,
                 remove_orphans,
+                remove_dangling,
+                concurrency,
             };
 
+            let store = connect_store(&config, &embedding).await?;
             let stats = cmd_prune(&config, &db, &store, options).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&stats)?);
+                output.write(&stats)?;
             } else {
                 print_prune_stats(&stats, dry_run);
             }
         }
 
+        Commands::Vacuum => {
+            let stats = cmd_vacuum(&db, &config.paths.db_file).await?;
+
+            if cli.json {
+                output.write(&stats)?;
+            } else {
+                print_vacuum_stats(&stats);
+            }
+        }
+
         Commands::Reindex { source, batch_size } => {
             let options = ReindexOptions {
                 source_ids: source,
                 batch_size,
             };
 
+            let (embedding_config, embedder) = resolved(&embedding);
+            let store = connect_store(&config, &embedding).await?;
             let stats =
-                cmd_reindex(&config, &embedding_config, &db, &store, embedder.as_ref(), options)
-                    .await?;
+                cmd_reindex(&config, embedding_config, &db, &store, embedder, options).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&stats)?);
+                output.write(&stats)?;
             } else {
                 print_reindex_stats(&stats);
             }
         }
 
+        Commands::ReembedMissing { source, batch_size } => {
+            let options = ReindexOptions {
+                source_ids: source,
+                batch_size,
+            };
+
+            let (embedding_config, embedder) = resolved(&embedding);
+            let store = connect_store(&config, &embedding).await?;
+            let stats =
+                cmd_reembed_missing(embedding_config, &db, &store, embedder, options).await?;
+
+            if cli.json {
+                output.write(&stats)?;
+            } else {
+                print_reembed_stats(&stats);
+            }
+        }
+
         Commands::Update { source, skip_prune } => {
             let options = UpdateOptions {
                 source_ids: source,
                 prune_orphans: !skip_prune,
             };
 
-            let stats = cmd_update(&config, &embedding_config, embedder.as_ref(), &db, &store, options).await?;
+            let (embedding_config, embedder) = resolved(&embedding);
+            let store = connect_store(&config, &embedding).await?;
+            let stats =
+                cmd_update(&config, embedding_config, embedder, &db, &store, options).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&stats)?);
+                output.write(&stats)?;
             } else {
                 print_update_stats(&stats);
             }
         }
 
+        Commands::Watch {
+            source_id,
+            debounce_ms,
+        } => {
+            let options = WatchOptions {
+                debounce: std::time::Duration::from_millis(debounce_ms),
+            };
+            let (embedding_config, embedder) = resolved(&embedding);
+            let store = connect_store(&config, &embedding).await?;
+            cmd_watch(
+                &config,
+                embedding_config,
+                embedder,
+                &db,
+                &store,
+                &source_id,
+                options,
+            )
+            .await?;
+        }
+
         Commands::Remove { source_id } => {
+            let store = connect_store(&config, &embedding).await?;
             let stats = cmd_remove_source(&db, &store, &source_id).await?;
 
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&stats)?);
+                output.write(&stats)?;
             } else {
-                println!("✓ Source '{}' removed successfully", source_id);
+                say!("✓ Source '{}' removed successfully", source_id);
                 print_prune_stats(&stats, false);
             }
         }
 
-        Commands::Rename { source_id, name } => {
-            let updated = cmd_rename_source(&db, &source_id, name).await?;
+        Commands::Rename {
+            source_id,
+            name,
+            no_clobber,
+        } => {
+            let updated = cmd_rename_source(&db, &source_id, name, no_clobber).await?;
             if cli.json {
-                println!("{}", serde_json::to_string_pretty(&updated)?);
+                output.write(&updated)?;
             } else {
-                println!(
+                say!(
                     "✓ Renamed source '{}': {}",
                     updated.id,
                     updated.name.as_deref().unwrap_or(&updated.uri)
@@ -439,11 +1242,88 @@ async fn run() -> Result<()> {
             }
         }
 
+        Commands::Tag { source_id, tags } => {
+            let updated = cmd_tag_source(&db, &source_id, &tags).await?;
+            if cli.json {
+                output.write(&updated)?;
+            } else {
+                say!(
+                    "✓ Tagged source '{}': {}",
+                    updated.id,
+                    updated.tags().join(", ")
+                );
+            }
+        }
+
+        Commands::Untag { source_id, tags } => {
+            let updated = cmd_untag_source(&db, &source_id, &tags).await?;
+            if cli.json {
+                output.write(&updated)?;
+            } else {
+                let remaining = updated.tags();
+                if remaining.is_empty() {
+                    say!("✓ Removed all tags from source '{}'", updated.id);
+                } else {
+                    say!(
+                        "✓ Untagged source '{}': {}",
+                        updated.id,
+                        remaining.join(", ")
+                    );
+                }
+            }
+        }
+
+        Commands::Reconfigure {
+            source_id,
+            max_pages,
+            max_depth,
+            path_prefix,
+            same_domain,
+            allow_subdomains,
+            js,
+            wait_for,
+            use_sitemap,
+            no_use_sitemap,
+            reset,
+        } => {
+            use librarian::commands::CrawlOverrides;
+            let overrides = CrawlOverrides {
+                max_pages,
+                max_depth,
+                path_prefix,
+                allow_subdomains: if allow_subdomains {
+                    Some(true)
+                } else if same_domain {
+                    Some(false)
+                } else {
+                    None
+                },
+                js_mode: js,
+                wait_for_selector: wait_for,
+                use_sitemap: if use_sitemap {
+                    Some(true)
+                } else if no_use_sitemap {
+                    Some(false)
+                } else {
+                    None
+                },
+                ..Default::default()
+            };
+            let updated = cmd_reconfigure_source(&db, &source_id, overrides, reset).await?;
+            if cli.json {
+                output.write(&CrawlOverrides::from_source(&updated))?;
+            } else {
+                print_crawl_overrides(&updated);
+            }
+        }
+
         Commands::Db { action } => {
-            handle_db_action(&config, action, cli.json).await?;
+            let store = connect_store(&config, &embedding).await?;
+            handle_db_action(&store, action, cli.json.then_some(&output)).await?;
         }
 
         Commands::Mcp => {
+            let store = connect_store(&config, &embedding).await?;
             let server = McpServer::new(config, db, store);
             server
                 .run()
@@ -451,7 +1331,7 @@ async fn run() -> Result<()> {
                 .map_err(|e| librarian::error::Error::McpProtocol(e.to_string()))?;
         }
 
-        Commands::Completions { .. } => unreachable!(),
+        Commands::Completions { .. } | Commands::Config { .. } => unreachable!(),
     }
 
     Ok(())
@@ -463,7 +1343,7 @@ fn print_completion_extras(shell: Shell) {
             println!();
             println!(
                 "{raw}",
-                raw = r#"# Dynamic completion for 'librarian remove' source IDs"#
+                raw = r#"# Dynamic completion of source IDs and names for 'librarian remove' and --source"#
             );
             println!("{raw}", raw = r#"_librarian_dynamic() {"#);
             println!("{raw}", raw = r#"    local cur prev words cword"#);
@@ -474,6 +1354,10 @@ fn print_completion_extras(shell: Shell) {
             println!("{raw}", raw = r#"        _init_completion -n : || return"#);
             println!("{raw}", raw = r#"    else"#);
             println!("{raw}", raw = r#"        cur="${COMP_WORDS[COMP_CWORD]}""#);
+            println!(
+                "{raw}",
+                raw = r#"        prev="${COMP_WORDS[COMP_CWORD-1]}""#
+            );
             println!("{raw}", raw = r#"        words=("${COMP_WORDS[@]}")"#);
             println!("{raw}", raw = r#"        cword=$COMP_CWORD"#);
             println!("{raw}", raw = r#"    fi"#);
@@ -489,8 +1373,7 @@ fn print_completion_extras(shell: Shell) {
             println!("{raw}", raw = r#"    done"#);
             println!(
                 "{raw}",
-                raw =
-                    r#"    if [[ $remove_index -ge 0 && $cword -eq $((remove_index + 1)) ]]; then"#
+                raw = r#"    if [[ "$prev" == "--source" || ( $remove_index -ge 0 && $cword -eq $((remove_index + 1)) ) ]]; then"#
             );
             println!(
                 "{raw}",
@@ -518,28 +1401,45 @@ fn print_completion_extras(shell: Shell) {
         Shell::Zsh => {
             println!();
             println!(
-                "{}",
-                r#"# Dynamic completion for 'librarian remove' source IDs"#
+                "{raw}",
+                raw = r#"# Dynamic completion of source IDs and names for 'librarian remove' and --source"#
+            );
+            println!("{raw}", raw = r#"_librarian_source_ids() {"#);
+            println!("{raw}", raw = r#"    local -a entries"#);
+            println!(
+                "{raw}",
+                raw = r#"    entries=("${(@f)$(librarian sources --completion zsh 2>/dev/null)}")"#
             );
-            println!("{}", r#"_librarian_source_ids() {"#);
-            println!("{}", r#"    local -a entries"#);
             println!(
-                "{}",
-                r#"    entries=("${(@f)$(librarian sources --completion zsh 2>/dev/null)}")"#
+                "{raw}",
+                raw = r#"    _describe -t sources 'sources' entries"#
             );
-            println!("{}", r#"    _describe -t sources 'source ids' entries"#);
-            println!("{}", r#"}"#);
-            println!("{}", r#"compdef _librarian_source_ids 'librarian remove'"#);
+            println!("{raw}", raw = r#"}"#);
+            println!("{raw}", raw = r#"_librarian_dynamic() {"#);
+            println!(
+                "{raw}",
+                raw = r#"    if [[ ${words[CURRENT-1]} == --source || ( ${words[2]} == remove && $CURRENT -eq 3 ) ]]; then"#
+            );
+            println!("{raw}", raw = r#"        _librarian_source_ids"#);
+            println!("{raw}", raw = r#"    else"#);
+            println!("{raw}", raw = r#"        _librarian "$@""#);
+            println!("{raw}", raw = r#"    fi"#);
+            println!("{raw}", raw = r#"}"#);
+            println!("{raw}", raw = r#"compdef _librarian_dynamic librarian"#);
         }
         Shell::Fish => {
             println!();
             println!(
-                "{}",
-                r#"# Dynamic completion for 'librarian remove' source IDs"#
+                "{raw}",
+                raw = r#"# Dynamic completion of source IDs and names for 'librarian remove' and --source"#
+            );
+            println!(
+                "{raw}",
+                raw = r#"complete -c librarian -n '__fish_seen_subcommand_from remove' -a '(librarian sources --completion fish 2>/dev/null)'"#
             );
             println!(
-                "{}",
-                r#"complete -c librarian -n '__fish_seen_subcommand_from remove' -a '(librarian sources --completion fish 2>/dev/null)'"#
+                "{raw}",
+                raw = r#"complete -c librarian -n '__fish_seen_subcommand_from query prune update reindex runs' -l source -x -a '(librarian sources --completion fish 2>/dev/null)'"#
             );
         }
         _ => {}
@@ -586,52 +1486,130 @@ async fn handle_init(cli: Cli) -> Result<()> {
     Ok(())
 }
 
-async fn handle_db_action(config: &Config, action: DbAction, json: bool) -> Result<()> {
-    let embedding_config = config.resolve_embedding_config().await?;
-    let store = QdrantStore::connect(config, &embedding_config).await?;
+fn handle_config_action(
+    config: &Config,
+    action: ConfigAction,
+    json: Option<&JsonOutput>,
+) -> Result<()> {
+    let (setting, updated) = match action {
+        ConfigAction::Get { key } => (cmd_config_get(config, &key)?, false),
+        ConfigAction::Set { key, value } => (cmd_config_set(config, &key, &value)?, true),
+        ConfigAction::Validate { .. } => unreachable!(),
+    };
+
+    if let Some(output) = json {
+        output.write(&setting)?;
+    } else {
+        if updated {
+            say!("✓ Updated {}", config.paths.config_file.display());
+        }
+        print_config_setting(&setting);
+    }
+    Ok(())
+}
 
+async fn handle_db_action(
+    store: &QdrantStore,
+    action: DbAction,
+    json: Option<&JsonOutput>,
+) -> Result<()> {
     match action {
         DbAction::Init => {
             store.ensure_collection().await?;
-            if json {
-                println!(r#"{{"status": "ok", "message": "Collection initialized"}}"#);
+            if let Some(output) = json {
+                output.write(&json!({
+                    "status": "ok",
+                    "message": "Collection initialized",
+                    "collection": store.collection_name(),
+                }))?;
             } else {
-                println!("✓ Qdrant collection initialized");
+                say!(
+                    "✓ Qdrant collection '{}' initialized",
+                    store.collection_name()
+                );
             }
         }
         DbAction::Status => match store.get_collection_info().await? {
             Some(info) => {
-                if json {
-                    println!(
-                        r#"{{"exists": true, "points_count": {}, "indexed_vectors_count": {}, "status": "{}"}}"#,
-                        info.points_count, info.indexed_vectors_count, info.status
-                    );
+                if let Some(output) = json {
+                    output.write(&json!({
+                        "exists": true,
+                        "points_count": info.points_count,
+                        "indexed_vectors_count": info.indexed_vectors_count,
+                        "status": info.status,
+                        "vector_size": info.vector_size(),
+                        "expected_dimension": info.expected_dimension,
+                        "dimension_matches": info.dimension_matches(),
+                        "warning": info.dimension_warning,
+                    }))?;
                 } else {
-                    println!("Qdrant Collection Status:");
-                    println!("  Status: {}", info.status);
-                    println!("  Points: {}", info.points_count);
-                    println!("  Indexed Vectors: {}", info.indexed_vectors_count);
+                    say!("Qdrant Collection Status:");
+                    say!("  Status: {}", info.status);
+                    say!("  Points: {}", info.points_count);
+                    say!("  Indexed Vectors: {}", info.indexed_vectors_count);
+                    say!(
+                        "  Vector Size: {}",
+                        info.vector_size()
+                            .map_or_else(|| "unknown".to_string(), |size| size.to_string())
+                    );
+                    say!(
+                        "  Expected Dimension: {}",
+                        info.expected_dimension
+                            .map_or_else(|| "unknown".to_string(), |dim| dim.to_string())
+                    );
+                    match info.dimension_matches() {
+                        Some(true) => say!("  Dimension Check: ✓ match"),
+                        Some(false) => say!("  Dimension Check: ✗ mismatch"),
+                        None => say!("  Dimension Check: ? model dimension unknown"),
+                    }
+                    if let Some(warning) = &info.dimension_warning {
+                        say!("  ⚠️  {}", warning);
+                    }
                 }
             }
             None => {
-                if json {
-                    println!(r#"{{"exists": false}}"#);
+                if let Some(output) = json {
+                    output.write(&json!({ "exists": false }))?;
                 } else {
-                    println!("Collection does not exist. Run 'librarian db init' to create it.");
+                    say!("Collection does not exist. Run 'librarian db init' to create it.");
                 }
             }
         },
+        DbAction::ReindexPayload => {
+            if !store.collection_exists().await? {
+                eprintln!("Collection does not exist. Run 'librarian db init' to create it.");
+                std::process::exit(exit_code::NOT_FOUND);
+            }
+            let created = store.ensure_payload_indexes().await?;
+            if let Some(output) = json {
+                output.write(&json!({ "status": "ok", "created": created }))?;
+            } else if created.is_empty() {
+                say!("✓ Payload indexes already present");
+            } else {
+                say!("✓ Created payload indexes: {}", created.join(", "));
+            }
+        }
         DbAction::Reset { yes } => {
             if !yes {
-                eprintln!("⚠️  This will delete ALL indexed data!");
+                eprintln!(
+                    "⚠️  This will delete ALL indexed data in collection '{}'!",
+                    store.collection_name()
+                );
                 eprintln!("Run with --yes to confirm.");
-                std::process::exit(1);
+                std::process::exit(exit_code::USAGE);
             }
             store.reset_collection().await?;
-            if json {
-                println!(r#"{{"status": "ok", "message": "Collection reset"}}"#);
+            if let Some(output) = json {
+                output.write(&json!({
+                    "status": "ok",
+                    "message": "Collection reset",
+                    "collection": store.collection_name(),
+                }))?;
             } else {
-                println!("✓ Qdrant collection reset (all data deleted and collection recreated)");
+                say!(
+                    "✓ Qdrant collection '{}' reset (all data deleted and collection recreated)",
+                    store.collection_name()
+                );
             }
         }
     }
@@ -639,7 +1617,11 @@ async fn handle_db_action(config: &Config, action: DbAction, json: bool) -> Resu
     Ok(())
 }
 
-async fn load_config(path: Option<&std::path::Path>) -> Result<Config> {
+async fn load_config(
+    path: Option<&std::path::Path>,
+    profile: Option<&str>,
+    collection: Option<&str>,
+) -> Result<Config> {
     let config_path = path
         .map(PathBuf::from)
         .unwrap_or_else(Config::default_config_path);
@@ -649,110 +1631,384 @@ async fn load_config(path: Option<&std::path::Path>) -> Result<Config> {
             "Config file not found: {}\nRun 'librarian init' first.",
             config_path.display()
         );
-        std::process::exit(1);
+        std::process::exit(exit_code::CONFIG);
+    }
+
+    let mut config = Config::load_profile(&config_path, profile)?;
+    if let Some(collection) = collection {
+        config.apply_collection_override(collection)?;
+    }
+    Ok(config)
+}
+
+/// Print the number of per-document errors from an ingest, if any
+fn print_ingest_errors(errors: &[String]) {
+    if !errors.is_empty() {
+        say!(
+            "  Errors: {} (run 'librarian status --show-errors' for details)",
+            errors.len()
+        );
+    }
+}
+
+/// Cancel `token` on the first Ctrl-C, so a crawl stops and ingests what it
+/// fetched; a second Ctrl-C exits immediately
+fn cancel_on_ctrl_c(token: CancellationToken) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            warn!("Stopping crawl; pages fetched so far will be ingested (press Ctrl-C again to abort)");
+            token.cancel();
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(exit_code::INTERRUPTED);
+            }
+        }
+    })
+}
+
+/// Exit with [`exit_code::NO_RESULTS`] once an empty query result is printed
+fn exit_no_results() -> ! {
+    let _ = std::io::stdout().flush();
+    std::process::exit(exit_code::NO_RESULTS);
+}
+
+/// Fail before ingesting when `--no-clobber` is set and another source has the name
+async fn ensure_name_free(db: &MetaDb, name: &Option<String>, uri: &str) -> Result<()> {
+    match name {
+        Some(name) => ensure_name_available(db, name, uri).await,
+        None => Ok(()),
     }
+}
 
-    Config::load(&config_path)
+/// The embedder and store an ingest that writes goes through
+fn ingest_backends<'a>(
+    embedding: &'a Option<Embedding>,
+    store: Option<&'a QdrantStore>,
+) -> (
+    &'a ResolvedEmbeddingConfig,
+    &'a dyn Embedder,
+    &'a QdrantStore,
+) {
+    let (embedding, embedder) = resolved(embedding);
+    let store = store.expect("store is connected for ingests that write");
+    (embedding, embedder, store)
 }
 
 async fn handle_ingest(
     config: &Config,
-    embedding: &librarian::config::ResolvedEmbeddingConfig,
-    embedder: &dyn librarian::embed::Embedder,
+    embedding: &Option<Embedding>,
     db: &MetaDb,
-    store: &QdrantStore,
+    store: Option<&QdrantStore>,
     source: IngestSource,
+    json: Option<&JsonOutput>,
 ) -> Result<()> {
-    match source {
+    let (stats, dry_run, kind, unit) = match source {
         IngestSource::Dir {
             path,
             name,
-            extensions: _,
-            exclude: _,
+            no_clobber,
+            extensions,
+            exclude,
+            follow_symlinks,
+            respect_gitignore,
+            concurrency,
+            dry_run,
         } => {
-            let stats =
-                cmd_ingest_dir(config, embedding, embedder, db, store, &path, name, RunOperation::Ingest, true).await?;
-
-            // Display overlap warnings
-            for warning in &stats.overlap_warnings {
-                println!("{}", warning);
+            if no_clobber {
+                let uri = path
+                    .canonicalize()
+                    .map_err(|e| Error::InvalidPath(format!("{}: {}", path.display(), e)))?;
+                ensure_name_free(db, &name, &uri.display().to_string()).await?;
             }
-
-            println!("\n✓ Directory ingestion complete");
-            println!("  Documents processed: {}", stats.docs_processed);
-            println!("  Chunks created: {}", stats.chunks_created);
-            println!("  Chunks updated: {}", stats.chunks_updated);
-            println!("  Chunks deleted: {}", stats.chunks_deleted);
+            let options = DirIngestOptions {
+                filter: DirFilter::from_options(
+                    extensions,
+                    exclude,
+                    follow_symlinks,
+                    respect_gitignore,
+                ),
+                concurrency,
+            };
+            let stats = if dry_run {
+                cmd_preview_ingest_dir(config, db, &path, options).await?
+            } else {
+                let (embedding, embedder, store) = ingest_backends(embedding, store);
+                cmd_ingest_dir(
+                    config,
+                    embedding,
+                    embedder,
+                    db,
+                    store,
+                    &path,
+                    name,
+                    options,
+                    RunOperation::Ingest,
+                    true,
+                )
+                .await?
+            };
+            (stats, dry_run, "Directory", "Documents")
         }
 
         IngestSource::Url {
             url,
             name,
+            no_clobber,
             max_pages,
             max_depth,
             path_prefix,
+            same_domain,
+            allow_subdomains,
+            js,
+            wait_for,
+            use_sitemap,
+            no_robots,
+            dry_run,
         } => {
             use librarian::commands::CrawlOverrides;
+            if no_clobber {
+                ensure_name_free(db, &name, &url).await?;
+            }
             let overrides = CrawlOverrides {
-                max_pages: Some(max_pages),
-                max_depth: Some(max_depth),
+                max_pages,
+                max_depth,
                 path_prefix,
+                allow_subdomains: if allow_subdomains {
+                    Some(true)
+                } else if same_domain {
+                    Some(false)
+                } else {
+                    None
+                },
+                js_mode: js,
+                wait_for_selector: wait_for,
+                use_sitemap: use_sitemap.then_some(true),
+                ignore_robots: no_robots,
             };
-            let stats = cmd_ingest_url(
+            let stats = if dry_run {
+                cmd_preview_ingest_url(config, db, &url, overrides).await?
+            } else {
+                let (embedding, embedder, store) = ingest_backends(embedding, store);
+                let cancel = CancellationToken::new();
+                let ctrl_c = cancel_on_ctrl_c(cancel.clone());
+                let stats = cmd_ingest_url(
+                    config,
+                    embedding,
+                    embedder,
+                    db,
+                    store,
+                    &url,
+                    name,
+                    overrides,
+                    RunOperation::Ingest,
+                    true,
+                    Some(&cancel),
+                )
+                .await;
+                ctrl_c.abort();
+                stats?
+            };
+            (stats, dry_run, "URL", "Pages")
+        }
+
+        IngestSource::Sitemap {
+            url,
+            name,
+            no_clobber,
+            max_pages,
+            no_robots,
+            dry_run,
+        } => {
+            if no_clobber {
+                ensure_name_free(db, &name, &url).await?;
+            }
+            let stats = if dry_run {
+                cmd_preview_ingest_sitemap(config, db, &url, max_pages, no_robots).await?
+            } else {
+                let (embedding, embedder, store) = ingest_backends(embedding, store);
+                let cancel = CancellationToken::new();
+                let ctrl_c = cancel_on_ctrl_c(cancel.clone());
+                let stats = cmd_ingest_sitemap(
+                    config,
+                    embedding,
+                    embedder,
+                    db,
+                    store,
+                    &url,
+                    name,
+                    max_pages,
+                    no_robots,
+                    RunOperation::Ingest,
+                    true,
+                    Some(&cancel),
+                )
+                .await;
+                ctrl_c.abort();
+                stats?
+            };
+            (stats, dry_run, "Sitemap", "Pages")
+        }
+
+        IngestSource::Urls {
+            from_file,
+            name,
+            no_clobber,
+        } => {
+            if no_clobber {
+                ensure_name_free(db, &name, &url_list_uri(&from_file)).await?;
+            }
+            let list = UrlList::read(&from_file)?;
+            // Conflict prompts cannot be answered once stdin held the list
+            let interactive = from_file != std::path::Path::new("-");
+            let cancel = CancellationToken::new();
+            let ctrl_c = cancel_on_ctrl_c(cancel.clone());
+            let (embedding, embedder, store) = ingest_backends(embedding, store);
+            let stats = cmd_ingest_urls(
                 config,
                 embedding,
                 embedder,
                 db,
                 store,
-                &url,
+                &url_list_uri(&from_file),
+                &list,
                 name,
-                overrides,
                 RunOperation::Ingest,
-                true,
+                interactive,
+                Some(&cancel),
             )
-            .await?;
-
-            // Display overlap warnings
-            for warning in &stats.overlap_warnings {
-                println!("{}", warning);
-            }
-
-            println!("\n✓ URL ingestion complete");
-            println!("  Pages processed: {}", stats.docs_processed);
-            println!("  Chunks created: {}", stats.chunks_created);
-            println!("  Chunks updated: {}", stats.chunks_updated);
+            .await;
+            ctrl_c.abort();
+            (stats?, false, "URL list", "Pages")
         }
 
-        IngestSource::Sitemap {
-            url,
+        IngestSource::Openapi {
+            location,
             name,
-            max_pages,
+            no_clobber,
         } => {
-            let stats = cmd_ingest_sitemap(
+            let uri = openapi_spec_uri(&location);
+            if no_clobber {
+                ensure_name_free(db, &name, &uri).await?;
+            }
+            let (embedding, embedder, store) = ingest_backends(embedding, store);
+            let stats = cmd_ingest_openapi(
                 config,
                 embedding,
                 embedder,
                 db,
                 store,
-                &url,
+                &uri,
                 name,
-                max_pages,
                 RunOperation::Ingest,
                 true,
             )
             .await?;
+            (stats, false, "OpenAPI spec", "Operations")
+        }
+    };
 
-            // Display overlap warnings
-            for warning in &stats.overlap_warnings {
-                println!("{}", warning);
-            }
+    if let Some(output) = json {
+        output.write(&stats)?;
+        return Ok(());
+    }
 
-            println!("\n✓ Sitemap ingestion complete");
-            println!("  Pages processed: {}", stats.docs_processed);
-            println!("  Chunks created: {}", stats.chunks_created);
-            println!("  Chunks updated: {}", stats.chunks_updated);
-        }
+    if dry_run {
+        print_ingest_preview(&stats, unit);
+        print_ingest_errors(&stats.errors);
+        return Ok(());
     }
 
+    // Display overlap warnings
+    for warning in &stats.overlap_warnings {
+        say!("{}", warning);
+    }
+
+    say!("\n✓ {} ingestion complete", kind);
+    say!("  {} processed: {}", unit, stats.docs_processed);
+    say!("  Chunks created: {}", stats.chunks_created);
+    say!("  Chunks updated: {}", stats.chunks_updated);
+    if kind == "Directory" {
+        say!("  Chunks deleted: {}", stats.chunks_deleted);
+    }
+    print_crawl_report(&stats);
+    print_ingest_errors(&stats.errors);
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_sources_runs_with_invalid_qdrant_url() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.config_file = tmp.path().join("config.toml");
+        config.qdrant_url = "not a qdrant url".to_string();
+        config.save().unwrap();
+
+        let config_path = config.paths.config_file.to_string_lossy().to_string();
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["librarian", "--config", &config_path].iter().chain(args)).unwrap()
+        };
+        execute(parse(&["sources", "--ids-only"])).await.unwrap();
+
+        // Commands that touch vectors still fail, naming the configured URL
+        let err = execute(parse(&["remove", "missing"])).await.unwrap_err();
+        assert!(err.to_string().contains("not a qdrant url"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_db_actions_target_named_collection() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.config_file = tmp.path().join("config.toml");
+        config.save().unwrap();
+        let config_path = config.paths.config_file.to_string_lossy().to_string();
+
+        for args in [
+            ["db", "init", "--collection", "migration_v2"].as_slice(),
+            ["db", "reset", "--collection", "migration_v2", "--yes"].as_slice(),
+            ["--collection", "migration_v2", "db", "reset"].as_slice(),
+        ] {
+            let cli =
+                Cli::try_parse_from(["librarian", "--config", &config_path].iter().chain(args))
+                    .unwrap();
+            let loaded = load_config(
+                cli.config.as_deref(),
+                cli.profile.as_deref(),
+                cli.collection.as_deref(),
+            )
+            .await
+            .unwrap();
+            let store = QdrantStore::connect_offline(&loaded).await.unwrap();
+            assert_eq!(store.collection_name(), "migration_v2", "{args:?}");
+            assert_ne!(store.collection_name(), config.collection_name);
+            // Reset still wants its confirmation
+            if let Commands::Db {
+                action: DbAction::Reset { yes },
+            } = cli.command
+            {
+                assert_eq!(yes, args.contains(&"--yes"), "{args:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_log_filter_precedence() {
+        let filter = |args: &[&str]| {
+            let cli = Cli::try_parse_from(["librarian"].iter().chain(args)).unwrap();
+            log_filter(&cli).to_string()
+        };
+
+        assert_eq!(filter(&["--quiet", "status"]), "error");
+        assert_eq!(filter(&["--verbose", "status"]), "debug");
+        assert_eq!(
+            filter(&["--quiet", "--log-level", "warn", "status"]),
+            "warn"
+        );
+        assert!(Cli::try_parse_from(["librarian", "--log-level", "[", "status"]).is_err());
+        assert!(Cli::try_parse_from(["librarian", "--quiet", "--verbose", "status"]).is_err());
+    }
+}
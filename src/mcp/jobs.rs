@@ -0,0 +1,177 @@
+//! Tracking for ingestion jobs started over MCP
+
+use crate::commands::IngestStats;
+use crate::error::Result;
+use crate::meta::SourceType;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Error recorded for jobs and runs cut short by a server shutdown
+pub const SHUTDOWN_ERROR: &str = "cancelled: server shut down";
+
+/// State of a background ingestion job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// An ingestion job, reported to clients as JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestJob {
+    pub job_id: String,
+    pub source_type: String,
+    pub uri: String,
+    pub status: JobStatus,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub stats: Option<IngestStats>,
+    pub error: Option<String>,
+}
+
+/// In-memory registry of ingestion jobs, shared with background tasks
+#[derive(Debug, Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<String, IngestJob>>>,
+    /// Background tasks (ingests, updates, reindexes) still owned by the server
+    tasks: Arc<Mutex<JoinSet<()>>>,
+}
+
+impl JobRegistry {
+    /// Register a new running job
+    pub fn start(&self, source_type: SourceType, uri: &str) -> IngestJob {
+        let job = IngestJob {
+            job_id: Uuid::new_v4().to_string(),
+            source_type: source_type.to_string(),
+            uri: uri.to_string(),
+            status: JobStatus::Running,
+            started_at: Utc::now().to_rfc3339(),
+            completed_at: None,
+            stats: None,
+            error: None,
+        };
+        self.lock().insert(job.job_id.clone(), job.clone());
+        job
+    }
+
+    /// Record the outcome of a job, returning its final state
+    pub fn finish(&self, job_id: &str, result: Result<IngestStats>) -> Option<IngestJob> {
+        let mut jobs = self.lock();
+        let job = jobs.get_mut(job_id)?;
+        job.completed_at = Some(Utc::now().to_rfc3339());
+        match result {
+            Ok(stats) => {
+                job.status = JobStatus::Completed;
+                job.stats = Some(stats);
+            }
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e.to_string());
+            }
+        }
+        Some(job.clone())
+    }
+
+    pub fn get(&self, job_id: &str) -> Option<IngestJob> {
+        self.lock().get(job_id).cloned()
+    }
+
+    /// Run `task` in the background until it finishes or [`Self::shutdown`] aborts it
+    pub fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let mut tasks = self.lock_tasks();
+        // Reap finished tasks so the set only holds live ones
+        while tasks.try_join_next().is_some() {}
+        tasks.spawn(task);
+    }
+
+    /// Abort every background task and wait up to `timeout` for them to
+    /// unwind and drop their resources, returning how many were still running.
+    /// Jobs that never finished are recorded as failed.
+    pub async fn shutdown(&self, timeout: Duration) -> usize {
+        let mut tasks = std::mem::take(&mut *self.lock_tasks());
+        while tasks.try_join_next().is_some() {}
+        let running = tasks.len();
+        tasks.abort_all();
+        let drained = tokio::time::timeout(timeout, async {
+            while tasks.join_next().await.is_some() {}
+        })
+        .await;
+        if drained.is_err() {
+            warn!(
+                "{} background tasks did not stop within {:?}",
+                tasks.len(),
+                timeout
+            );
+        }
+
+        let completed_at = Utc::now().to_rfc3339();
+        for job in self.lock().values_mut() {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Failed;
+                job.completed_at = Some(completed_at.clone());
+                job.error = Some(SHUTDOWN_ERROR.to_string());
+            }
+        }
+        running
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, IngestJob>> {
+        // A panic while holding the lock leaves the map itself intact
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_tasks(&self) -> std::sync::MutexGuard<'_, JoinSet<()>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_job_registry_tracks_outcome() {
+        let jobs = JobRegistry::default();
+        let ok = jobs.start(SourceType::Dir, "/docs");
+        let failed = jobs.start(SourceType::Url, "https://example.com");
+        assert_eq!(jobs.get(&ok.job_id).unwrap().status, JobStatus::Running);
+
+        jobs.finish(&ok.job_id, Ok(IngestStats::default()));
+        jobs.finish(&failed.job_id, Err(Error::Other("boom".to_string())));
+
+        let ok = jobs.get(&ok.job_id).unwrap();
+        assert_eq!(ok.status, JobStatus::Completed);
+        assert!(ok.stats.is_some() && ok.completed_at.is_some());
+        let failed = jobs.get(&failed.job_id).unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+        assert!(jobs.finish("missing", Ok(IngestStats::default())).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_fails_unfinished_jobs() {
+        let jobs = JobRegistry::default();
+        let done = jobs.start(SourceType::Dir, "/docs");
+        jobs.finish(&done.job_id, Ok(IngestStats::default()));
+        let stuck = jobs.start(SourceType::Url, "https://example.com");
+        jobs.spawn(std::future::pending());
+
+        assert_eq!(jobs.shutdown(Duration::from_secs(1)).await, 1);
+
+        assert_eq!(jobs.get(&done.job_id).unwrap().status, JobStatus::Completed);
+        let stuck = jobs.get(&stuck.job_id).unwrap();
+        assert_eq!(stuck.status, JobStatus::Failed);
+        assert!(stuck.completed_at.is_some());
+        assert_eq!(stuck.error.as_deref(), Some(SHUTDOWN_ERROR));
+    }
+}
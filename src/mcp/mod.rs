@@ -1,10 +1,17 @@
 //! MCP (Model Context Protocol) server implementation
 //!
-//! Exposes RAG functionality over stdio for VS Code integration.
+//! Exposes RAG functionality over stdio for VS Code integration. Stdout
+//! carries only protocol frames, so printing to it is denied here.
 
+#![deny(clippy::print_stdout)]
+
+mod jobs;
+mod resources;
 mod server;
 mod tools;
 mod types;
 
+pub use jobs::{IngestJob, JobRegistry, JobStatus};
+pub use resources::{ResourceContents, ResourceDefinition, ResourceUri};
 pub use server::McpServer;
 pub use types::{McpError, McpRequest, McpResponse};
@@ -0,0 +1,201 @@
+//! MCP resources: registered sources and their documents
+//!
+//! Resource URIs are stable across re-ingestion:
+//! - `librarian://source/<source_id>` lists a source's documents
+//! - `librarian://source/<source_id>/doc/<encoded document uri>` returns a document's chunk text
+
+use crate::error::{Error, Result};
+use crate::meta::{Document, MetaDb, Source};
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+const SCHEME_PREFIX: &str = "librarian://source/";
+const DOC_SEGMENT: &str = "/doc/";
+
+/// Resource entry returned by `resources/list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceDefinition {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+/// Resource body returned by `resources/read`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+/// A parsed librarian resource URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    Source { source_id: String },
+    Document { source_id: String, doc_uri: String },
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Result<Self> {
+        let rest = uri
+            .strip_prefix(SCHEME_PREFIX)
+            .ok_or_else(|| Error::McpProtocol(format!("Unknown resource URI: {}", uri)))?;
+
+        let parsed = match rest.split_once(DOC_SEGMENT) {
+            Some((source_id, encoded)) => Self::Document {
+                source_id: source_id.to_string(),
+                doc_uri: decode_component(encoded),
+            },
+            None => Self::Source {
+                source_id: rest.to_string(),
+            },
+        };
+
+        match &parsed {
+            Self::Source { source_id } | Self::Document { source_id, .. }
+                if source_id.is_empty() || source_id.contains('/') =>
+            {
+                Err(Error::McpProtocol(format!("Invalid resource URI: {}", uri)))
+            }
+            _ => Ok(parsed),
+        }
+    }
+}
+
+impl std::fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Source { source_id } => write!(f, "{}{}", SCHEME_PREFIX, source_id),
+            Self::Document { source_id, doc_uri } => write!(
+                f,
+                "{}{}{}{}",
+                SCHEME_PREFIX,
+                source_id,
+                DOC_SEGMENT,
+                encode_component(doc_uri)
+            ),
+        }
+    }
+}
+
+fn encode_component(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+fn decode_component(value: &str) -> String {
+    form_urlencoded::parse(format!("v={}", value).as_bytes())
+        .next()
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_default()
+}
+
+/// List every source and document as a resource
+pub async fn list_resources(db: &MetaDb) -> Result<Vec<ResourceDefinition>> {
+    let mut resources = Vec::new();
+
+    for source in db.list_sources().await? {
+        resources.push(source_resource(&source));
+        for doc in db.list_source_documents(&source.id).await? {
+            resources.push(document_resource(&source, &doc));
+        }
+    }
+
+    Ok(resources)
+}
+
+fn source_resource(source: &Source) -> ResourceDefinition {
+    ResourceDefinition {
+        uri: ResourceUri::Source {
+            source_id: source.id.clone(),
+        }
+        .to_string(),
+        name: source.name.clone().unwrap_or_else(|| source.uri.clone()),
+        description: Some(format!("{} source: {}", source.source_type, source.uri)),
+        mime_type: "text/plain".to_string(),
+    }
+}
+
+fn document_resource(source: &Source, doc: &Document) -> ResourceDefinition {
+    ResourceDefinition {
+        uri: ResourceUri::Document {
+            source_id: source.id.clone(),
+            doc_uri: doc.uri.clone(),
+        }
+        .to_string(),
+        name: doc.title.clone().unwrap_or_else(|| doc.uri.clone()),
+        description: Some(doc.uri.clone()),
+        mime_type: "text/plain".to_string(),
+    }
+}
+
+/// Read a resource: a source's document listing or a document's chunk text
+pub async fn read_resource(db: &MetaDb, uri: &str) -> Result<ResourceContents> {
+    let text = match ResourceUri::parse(uri)? {
+        ResourceUri::Source { source_id } => {
+            let source = db
+                .get_source(&source_id)
+                .await?
+                .ok_or_else(|| Error::SourceNotFound(source_id.clone()))?;
+            let docs = db.list_source_documents(&source.id).await?;
+
+            let mut text = format!(
+                "{} [{}]\nURI: {}\nDocuments: {}\n",
+                source.name.as_deref().unwrap_or(&source.uri),
+                source.source_type,
+                source.uri,
+                docs.len()
+            );
+            for doc in &docs {
+                text.push_str(&format!("\n- {}", doc.uri));
+            }
+            text
+        }
+        ResourceUri::Document { source_id, doc_uri } => {
+            let doc = db
+                .get_document_by_uri(&source_id, &doc_uri)
+                .await?
+                .ok_or_else(|| Error::DocumentNotFound(doc_uri.clone()))?;
+            db.get_chunks_by_modality(&doc.id, "text")
+                .await?
+                .into_iter()
+                .map(|c| c.chunk_text)
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        }
+    };
+
+    Ok(ResourceContents {
+        uri: uri.to_string(),
+        mime_type: "text/plain".to_string(),
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_uri_round_trip() {
+        let doc = ResourceUri::Document {
+            source_id: "abc".to_string(),
+            doc_uri: "https://example.com/docs/a b?x=1&y=/2".to_string(),
+        };
+        let uri = doc.to_string();
+        assert!(uri.starts_with("librarian://source/abc/doc/"));
+        assert!(!uri["librarian://source/abc/doc/".len()..].contains('/'));
+        assert_eq!(ResourceUri::parse(&uri).unwrap(), doc);
+
+        let source = ResourceUri::Source {
+            source_id: "abc".to_string(),
+        };
+        assert_eq!(ResourceUri::parse(&source.to_string()).unwrap(), source);
+
+        assert!(ResourceUri::parse("file:///tmp/a").is_err());
+        assert!(ResourceUri::parse("librarian://source/").is_err());
+    }
+}
@@ -1,42 +1,96 @@
 //! MCP stdio server implementation
 
+use super::jobs::{JobRegistry, SHUTDOWN_ERROR};
+use super::resources::{list_resources, read_resource};
 use super::tools::{get_tool_definitions, handle_tool_call};
-use super::types::{McpError, McpMessage, McpNotification, McpRequest, McpResponse};
+use super::types::{
+    InitializeParams, InitializeResult, McpError, McpMessage, McpNotification, McpRequest,
+    McpResponse, PromptsCapability, ResourcesCapability, ServerCapabilities, ServerInfo,
+    ToolsCapability,
+};
 use crate::config::Config;
+use crate::crawl::is_js_rendering_available;
+use crate::error::Error;
 use crate::meta::MetaDb;
+use crate::parse::is_pdf_available;
+use crate::progress::StdoutGuard;
 use crate::store::QdrantStore;
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::io::{self, BufRead, Write};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::time::Instant;
 use tracing::{debug, error, info, warn};
 
+/// MCP protocol revision the server implements
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// How long requests still in flight when input closes may take to finish
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long shutdown waits for aborted background tasks to unwind
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// MCP Server implementation
 pub struct McpServer {
     config: Config,
     db: MetaDb,
     store: QdrantStore,
+    jobs: JobRegistry,
+    drain_timeout: Duration,
 }
 
 impl McpServer {
     /// Create a new MCP server
     pub fn new(config: Config, db: MetaDb, store: QdrantStore) -> Self {
-        Self { config, db, store }
+        Self {
+            config,
+            db,
+            store,
+            jobs: JobRegistry::default(),
+            drain_timeout: DRAIN_TIMEOUT,
+        }
     }
 
-    /// Run the MCP server loop over stdio
+    /// Run the MCP server loop over stdio, reserving stdout for protocol
+    /// frames until the client disconnects
     pub async fn run(&self) -> Result<(), McpError> {
-        let stdin = io::stdin();
-        let mut stdout = io::stdout();
-
+        let _stdout = StdoutGuard::reserve();
         info!("MCP server starting on stdio");
+        self.serve(BufReader::new(tokio::io::stdin()), io::stdout())
+            .await
+    }
 
-        for line in stdin.lock().lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    error!("Failed to read line: {}", e);
-                    continue;
-                }
+    /// Answer newline-delimited JSON-RPC messages from `input`, writing one
+    /// frame per line to `output` and nothing else
+    ///
+    /// Input is read while each request runs. Once the client closes it (the
+    /// editor quit), the request in flight and any already queued behind it
+    /// get [`DRAIN_TIMEOUT`] to finish before they are abandoned and the
+    /// server shuts down, see [`Self::shutdown`].
+    pub async fn serve(
+        &self,
+        input: impl AsyncBufRead + Unpin,
+        mut output: impl Write,
+    ) -> Result<(), McpError> {
+        let mut lines = input.lines();
+        // Messages that arrived while a request was being handled
+        let mut pending = VecDeque::new();
+        // Set once input closes; in-flight work is abandoned after it
+        let mut deadline = None;
+
+        loop {
+            let line = match pending.pop_front() {
+                Some(line) => line,
+                None => match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Failed to read line: {}", e);
+                        continue;
+                    }
+                },
             };
 
             if line.is_empty() {
@@ -58,8 +112,8 @@ impl McpServer {
                             "message": format!("Parse error: {}", e)
                         }
                     });
-                    writeln!(stdout, "{}", error_response)?;
-                    stdout.flush()?;
+                    writeln!(output, "{}", error_response)?;
+                    output.flush()?;
                     continue;
                 }
             };
@@ -67,11 +121,34 @@ impl McpServer {
             // Handle the message
             match message {
                 McpMessage::Request(req) => {
-                    let response = self.handle_request(req).await;
+                    let request = self.handle_request(req);
+                    tokio::pin!(request);
+                    let response = loop {
+                        tokio::select! {
+                            biased;
+                            response = &mut request => break Some(response),
+                            line = lines.next_line(), if deadline.is_none() => match line {
+                                Ok(Some(line)) => pending.push_back(line),
+                                Ok(None) => {
+                                    info!("Input closed; draining in-flight requests");
+                                    deadline = Some(Instant::now() + self.drain_timeout);
+                                }
+                                Err(e) => error!("Failed to read line: {}", e),
+                            },
+                            _ = sleep_until_deadline(deadline) => break None,
+                        }
+                    };
+                    let Some(response) = response else {
+                        warn!(
+                            "Abandoning in-flight request and {} queued messages",
+                            pending.len()
+                        );
+                        break;
+                    };
                     let response_str = serde_json::to_string(&response)?;
                     debug!("Sending: {}", response_str);
-                    writeln!(stdout, "{}", response_str)?;
-                    stdout.flush()?;
+                    writeln!(output, "{}", response_str)?;
+                    output.flush()?;
                 }
                 McpMessage::Notification(notif) => {
                     self.handle_notification(notif).await;
@@ -82,10 +159,30 @@ impl McpServer {
             }
         }
 
-        info!("MCP server shutting down");
+        self.shutdown().await;
         Ok(())
     }
 
+    /// Abort background ingests, updates and reindexes, giving them up to
+    /// [`SHUTDOWN_TIMEOUT`] to drop their connections and browsers, mark the
+    /// runs they left `running` as failed, then close the database. The Qdrant
+    /// channel closes when the server is dropped.
+    async fn shutdown(&self) {
+        info!("MCP server shutting down");
+        let aborted = self.jobs.shutdown(SHUTDOWN_TIMEOUT).await;
+        if aborted > 0 {
+            info!("Cancelled {} background tasks", aborted);
+        }
+        match self.db.fail_abandoned_runs(SHUTDOWN_ERROR).await {
+            Ok(runs) if !runs.is_empty() => {
+                info!("Marked {} cancelled runs as failed", runs.len())
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to record cancelled runs: {}", e),
+        }
+        self.db.close().await;
+    }
+
     /// Handle an MCP request
     async fn handle_request(&self, request: McpRequest) -> McpResponse {
         let id = request.id.clone();
@@ -95,6 +192,7 @@ impl McpServer {
             "tools/list" => self.handle_tools_list(id).await,
             "tools/call" => self.handle_tools_call(id, request.params).await,
             "resources/list" => self.handle_resources_list(id).await,
+            "resources/read" => self.handle_resources_read(id, request.params).await,
             "prompts/list" => self.handle_prompts_list(id).await,
             _ => McpResponse::error_with_code(
                 id,
@@ -120,29 +218,65 @@ impl McpServer {
     }
 
     /// Handle initialize request
-    fn handle_initialize(&self, id: Option<Value>, _params: Option<Value>) -> McpResponse {
-        McpResponse::success(
-            id,
-            json!({
-                "protocolVersion": "2024-11-05",
-                "capabilities": {
-                    "tools": {
-                        "listChanged": false
-                    },
-                    "resources": {
-                        "subscribe": false,
-                        "listChanged": false
-                    },
-                    "prompts": {
-                        "listChanged": false
-                    }
-                },
-                "serverInfo": {
-                    "name": "librarian",
-                    "version": env!("CARGO_PKG_VERSION")
-                }
-            }),
-        )
+    ///
+    /// The server speaks a single protocol revision, so it answers with that
+    /// one whatever the client asked for and leaves the client to disconnect
+    /// if it cannot use it.
+    fn handle_initialize(&self, id: Option<Value>, params: Option<Value>) -> McpResponse {
+        let params: InitializeParams = params
+            .and_then(|p| serde_json::from_value(p).ok())
+            .unwrap_or_default();
+        let client = params
+            .client_info
+            .map(|c| format!("{} {}", c.name, c.version))
+            .unwrap_or_else(|| "unknown client".to_string());
+        info!(
+            "Initializing for {} (protocol {})",
+            client, params.protocol_version
+        );
+        if params.protocol_version != PROTOCOL_VERSION {
+            warn!(
+                "Client requested protocol {:?}; answering with {}",
+                params.protocol_version, PROTOCOL_VERSION
+            );
+        }
+
+        let result = InitializeResult {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            capabilities: self.capabilities(),
+            server_info: ServerInfo {
+                name: "librarian".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+        };
+        match serde_json::to_value(&result) {
+            Ok(result) => McpResponse::success(id, result),
+            Err(e) => McpResponse::error(id, McpError::internal_error(e.to_string())),
+        }
+    }
+
+    /// Standard MCP capabilities, plus the registered tools and the optional
+    /// features this build and config enable under `experimental.librarian`
+    fn capabilities(&self) -> ServerCapabilities {
+        let tools: Vec<String> = get_tool_definitions()
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        let librarian = json!({
+            "tools": tools,
+            "features": {
+                "js_rendering": is_js_rendering_available(),
+                "pdf": is_pdf_available(),
+                "multimodal": self.config.crawl.multimodal.enabled,
+            },
+        });
+
+        ServerCapabilities {
+            tools: Some(ToolsCapability::default()),
+            resources: Some(ResourcesCapability::default()),
+            prompts: Some(PromptsCapability::default()),
+            experimental: Some(HashMap::from([("librarian".to_string(), librarian)])),
+        }
     }
 
     /// Handle tools/list request
@@ -170,7 +304,19 @@ impl McpServer {
 
         debug!("Calling tool: {} with args: {:?}", name, arguments);
 
-        let result = handle_tool_call(&name, &arguments, &self.config, &self.db, &self.store).await;
+        let result = match handle_tool_call(
+            &name,
+            &arguments,
+            &self.config,
+            &self.db,
+            &self.store,
+            &self.jobs,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => return McpResponse::error_with_code(id, e.code, e.message),
+        };
 
         McpResponse::success(
             id,
@@ -183,7 +329,37 @@ impl McpServer {
 
     /// Handle resources/list request
     async fn handle_resources_list(&self, id: Option<Value>) -> McpResponse {
-        McpResponse::success(id, json!({ "resources": [] }))
+        match list_resources(&self.db).await {
+            Ok(resources) => McpResponse::success(id, json!({ "resources": resources })),
+            Err(e) => {
+                McpResponse::error_with_code(id, -32603, format!("Failed to list resources: {}", e))
+            }
+        }
+    }
+
+    /// Handle resources/read request
+    async fn handle_resources_read(&self, id: Option<Value>, params: Option<Value>) -> McpResponse {
+        let uri = match params
+            .as_ref()
+            .and_then(|p| p.get("uri"))
+            .and_then(|v| v.as_str())
+        {
+            Some(uri) => uri.to_string(),
+            None => return McpResponse::error_with_code(id, -32602, "Missing resource uri"),
+        };
+
+        match read_resource(&self.db, &uri).await {
+            Ok(contents) => McpResponse::success(id, json!({ "contents": [contents] })),
+            Err(e @ Error::McpProtocol(_)) => {
+                McpResponse::error_with_code(id, -32602, e.to_string())
+            }
+            Err(e @ (Error::SourceNotFound(_) | Error::DocumentNotFound(_))) => {
+                McpResponse::error_with_code(id, -32002, format!("Resource not found: {}", e))
+            }
+            Err(e) => {
+                McpResponse::error_with_code(id, -32603, format!("Failed to read resource: {}", e))
+            }
+        }
     }
 
     /// Handle prompts/list request
@@ -191,3 +367,438 @@ impl McpServer {
         McpResponse::success(id, json!({ "prompts": [] }))
     }
 }
+
+/// Sleep until `deadline`, or forever when there is none
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use wiremock::matchers::any;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn test_server(tmp: &TempDir) -> McpServer {
+        let mut config = Config::default();
+        config.paths.db_file = tmp.path().join("test.db");
+        let db = MetaDb::new(&config.paths.db_file).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:6334", "test_collection", 3, None)
+            .await
+            .unwrap();
+        McpServer::new(config, db, store)
+    }
+
+    async fn call_tool(server: &McpServer, name: &str, arguments: Value) -> Value {
+        let response = server
+            .handle_request(McpRequest {
+                jsonrpc: "2.0".to_string(),
+                id: Some(json!(1)),
+                method: "tools/call".to_string(),
+                params: Some(json!({ "name": name, "arguments": arguments })),
+            })
+            .await;
+        assert!(response.error.is_none());
+        response.result.unwrap()
+    }
+
+    fn result_json(result: &Value) -> Value {
+        let text = result["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ingest_dir_tool_returns_pollable_job() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        let server = test_server(&tmp).await;
+
+        let result = call_tool(
+            &server,
+            "rag_ingest_dir",
+            json!({ "path": docs.display().to_string(), "extensions": "md" }),
+        )
+        .await;
+        assert!(result["isError"].is_null());
+        let job = result_json(&result);
+        let job_id = job["job_id"].as_str().unwrap().to_string();
+        assert_eq!(job["status"], "running");
+        assert_eq!(job["source_type"], "dir");
+        assert_eq!(job["uri"], docs.display().to_string());
+        assert!(job["started_at"].is_string());
+
+        let status = call_tool(&server, "rag_status", json!({ "job_id": job_id })).await;
+        let polled = result_json(&status);
+        assert_eq!(polled["job_id"], job_id.as_str());
+        assert!(["running", "completed", "failed"].contains(&polled["status"].as_str().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_output_holds_only_protocol_frames() {
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            "not json",
+            "",
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"rag_sources","arguments":{}}}"#,
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"rag_search","arguments":{"query":" "}}}"#,
+            r#"{"jsonrpc":"2.0","id":5,"method":"resources/list"}"#,
+            r#"{"jsonrpc":"2.0","id":6,"method":"no/such/method"}"#,
+        ]
+        .join("\n");
+
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).await.unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let frames: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{e}: {line}")))
+            .collect();
+        for frame in &frames {
+            assert_eq!(frame["jsonrpc"], "2.0", "{frame}");
+            assert!(
+                frame.get("result").is_some() != frame.get("error").is_some(),
+                "{frame}"
+            );
+        }
+        // One response per request, none for the notification or blank line
+        let ids: Vec<Option<i64>> = frames.iter().map(|f| f["id"].as_i64()).collect();
+        let expected = [Some(1), Some(2), None, Some(3), Some(4), Some(5), Some(6)];
+        assert_eq!(ids, expected);
+    }
+
+    #[tokio::test]
+    async fn test_input_closing_mid_request_shuts_server_down() {
+        let tmp = TempDir::new().unwrap();
+        // An embedding backend that never answers in time keeps the search in flight
+        let backend = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(60)))
+            .mount(&backend)
+            .await;
+        let mut server = test_server(&tmp).await;
+        server.config.embedding.url = backend.uri();
+        server.drain_timeout = Duration::from_millis(100);
+
+        let resource = Arc::new(());
+        let held = Arc::clone(&resource);
+        server.jobs.spawn(async move {
+            let _held = held;
+            std::future::pending::<()>().await
+        });
+
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"rag_search","arguments":{"query":"hello"}}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#,
+        ]
+        .join("\n");
+
+        let mut output = Vec::new();
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            server.serve(input.as_bytes(), &mut output),
+        )
+        .await
+        .expect("server should return once input closes")
+        .unwrap();
+
+        // The abandoned search and the request queued behind it get no reply
+        let output = String::from_utf8(output).unwrap();
+        let ids: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].clone())
+            .collect();
+        assert_eq!(ids, vec![json!(1)]);
+        assert_eq!(Arc::strong_count(&resource), 1);
+        assert!(server.db.is_closed());
+    }
+
+    async fn request(server: &McpServer, method: &str, params: Value) -> McpResponse {
+        server
+            .handle_request(McpRequest {
+                jsonrpc: "2.0".to_string(),
+                id: Some(json!(1)),
+                method: method.to_string(),
+                params: Some(params),
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_initialize_advertises_registered_tools_and_features() {
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+
+        let response = request(
+            &server,
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "test-client", "version": "1.0" }
+            }),
+        )
+        .await;
+        let result = response.result.unwrap();
+        assert_eq!(result["protocolVersion"], PROTOCOL_VERSION);
+        assert_eq!(result["serverInfo"]["name"], "librarian");
+        assert_eq!(result["serverInfo"]["version"], env!("CARGO_PKG_VERSION"));
+        let capabilities = &result["capabilities"];
+        assert_eq!(capabilities["tools"]["listChanged"], false);
+        assert_eq!(capabilities["resources"]["subscribe"], false);
+        assert!(capabilities["prompts"].is_object());
+
+        let listed = request(&server, "tools/list", json!({}))
+            .await
+            .result
+            .unwrap();
+        let registered: Vec<&str> = listed["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        let librarian = &capabilities["experimental"]["librarian"];
+        let advertised: Vec<&str> = librarian["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool.as_str().unwrap())
+            .collect();
+        assert_eq!(advertised, registered);
+        assert!(advertised.contains(&"rag_search"));
+
+        let features = &librarian["features"];
+        assert_eq!(features["js_rendering"], cfg!(feature = "js-rendering"));
+        assert_eq!(features["pdf"], cfg!(feature = "pdf"));
+        assert_eq!(features["multimodal"], false);
+
+        // Clients asking for another revision still get the one spoken here
+        let response = request(
+            &server,
+            "initialize",
+            json!({ "protocolVersion": "1999-01-01" }),
+        )
+        .await;
+        assert_eq!(
+            response.result.unwrap()["protocolVersion"],
+            PROTOCOL_VERSION
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sources_tool_lists_sources_with_stats_as_json() {
+        use crate::meta::{Document, Source, SourceType};
+
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+        let source = Source::new(
+            SourceType::Dir,
+            "/docs".to_string(),
+            Some("Docs".to_string()),
+        );
+        server.db.insert_source(&source).await.unwrap();
+        let doc = Document::new(
+            source.id.clone(),
+            "/docs/a.md".to_string(),
+            "hash".to_string(),
+        );
+        server.db.upsert_document(&doc).await.unwrap();
+
+        let result = call_tool(&server, "rag_sources", json!({ "format": "json" })).await;
+        let sources = result_json(&result);
+        assert_eq!(sources.as_array().unwrap().len(), 1);
+        assert_eq!(sources[0]["id"], source.id.as_str());
+        assert_eq!(sources[0]["name"], "Docs");
+        assert_eq!(sources[0]["stats"]["document_count"], 1);
+
+        let response = request(
+            &server,
+            "tools/call",
+            json!({ "name": "rag_sources", "arguments": { "format": "yaml" } }),
+        )
+        .await;
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_remove_source_tool_requires_confirmation() {
+        use crate::meta::{Document, Source, SourceType};
+
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+        let source = Source::new(
+            SourceType::Dir,
+            "/docs".to_string(),
+            Some("Docs".to_string()),
+        );
+        server.db.insert_source(&source).await.unwrap();
+        let doc = Document::new(
+            source.id.clone(),
+            "/docs/a.md".to_string(),
+            "hash".to_string(),
+        );
+        server.db.upsert_document(&doc).await.unwrap();
+
+        for arguments in [
+            json!({ "source": "Docs" }),
+            json!({ "source": "Docs", "confirm": false }),
+            json!({ "source": "Docs", "confirm": "true" }),
+        ] {
+            let response = request(
+                &server,
+                "tools/call",
+                json!({ "name": "rag_remove_source", "arguments": arguments }),
+            )
+            .await;
+            let error = response.error.unwrap();
+            assert_eq!(error.code, -32602);
+            assert!(error.message.contains("confirm"), "{}", error.message);
+        }
+        assert!(server.db.get_source(&source.id).await.unwrap().is_some());
+
+        let result = call_tool(
+            &server,
+            "rag_remove_source",
+            json!({ "source": "Docs", "confirm": true }),
+        )
+        .await;
+        assert!(result["isError"].is_null());
+        let removed = result_json(&result);
+        assert_eq!(removed["removed"]["id"], source.id.as_str());
+        assert_eq!(removed["prune"]["documents_removed"], 1);
+        assert!(server.db.get_source(&source.id).await.unwrap().is_none());
+
+        // Nothing is left to remove
+        let result = call_tool(
+            &server,
+            "rag_remove_source",
+            json!({ "source": "Docs", "confirm": true }),
+        )
+        .await;
+        assert_eq!(result["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_and_read_round_trip() {
+        use crate::meta::{Chunk, Document, Source, SourceType};
+
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+        let source = Source::new(
+            SourceType::Url,
+            "https://example.com/docs".to_string(),
+            Some("Example".to_string()),
+        );
+        server.db.insert_source(&source).await.unwrap();
+        let doc = Document::new(
+            source.id.clone(),
+            "https://example.com/docs/intro?lang=en".to_string(),
+            "hash".to_string(),
+        );
+        let doc = server.db.upsert_document(&doc).await.unwrap();
+        for (i, text) in ["First chunk.", "Second chunk."].iter().enumerate() {
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                i as i32,
+                format!("chunk-{}", i),
+                text.to_string(),
+                0,
+                text.len() as i32,
+                None,
+            );
+            server.db.upsert_chunk(&chunk).await.unwrap();
+        }
+
+        let list = request(&server, "resources/list", json!({})).await;
+        let resources = list.result.unwrap()["resources"]
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(resources.len(), 2);
+        assert_eq!(
+            resources[0]["uri"],
+            format!("librarian://source/{}", source.id)
+        );
+        assert_eq!(resources[0]["name"], "Example");
+        let doc_uri = resources[1]["uri"].as_str().unwrap().to_string();
+        assert!(doc_uri.starts_with(&format!("librarian://source/{}/doc/", source.id)));
+
+        let read = request(&server, "resources/read", json!({ "uri": doc_uri })).await;
+        let contents = &read.result.unwrap()["contents"][0];
+        assert_eq!(contents["uri"], doc_uri.as_str());
+        assert_eq!(contents["mimeType"], "text/plain");
+        assert_eq!(contents["text"], "First chunk.\n\nSecond chunk.");
+
+        let missing = format!("librarian://source/{}/doc/nope", source.id);
+        let read = request(&server, "resources/read", json!({ "uri": missing })).await;
+        assert_eq!(read.error.unwrap().code, -32002);
+    }
+
+    #[tokio::test]
+    async fn test_search_tool_rejects_out_of_range_min_score() {
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+
+        let response = request(
+            &server,
+            "tools/call",
+            json!({ "name": "rag_search", "arguments": { "query": "x", "min_score": 2.0 } }),
+        )
+        .await;
+        assert!(response.result.is_none());
+        assert_eq!(response.error.unwrap().code, -32602);
+    }
+
+    #[tokio::test]
+    async fn test_search_tool_reports_unreachable_backend_code() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+        config.paths.db_file = tmp.path().join("test.db");
+        config.embedding.url = "http://127.0.0.1:9".to_string();
+        let db = MetaDb::new(&config.paths.db_file).await.unwrap();
+        let store = QdrantStore::new("http://127.0.0.1:6334", "test_collection", 3, None)
+            .await
+            .unwrap();
+        let server = McpServer::new(config, db, store);
+
+        let response = request(
+            &server,
+            "tools/call",
+            json!({ "name": "rag_search", "arguments": { "query": "x" } }),
+        )
+        .await;
+        let error = response.error.unwrap();
+        assert_eq!(error.code, -32010);
+        assert!(error.message.contains("127.0.0.1:9"));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_dir_tool_rejects_missing_path() {
+        let tmp = TempDir::new().unwrap();
+        let server = test_server(&tmp).await;
+
+        let result = call_tool(
+            &server,
+            "rag_ingest_dir",
+            json!({ "path": tmp.path().join("missing").display().to_string() }),
+        )
+        .await;
+        assert_eq!(result["isError"], true);
+
+        let result = call_tool(&server, "rag_status", json!({ "job_id": "nope" })).await;
+        assert_eq!(result["isError"], true);
+    }
+}
@@ -1,14 +1,17 @@
 //! MCP tool definitions and handlers
 
-use super::types::ToolResult;
+use super::jobs::{IngestJob, JobRegistry};
+use super::types::{McpError, ToolResult};
 use crate::commands::{
     cmd_ingest_dir, cmd_ingest_sitemap, cmd_ingest_url, cmd_list_sources, cmd_query, cmd_reindex,
-    cmd_update, CrawlOverrides, QueryOptions, ReindexOptions, UpdateOptions,
+    cmd_remove_source, cmd_update, resolve_source, validate_query, CrawlOverrides, DirFilter,
+    DirIngestOptions, IngestStats, QueryOptions, ReindexOptions, UpdateOptions,
 };
 use crate::config::Config;
 use crate::embed::create_embedder;
 use crate::error::Error;
 use crate::meta::{MetaDb, RunOperation, SourceType};
+use crate::rank::RankedResult;
 use crate::store::QdrantStore;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -52,10 +55,29 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                     },
                     "min_score": {
                         "type": "number",
-                        "description": "Minimum similarity score (0-1, default: 0.5)",
-                        "default": 0.5,
+                        "description": "Minimum similarity score (0-1, default: query.min_score from the server config)",
                         "minimum": 0,
                         "maximum": 1
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Optional: Filter to sources carrying any of these tags"
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "Optional: Only match chunks under this heading path, starting from the top-level heading, e.g. 'Guide/Configuration' (case-insensitive)"
+                    },
+                    "dedupe": {
+                        "type": "boolean",
+                        "description": "Return at most one result per document (default: true)",
+                        "default": true
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["markdown", "json"],
+                        "description": "Response format: markdown blockquotes with source links, or JSON with a citation object per result (default: markdown)",
+                        "default": "markdown"
                     }
                 },
                 "required": ["query"]
@@ -63,18 +85,48 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         },
         ToolDefinition {
             name: "rag_sources".to_string(),
-            description: "List all registered documentation sources in the RAG index.".to_string(),
+            description: "List all registered documentation sources in the RAG index, with their document and chunk counts and latest run.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "format": {
+                        "type": "string",
+                        "enum": ["markdown", "json"],
+                        "description": "Response format: a markdown list, or a JSON array with each source's ID, tags, stats and latest run (default: markdown)",
+                        "default": "markdown"
+                    }
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "rag_remove_source".to_string(),
+            description: "Remove a source with all its documents, chunks and vectors. Destructive: requires confirm set to true.".to_string(),
             input_schema: json!({
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Source ID, name, or unique ID prefix"
+                    },
+                    "confirm": {
+                        "type": "boolean",
+                        "description": "Must be true; guards against accidental removal"
+                    }
+                },
+                "required": ["source", "confirm"]
             }),
         },
         ToolDefinition {
             name: "rag_status".to_string(),
-            description: "Get the current status of the RAG system including database stats and Qdrant connection.".to_string(),
+            description: "Get the current status of the RAG system including database stats and Qdrant connection. Pass job_id to poll an ingestion job.".to_string(),
             input_schema: json!({
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "job_id": {
+                        "type": "string",
+                        "description": "Optional: Job ID returned by an ingest tool"
+                    }
+                }
             }),
         },
         ToolDefinition {
@@ -112,6 +164,116 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                 "required": ["source_type", "uri"]
             }),
         },
+        ToolDefinition {
+            name: "rag_ingest_dir".to_string(),
+            description: "Ingest a local directory. Returns a job ID to poll with rag_status, or the ingestion stats when wait is true.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to ingest"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Optional friendly name for the source"
+                    },
+                    "extensions": {
+                        "type": "string",
+                        "description": "Comma-separated file extensions to include (e.g. md,txt,html)"
+                    },
+                    "exclude": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Glob patterns to exclude, relative to the directory"
+                    },
+                    "respect_gitignore": {
+                        "type": "boolean",
+                        "description": "Skip paths excluded by .gitignore and .ignore files",
+                        "default": true
+                    },
+                    "follow_symlinks": {
+                        "type": "boolean",
+                        "description": "Follow symlinked files and directories (each target is ingested once)",
+                        "default": false
+                    },
+                    "concurrency": {
+                        "type": "integer",
+                        "description": "Number of files to process in parallel",
+                        "default": 4,
+                        "minimum": 1
+                    },
+                    "wait": {
+                        "type": "boolean",
+                        "description": "Wait for ingestion to finish and return its stats",
+                        "default": false
+                    }
+                },
+                "required": ["path"]
+            }),
+        },
+        ToolDefinition {
+            name: "rag_ingest_url".to_string(),
+            description: "Crawl and ingest a URL. Returns a job ID to poll with rag_status, or the ingestion stats when wait is true.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "URL to start crawling from"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Optional friendly name for the source"
+                    },
+                    "max_pages": {
+                        "type": "integer",
+                        "description": "Maximum pages to crawl"
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": "Maximum crawl depth"
+                    },
+                    "path_prefix": {
+                        "type": "string",
+                        "description": "Limit crawling to this path prefix"
+                    },
+                    "wait": {
+                        "type": "boolean",
+                        "description": "Wait for ingestion to finish and return its stats",
+                        "default": false
+                    }
+                },
+                "required": ["url"]
+            }),
+        },
+        ToolDefinition {
+            name: "rag_ingest_sitemap".to_string(),
+            description: "Ingest the pages listed in a sitemap. Returns a job ID to poll with rag_status, or the ingestion stats when wait is true.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "Sitemap URL"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Optional friendly name for the source"
+                    },
+                    "max_pages": {
+                        "type": "integer",
+                        "description": "Maximum pages to fetch"
+                    },
+                    "wait": {
+                        "type": "boolean",
+                        "description": "Wait for ingestion to finish and return its stats",
+                        "default": false
+                    }
+                },
+                "required": ["url"]
+            }),
+        },
         ToolDefinition {
             name: "rag_update".to_string(),
             description: "Trigger an update run for registered sources asynchronously.".to_string(),
@@ -160,72 +322,142 @@ pub async fn handle_tool_call(
     config: &Config,
     db: &MetaDb,
     store: &QdrantStore,
-) -> ToolResult {
-    match name {
-        "rag_search" => handle_search(arguments, config, db, store).await,
-        "rag_sources" => handle_sources(db).await,
-        "rag_status" => handle_status(config, db, store).await,
-        "rag_ingest_source" => handle_ingest_trigger(arguments, config).await,
-        "rag_update" => handle_update_trigger(arguments, config).await,
-        "rag_reindex" => handle_reindex_trigger(arguments, config).await,
+    jobs: &JobRegistry,
+) -> Result<ToolResult, McpError> {
+    let result = match name {
+        "rag_search" => handle_search(arguments, config, db, store).await?,
+        "rag_sources" => handle_sources(arguments, db).await?,
+        "rag_remove_source" => handle_remove_source(arguments, db, store).await?,
+        "rag_status" => handle_status(arguments, config, db, store, jobs).await,
+        "rag_ingest_source" => handle_ingest_trigger(arguments, config, jobs).await,
+        "rag_ingest_dir" => handle_ingest_dir(arguments, config, jobs).await,
+        "rag_ingest_url" => handle_ingest_url(arguments, config, jobs).await,
+        "rag_ingest_sitemap" => handle_ingest_sitemap(arguments, config, jobs).await,
+        "rag_update" => handle_update_trigger(arguments, config, jobs).await,
+        "rag_reindex" => handle_reindex_trigger(arguments, config, jobs).await,
         _ => ToolResult::error(format!("Unknown tool: {}", name)),
-    }
+    };
+    Ok(result)
 }
 
-/// Handle rag_search tool
-async fn handle_search(
-    arguments: &HashMap<String, Value>,
-    config: &Config,
-    db: &MetaDb,
-    store: &QdrantStore,
-) -> ToolResult {
-    // Extract query parameter
+/// Validated rag_search arguments
+#[derive(Debug)]
+struct SearchRequest {
+    query: String,
+    options: QueryOptions,
+    format: ResponseFormat,
+}
+
+/// Parse and validate rag_search arguments into query options
+fn parse_search_arguments(arguments: &HashMap<String, Value>) -> Result<SearchRequest, McpError> {
     let query = match arguments.get("query") {
-        Some(Value::String(q)) => q.clone(),
-        _ => return ToolResult::error("Missing required parameter: query"),
+        Some(Value::String(q)) => match validate_query(q) {
+            Ok(q) => q.to_string(),
+            Err(e) => return Err(McpError::invalid_params(e.to_string())),
+        },
+        _ => {
+            return Err(McpError::invalid_params(
+                "Missing required parameter: query",
+            ))
+        }
     };
 
-    // Extract optional parameters
-    let limit = arguments
-        .get("limit")
-        .and_then(|v| v.as_u64())
-        .map(|v| v.min(20) as usize)
-        .unwrap_or(5);
+    let limit = match arguments.get("limit") {
+        None => 5,
+        Some(v) => match v.as_u64() {
+            Some(limit) if limit >= 1 => limit.min(20) as usize,
+            _ => return Err(McpError::invalid_params("limit must be a positive integer")),
+        },
+    };
 
-    let source_ids = arguments.get("source_ids").and_then(|v| {
-        v.as_array().map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect::<Vec<_>>()
-        })
-    });
+    // Left unset, the query falls back to query.min_score
+    let min_score = match arguments.get("min_score") {
+        None => None,
+        Some(v) => match v.as_f64() {
+            Some(score) if (0.0..=1.0).contains(&score) => Some(score as f32),
+            _ => {
+                return Err(McpError::invalid_params(
+                    "min_score must be a number between 0 and 1",
+                ))
+            }
+        },
+    };
 
-    let min_score = arguments
-        .get("min_score")
-        .and_then(|v| v.as_f64())
-        .map(|v| v as f32)
-        .unwrap_or(0.5);
+    let source_ids = parse_strict_string_array(arguments, "source_ids")?;
+    let tags = parse_strict_string_array(arguments, "tags")?;
 
-    // Build query options
-    let options = QueryOptions {
-        k: Some(limit),
-        source_ids,
-        min_score: Some(min_score),
-        dedupe_docs: true,
-        ..Default::default()
+    let section = match arguments.get("section") {
+        None => None,
+        Some(Value::String(section)) => Some(section.clone()),
+        Some(_) => return Err(McpError::invalid_params("section must be a string")),
+    };
+
+    let dedupe_docs = match arguments.get("dedupe") {
+        None => true,
+        Some(Value::Bool(dedupe)) => *dedupe,
+        Some(_) => return Err(McpError::invalid_params("dedupe must be a boolean")),
+    };
+
+    let format = parse_format(arguments)?;
+
+    Ok(SearchRequest {
+        query,
+        options: QueryOptions {
+            k: Some(limit),
+            min_score,
+            source_ids,
+            tags,
+            section,
+            dedupe_docs,
+            ..Default::default()
+        },
+        format,
+    })
+}
+
+/// Like `parse_string_array`, but rejects values that are not arrays of strings
+fn parse_strict_string_array(
+    arguments: &HashMap<String, Value>,
+    key: &str,
+) -> Result<Option<Vec<String>>, McpError> {
+    let Some(value) = arguments.get(key) else {
+        return Ok(None);
     };
+    value
+        .as_array()
+        .and_then(|arr| {
+            arr.iter()
+                .map(|item| item.as_str().map(ToString::to_string))
+                .collect::<Option<Vec<_>>>()
+        })
+        .map(Some)
+        .ok_or_else(|| McpError::invalid_params(format!("{} must be an array of strings", key)))
+}
+
+/// Handle rag_search tool
+async fn handle_search(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    db: &MetaDb,
+    store: &QdrantStore,
+) -> Result<ToolResult, McpError> {
+    let SearchRequest {
+        query,
+        options,
+        format,
+    } = parse_search_arguments(arguments)?;
 
-    let embedding_config = match config.resolve_embedding_config().await {
+    let embedding_config = match config.resolve_embedding_config_cached(false).await {
         Ok(cfg) => cfg,
-        Err(e) => return ToolResult::error(format!("Embedding config error: {}", e)),
+        Err(e) => return tool_failure("Embedding config error", e),
     };
     let embedder = match create_embedder(&embedding_config) {
         Ok(embedder) => embedder,
-        Err(e) => return ToolResult::error(format!("Embedding backend error: {}", e)),
+        Err(e) => return tool_failure("Embedding backend error", e),
     };
 
     // Execute query
-    match cmd_query(
+    let result = match cmd_query(
         config,
         &embedding_config,
         embedder.as_ref(),
@@ -236,44 +468,171 @@ async fn handle_search(
     )
     .await {
         Ok(result) => {
-            if result.results.is_empty() {
-                return ToolResult::text("No results found matching your query.");
+            if result.results.is_empty() && format == ResponseFormat::Markdown {
+                return Ok(ToolResult::text("No results found matching your query."));
             }
 
-            // Format results as markdown
-            let mut output = String::new();
-            output.push_str(&format!("Found {} results:\n\n", result.results.len()));
-
-            for (i, r) in result.results.iter().enumerate() {
-                output.push_str(&format!("## Result {} (score: {:.2})\n", i + 1, r.score));
-                output.push_str(&format!("**Source:** {}\n", r.doc_uri));
-                if let Some(title) = &r.title {
-                    output.push_str(&format!("**Title:** {}\n", title));
+            let mut source_names: HashMap<String, Option<String>> = HashMap::new();
+            for r in &result.results {
+                if !source_names.contains_key(&r.source_id) {
+                    let name = db
+                        .get_source(&r.source_id)
+                        .await
+                        .ok()
+                        .flatten()
+                        .and_then(|source| source.name);
+                    source_names.insert(r.source_id.clone(), name);
                 }
-                if let Some(headings) = &r.headings {
-                    if !headings.is_empty() {
-                        output.push_str(&format!("**Section:** {}\n", headings.join(" > ")));
+            }
+
+            format_search_results(&result.query, &result.results, &source_names, format)
+        }
+        Err(e) => return tool_failure("Search failed", e),
+    };
+    Ok(result)
+}
+
+/// Report a failed command as a protocol error when its error has a
+/// dedicated MCP code, and as a tool error otherwise
+fn tool_failure(context: &str, e: Error) -> Result<ToolResult, McpError> {
+    if e.is_backend_unavailable() {
+        Err(e.into())
+    } else {
+        Ok(ToolResult::error(format!("{}: {}", context, e)))
+    }
+}
+
+/// Output format for tools that answer in markdown or JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Markdown,
+    Json,
+}
+
+/// Parse the optional `format` argument, defaulting to markdown
+fn parse_format(arguments: &HashMap<String, Value>) -> Result<ResponseFormat, McpError> {
+    match arguments.get("format") {
+        None => Ok(ResponseFormat::Markdown),
+        Some(v) => match v.as_str() {
+            Some("markdown") => Ok(ResponseFormat::Markdown),
+            Some("json") => Ok(ResponseFormat::Json),
+            _ => Err(McpError::invalid_params(
+                "format must be 'markdown' or 'json'",
+            )),
+        },
+    }
+}
+
+/// Everything needed to cite a search hit
+#[derive(Debug, Clone, serde::Serialize)]
+struct Citation {
+    source_id: String,
+    source_name: Option<String>,
+    source_type: String,
+    source_uri: String,
+    doc_uri: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    title: Option<String>,
+    headings: Vec<String>,
+    chunk_index: i32,
+    score: f32,
+}
+
+impl Citation {
+    fn new(result: &RankedResult, source_name: Option<String>) -> Self {
+        Self {
+            source_id: result.source_id.clone(),
+            source_name,
+            source_type: result.source_type.clone(),
+            source_uri: result.source_uri.clone(),
+            doc_uri: result.doc_uri.clone(),
+            aliases: result.aliases.clone(),
+            title: result.title.clone(),
+            headings: result.headings.clone().unwrap_or_default(),
+            chunk_index: result.chunk_index,
+            score: result.score,
+        }
+    }
+
+    /// Human-readable label: source name, falling back to the source URI
+    fn source_label(&self) -> &str {
+        self.source_name.as_deref().unwrap_or(&self.source_uri)
+    }
+}
+
+fn format_search_results(
+    query: &str,
+    results: &[RankedResult],
+    source_names: &HashMap<String, Option<String>>,
+    format: ResponseFormat,
+) -> ToolResult {
+    let hits: Vec<(&RankedResult, Citation)> = results
+        .iter()
+        .map(|r| {
+            let name = source_names.get(&r.source_id).cloned().flatten();
+            (r, Citation::new(r, name))
+        })
+        .collect();
+
+    match format {
+        ResponseFormat::Json => ToolResult::json(&json!({
+            "query": query,
+            "results": hits
+                .iter()
+                .map(|(r, citation)| json!({ "text": r.chunk_text, "citation": citation }))
+                .collect::<Vec<_>>(),
+        })),
+        ResponseFormat::Markdown => {
+            let mut output = format!("Found {} results:\n\n", hits.len());
+
+            for (i, (r, citation)) in hits.iter().enumerate() {
+                let title = citation.title.as_deref().unwrap_or(&citation.doc_uri);
+                output.push_str(&format!("### [{}] {}\n\n", i + 1, title));
+                for line in r.chunk_text.lines() {
+                    if line.is_empty() {
+                        output.push_str(">\n");
+                    } else {
+                        output.push_str(&format!("> {}\n", line));
                     }
                 }
-                output.push_str("\n```\n");
-                output.push_str(&r.chunk_text);
-                output.push_str("\n```\n\n");
+                output.push_str(&format!(
+                    "\nSource: [{}]({}) · {}",
+                    title,
+                    citation.doc_uri,
+                    citation.source_label()
+                ));
+                if !citation.headings.is_empty() {
+                    output.push_str(&format!(" · Section: {}", citation.headings.join(" > ")));
+                }
+                output.push_str(&format!(
+                    " · chunk {} · score {:.2}\n",
+                    citation.chunk_index, citation.score
+                ));
+                if !citation.aliases.is_empty() {
+                    output.push_str(&format!("Also at: {}\n", citation.aliases.join(", ")));
+                }
+                output.push('\n');
             }
 
             ToolResult::text(output)
         }
-        Err(e) => ToolResult::error(format!("Search failed: {}", e)),
     }
 }
 
 /// Handle rag_sources tool
-async fn handle_sources(db: &MetaDb) -> ToolResult {
-    match cmd_list_sources(db).await {
+async fn handle_sources(
+    arguments: &HashMap<String, Value>,
+    db: &MetaDb,
+) -> Result<ToolResult, McpError> {
+    let format = parse_format(arguments)?;
+    let result = match cmd_list_sources(db).await {
+        Ok(sources) if format == ResponseFormat::Json => ToolResult::json(&sources),
         Ok(sources) => {
             if sources.is_empty() {
-                return ToolResult::text(
+                return Ok(ToolResult::text(
                     "No sources registered. Use 'librarian ingest' to add documentation sources.",
-                );
+                ));
             }
 
             let mut output = String::new();
@@ -306,11 +665,64 @@ async fn handle_sources(db: &MetaDb) -> ToolResult {
             ToolResult::text(output)
         }
         Err(e) => ToolResult::error(format!("Failed to list sources: {}", e)),
+    };
+    Ok(result)
+}
+
+/// Handle rag_remove_source tool
+async fn handle_remove_source(
+    arguments: &HashMap<String, Value>,
+    db: &MetaDb,
+    store: &QdrantStore,
+) -> Result<ToolResult, McpError> {
+    let source = match arguments.get("source") {
+        Some(Value::String(source)) if !source.trim().is_empty() => source.trim(),
+        _ => {
+            return Err(McpError::invalid_params(
+                "Missing required parameter: source",
+            ))
+        }
+    };
+    if arguments.get("confirm") != Some(&Value::Bool(true)) {
+        return Err(McpError::invalid_params(
+            "Removing a source deletes all of its data; pass confirm: true to proceed",
+        ));
     }
+
+    let source = match resolve_source(db, source).await {
+        Ok(source) => source,
+        Err(e) => return Ok(ToolResult::error(e.to_string())),
+    };
+    let result = match cmd_remove_source(db, store, &source.id).await {
+        Ok(stats) => ToolResult::json(&json!({
+            "removed": {
+                "id": source.id,
+                "name": source.name,
+                "source_type": source.source_type,
+                "uri": source.uri,
+            },
+            "prune": stats,
+        })),
+        Err(e) => return tool_failure("Failed to remove source", e),
+    };
+    Ok(result)
 }
 
 /// Handle rag_status tool
-async fn handle_status(config: &Config, db: &MetaDb, store: &QdrantStore) -> ToolResult {
+async fn handle_status(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    db: &MetaDb,
+    store: &QdrantStore,
+    jobs: &JobRegistry,
+) -> ToolResult {
+    if let Some(job_id) = arguments.get("job_id").and_then(|v| v.as_str()) {
+        return match jobs.get(job_id) {
+            Some(job) => ToolResult::json(&job),
+            None => ToolResult::error(format!("Unknown job_id: {}", job_id)),
+        };
+    }
+
     let db_stats = match db.get_global_stats().await {
         Ok(s) => s,
         Err(e) => return ToolResult::error(format!("Failed to get DB stats: {}", e)),
@@ -376,15 +788,21 @@ async fn handle_status(config: &Config, db: &MetaDb, store: &QdrantStore) -> Too
     ToolResult::text(output)
 }
 
-async fn handle_ingest_trigger(arguments: &HashMap<String, Value>, config: &Config) -> ToolResult {
+async fn handle_ingest_trigger(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+) -> ToolResult {
     let source_type_str = match arguments.get("source_type").and_then(|v| v.as_str()) {
         Some(s) => s,
         None => return ToolResult::error("Missing required parameter: source_type"),
     };
 
     let source_type = match SourceType::from_str(source_type_str) {
+        Ok(SourceType::Urls | SourceType::Openapi) | Err(_) => {
+            return ToolResult::error("Invalid source_type. Use dir, url, or sitemap.")
+        }
         Ok(t) => t,
-        Err(_) => return ToolResult::error("Invalid source_type. Use dir, url, or sitemap."),
     };
 
     let uri = match arguments.get("uri").and_then(|v| v.as_str()) {
@@ -409,30 +827,180 @@ async fn handle_ingest_trigger(arguments: &HashMap<String, Value>, config: &Conf
         .and_then(|v| v.as_str())
         .map(ToString::to_string);
 
-    let config_clone = config.clone();
-    tokio::spawn(async move {
-        if let Err(e) = run_ingest_background(
-            config_clone,
-            source_type,
-            uri,
-            name,
+    let request = IngestRequest {
+        source_type,
+        uri,
+        name,
+        overrides: CrawlOverrides {
             max_pages,
             max_depth,
             path_prefix,
-        )
-        .await
-        {
+            ..Default::default()
+        },
+        dir_options: DirIngestOptions::default(),
+    };
+    let job = start_ingest_job(config, jobs, request);
+
+    ToolResult::text(format!(
+        "Started {} ingestion (job {}). Check rag_status with this job_id for progress.",
+        source_type, job.job_id
+    ))
+}
+
+async fn handle_ingest_dir(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+) -> ToolResult {
+    let path = match arguments.get("path").and_then(|v| v.as_str()) {
+        Some(p) => PathBuf::from(p),
+        None => return ToolResult::error("Missing required parameter: path"),
+    };
+    if !path.is_dir() {
+        return ToolResult::error(format!("Not a directory: {}", path.display()));
+    }
+
+    let extensions = arguments
+        .get("extensions")
+        .and_then(|v| v.as_str())
+        .map(ToString::to_string);
+    let follow_symlinks = arguments
+        .get("follow_symlinks")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let filter = DirFilter::from_options(
+        extensions,
+        parse_string_array(arguments.get("exclude")),
+        follow_symlinks,
+        arguments.get("respect_gitignore").and_then(|v| v.as_bool()),
+    );
+    if let Some(filter) = &filter {
+        if let Err(e) = filter.exclude_set() {
+            return ToolResult::error(e.to_string());
+        }
+    }
+    let concurrency = arguments
+        .get("concurrency")
+        .and_then(|v| v.as_u64())
+        .map(|v| v.max(1) as usize)
+        .unwrap_or_else(|| DirIngestOptions::default().concurrency);
+
+    let request = IngestRequest {
+        source_type: SourceType::Dir,
+        uri: path.display().to_string(),
+        name: parse_name(arguments),
+        overrides: CrawlOverrides::default(),
+        dir_options: DirIngestOptions {
+            filter,
+            concurrency,
+        },
+    };
+    run_ingest_tool(arguments, config, jobs, request).await
+}
+
+async fn handle_ingest_url(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+) -> ToolResult {
+    let url = match arguments.get("url").and_then(|v| v.as_str()) {
+        Some(u) => u.to_string(),
+        None => return ToolResult::error("Missing required parameter: url"),
+    };
+
+    let request = IngestRequest {
+        source_type: SourceType::Url,
+        uri: url,
+        name: parse_name(arguments),
+        overrides: CrawlOverrides {
+            max_pages: parse_u32(arguments.get("max_pages")),
+            max_depth: parse_u32(arguments.get("max_depth")),
+            path_prefix: arguments
+                .get("path_prefix")
+                .and_then(|v| v.as_str())
+                .map(ToString::to_string),
+            ..Default::default()
+        },
+        dir_options: DirIngestOptions::default(),
+    };
+    run_ingest_tool(arguments, config, jobs, request).await
+}
+
+async fn handle_ingest_sitemap(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+) -> ToolResult {
+    let url = match arguments.get("url").and_then(|v| v.as_str()) {
+        Some(u) => u.to_string(),
+        None => return ToolResult::error("Missing required parameter: url"),
+    };
+
+    let request = IngestRequest {
+        source_type: SourceType::Sitemap,
+        uri: url,
+        name: parse_name(arguments),
+        overrides: CrawlOverrides {
+            max_pages: parse_u32(arguments.get("max_pages")),
+            ..Default::default()
+        },
+        dir_options: DirIngestOptions::default(),
+    };
+    run_ingest_tool(arguments, config, jobs, request).await
+}
+
+/// Run an ingest tool, either inline (`wait: true`) or as a background job
+async fn run_ingest_tool(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+    request: IngestRequest,
+) -> ToolResult {
+    let wait = arguments
+        .get("wait")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if !wait {
+        return ToolResult::json(&start_ingest_job(config, jobs, request));
+    }
+
+    let job = jobs.start(request.source_type, &request.uri);
+    let result = run_ingest_background(config.clone(), request).await;
+    let failed = result.is_err();
+    let job = jobs.finish(&job.job_id, result).unwrap_or(job);
+
+    let mut tool_result = ToolResult::json(&job);
+    if failed {
+        tool_result.is_error = Some(true);
+    }
+    tool_result
+}
+
+/// Register a job and run the ingestion in the background
+fn start_ingest_job(config: &Config, jobs: &JobRegistry, request: IngestRequest) -> IngestJob {
+    let job = jobs.start(request.source_type, &request.uri);
+
+    let config_clone = config.clone();
+    let jobs = jobs.clone();
+    let job_id = job.job_id.clone();
+    let registry = jobs.clone();
+    registry.spawn(async move {
+        let result = run_ingest_background(config_clone, request).await;
+        if let Err(e) = &result {
             error!(error=?e, "Background ingestion failed");
         }
+        jobs.finish(&job_id, result);
     });
 
-    ToolResult::text(format!(
-        "Started {} ingestion. Check rag_status for progress.",
-        source_type
-    ))
+    job
 }
 
-async fn handle_update_trigger(arguments: &HashMap<String, Value>, config: &Config) -> ToolResult {
+async fn handle_update_trigger(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+) -> ToolResult {
     let source_ids = parse_string_array(arguments.get("source_ids"));
     let prune_orphans = !arguments
         .get("skip_prune")
@@ -440,7 +1008,7 @@ async fn handle_update_trigger(arguments: &HashMap<String, Value>, config: &Conf
         .unwrap_or(false);
 
     let config_clone = config.clone();
-    tokio::spawn(async move {
+    jobs.spawn(async move {
         if let Err(e) = run_update_background(config_clone, source_ids, prune_orphans).await {
             error!(error=?e, "Background update failed");
         }
@@ -449,7 +1017,11 @@ async fn handle_update_trigger(arguments: &HashMap<String, Value>, config: &Conf
     ToolResult::text("Update started. Check rag_status for progress.".to_string())
 }
 
-async fn handle_reindex_trigger(arguments: &HashMap<String, Value>, config: &Config) -> ToolResult {
+async fn handle_reindex_trigger(
+    arguments: &HashMap<String, Value>,
+    config: &Config,
+    jobs: &JobRegistry,
+) -> ToolResult {
     let source_ids = parse_string_array(arguments.get("source_ids"));
     let batch_size = arguments
         .get("batch_size")
@@ -458,7 +1030,7 @@ async fn handle_reindex_trigger(arguments: &HashMap<String, Value>, config: &Con
         .unwrap_or(32);
 
     let config_clone = config.clone();
-    tokio::spawn(async move {
+    jobs.spawn(async move {
         if let Err(e) = run_reindex_background(config_clone, source_ids, batch_size).await {
             error!(error=?e, "Background reindex failed");
         }
@@ -467,6 +1039,17 @@ async fn handle_reindex_trigger(arguments: &HashMap<String, Value>, config: &Con
     ToolResult::text("Reindex started. Check rag_status for progress.".to_string())
 }
 
+fn parse_name(arguments: &HashMap<String, Value>) -> Option<String> {
+    arguments
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(ToString::to_string)
+}
+
+fn parse_u32(value: Option<&Value>) -> Option<u32> {
+    value.and_then(|v| v.as_u64()).map(|v| v as u32)
+}
+
 fn parse_string_array(value: Option<&Value>) -> Option<Vec<String>> {
     value.and_then(|v| {
         v.as_array().map(|arr| {
@@ -479,22 +1062,31 @@ fn parse_string_array(value: Option<&Value>) -> Option<Vec<String>> {
 
 type AppResult<T> = std::result::Result<T, Error>;
 
-async fn run_ingest_background(
-    config: Config,
+/// Parameters for an ingestion started over MCP
+struct IngestRequest {
     source_type: SourceType,
     uri: String,
     name: Option<String>,
-    max_pages: Option<u32>,
-    max_depth: Option<u32>,
-    path_prefix: Option<String>,
-) -> AppResult<()> {
+    overrides: CrawlOverrides,
+    dir_options: DirIngestOptions,
+}
+
+async fn run_ingest_background(config: Config, request: IngestRequest) -> AppResult<IngestStats> {
+    let IngestRequest {
+        source_type,
+        uri,
+        name,
+        overrides,
+        dir_options,
+    } = request;
+
     let db = MetaDb::connect(&config).await?;
     db.init_schema().await?;
-    let embedding_config = config.resolve_embedding_config().await?;
+    let embedding_config = config.resolve_embedding_config_cached(false).await?;
     let embedder = create_embedder(&embedding_config)?;
     let store = QdrantStore::connect(&config, &embedding_config).await?;
 
-    match source_type {
+    let stats = match source_type {
         SourceType::Dir => {
             let path = PathBuf::from(&uri);
             cmd_ingest_dir(
@@ -505,17 +1097,13 @@ async fn run_ingest_background(
                 &store,
                 &path,
                 name,
+                dir_options,
                 RunOperation::Ingest,
                 false,
             )
-            .await?;
+            .await?
         }
         SourceType::Url => {
-            let overrides = CrawlOverrides {
-                max_pages,
-                max_depth,
-                path_prefix,
-            };
             cmd_ingest_url(
                 &config,
                 &embedding_config,
@@ -527,8 +1115,9 @@ async fn run_ingest_background(
                 overrides,
                 RunOperation::Ingest,
                 false,
+                None,
             )
-            .await?;
+            .await?
         }
         SourceType::Sitemap => {
             cmd_ingest_sitemap(
@@ -539,15 +1128,27 @@ async fn run_ingest_background(
                 &store,
                 &uri,
                 name,
-                max_pages,
+                overrides.max_pages,
+                false,
                 RunOperation::Ingest,
                 false,
+                None,
             )
-            .await?;
+            .await?
         }
-    }
+        SourceType::Urls => {
+            return Err(Error::Config(
+                "URL lists are ingested with 'librarian ingest urls'".to_string(),
+            ))
+        }
+        SourceType::Openapi => {
+            return Err(Error::Config(
+                "OpenAPI specs are ingested with 'librarian ingest openapi'".to_string(),
+            ))
+        }
+    };
 
-    Ok(())
+    Ok(stats)
 }
 
 async fn run_update_background(
@@ -557,7 +1158,7 @@ async fn run_update_background(
 ) -> AppResult<()> {
     let db = MetaDb::connect(&config).await?;
     db.init_schema().await?;
-    let embedding_config = config.resolve_embedding_config().await?;
+    let embedding_config = config.resolve_embedding_config_cached(false).await?;
     let embedder = create_embedder(&embedding_config)?;
     let store = QdrantStore::connect(&config, &embedding_config).await?;
 
@@ -577,7 +1178,7 @@ async fn run_reindex_background(
 ) -> AppResult<()> {
     let db = MetaDb::connect(&config).await?;
     db.init_schema().await?;
-    let embedding_config = config.resolve_embedding_config().await?;
+    let embedding_config = config.resolve_embedding_config_cached(false).await?;
     let embedder = create_embedder(&embedding_config)?;
     let store = QdrantStore::connect(&config, &embedding_config).await?;
 
@@ -597,3 +1198,168 @@ async fn run_reindex_background(
     .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked_result(
+        source_id: &str,
+        chunk_index: i32,
+        headings: Option<Vec<String>>,
+    ) -> RankedResult {
+        RankedResult {
+            id: format!("point-{}", chunk_index),
+            score: 0.87,
+            vector_score: Some(0.87),
+            bm25_score: None,
+            fused_score: None,
+            boost_score: None,
+            rerank_score: None,
+            doc_uri: "https://example.com/docs/intro".to_string(),
+            title: Some("Introduction".to_string()),
+            chunk_text: "First line.\n\nSecond line.".to_string(),
+            headings,
+            chunk_index,
+            source_id: source_id.to_string(),
+            source_type: "url".to_string(),
+            source_uri: "https://example.com/docs".to_string(),
+            modality: None,
+            media_url: None,
+            media_hash: None,
+            snippet: None,
+            context: None,
+            aliases: Vec::new(),
+            model_id: None,
+            model_mismatch: false,
+        }
+    }
+
+    #[test]
+    fn test_tool_failure_keeps_backend_error_codes() {
+        let unreachable = Error::BackendUnreachable {
+            url: "http://127.0.0.1:9".to_string(),
+            reason: "Connection refused".to_string(),
+        };
+        let err = tool_failure("Search failed", unreachable).unwrap_err();
+        assert_eq!(err.code, -32010);
+
+        let result = tool_failure("Search failed", Error::Other("boom".to_string())).unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result_text(&result), "Search failed: boom");
+    }
+
+    fn result_text(result: &ToolResult) -> String {
+        match &result.content[0] {
+            super::super::types::ToolContent::Text { text } => text.clone(),
+        }
+    }
+
+    fn arguments(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_search_arguments_map_onto_query_options() {
+        let request = parse_search_arguments(&arguments(json!({
+            "query": "install",
+            "limit": 50,
+            "min_score": 0.25,
+            "source_ids": ["src-1", "src-2"],
+            "tags": ["rust"],
+            "dedupe": false,
+            "format": "json"
+        })))
+        .unwrap();
+
+        assert_eq!(request.query, "install");
+        assert_eq!(request.options.k, Some(20));
+        assert_eq!(request.options.min_score, Some(0.25));
+        assert_eq!(
+            request.options.source_ids,
+            Some(vec!["src-1".to_string(), "src-2".to_string()])
+        );
+        assert_eq!(request.options.tags, Some(vec!["rust".to_string()]));
+        assert!(!request.options.dedupe_docs);
+        assert_eq!(request.format, ResponseFormat::Json);
+
+        let defaults = parse_search_arguments(&arguments(json!({ "query": " install " }))).unwrap();
+        assert_eq!(defaults.query, "install");
+        assert_eq!(defaults.options.k, Some(5));
+        assert_eq!(defaults.options.min_score, None);
+        assert!(defaults.options.source_ids.is_none());
+        assert!(defaults.options.dedupe_docs);
+        assert_eq!(defaults.format, ResponseFormat::Markdown);
+    }
+
+    #[test]
+    fn test_search_arguments_reject_invalid_input() {
+        for invalid in [
+            json!({}),
+            json!({ "query": "" }),
+            json!({ "query": "   " }),
+            json!({ "query": 42 }),
+            json!({ "query": "x", "min_score": 1.5 }),
+            json!({ "query": "x", "min_score": -0.1 }),
+            json!({ "query": "x", "min_score": "high" }),
+            json!({ "query": "x", "limit": 0 }),
+            json!({ "query": "x", "source_ids": "src-1" }),
+            json!({ "query": "x", "source_ids": [1, 2] }),
+            json!({ "query": "x", "dedupe": "yes" }),
+            json!({ "query": "x", "format": "html" }),
+        ] {
+            let err = parse_search_arguments(&arguments(invalid.clone())).unwrap_err();
+            assert_eq!(err.code, -32602, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_json_search_results_include_citations() {
+        let results = vec![
+            ranked_result(
+                "src-1",
+                2,
+                Some(vec!["Guide".to_string(), "Setup".to_string()]),
+            ),
+            ranked_result("src-2", 0, None),
+        ];
+        let names = HashMap::from([
+            ("src-1".to_string(), Some("Example Docs".to_string())),
+            ("src-2".to_string(), None),
+        ]);
+
+        let output = format_search_results("setup", &results, &names, ResponseFormat::Json);
+        let json: Value = serde_json::from_str(&result_text(&output)).unwrap();
+        let hits = json["results"].as_array().unwrap();
+        assert_eq!(hits.len(), 2);
+        for hit in hits {
+            let citation = &hit["citation"];
+            assert!(citation.is_object());
+            assert_eq!(citation["doc_uri"], "https://example.com/docs/intro");
+            assert_eq!(citation["title"], "Introduction");
+            assert!(citation["score"].as_f64().unwrap() > 0.8);
+            assert!(citation["headings"].is_array());
+            assert!(hit["text"].as_str().unwrap().contains("Second line."));
+        }
+        assert_eq!(hits[0]["citation"]["source_name"], "Example Docs");
+        assert_eq!(hits[0]["citation"]["chunk_index"], 2);
+        assert_eq!(hits[0]["citation"]["headings"], json!(["Guide", "Setup"]));
+        assert!(hits[1]["citation"]["source_name"].is_null());
+    }
+
+    #[test]
+    fn test_markdown_search_results_quote_and_link_sources() {
+        let results = vec![ranked_result("src-1", 1, Some(vec!["Guide".to_string()]))];
+        let names = HashMap::from([("src-1".to_string(), Some("Example Docs".to_string()))]);
+
+        let output = result_text(&format_search_results(
+            "setup",
+            &results,
+            &names,
+            ResponseFormat::Markdown,
+        ));
+        assert!(output.contains("> First line.\n>\n> Second line.\n"));
+        assert!(output.contains("[Introduction](https://example.com/docs/intro) · Example Docs"));
+        assert!(output.contains("Section: Guide · chunk 1 · score 0.87"));
+    }
+}
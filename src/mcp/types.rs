@@ -17,6 +17,11 @@ pub enum ErrorCode {
     MethodNotFound = -32601,
     InvalidParams = -32602,
     InternalError = -32603,
+    // Server-defined codes for an embedding backend that cannot serve the model
+    BackendUnreachable = -32010,
+    ModelNotAdvertised = -32011,
+    DimensionMismatch = -32012,
+    ProbeFailed = -32013,
 }
 
 /// MCP error response
@@ -56,6 +61,20 @@ impl From<serde_json::Error> for McpError {
     }
 }
 
+impl From<crate::error::Error> for McpError {
+    fn from(e: crate::error::Error) -> Self {
+        use crate::error::Error;
+        let code = match e {
+            Error::BackendUnreachable { .. } => ErrorCode::BackendUnreachable,
+            Error::ModelNotAdvertised { .. } => ErrorCode::ModelNotAdvertised,
+            Error::DimensionMismatch { .. } => ErrorCode::DimensionMismatch,
+            Error::ProbeFailed { .. } => ErrorCode::ProbeFailed,
+            _ => ErrorCode::InternalError,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
 impl McpError {
     pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
@@ -204,11 +223,32 @@ pub struct ServerInfo {
 pub struct ServerCapabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<PromptsCapability>,
+    /// Non-standard capabilities, keyed by vendor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<HashMap<String, Value>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ToolsCapability {
+    #[serde(rename = "listChanged", default)]
+    pub list_changed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourcesCapability {
     #[serde(default)]
+    pub subscribe: bool,
+    #[serde(rename = "listChanged", default)]
+    pub list_changed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptsCapability {
+    #[serde(rename = "listChanged", default)]
     pub list_changed: bool,
 }
 
@@ -251,6 +291,14 @@ impl ToolResult {
             is_error: Some(true),
         }
     }
+
+    /// Structured result, serialized as pretty-printed JSON text
+    pub fn json(value: &impl Serialize) -> Self {
+        match serde_json::to_string_pretty(value) {
+            Ok(text) => Self::text(text),
+            Err(e) => Self::error(format!("Failed to serialize result: {}", e)),
+        }
+    }
 }
 
 /// Tool content types
@@ -267,14 +315,16 @@ impl ToolContent {
     }
 }
 
-/// Initialize request params
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Initialize request params; missing fields are tolerated so older clients
+/// still get an answer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InitializeParams {
-    #[serde(rename = "protocolVersion")]
+    #[serde(rename = "protocolVersion", default)]
     pub protocol_version: String,
+    #[serde(default)]
     pub capabilities: ClientCapabilities,
-    #[serde(rename = "clientInfo")]
-    pub client_info: ClientInfo,
+    #[serde(rename = "clientInfo", default)]
+    pub client_info: Option<ClientInfo>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -10,14 +10,21 @@ mod schema;
 
 pub use schema::*;
 
+use crate::chunk::{heading_texts, parse_heading_trail, section_paths, SectionHeading};
 use crate::config::Config;
 use crate::error::{Error, Result};
+use crate::parse::ApiEndpoint;
+use crate::time_range::TimeRange;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
 use sqlx::FromRow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::path::Path;
 use std::str::FromStr;
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 /// Source types
@@ -27,6 +34,10 @@ pub enum SourceType {
     Dir,
     Url,
     Sitemap,
+    /// A fixed list of pages, fetched without following links
+    Urls,
+    /// An OpenAPI / Swagger spec, one document per operation
+    Openapi,
 }
 
 impl std::fmt::Display for SourceType {
@@ -35,6 +46,8 @@ impl std::fmt::Display for SourceType {
             SourceType::Dir => write!(f, "dir"),
             SourceType::Url => write!(f, "url"),
             SourceType::Sitemap => write!(f, "sitemap"),
+            SourceType::Urls => write!(f, "urls"),
+            SourceType::Openapi => write!(f, "openapi"),
         }
     }
 }
@@ -47,6 +60,8 @@ impl FromStr for SourceType {
             "dir" => Ok(SourceType::Dir),
             "url" => Ok(SourceType::Url),
             "sitemap" => Ok(SourceType::Sitemap),
+            "urls" => Ok(SourceType::Urls),
+            "openapi" => Ok(SourceType::Openapi),
             _ => Err(Error::Config(format!("Unknown source type: {}", s))),
         }
     }
@@ -67,6 +82,21 @@ pub enum RunOperation {
     Ingest,
     Update,
     Reindex,
+    Reembed,
+}
+
+impl RunStatus {
+    /// Final status of a run over `handled` documents, `failed` of which
+    /// errored. A run where some documents made it through is a partial
+    /// success: completed, with its errors recorded alongside. It only fails
+    /// when every document it handled errored.
+    pub fn finished(handled: usize, failed: usize) -> Self {
+        if failed > 0 && handled <= failed {
+            RunStatus::Failed
+        } else {
+            RunStatus::Completed
+        }
+    }
 }
 
 impl std::fmt::Display for RunStatus {
@@ -85,6 +115,7 @@ impl std::fmt::Display for RunOperation {
             RunOperation::Ingest => write!(f, "ingest"),
             RunOperation::Update => write!(f, "update"),
             RunOperation::Reindex => write!(f, "reindex"),
+            RunOperation::Reembed => write!(f, "reembed"),
         }
     }
 }
@@ -110,6 +141,7 @@ impl FromStr for RunOperation {
             "ingest" => Ok(RunOperation::Ingest),
             "update" => Ok(RunOperation::Update),
             "reindex" => Ok(RunOperation::Reindex),
+            "reembed" => Ok(RunOperation::Reembed),
             _ => Err(Error::Config(format!("Unknown run operation: {}", s))),
         }
     }
@@ -125,6 +157,8 @@ pub struct Source {
     pub created_at: String,
     pub updated_at: String,
     pub config_json: Option<String>,
+    /// JSON array of tags used to group sources
+    pub tags: Option<String>,
 }
 
 impl Source {
@@ -138,12 +172,34 @@ impl Source {
             created_at: now.clone(),
             updated_at: now,
             config_json: None,
+            tags: None,
         }
     }
 
     pub fn get_type(&self) -> Result<SourceType> {
         self.source_type.parse()
     }
+
+    pub fn tags(&self) -> Vec<String> {
+        self.tags
+            .as_ref()
+            .and_then(|j| serde_json::from_str(j).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Normalize tags: trim, lowercase, drop empties, dedupe and sort
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let set: std::collections::BTreeSet<String> = tags
+        .into_iter()
+        .map(|t| t.as_ref().trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    set.into_iter().collect()
 }
 
 /// A document (file or web page)
@@ -157,6 +213,8 @@ pub struct Document {
     pub content_type: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// The API operation an openapi document describes, as JSON
+    pub api_endpoint_json: Option<String>,
 }
 
 impl Document {
@@ -171,8 +229,14 @@ impl Document {
             content_type: None,
             created_at: now.clone(),
             updated_at: now,
+            api_endpoint_json: None,
         }
     }
+
+    /// The API operation this document describes, if it came from an OpenAPI spec
+    pub fn api_endpoint(&self) -> Option<ApiEndpoint> {
+        serde_json::from_str(self.api_endpoint_json.as_deref()?).ok()
+    }
 }
 
 /// A text chunk
@@ -202,11 +266,13 @@ impl Chunk {
         chunk_text: String,
         char_start: i32,
         char_end: i32,
-        headings: Option<Vec<String>>,
+        headings: Option<Vec<SectionHeading>>,
     ) -> Self {
         let now = Utc::now().to_rfc3339();
-        // Use chunk_hash to derive stable Qdrant point ID
-        let point_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, chunk_hash.as_bytes()).to_string();
+        // Stable Qdrant point ID; the chunk hash covers only the text, so mix in
+        // the document and position to keep identical text in two places apart
+        let point_key = format!("{}:{}:{}", doc_id, chunk_index, chunk_hash);
+        let point_id = Uuid::new_v5(&Uuid::NAMESPACE_OID, point_key.as_bytes()).to_string();
 
         Self {
             id: Uuid::new_v4().to_string(),
@@ -257,10 +323,11 @@ impl Chunk {
         }
     }
 
-    pub fn headings(&self) -> Vec<String> {
+    /// Heading trail above the chunk, outermost first
+    pub fn headings(&self) -> Vec<SectionHeading> {
         self.headings_json
-            .as_ref()
-            .and_then(|j| serde_json::from_str(j).ok())
+            .as_deref()
+            .map(parse_heading_trail)
             .unwrap_or_default()
     }
 }
@@ -279,6 +346,14 @@ pub struct IngestionRun {
     pub chunks_updated: i32,
     pub chunks_deleted: i32,
     pub errors_json: Option<String>,
+    /// Last time a live process reported progress on this run
+    #[sqlx(default)]
+    #[serde(default)]
+    pub heartbeat_at: Option<String>,
+    /// The crawl bypassed robots.txt (`--no-robots`)
+    #[sqlx(default)]
+    #[serde(default)]
+    pub robots_ignored: bool,
 }
 
 impl IngestionRun {
@@ -295,10 +370,145 @@ impl IngestionRun {
             chunks_updated: 0,
             chunks_deleted: 0,
             errors_json: None,
+            heartbeat_at: None,
+            robots_ignored: false,
+        }
+    }
+
+    /// Errors recorded for this run
+    pub fn errors(&self) -> Vec<String> {
+        self.errors_json
+            .as_deref()
+            .and_then(|e| serde_json::from_str(e).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// How often a live ingestion run records a heartbeat
+pub const RUN_HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How long a running run may go without a heartbeat before it is treated as interrupted
+pub const STALE_RUN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Background heartbeat for an ingestion run; stops when dropped
+pub struct RunHeartbeat {
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for RunHeartbeat {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Runs this process started and has not yet completed, across every database
+/// handle, so a shutting-down server can fail the ones its aborted tasks left
+fn live_runs() -> std::sync::MutexGuard<'static, HashSet<String>> {
+    static LIVE_RUNS: std::sync::OnceLock<std::sync::Mutex<HashSet<String>>> =
+        std::sync::OnceLock::new();
+    LIVE_RUNS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// How long a connection waits on a locked database before SQLite reports it busy
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Attempts for a write that keeps hitting a locked database
+const LOCK_RETRY_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry of a locked write; doubles on each attempt
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Most parameters bound to one statement; older SQLite builds cap this at 999
+const MAX_BIND_PARAMS: usize = 900;
+
+/// SQLite primary result codes for a database held by another connection
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+fn connect_options(db_path: &Path) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(db_path)
+        .create_if_missing(true)
+        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT)
+}
+
+/// Whether SQLite rejected a statement because another connection holds the lock
+fn is_lock_error(err: &sqlx::Error) -> bool {
+    let sqlx::Error::Database(db_err) = err else {
+        return false;
+    };
+    db_err
+        .code()
+        .and_then(|code| code.parse::<i32>().ok())
+        // Extended result codes keep the primary code in the low byte
+        .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+}
+
+/// Run a write, retrying with backoff while another process holds the database lock.
+///
+/// The busy timeout covers most contention; this catches the cases SQLite reports
+/// immediately, such as a WAL snapshot going stale under a concurrent writer.
+async fn retry_locked<T, F, Fut>(mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, sqlx::Error>>,
+{
+    let mut delay = LOCK_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if is_lock_error(&e) => {
+                if attempt == LOCK_RETRY_ATTEMPTS {
+                    return Err(Error::DatabaseLocked(format!(
+                        "still locked after {} attempts ({}); another librarian process \
+                         such as `watch` or a parallel ingest is writing to it",
+                        attempt, e
+                    )));
+                }
+                debug!(
+                    "Metadata database locked (attempt {}/{}), retrying in {:?}",
+                    attempt, LOCK_RETRY_ATTEMPTS, delay
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return Ok(result?),
         }
     }
 }
 
+/// Escape `%`, `_` and `\` for a `LIKE ... ESCAPE '\'` pattern
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Normalized path of a chunk's full heading trail, stored so keyword search
+/// can filter on sections in SQL
+fn chunk_section_path(headings_json: Option<&str>) -> Option<String> {
+    let trail = heading_texts(&parse_heading_trail(headings_json?));
+    section_paths(&trail).pop()
+}
+
+/// FTS5 query matching any of `terms` as a word prefix, or `None` when no
+/// term contains a character the index tokenizes
+fn fts_match_query(terms: &[String]) -> Option<String> {
+    let phrases: Vec<String> = terms
+        .iter()
+        .filter(|term| term.chars().any(char::is_alphanumeric))
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    (!phrases.is_empty()).then(|| phrases.join(" OR "))
+}
+
 /// Metadata database handle
 #[derive(Clone)]
 pub struct MetaDb {
@@ -315,11 +525,7 @@ impl MetaDb {
             std::fs::create_dir_all(parent)?;
         }
 
-        let options = SqliteConnectOptions::new()
-            .filename(db_path)
-            .create_if_missing(true)
-            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal);
+        let options = connect_options(db_path);
 
         debug!("Connecting to SQLite database at {:?}", db_path);
 
@@ -331,12 +537,24 @@ impl MetaDb {
         Ok(Self { pool })
     }
 
+    /// Close every pooled connection, checkpointing the WAL; later queries fail
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.pool.is_closed()
+    }
+
     /// Initialize the database schema
     pub async fn init_schema(&self) -> Result<()> {
         info!("Initializing database schema");
         sqlx::query(SCHEMA_SQL).execute(&self.pool).await?;
+        self.migrate_schema().await
+    }
 
-        // Backfill optional columns for existing installations
+    /// Backfill optional columns for existing installations
+    async fn migrate_schema(&self) -> Result<()> {
         // Add ingestion_runs.operation if missing
         let has_operation: Option<(i32,)> = sqlx::query_as(
             "SELECT 1 FROM pragma_table_info('ingestion_runs') WHERE name='operation'",
@@ -352,6 +570,32 @@ impl MetaDb {
             .await?;
         }
 
+        let has_heartbeat: Option<(i32,)> = sqlx::query_as(
+            "SELECT 1 FROM pragma_table_info('ingestion_runs') WHERE name='heartbeat_at'",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if has_heartbeat.is_none() {
+            sqlx::query("ALTER TABLE ingestion_runs ADD COLUMN heartbeat_at TEXT")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        let has_robots_ignored: Option<(i32,)> = sqlx::query_as(
+            "SELECT 1 FROM pragma_table_info('ingestion_runs') WHERE name='robots_ignored'",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if has_robots_ignored.is_none() {
+            sqlx::query(
+                "ALTER TABLE ingestion_runs ADD COLUMN robots_ignored INTEGER NOT NULL DEFAULT 0",
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
         let has_modality: Option<(i32,)> = sqlx::query_as(
             "SELECT 1 FROM pragma_table_info('chunks') WHERE name='modality'",
         )
@@ -387,6 +631,83 @@ impl MetaDb {
                 .execute(&self.pool)
                 .await?;
         }
+
+        let has_tags: Option<(i32,)> = sqlx::query_as(
+            "SELECT 1 FROM pragma_table_info('sources') WHERE name='tags'",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if has_tags.is_none() {
+            sqlx::query("ALTER TABLE sources ADD COLUMN tags TEXT")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        let has_api_endpoint: Option<(i32,)> = sqlx::query_as(
+            "SELECT 1 FROM pragma_table_info('documents') WHERE name='api_endpoint_json'",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if has_api_endpoint.is_none() {
+            sqlx::query("ALTER TABLE documents ADD COLUMN api_endpoint_json TEXT")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        let has_section_path: Option<(i32,)> =
+            sqlx::query_as("SELECT 1 FROM pragma_table_info('chunks') WHERE name='section_path'")
+                .fetch_optional(&self.pool)
+                .await?;
+
+        if has_section_path.is_none() {
+            sqlx::query("ALTER TABLE chunks ADD COLUMN section_path TEXT")
+                .execute(&self.pool)
+                .await?;
+            self.backfill_section_paths().await?;
+        }
+
+        let has_fts: Option<(i32,)> =
+            sqlx::query_as("SELECT 1 FROM sqlite_master WHERE type='table' AND name='chunks_fts'")
+                .fetch_optional(&self.pool)
+                .await?;
+
+        if has_fts.is_none() {
+            sqlx::query(FTS_SCHEMA_SQL).execute(&self.pool).await?;
+            // Index the chunks written before the table existed
+            self.rebuild_fts_index().await?;
+        }
+        Ok(())
+    }
+
+    /// Fill in `chunks.section_path` for chunks written before the column existed
+    async fn backfill_section_paths(&self) -> Result<()> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, headings_json FROM chunks WHERE headings_json IS NOT NULL")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut tx = self.pool.begin().await?;
+        for (id, headings_json) in rows {
+            let Some(section_path) = chunk_section_path(Some(&headings_json)) else {
+                continue;
+            };
+            sqlx::query("UPDATE chunks SET section_path = ? WHERE id = ?")
+                .bind(section_path)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Re-index every chunk's text in `chunks_fts` from the `chunks` table
+    async fn rebuild_fts_index(&self) -> Result<()> {
+        sqlx::query("INSERT INTO chunks_fts(chunks_fts) VALUES ('rebuild')")
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
 
@@ -403,20 +724,23 @@ impl MetaDb {
 
     /// Insert a new source
     pub async fn insert_source(&self, source: &Source) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO sources (id, source_type, uri, name, created_at, updated_at, config_json)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(&source.id)
-        .bind(&source.source_type)
-        .bind(&source.uri)
-        .bind(&source.name)
-        .bind(&source.created_at)
-        .bind(&source.updated_at)
-        .bind(&source.config_json)
-        .execute(&self.pool)
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO sources (id, source_type, uri, name, created_at, updated_at, config_json, tags)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&source.id)
+            .bind(&source.source_type)
+            .bind(&source.uri)
+            .bind(&source.name)
+            .bind(&source.created_at)
+            .bind(&source.updated_at)
+            .bind(&source.config_json)
+            .bind(&source.tags)
+            .execute(&self.pool)
+        })
         .await?;
         Ok(())
     }
@@ -459,49 +783,135 @@ impl MetaDb {
     /// Delete a source and all its documents/chunks
     pub async fn delete_source(&self, id: &str) -> Result<()> {
         // Delete chunks first (cascade)
-        sqlx::query(
-            "DELETE FROM chunks WHERE doc_id IN (SELECT id FROM documents WHERE source_id = ?)",
-        )
-        .bind(id)
-        .execute(&self.pool)
-        .await?;
-
-        // Delete documents
-        sqlx::query("DELETE FROM documents WHERE source_id = ?")
+        retry_locked(|| {
+            sqlx::query(
+                "DELETE FROM chunks WHERE doc_id IN (SELECT id FROM documents WHERE source_id = ?)",
+            )
             .bind(id)
             .execute(&self.pool)
-            .await?;
+        })
+        .await?;
+
+        // Delete aliases and documents
+        retry_locked(|| {
+            sqlx::query("DELETE FROM document_aliases WHERE source_id = ?")
+                .bind(id)
+                .execute(&self.pool)
+        })
+        .await?;
+
+        retry_locked(|| {
+            sqlx::query("DELETE FROM documents WHERE source_id = ?")
+                .bind(id)
+                .execute(&self.pool)
+        })
+        .await?;
 
         // Delete ingestion runs
-        sqlx::query("DELETE FROM ingestion_runs WHERE source_id = ?")
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
+        retry_locked(|| {
+            sqlx::query("DELETE FROM ingestion_runs WHERE source_id = ?")
+                .bind(id)
+                .execute(&self.pool)
+        })
+        .await?;
 
         // Delete source
-        sqlx::query("DELETE FROM sources WHERE id = ?")
-            .bind(id)
-            .execute(&self.pool)
-            .await?;
+        retry_locked(|| {
+            sqlx::query("DELETE FROM sources WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+        })
+        .await?;
 
         Ok(())
     }
 
     /// Update source name
     pub async fn update_source_name(&self, id: &str, new_name: Option<String>) -> Result<()> {
-        sqlx::query(
-            r#"
-            UPDATE sources SET name = ?, updated_at = ? WHERE id = ?
-            "#,
-        )
-        .bind(new_name)
-        .bind(Utc::now().to_rfc3339())
-        .bind(id)
-        .execute(&self.pool)
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                UPDATE sources SET name = ?, updated_at = ? WHERE id = ?
+                "#,
+            )
+            .bind(&new_name)
+            .bind(Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Update source ingest configuration (JSON)
+    pub async fn update_source_config(&self, id: &str, config_json: Option<String>) -> Result<()> {
+        retry_locked(|| {
+            sqlx::query("UPDATE sources SET config_json = ?, updated_at = ? WHERE id = ?")
+                .bind(&config_json)
+                .bind(Utc::now().to_rfc3339())
+                .bind(id)
+                .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the tag set of a source
+    pub async fn update_source_tags(&self, id: &str, tags: &[String]) -> Result<()> {
+        let tags = normalize_tags(tags);
+        let tags_json = if tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&tags)?)
+        };
+        retry_locked(|| {
+            sqlx::query("UPDATE sources SET tags = ?, updated_at = ? WHERE id = ?")
+                .bind(&tags_json)
+                .bind(Utc::now().to_rfc3339())
+                .bind(id)
+                .execute(&self.pool)
+        })
         .await?;
         Ok(())
     }
 
+    /// Add tags to a source, returning the resulting tag set
+    pub async fn add_source_tags(&self, id: &str, tags: &[String]) -> Result<Vec<String>> {
+        let source = self
+            .get_source(id)
+            .await?
+            .ok_or_else(|| Error::SourceNotFound(id.to_string()))?;
+        let merged = normalize_tags(source.tags().iter().chain(tags.iter()));
+        self.update_source_tags(id, &merged).await?;
+        Ok(merged)
+    }
+
+    /// Remove tags from a source, returning the remaining tag set
+    pub async fn remove_source_tags(&self, id: &str, tags: &[String]) -> Result<Vec<String>> {
+        let source = self
+            .get_source(id)
+            .await?
+            .ok_or_else(|| Error::SourceNotFound(id.to_string()))?;
+        let removed = normalize_tags(tags);
+        let remaining: Vec<String> = source
+            .tags()
+            .into_iter()
+            .filter(|t| !removed.contains(t))
+            .collect();
+        self.update_source_tags(id, &remaining).await?;
+        Ok(remaining)
+    }
+
+    /// List sources carrying any of the given tags
+    pub async fn list_sources_by_tags(&self, tags: &[String]) -> Result<Vec<Source>> {
+        let wanted = normalize_tags(tags);
+        let sources = self.list_sources().await?;
+        Ok(sources
+            .into_iter()
+            .filter(|s| s.tags().iter().any(|t| wanted.contains(t)))
+            .collect())
+    }
+
     // ===== Document Operations =====
 
     /// Insert or update a document, returning the stored document with canonical ID.
@@ -510,26 +920,30 @@ impl MetaDb {
     /// the original document ID. Callers MUST use the returned document's ID
     /// for any subsequent operations (e.g., chunk writes) to avoid FK violations.
     pub async fn upsert_document(&self, doc: &Document) -> Result<Document> {
-        sqlx::query(
-            r#"
-            INSERT INTO documents (id, source_id, uri, title, content_hash, content_type, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            ON CONFLICT(source_id, uri) DO UPDATE SET
-                title = excluded.title,
-                content_hash = excluded.content_hash,
-                content_type = excluded.content_type,
-                updated_at = excluded.updated_at
-            "#,
-        )
-        .bind(&doc.id)
-        .bind(&doc.source_id)
-        .bind(&doc.uri)
-        .bind(&doc.title)
-        .bind(&doc.content_hash)
-        .bind(&doc.content_type)
-        .bind(&doc.created_at)
-        .bind(&doc.updated_at)
-        .execute(&self.pool)
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO documents (id, source_id, uri, title, content_hash, content_type, created_at, updated_at, api_endpoint_json)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(source_id, uri) DO UPDATE SET
+                    title = excluded.title,
+                    content_hash = excluded.content_hash,
+                    content_type = excluded.content_type,
+                    updated_at = excluded.updated_at,
+                    api_endpoint_json = excluded.api_endpoint_json
+                "#,
+            )
+            .bind(&doc.id)
+            .bind(&doc.source_id)
+            .bind(&doc.uri)
+            .bind(&doc.title)
+            .bind(&doc.content_hash)
+            .bind(&doc.content_type)
+            .bind(&doc.created_at)
+            .bind(&doc.updated_at)
+            .bind(&doc.api_endpoint_json)
+            .execute(&self.pool)
+        })
         .await?;
 
         let stored = self
@@ -584,88 +998,304 @@ impl MetaDb {
     }
 
     /// Delete documents not in the given URI list
+    ///
+    /// A stale document with an alias URI in `current_uris` is moved to that
+    /// alias instead of being deleted. Aliases not in `current_uris` are dropped.
     pub async fn delete_stale_documents(
         &self,
         source_id: &str,
         current_uris: &[String],
-    ) -> Result<Vec<String>> {
-        // Get stale doc IDs first
-        let placeholders = current_uris
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(",");
-        let query = if current_uris.is_empty() {
-            "SELECT id FROM documents WHERE source_id = ?".to_string()
-        } else {
-            format!(
-                "SELECT id FROM documents WHERE source_id = ? AND uri NOT IN ({})",
-                placeholders
+    ) -> Result<StaleDocuments> {
+        // Bind the URI list as one JSON array so large crawls stay under the variable limit
+        let current = serde_json::to_string(current_uris)?;
+        const STALE: &str = "SELECT id FROM documents WHERE source_id = ?1 \
+             AND uri NOT IN (SELECT value FROM json_each(?2))";
+
+        // Promote the first current alias of each stale document
+        let promoted_ids: Vec<String> = retry_locked(|| {
+            sqlx::query_scalar::<_, String>(
+                r#"
+                UPDATE documents SET
+                    uri = (SELECT MIN(a.uri) FROM document_aliases a
+                           WHERE a.doc_id = documents.id
+                             AND a.uri IN (SELECT value FROM json_each(?2))),
+                    updated_at = ?3
+                WHERE source_id = ?1
+                  AND uri NOT IN (SELECT value FROM json_each(?2))
+                  AND EXISTS (SELECT 1 FROM document_aliases a
+                              WHERE a.doc_id = documents.id
+                                AND a.uri IN (SELECT value FROM json_each(?2)))
+                RETURNING id
+                "#,
             )
-        };
+            .bind(source_id)
+            .bind(&current)
+            .bind(Utc::now().to_rfc3339())
+            .fetch_all(&self.pool)
+        })
+        .await?;
+        let mut promoted = Vec::with_capacity(promoted_ids.len());
+        if !promoted_ids.is_empty() {
+            retry_locked(|| {
+                sqlx::query(
+                    "DELETE FROM document_aliases WHERE source_id = ?1 \
+                     AND uri IN (SELECT uri FROM documents WHERE source_id = ?1)",
+                )
+                .bind(source_id)
+                .execute(&self.pool)
+            })
+            .await?;
 
-        let mut query_builder = sqlx::query_scalar::<_, String>(&query).bind(source_id);
-        for uri in current_uris {
-            query_builder = query_builder.bind(uri);
+            let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+                "SELECT d.id, d.uri, c.qdrant_point_id FROM documents d \
+                 LEFT JOIN chunks c ON c.doc_id = d.id \
+                 WHERE d.id IN (SELECT value FROM json_each(?)) ORDER BY d.id",
+            )
+            .bind(serde_json::to_string(&promoted_ids)?)
+            .fetch_all(&self.pool)
+            .await?;
+            for (doc_id, uri, point_id) in rows {
+                if promoted
+                    .last()
+                    .is_none_or(|p: &PromotedDocument| p.doc_id != doc_id)
+                {
+                    promoted.push(PromotedDocument {
+                        doc_id,
+                        uri,
+                        point_ids: Vec::new(),
+                    });
+                }
+                if let (Some(point_id), Some(doc)) = (point_id, promoted.last_mut()) {
+                    doc.point_ids.push(point_id);
+                }
+            }
         }
-        let stale_ids: Vec<String> = query_builder.fetch_all(&self.pool).await?;
 
-        // Delete chunks for stale docs
-        for id in &stale_ids {
-            sqlx::query("DELETE FROM chunks WHERE doc_id = ?")
-                .bind(id)
-                .execute(&self.pool)
-                .await?;
-            sqlx::query("DELETE FROM documents WHERE id = ?")
-                .bind(id)
-                .execute(&self.pool)
+        let deleted: Vec<String> = sqlx::query_scalar(STALE)
+            .bind(source_id)
+            .bind(&current)
+            .fetch_all(&self.pool)
+            .await?;
+        let mut deleted_point_ids = Vec::new();
+        if !deleted.is_empty() {
+            deleted_point_ids = sqlx::query_scalar(&format!(
+                "SELECT qdrant_point_id FROM chunks WHERE doc_id IN ({})",
+                STALE
+            ))
+            .bind(source_id)
+            .bind(&current)
+            .fetch_all(&self.pool)
+            .await?;
+
+            for delete in [
+                format!("DELETE FROM document_aliases WHERE doc_id IN ({})", STALE),
+                format!("DELETE FROM chunks WHERE doc_id IN ({})", STALE),
+                format!("DELETE FROM documents WHERE id IN ({})", STALE),
+            ] {
+                retry_locked(|| {
+                    sqlx::query(&delete)
+                        .bind(source_id)
+                        .bind(&current)
+                        .execute(&self.pool)
+                })
                 .await?;
+            }
         }
 
-        Ok(stale_ids)
+        // Drop aliases whose URIs were not seen this run
+        retry_locked(|| {
+            sqlx::query(
+                "DELETE FROM document_aliases WHERE source_id = ? \
+                 AND uri NOT IN (SELECT value FROM json_each(?))",
+            )
+            .bind(source_id)
+            .bind(&current)
+            .execute(&self.pool)
+        })
+        .await?;
+
+        Ok(StaleDocuments {
+            deleted,
+            deleted_point_ids,
+            promoted,
+        })
     }
 
-    // ===== Chunk Operations =====
+    /// Record redirect aliases in one statement, mapping each requested URL to
+    /// the document stored under the URL it redirected to
+    ///
+    /// A requested URL that still has a document of its own is left for stale
+    /// cleanup; the alias follows on the next ingest. Returns the requested
+    /// URLs that were recorded.
+    pub async fn record_redirect_aliases(
+        &self,
+        source_id: &str,
+        redirects: &[(String, String)],
+    ) -> Result<Vec<String>> {
+        if redirects.is_empty() {
+            return Ok(Vec::new());
+        }
+        let redirects = serde_json::to_string(redirects)?;
+        let recorded = retry_locked(|| {
+            sqlx::query_scalar::<_, String>(
+                r#"
+                INSERT INTO document_aliases (source_id, uri, doc_id, created_at)
+                SELECT ?1, r.value ->> 0, d.id, ?3
+                FROM json_each(?2) r
+                JOIN documents d ON d.source_id = ?1 AND d.uri = r.value ->> 1
+                WHERE NOT EXISTS (SELECT 1 FROM documents e
+                                  WHERE e.source_id = ?1 AND e.uri = r.value ->> 0)
+                ON CONFLICT(source_id, uri) DO UPDATE SET doc_id = excluded.doc_id
+                RETURNING uri
+                "#,
+            )
+            .bind(source_id)
+            .bind(&redirects)
+            .bind(Utc::now().to_rfc3339())
+            .fetch_all(&self.pool)
+        })
+        .await?;
+        Ok(recorded)
+    }
 
-    /// Insert or update a chunk
-    pub async fn upsert_chunk(&self, chunk: &Chunk) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO chunks (id, doc_id, chunk_index, chunk_hash, chunk_text, char_start, char_end, headings_json, qdrant_point_id, modality, media_url, media_hash, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            ON CONFLICT(doc_id, chunk_index) DO UPDATE SET
-                chunk_hash = excluded.chunk_hash,
-                chunk_text = excluded.chunk_text,
-                char_start = excluded.char_start,
-                char_end = excluded.char_end,
-                headings_json = excluded.headings_json,
-                qdrant_point_id = excluded.qdrant_point_id,
-                modality = excluded.modality,
-                media_url = excluded.media_url,
-                media_hash = excluded.media_hash,
-                updated_at = excluded.updated_at
-            "#,
+    /// Find another document in the source with identical content
+    pub async fn find_document_by_hash(
+        &self,
+        source_id: &str,
+        content_hash: &str,
+        exclude_uri: &str,
+    ) -> Result<Option<Document>> {
+        let doc = sqlx::query_as::<_, Document>(
+            "SELECT * FROM documents WHERE source_id = ? AND content_hash = ? AND uri != ? \
+             ORDER BY created_at LIMIT 1",
         )
-        .bind(&chunk.id)
-        .bind(&chunk.doc_id)
-        .bind(chunk.chunk_index)
-        .bind(&chunk.chunk_hash)
-        .bind(&chunk.chunk_text)
-        .bind(chunk.char_start)
-        .bind(chunk.char_end)
-        .bind(&chunk.headings_json)
-        .bind(&chunk.qdrant_point_id)
-        .bind(&chunk.modality)
-        .bind(&chunk.media_url)
-        .bind(&chunk.media_hash)
-        .bind(&chunk.created_at)
-        .bind(&chunk.updated_at)
-        .execute(&self.pool)
+        .bind(source_id)
+        .bind(content_hash)
+        .bind(exclude_uri)
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(())
+        Ok(doc)
     }
 
-    /// Get chunks for a document
+    /// Record `uri` as an alias of an existing document
+    pub async fn upsert_document_alias(
+        &self,
+        source_id: &str,
+        uri: &str,
+        doc_id: &str,
+    ) -> Result<()> {
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO document_aliases (source_id, uri, doc_id, created_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(source_id, uri) DO UPDATE SET doc_id = excluded.doc_id
+                "#,
+            )
+            .bind(source_id)
+            .bind(uri)
+            .bind(doc_id)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Remove `uri` as an alias, e.g. once it serves its own content
+    pub async fn delete_document_alias(&self, source_id: &str, uri: &str) -> Result<()> {
+        retry_locked(|| {
+            sqlx::query("DELETE FROM document_aliases WHERE source_id = ? AND uri = ?")
+                .bind(source_id)
+                .bind(uri)
+                .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Alias URIs of a document, in URI order
+    pub async fn get_document_aliases(&self, doc_id: &str) -> Result<Vec<String>> {
+        let uris = sqlx::query_scalar::<_, String>(
+            "SELECT uri FROM document_aliases WHERE doc_id = ? ORDER BY uri",
+        )
+        .bind(doc_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(uris)
+    }
+
+    /// Alias URIs of several documents, keyed by document ID in URI order.
+    ///
+    /// Documents without aliases are left out of the map.
+    pub async fn get_aliases_by_doc_ids(
+        &self,
+        doc_ids: &[String],
+    ) -> Result<HashMap<String, Vec<String>>> {
+        let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+        for batch in doc_ids.chunks(MAX_BIND_PARAMS) {
+            let placeholders = vec!["?"; batch.len()].join(",");
+            let sql = format!(
+                "SELECT doc_id, uri FROM document_aliases WHERE doc_id IN ({}) ORDER BY doc_id, uri",
+                placeholders
+            );
+            let mut query = sqlx::query_as::<_, (String, String)>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            for (doc_id, uri) in query.fetch_all(&self.pool).await? {
+                aliases.entry(doc_id).or_default().push(uri);
+            }
+        }
+        Ok(aliases)
+    }
+
+    // ===== Chunk Operations =====
+
+    /// Insert or update a chunk
+    pub async fn upsert_chunk(&self, chunk: &Chunk) -> Result<()> {
+        let section_path = chunk_section_path(chunk.headings_json.as_deref());
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO chunks (id, doc_id, chunk_index, chunk_hash, chunk_text, char_start, char_end, headings_json, section_path, qdrant_point_id, modality, media_url, media_hash, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(doc_id, chunk_index) DO UPDATE SET
+                    chunk_hash = excluded.chunk_hash,
+                    chunk_text = excluded.chunk_text,
+                    char_start = excluded.char_start,
+                    char_end = excluded.char_end,
+                    headings_json = excluded.headings_json,
+                    section_path = excluded.section_path,
+                    qdrant_point_id = excluded.qdrant_point_id,
+                    modality = excluded.modality,
+                    media_url = excluded.media_url,
+                    media_hash = excluded.media_hash,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&chunk.id)
+            .bind(&chunk.doc_id)
+            .bind(chunk.chunk_index)
+            .bind(&chunk.chunk_hash)
+            .bind(&chunk.chunk_text)
+            .bind(chunk.char_start)
+            .bind(chunk.char_end)
+            .bind(&chunk.headings_json)
+            .bind(&section_path)
+            .bind(&chunk.qdrant_point_id)
+            .bind(&chunk.modality)
+            .bind(&chunk.media_url)
+            .bind(&chunk.media_hash)
+            .bind(&chunk.created_at)
+            .bind(&chunk.updated_at)
+            .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Get chunks for a document
     pub async fn get_chunks(&self, doc_id: &str) -> Result<Vec<Chunk>> {
         let chunks = sqlx::query_as::<_, Chunk>(
             "SELECT * FROM chunks WHERE doc_id = ? ORDER BY chunk_index",
@@ -688,6 +1318,35 @@ impl MetaDb {
         Ok(chunks)
     }
 
+    /// Get the text chunks in each `(doc_id, from_index, to_index)` window in
+    /// one query, keyed by document ID in chunk order.
+    ///
+    /// Overlapping windows of the same document share their chunks.
+    pub async fn get_chunk_windows(
+        &self,
+        windows: &[(String, i32, i32)],
+    ) -> Result<HashMap<String, Vec<Chunk>>> {
+        let mut by_doc: HashMap<String, Vec<Chunk>> = HashMap::new();
+        if windows.is_empty() {
+            return Ok(by_doc);
+        }
+        let chunks = sqlx::query_as::<_, Chunk>(
+            "SELECT * FROM chunks c WHERE c.modality = 'text' AND EXISTS (
+                 SELECT 1 FROM json_each(?) w
+                 WHERE c.doc_id = json_extract(w.value, '$[0]')
+                   AND c.chunk_index BETWEEN json_extract(w.value, '$[1]')
+                                         AND json_extract(w.value, '$[2]'))
+             ORDER BY c.doc_id, c.chunk_index",
+        )
+        .bind(serde_json::to_string(windows)?)
+        .fetch_all(&self.pool)
+        .await?;
+        for chunk in chunks {
+            by_doc.entry(chunk.doc_id.clone()).or_default().push(chunk);
+        }
+        Ok(by_doc)
+    }
+
     /// Get chunk by Qdrant point ID
     pub async fn get_chunk_by_point_id(&self, point_id: &str) -> Result<Option<Chunk>> {
         let chunk = sqlx::query_as::<_, Chunk>("SELECT * FROM chunks WHERE qdrant_point_id = ?")
@@ -697,6 +1356,169 @@ impl MetaDb {
         Ok(chunk)
     }
 
+    /// Get chunks by Qdrant point ID in one query, keyed by point ID.
+    ///
+    /// IDs without a chunk are left out of the map.
+    pub async fn get_chunks_by_point_ids(
+        &self,
+        point_ids: &[String],
+    ) -> Result<HashMap<String, Chunk>> {
+        let mut chunks = HashMap::with_capacity(point_ids.len());
+        // Stay under SQLite's default limit on bound parameters
+        for batch in point_ids.chunks(MAX_BIND_PARAMS) {
+            let placeholders = vec!["?"; batch.len()].join(",");
+            let sql = format!(
+                "SELECT * FROM chunks WHERE qdrant_point_id IN ({})",
+                placeholders
+            );
+            let mut query = sqlx::query_as::<_, Chunk>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            for chunk in query.fetch_all(&self.pool).await? {
+                chunks.insert(chunk.qdrant_point_id.clone(), chunk);
+            }
+        }
+        Ok(chunks)
+    }
+
+    /// IDs of the sources owning the chunks with these Qdrant point IDs, in ID order
+    pub async fn get_source_ids_by_point_ids(&self, point_ids: &[String]) -> Result<Vec<String>> {
+        let mut source_ids = BTreeSet::new();
+        for batch in point_ids.chunks(MAX_BIND_PARAMS) {
+            let placeholders = vec!["?"; batch.len()].join(",");
+            let sql = format!(
+                "SELECT DISTINCT d.source_id FROM chunks c JOIN documents d ON d.id = c.doc_id \
+                 WHERE c.qdrant_point_id IN ({})",
+                placeholders
+            );
+            let mut query = sqlx::query_scalar::<_, String>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            source_ids.extend(query.fetch_all(&self.pool).await?);
+        }
+        Ok(source_ids.into_iter().collect())
+    }
+
+    /// Delete chunks by Qdrant point ID, returning how many were deleted.
+    ///
+    /// The content hash of each owning document is cleared so the next ingest
+    /// re-parses it and re-embeds the missing chunks.
+    pub async fn delete_chunks_by_point_ids(&self, point_ids: &[String]) -> Result<usize> {
+        let mut deleted = 0;
+        for batch in point_ids.chunks(MAX_BIND_PARAMS) {
+            let placeholders = vec!["?"; batch.len()].join(",");
+            let invalidate = format!(
+                "UPDATE documents SET content_hash = '' WHERE id IN \
+                 (SELECT doc_id FROM chunks WHERE qdrant_point_id IN ({}))",
+                placeholders
+            );
+            retry_locked(|| {
+                let mut query = sqlx::query(&invalidate);
+                for id in batch {
+                    query = query.bind(id);
+                }
+                query.execute(&self.pool)
+            })
+            .await?;
+
+            let delete = format!(
+                "DELETE FROM chunks WHERE qdrant_point_id IN ({})",
+                placeholders
+            );
+            let result = retry_locked(|| {
+                let mut query = sqlx::query(&delete);
+                for id in batch {
+                    query = query.bind(id);
+                }
+                query.execute(&self.pool)
+            })
+            .await?;
+            deleted += result.rows_affected() as usize;
+        }
+        Ok(deleted)
+    }
+
+    /// Up to `limit` text chunks matching any of `terms`, best first by the
+    /// full-text index's BM25 rank, with their document and source, for
+    /// keyword search without the vector store.
+    ///
+    /// Each term matches words starting with it, so `crawl` also finds
+    /// `crawler`.
+    pub async fn keyword_matches(
+        &self,
+        terms: &[String],
+        filter: &KeywordFilter,
+        limit: usize,
+    ) -> Result<Vec<KeywordMatch>> {
+        let Some(fts_query) = fts_match_query(terms) else {
+            return Ok(Vec::new());
+        };
+
+        let mut sql = String::from(
+            r#"
+            SELECT c.qdrant_point_id AS point_id, c.chunk_index, c.chunk_text,
+                   c.headings_json, d.uri AS doc_uri, d.title,
+                   s.id AS source_id, s.source_type, s.uri AS source_uri,
+                   -bm25(chunks_fts) AS rank
+            FROM chunks_fts
+            JOIN chunks c ON c.rowid = chunks_fts.rowid
+            JOIN documents d ON c.doc_id = d.id
+            JOIN sources s ON d.source_id = s.id
+            WHERE chunks_fts MATCH ? AND c.modality = 'text'
+            "#,
+        );
+        if let Some(ids) = &filter.source_ids {
+            sql.push_str(&format!(
+                " AND s.id IN ({})",
+                vec!["?"; ids.len()].join(",")
+            ));
+        }
+        if let Some(types) = &filter.source_types {
+            sql.push_str(&format!(
+                " AND s.source_type IN ({})",
+                vec!["?"; types.len()].join(",")
+            ));
+        }
+        if filter.path_prefix.is_some() {
+            sql.push_str(" AND d.uri LIKE ? ESCAPE '\\'");
+        }
+        if filter.section.is_some() {
+            sql.push_str(" AND (c.section_path = ? OR c.section_path LIKE ? ESCAPE '\\')");
+        }
+        sql.push_str(" ORDER BY bm25(chunks_fts), d.uri, c.chunk_index LIMIT ?");
+
+        let mut query = sqlx::query_as::<_, KeywordMatch>(&sql).bind(fts_query);
+        for id in filter.source_ids.as_deref().unwrap_or_default() {
+            query = query.bind(id);
+        }
+        for source_type in filter.source_types.as_deref().unwrap_or_default() {
+            query = query.bind(source_type);
+        }
+        if let Some(prefix) = &filter.path_prefix {
+            query = query.bind(format!("{}%", escape_like(prefix)));
+        }
+        if let Some(section) = &filter.section {
+            query = query
+                .bind(section)
+                .bind(format!("{}/%", escape_like(section)));
+        }
+        query = query.bind(limit as i64);
+        Ok(query.fetch_all(&self.pool).await?)
+    }
+
+    /// Delete a single chunk by its Qdrant point ID
+    pub async fn delete_chunk_by_point_id(&self, point_id: &str) -> Result<()> {
+        retry_locked(|| {
+            sqlx::query("DELETE FROM chunks WHERE qdrant_point_id = ?")
+                .bind(point_id)
+                .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
     /// Delete chunks with index >= given value
     pub async fn delete_chunks_from_index(
         &self,
@@ -711,11 +1533,13 @@ impl MetaDb {
         .fetch_all(&self.pool)
         .await?;
 
-        sqlx::query("DELETE FROM chunks WHERE doc_id = ? AND chunk_index >= ? AND (modality IS NULL OR modality = 'text')")
-            .bind(doc_id)
-            .bind(from_index)
-            .execute(&self.pool)
-            .await?;
+        retry_locked(|| {
+            sqlx::query("DELETE FROM chunks WHERE doc_id = ? AND chunk_index >= ? AND (modality IS NULL OR modality = 'text')")
+                .bind(doc_id)
+                .bind(from_index)
+                .execute(&self.pool)
+        })
+        .await?;
 
         Ok(point_ids)
     }
@@ -734,11 +1558,13 @@ impl MetaDb {
         .fetch_all(&self.pool)
         .await?;
 
-        sqlx::query("DELETE FROM chunks WHERE doc_id = ? AND modality = ?")
-            .bind(doc_id)
-            .bind(modality)
-            .execute(&self.pool)
-            .await?;
+        retry_locked(|| {
+            sqlx::query("DELETE FROM chunks WHERE doc_id = ? AND modality = ?")
+                .bind(doc_id)
+                .bind(modality)
+                .execute(&self.pool)
+        })
+        .await?;
 
         Ok(point_ids)
     }
@@ -767,24 +1593,28 @@ impl MetaDb {
         source_id: &str,
         operation: RunOperation,
     ) -> Result<IngestionRun> {
+        self.reap_stale_runs().await?;
         let run = IngestionRun::new(source_id.to_string(), operation);
-        sqlx::query(
-            r#"
-            INSERT INTO ingestion_runs (id, source_id, operation, started_at, status, docs_processed, chunks_created, chunks_updated, chunks_deleted)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(&run.id)
-        .bind(&run.source_id)
-        .bind(&run.operation)
-        .bind(&run.started_at)
-        .bind(&run.status)
-        .bind(run.docs_processed)
-        .bind(run.chunks_created)
-        .bind(run.chunks_updated)
-        .bind(run.chunks_deleted)
-        .execute(&self.pool)
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                INSERT INTO ingestion_runs (id, source_id, operation, started_at, status, docs_processed, chunks_created, chunks_updated, chunks_deleted)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&run.id)
+            .bind(&run.source_id)
+            .bind(&run.operation)
+            .bind(&run.started_at)
+            .bind(&run.status)
+            .bind(run.docs_processed)
+            .bind(run.chunks_created)
+            .bind(run.chunks_updated)
+            .bind(run.chunks_deleted)
+            .execute(&self.pool)
+        })
         .await?;
+        live_runs().insert(run.id.clone());
         Ok(run)
     }
 
@@ -800,32 +1630,182 @@ impl MetaDb {
         errors: Option<Vec<String>>,
     ) -> Result<()> {
         let errors_json = errors.map(|e| serde_json::to_string(&e).unwrap_or_default());
-        sqlx::query(
-            r#"
-            UPDATE ingestion_runs SET
-                completed_at = ?,
-                status = ?,
-                docs_processed = ?,
-                chunks_created = ?,
-                chunks_updated = ?,
-                chunks_deleted = ?,
-                errors_json = ?
-            WHERE id = ?
-            "#,
-        )
-        .bind(Utc::now().to_rfc3339())
-        .bind(status.to_string())
-        .bind(docs_processed)
-        .bind(chunks_created)
-        .bind(chunks_updated)
-        .bind(chunks_deleted)
-        .bind(errors_json)
-        .bind(id)
-        .execute(&self.pool)
+        retry_locked(|| {
+            sqlx::query(
+                r#"
+                UPDATE ingestion_runs SET
+                    completed_at = ?,
+                    status = ?,
+                    docs_processed = ?,
+                    chunks_created = ?,
+                    chunks_updated = ?,
+                    chunks_deleted = ?,
+                    errors_json = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(Utc::now().to_rfc3339())
+            .bind(status.to_string())
+            .bind(docs_processed)
+            .bind(chunks_created)
+            .bind(chunks_updated)
+            .bind(chunks_deleted)
+            .bind(&errors_json)
+            .bind(id)
+            .execute(&self.pool)
+        })
+        .await?;
+        live_runs().remove(id);
+        Ok(())
+    }
+
+    /// Record that a run crawled without checking robots.txt
+    pub async fn mark_run_robots_ignored(&self, id: &str) -> Result<()> {
+        retry_locked(|| {
+            sqlx::query("UPDATE ingestion_runs SET robots_ignored = 1 WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+        })
         .await?;
         Ok(())
     }
 
+    /// Record that a running ingestion run is still alive
+    pub async fn touch_ingestion_run(&self, id: &str) -> Result<()> {
+        retry_locked(|| {
+            sqlx::query("UPDATE ingestion_runs SET heartbeat_at = ? WHERE id = ? AND status = ?")
+                .bind(Utc::now().to_rfc3339())
+                .bind(id)
+                .bind(RunStatus::Running.to_string())
+                .execute(&self.pool)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Touch a run every [`RUN_HEARTBEAT_INTERVAL`] until the returned guard is dropped
+    pub fn start_run_heartbeat(&self, run_id: &str) -> RunHeartbeat {
+        let db = self.clone();
+        let run_id = run_id.to_string();
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RUN_HEARTBEAT_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = db.touch_ingestion_run(&run_id).await {
+                    debug!("Failed to record heartbeat for run {}: {}", run_id, e);
+                }
+            }
+        });
+        RunHeartbeat { handle }
+    }
+
+    /// Mark running runs with no heartbeat within `stale_after` as failed.
+    ///
+    /// These are left behind when a process is killed mid-ingest. Returns the
+    /// runs that were reclassified.
+    pub async fn fail_stale_runs(
+        &self,
+        stale_after: std::time::Duration,
+    ) -> Result<Vec<IngestionRun>> {
+        let cutoff =
+            Utc::now() - chrono::Duration::from_std(stale_after).unwrap_or(chrono::Duration::MAX);
+        let running =
+            sqlx::query_as::<_, IngestionRun>("SELECT * FROM ingestion_runs WHERE status = ?")
+                .bind(RunStatus::Running.to_string())
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut reaped = Vec::new();
+        for run in running {
+            let last_seen = run.heartbeat_at.as_deref().unwrap_or(&run.started_at);
+            let is_stale = chrono::DateTime::parse_from_rfc3339(last_seen)
+                .map(|t| t < cutoff)
+                .unwrap_or(true);
+            if is_stale && self.fail_run_last_seen_at(&run, last_seen).await? {
+                reaped.push(run);
+            }
+        }
+        Ok(reaped)
+    }
+
+    /// Fail a running run unless it has recorded a heartbeat or finished since
+    /// it was last seen at `last_seen`, returning whether it was failed
+    async fn fail_run_last_seen_at(&self, run: &IngestionRun, last_seen: &str) -> Result<bool> {
+        let mut errors = run.errors();
+        errors.push(format!("interrupted: no progress since {}", last_seen));
+        let errors_json = serde_json::to_string(&errors).unwrap_or_default();
+        let result = retry_locked(|| {
+            sqlx::query(
+                r#"
+                UPDATE ingestion_runs SET completed_at = ?, status = ?, errors_json = ?
+                WHERE id = ? AND status = ? AND COALESCE(heartbeat_at, started_at) = ?
+                "#,
+            )
+            .bind(Utc::now().to_rfc3339())
+            .bind(RunStatus::Failed.to_string())
+            .bind(&errors_json)
+            .bind(&run.id)
+            .bind(RunStatus::Running.to_string())
+            .bind(last_seen)
+            .execute(&self.pool)
+        })
+        .await?;
+        let failed = result.rows_affected() > 0;
+        if failed {
+            live_runs().remove(&run.id);
+        }
+        Ok(failed)
+    }
+
+    /// Fail runs a killed process left running, logging each one. Done before
+    /// a new run starts and before runs are reported, not on every command.
+    pub async fn reap_stale_runs(&self) -> Result<()> {
+        for run in self.fail_stale_runs(STALE_RUN_TIMEOUT).await? {
+            warn!(
+                "Marked interrupted {} run {} for source {} as failed",
+                run.operation, run.id, run.source_id
+            );
+        }
+        Ok(())
+    }
+
+    /// Mark runs this process started and never completed as failed with `reason`.
+    ///
+    /// A task aborted mid-run never reaches [`Self::complete_ingestion_run`], so
+    /// the server calls this once its background tasks are gone rather than
+    /// leaving the runs `running` until [`STALE_RUN_TIMEOUT`] passes. Runs owned
+    /// by other processes are not touched. Returns the runs that were failed.
+    pub async fn fail_abandoned_runs(&self, reason: &str) -> Result<Vec<IngestionRun>> {
+        let ids: Vec<String> = live_runs().iter().cloned().collect();
+        let mut failed = Vec::new();
+        for id in ids {
+            let run = sqlx::query_as::<_, IngestionRun>(
+                "SELECT * FROM ingestion_runs WHERE id = ? AND status = ?",
+            )
+            .bind(&id)
+            .bind(RunStatus::Running.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+            // Runs recorded in another database
+            let Some(run) = run else { continue };
+
+            let mut errors = run.errors();
+            errors.push(reason.to_string());
+            self.complete_ingestion_run(
+                &run.id,
+                RunStatus::Failed,
+                run.docs_processed,
+                run.chunks_created,
+                run.chunks_updated,
+                run.chunks_deleted,
+                Some(errors),
+            )
+            .await?;
+            failed.push(run);
+        }
+        Ok(failed)
+    }
+
     /// Get latest ingestion run for a source
     pub async fn get_latest_run(&self, source_id: &str) -> Result<Option<IngestionRun>> {
         let run = sqlx::query_as::<_, IngestionRun>(
@@ -837,6 +1817,44 @@ impl MetaDb {
         Ok(run)
     }
 
+    /// List ingestion runs, newest first, optionally for a single source
+    pub async fn list_runs(
+        &self,
+        source_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<IngestionRun>> {
+        self.list_runs_in_range(source_id, &TimeRange::default(), limit)
+            .await
+    }
+
+    /// List ingestion runs that started inside `range`, newest first
+    pub async fn list_runs_in_range(
+        &self,
+        source_id: Option<&str>,
+        range: &TimeRange,
+        limit: usize,
+    ) -> Result<Vec<IngestionRun>> {
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        // Start times are stored as UTC RFC3339, so they order as strings
+        let runs = sqlx::query_as::<_, IngestionRun>(
+            r#"
+            SELECT * FROM ingestion_runs
+            WHERE (?1 IS NULL OR source_id = ?1)
+              AND (?2 IS NULL OR started_at >= ?2)
+              AND (?3 IS NULL OR started_at < ?3)
+            ORDER BY started_at DESC, rowid DESC
+            LIMIT ?4
+            "#,
+        )
+        .bind(source_id)
+        .bind(range.since.map(|t| t.to_rfc3339()))
+        .bind(range.until.map(|t| t.to_rfc3339()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(runs)
+    }
+
     // ===== Statistics =====
 
     /// Get source statistics
@@ -885,18 +1903,39 @@ impl MetaDb {
         })
     }
 
+    // ===== Maintenance =====
+
+    /// Rebuild the database file to reclaim free pages and refresh planner stats
+    pub async fn vacuum(&self) -> Result<()> {
+        sqlx::query("VACUUM").execute(&self.pool).await?;
+        // VACUUM may renumber the chunk rowids the full-text index points at
+        self.rebuild_fts_index().await?;
+        sqlx::query("ANALYZE").execute(&self.pool).await?;
+        // In WAL mode the rebuilt pages land in the WAL; fold them back and truncate it
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Check that the database accepts writes, leaving it unchanged
+    pub async fn check_writable(&self) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("CREATE TABLE write_check (id INTEGER)")
+            .execute(&mut *tx)
+            .await?;
+        tx.rollback().await?;
+        Ok(())
+    }
+
     /// Create database with path directly (without full config)
-    pub async fn new(db_path: &std::path::Path) -> Result<Self> {
+    pub async fn new(db_path: &Path) -> Result<Self> {
         // Create parent directory if needed
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let options = SqliteConnectOptions::new()
-            .filename(db_path)
-            .create_if_missing(true)
-            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal);
+        let options = connect_options(db_path);
 
         debug!("Connecting to SQLite database at {:?}", db_path);
 
@@ -907,9 +1946,11 @@ impl MetaDb {
 
         let db = Self { pool };
 
-        // Auto-initialize schema if needed
+        // Auto-initialize schema if needed, otherwise backfill newer columns
         if !db.is_initialized().await? {
             db.init_schema().await?;
+        } else {
+            db.migrate_schema().await?;
         }
 
         Ok(db)
@@ -946,19 +1987,66 @@ impl MetaDb {
 
     /// Delete a document and its chunks
     pub async fn delete_document(&self, doc_id: &str) -> Result<()> {
-        sqlx::query("DELETE FROM chunks WHERE doc_id = ?")
-            .bind(doc_id)
-            .execute(&self.pool)
-            .await?;
+        retry_locked(|| {
+            sqlx::query("DELETE FROM document_aliases WHERE doc_id = ?")
+                .bind(doc_id)
+                .execute(&self.pool)
+        })
+        .await?;
 
-        sqlx::query("DELETE FROM documents WHERE id = ?")
-            .bind(doc_id)
-            .execute(&self.pool)
-            .await?;
+        retry_locked(|| {
+            sqlx::query("DELETE FROM chunks WHERE doc_id = ?")
+                .bind(doc_id)
+                .execute(&self.pool)
+        })
+        .await?;
+
+        retry_locked(|| {
+            sqlx::query("DELETE FROM documents WHERE id = ?")
+                .bind(doc_id)
+                .execute(&self.pool)
+        })
+        .await?;
 
         Ok(())
     }
 
+    /// Up to `limit` chunk point IDs after `after`, in ID order, for paging
+    /// through every chunk without loading them all
+    pub async fn list_chunk_ids_page(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT qdrant_point_id FROM chunks WHERE qdrant_point_id > ? \
+             ORDER BY qdrant_point_id LIMIT ?",
+        )
+        .bind(after.unwrap_or(""))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    /// The IDs among `point_ids` that belong to a chunk
+    pub async fn existing_chunk_ids(&self, point_ids: &[String]) -> Result<HashSet<String>> {
+        let mut existing = HashSet::new();
+        for batch in point_ids.chunks(MAX_BIND_PARAMS) {
+            let placeholders = vec!["?"; batch.len()].join(",");
+            let sql = format!(
+                "SELECT qdrant_point_id FROM chunks WHERE qdrant_point_id IN ({})",
+                placeholders
+            );
+            let mut query = sqlx::query_scalar::<_, String>(&sql);
+            for id in batch {
+                query = query.bind(id);
+            }
+            existing.extend(query.fetch_all(&self.pool).await?);
+        }
+        Ok(existing)
+    }
+
     /// List all chunk IDs (for orphan detection)
     pub async fn list_all_chunk_ids(&self) -> Result<Vec<String>> {
         let ids: Vec<String> = sqlx::query_scalar("SELECT qdrant_point_id FROM chunks")
@@ -981,6 +2069,56 @@ pub struct ChunkRecord {
     pub media_hash: Option<String>,
 }
 
+/// A text chunk found by [`MetaDb::keyword_matches`], with the document and
+/// source fields a search result needs
+#[derive(Debug, Clone, FromRow)]
+pub struct KeywordMatch {
+    pub point_id: String,
+    pub chunk_index: i32,
+    pub chunk_text: String,
+    pub headings_json: Option<String>,
+    pub doc_uri: String,
+    pub title: Option<String>,
+    pub source_id: String,
+    pub source_type: String,
+    pub source_uri: String,
+    /// BM25 rank from the full-text index; higher is a better match
+    pub rank: f64,
+}
+
+/// Restrictions on [`MetaDb::keyword_matches`], mirroring the vector store's
+/// search filter
+#[derive(Debug, Clone, Default)]
+pub struct KeywordFilter {
+    pub source_ids: Option<Vec<String>>,
+    pub source_types: Option<Vec<String>>,
+    /// Only match documents whose URI starts with this prefix
+    pub path_prefix: Option<String>,
+    /// Only match chunks whose heading trail starts with this normalized
+    /// section path (see [`crate::chunk::normalize_section_path`])
+    pub section: Option<String>,
+}
+
+/// Documents removed or moved by [`MetaDb::delete_stale_documents`]
+#[derive(Debug, Clone, Default)]
+pub struct StaleDocuments {
+    /// IDs of the deleted documents
+    pub deleted: Vec<String>,
+    /// Qdrant point IDs of the deleted documents' chunks
+    pub deleted_point_ids: Vec<String>,
+    /// Stale documents moved to one of their alias URIs
+    pub promoted: Vec<PromotedDocument>,
+}
+
+/// A document moved to an alias URI seen in the latest run
+#[derive(Debug, Clone)]
+pub struct PromotedDocument {
+    pub doc_id: String,
+    pub uri: String,
+    /// Qdrant point IDs of the document's chunks, whose `doc_uri` needs updating
+    pub point_ids: Vec<String>,
+}
+
 /// Statistics for a single source
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SourceStats {
@@ -1034,6 +2172,94 @@ mod tests {
         assert_eq!(sources.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_document_aliases_follow_stale_cleanup() {
+        let (db, _tmp) = setup_test_db().await;
+
+        let source = Source::new(SourceType::Url, "https://example.com".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "https://example.com/a".to_string(),
+                "same".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        let found = db
+            .find_document_by_hash(&source.id, "same", "https://example.com/b")
+            .await
+            .unwrap();
+        assert_eq!(found.map(|d| d.id), Some(doc.id.clone()));
+        assert!(db
+            .find_document_by_hash(&source.id, "same", "https://example.com/a")
+            .await
+            .unwrap()
+            .is_none());
+
+        for uri in ["https://example.com/b", "https://example.com/c"] {
+            db.upsert_document_alias(&source.id, uri, &doc.id)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            db.get_document_aliases(&doc.id).await.unwrap(),
+            vec!["https://example.com/b", "https://example.com/c"]
+        );
+
+        // An alias not seen in the latest run is dropped with the stale documents
+        let current = vec![
+            "https://example.com/a".to_string(),
+            "https://example.com/b".to_string(),
+        ];
+        let stale = db
+            .delete_stale_documents(&source.id, &current)
+            .await
+            .unwrap();
+        assert!(stale.deleted.is_empty() && stale.promoted.is_empty());
+        assert_eq!(
+            db.get_document_aliases(&doc.id).await.unwrap(),
+            vec!["https://example.com/b"]
+        );
+
+        // A stale document whose alias was seen moves to the alias URI
+        let chunk = Chunk::new(
+            doc.id.clone(),
+            0,
+            "h".to_string(),
+            "text".to_string(),
+            0,
+            4,
+            None,
+        );
+        db.upsert_chunk(&chunk).await.unwrap();
+        let gone = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "https://example.com/gone".to_string(),
+                "other".to_string(),
+            ))
+            .await
+            .unwrap();
+        let stale = db
+            .delete_stale_documents(&source.id, &["https://example.com/b".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(stale.deleted, vec![gone.id.clone()]);
+        assert_eq!(stale.promoted.len(), 1);
+        assert_eq!(stale.promoted[0].doc_id, doc.id);
+        assert_eq!(stale.promoted[0].uri, "https://example.com/b");
+        assert_eq!(stale.promoted[0].point_ids, vec![chunk.qdrant_point_id]);
+        let moved = db.get_document(&doc.id).await.unwrap().unwrap();
+        assert_eq!(moved.uri, "https://example.com/b");
+        assert_eq!(db.get_chunks(&doc.id).await.unwrap().len(), 1);
+        assert!(db.get_document_aliases(&doc.id).await.unwrap().is_empty());
+
+        db.delete_document(&doc.id).await.unwrap();
+        assert!(db.get_document_aliases(&doc.id).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_document_upsert() {
         let (db, _tmp) = setup_test_db().await;
@@ -1101,7 +2327,10 @@ mod tests {
             "First chunk text".to_string(),
             0,
             15,
-            Some(vec!["Introduction".to_string()]),
+            Some(vec![
+                SectionHeading::new(1, "Guide"),
+                SectionHeading::new(2, "Introduction"),
+            ]),
         );
         let chunk2 = Chunk::new(
             doc.id.clone(),
@@ -1118,7 +2347,14 @@ mod tests {
 
         let chunks = db.get_chunks(&doc.id).await.unwrap();
         assert_eq!(chunks.len(), 2);
-        assert_eq!(chunks[0].headings(), vec!["Introduction".to_string()]);
+        assert_eq!(
+            chunks[0].headings(),
+            vec![
+                SectionHeading::new(1, "Guide"),
+                SectionHeading::new(2, "Introduction"),
+            ]
+        );
+        assert!(chunks[1].headings().is_empty());
     }
 
     /// Regression test: re-ingesting an existing document with new chunks must use the canonical
@@ -1180,4 +2416,596 @@ mod tests {
         let chunks = db.get_chunks(&original_doc_id).await.unwrap();
         assert_eq!(chunks.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_source_tags_add_remove_list() {
+        let (db, _tmp) = setup_test_db().await;
+
+        let source = Source::new(SourceType::Dir, "/path/to/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        assert!(source.tags().is_empty());
+
+        let tags = db
+            .add_source_tags(&source.id, &["Rust".to_string(), " internal ".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(tags, vec!["internal".to_string(), "rust".to_string()]);
+
+        // Adding an existing tag is a no-op
+        let tags = db
+            .add_source_tags(&source.id, &["rust".to_string(), "archived".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(tags, vec!["archived", "internal", "rust"]);
+
+        let loaded = db.get_source(&source.id).await.unwrap().unwrap();
+        assert_eq!(loaded.tags(), vec!["archived", "internal", "rust"]);
+
+        let tags = db
+            .remove_source_tags(&source.id, &["internal".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(tags, vec!["archived", "rust"]);
+
+        let tags = db
+            .remove_source_tags(&source.id, &["archived".to_string(), "rust".to_string()])
+            .await
+            .unwrap();
+        assert!(tags.is_empty());
+        let loaded = db.get_source(&source.id).await.unwrap().unwrap();
+        assert!(loaded.tags.is_none());
+
+        let missing = db.add_source_tags("missing", &["rust".to_string()]).await;
+        assert!(matches!(missing, Err(Error::SourceNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_runs_newest_first_with_limit() {
+        let (db, _tmp) = setup_test_db().await;
+        let a = Source::new(SourceType::Dir, "/docs/a".to_string(), None);
+        let b = Source::new(SourceType::Dir, "/docs/b".to_string(), None);
+        db.insert_source(&a).await.unwrap();
+        db.insert_source(&b).await.unwrap();
+
+        let mut ids = Vec::new();
+        for source in [&a, &b, &a, &b, &a] {
+            let run = db
+                .start_ingestion_run(&source.id, RunOperation::Ingest)
+                .await
+                .unwrap();
+            ids.push(run.id);
+        }
+
+        let all = db.list_runs(None, 10).await.unwrap();
+        let newest_first: Vec<String> = ids.iter().rev().cloned().collect();
+        assert_eq!(
+            all.iter().map(|r| r.id.clone()).collect::<Vec<_>>(),
+            newest_first
+        );
+
+        let limited = db.list_runs(None, 2).await.unwrap();
+        assert_eq!(limited.len(), 2);
+        assert_eq!(limited[0].id, ids[4]);
+        assert_eq!(limited[1].id, ids[3]);
+
+        let only_a = db.list_runs(Some(&a.id), 10).await.unwrap();
+        assert_eq!(only_a.len(), 3);
+        assert!(only_a.iter().all(|r| r.source_id == a.id));
+        assert_eq!(only_a[0].id, ids[4]);
+        assert_eq!(only_a[2].id, ids[0]);
+    }
+
+    #[tokio::test]
+    async fn test_list_runs_in_range_filters_on_started_at() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let mut ids = Vec::new();
+        for started_at in [
+            "2024-05-01T00:00:00+00:00",
+            "2024-05-01T23:59:59.5+00:00",
+            "2024-05-02T00:00:00+00:00",
+        ] {
+            let run = db
+                .start_ingestion_run(&source.id, RunOperation::Ingest)
+                .await
+                .unwrap();
+            sqlx::query("UPDATE ingestion_runs SET started_at = ? WHERE id = ?")
+                .bind(started_at)
+                .bind(&run.id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+            ids.push(run.id);
+        }
+
+        let day = |s: &str| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let range = TimeRange::new(
+            Some(day("2024-05-01T00:00:00Z")),
+            Some(day("2024-05-02T00:00:00Z")),
+        );
+        let runs = db.list_runs_in_range(None, &range, 10).await.unwrap();
+        assert_eq!(
+            runs.iter().map(|r| r.id.clone()).collect::<Vec<_>>(),
+            vec![ids[1].clone(), ids[0].clone()]
+        );
+
+        let since_only = TimeRange::new(Some(day("2024-05-01T12:00:00Z")), None);
+        let runs = db
+            .list_runs_in_range(Some(&source.id), &since_only, 10)
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fail_stale_runs_reclassifies_interrupted_runs() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        // A run left behind by a killed process, and a long run that is still
+        // heartbeating, both started an hour ago
+        let stale = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        let live = db
+            .start_ingestion_run(&source.id, RunOperation::Update)
+            .await
+            .unwrap();
+        let an_hour_ago = (Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        sqlx::query("UPDATE ingestion_runs SET started_at = ?")
+            .bind(&an_hour_ago)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        db.touch_ingestion_run(&live.id).await.unwrap();
+
+        let reaped = db.fail_stale_runs(STALE_RUN_TIMEOUT).await.unwrap();
+        assert_eq!(reaped.len(), 1);
+        assert_eq!(reaped[0].id, stale.id);
+
+        let runs = db.list_runs(Some(&source.id), 10).await.unwrap();
+        let stale = runs.iter().find(|r| r.id == stale.id).unwrap();
+        assert_eq!(stale.status, "failed");
+        assert!(stale.completed_at.is_some());
+        assert!(stale.errors()[0].starts_with("interrupted"));
+        let live = runs.iter().find(|r| r.id == live.id).unwrap();
+        assert_eq!(live.status, "running");
+
+        // Already-failed runs are not touched again
+        assert!(db
+            .fail_stale_runs(STALE_RUN_TIMEOUT)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stale_run_that_heartbeats_before_the_update_is_kept() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let run = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        let an_hour_ago = (Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        sqlx::query("UPDATE ingestion_runs SET started_at = ? WHERE id = ?")
+            .bind(&an_hour_ago)
+            .bind(&run.id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        // Seen as stale, then a heartbeat lands before it is failed
+        let seen = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        db.touch_ingestion_run(&run.id).await.unwrap();
+        assert!(!db.fail_run_last_seen_at(&seen, &an_hour_ago).await.unwrap());
+
+        let run = db.get_latest_run(&source.id).await.unwrap().unwrap();
+        assert_eq!(run.status, "running");
+    }
+
+    #[tokio::test]
+    async fn test_fail_abandoned_runs_fails_only_unfinished_runs() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let abandoned = db
+            .start_ingestion_run(&source.id, RunOperation::Ingest)
+            .await
+            .unwrap();
+        let finished = db
+            .start_ingestion_run(&source.id, RunOperation::Update)
+            .await
+            .unwrap();
+        db.complete_ingestion_run(&finished.id, RunStatus::Completed, 1, 1, 0, 0, None)
+            .await
+            .unwrap();
+        // A run another process is writing to the same database
+        let foreign = IngestionRun::new(source.id.clone(), RunOperation::Ingest);
+        sqlx::query(
+            "INSERT INTO ingestion_runs (id, source_id, operation, started_at, status) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&foreign.id)
+        .bind(&foreign.source_id)
+        .bind(&foreign.operation)
+        .bind(&foreign.started_at)
+        .bind(&foreign.status)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let failed = db
+            .fail_abandoned_runs("cancelled: shut down")
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, abandoned.id);
+
+        let runs = db.list_runs(Some(&source.id), 10).await.unwrap();
+        let run = |id: &str| runs.iter().find(|r| r.id == id).unwrap().clone();
+        let abandoned = run(&abandoned.id);
+        assert_eq!(abandoned.status, "failed");
+        assert!(abandoned.completed_at.is_some());
+        assert_eq!(abandoned.errors(), vec!["cancelled: shut down".to_string()]);
+        assert_eq!(run(&finished.id).status, "completed");
+        assert_eq!(run(&foreign.id).status, "running");
+
+        assert!(db
+            .fail_abandoned_runs("cancelled: shut down")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_sources_by_tags() {
+        let (db, _tmp) = setup_test_db().await;
+
+        let rust = Source::new(SourceType::Dir, "/docs/rust".to_string(), None);
+        let python = Source::new(SourceType::Dir, "/docs/python".to_string(), None);
+        let untagged = Source::new(SourceType::Dir, "/docs/misc".to_string(), None);
+        db.insert_source(&rust).await.unwrap();
+        db.insert_source(&python).await.unwrap();
+        db.insert_source(&untagged).await.unwrap();
+
+        db.add_source_tags(&rust.id, &["rust".to_string(), "lang".to_string()])
+            .await
+            .unwrap();
+        db.add_source_tags(&python.id, &["python".to_string(), "lang".to_string()])
+            .await
+            .unwrap();
+
+        let matched = db.list_sources_by_tags(&["RUST".to_string()]).await.unwrap();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].id, rust.id);
+
+        let matched = db.list_sources_by_tags(&["lang".to_string()]).await.unwrap();
+        assert_eq!(matched.len(), 2);
+        assert!(matched.iter().all(|s| s.id != untagged.id));
+
+        let matched = db.list_sources_by_tags(&["go".to_string()]).await.unwrap();
+        assert!(matched.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_chunks_by_point_ids_returns_each_match() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "/docs/a.md".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        let mut point_ids = Vec::new();
+        for i in 0..3 {
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                i,
+                format!("hash-{}", i),
+                format!("text {}", i),
+                0,
+                6,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+            point_ids.push(chunk.qdrant_point_id);
+        }
+        point_ids.reverse();
+        point_ids.push("missing".to_string());
+
+        let chunks = db.get_chunks_by_point_ids(&point_ids).await.unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[&point_ids[0]].chunk_text, "text 2");
+        assert_eq!(chunks[&point_ids[2]].chunk_text, "text 0");
+        assert!(!chunks.contains_key("missing"));
+        assert!(db.get_chunks_by_point_ids(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batched_windows_and_aliases() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Url, "https://example.com".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let mut docs = Vec::new();
+        for name in ["a", "b"] {
+            let doc = db
+                .upsert_document(&Document::new(
+                    source.id.clone(),
+                    format!("https://example.com/{}", name),
+                    format!("hash-{}", name),
+                ))
+                .await
+                .unwrap();
+            for i in 0..6 {
+                let chunk = Chunk::new(
+                    doc.id.clone(),
+                    i,
+                    format!("hash-{}-{}", name, i),
+                    format!("{} {}", name, i),
+                    0,
+                    3,
+                    None,
+                );
+                db.upsert_chunk(&chunk).await.unwrap();
+            }
+            docs.push(doc);
+        }
+        db.upsert_document_alias(&source.id, "https://example.com/a2", &docs[0].id)
+            .await
+            .unwrap();
+
+        // Overlapping windows of one document share their chunks
+        let windows = vec![
+            (docs[0].id.clone(), 0, 1),
+            (docs[0].id.clone(), 1, 2),
+            (docs[1].id.clone(), 4, 9),
+        ];
+        let by_doc = db.get_chunk_windows(&windows).await.unwrap();
+        let indexes = |id: &str| -> Vec<i32> { by_doc[id].iter().map(|c| c.chunk_index).collect() };
+        assert_eq!(indexes(&docs[0].id), vec![0, 1, 2]);
+        assert_eq!(indexes(&docs[1].id), vec![4, 5]);
+        assert!(db.get_chunk_windows(&[]).await.unwrap().is_empty());
+
+        let ids: Vec<String> = docs.iter().map(|d| d.id.clone()).collect();
+        let aliases = db.get_aliases_by_doc_ids(&ids).await.unwrap();
+        assert_eq!(aliases[&docs[0].id], vec!["https://example.com/a2"]);
+        assert!(!aliases.contains_key(&docs[1].id));
+    }
+
+    #[tokio::test]
+    async fn test_keyword_matches_rank_and_filter_in_sql() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Url, "https://example.com".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+
+        let pages = [
+            (
+                "https://example.com/guide/crawl",
+                "The crawler honours the crawl delay.",
+            ),
+            (
+                "https://example.com/guide/query",
+                "Queries time out after ten seconds.",
+            ),
+            (
+                "https://example.com/blog/crawl",
+                "We made the crawler faster.",
+            ),
+        ];
+        for (uri, text) in pages {
+            let doc = db
+                .upsert_document(&Document::new(
+                    source.id.clone(),
+                    uri.to_string(),
+                    format!("hash-{}", uri),
+                ))
+                .await
+                .unwrap();
+            let chunk = Chunk::new(
+                doc.id.clone(),
+                0,
+                format!("chunk-{}", uri),
+                text.to_string(),
+                0,
+                text.len() as i32,
+                None,
+            );
+            db.upsert_chunk(&chunk).await.unwrap();
+        }
+        let terms = vec!["crawl".to_string()];
+        let uris = |matches: Vec<KeywordMatch>| -> Vec<String> {
+            matches.into_iter().map(|m| m.doc_uri).collect()
+        };
+
+        // Terms match word prefixes; the page using them more often ranks first
+        let matches = db
+            .keyword_matches(&terms, &KeywordFilter::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            uris(matches),
+            vec![
+                "https://example.com/guide/crawl",
+                "https://example.com/blog/crawl"
+            ]
+        );
+        let matches = db
+            .keyword_matches(&terms, &KeywordFilter::default(), 1)
+            .await
+            .unwrap();
+        assert_eq!(uris(matches), vec!["https://example.com/guide/crawl"]);
+
+        let filter = KeywordFilter {
+            path_prefix: Some("https://example.com/blog/".to_string()),
+            ..Default::default()
+        };
+        let matches = db.keyword_matches(&terms, &filter, 10).await.unwrap();
+        assert_eq!(uris(matches), vec!["https://example.com/blog/crawl"]);
+
+        // The index follows rewritten and deleted chunks, and survives VACUUM
+        let doc = db
+            .get_document_by_uri(&source.id, "https://example.com/guide/query")
+            .await
+            .unwrap()
+            .unwrap();
+        let chunk = Chunk::new(
+            doc.id.clone(),
+            0,
+            "chunk-rewritten".to_string(),
+            "Crawling is configured per source.".to_string(),
+            0,
+            35,
+            None,
+        );
+        db.upsert_chunk(&chunk).await.unwrap();
+        let blog = db
+            .get_document_by_uri(&source.id, "https://example.com/blog/crawl")
+            .await
+            .unwrap()
+            .unwrap();
+        db.delete_document(&blog.id).await.unwrap();
+        db.vacuum().await.unwrap();
+        let matches = db
+            .keyword_matches(&terms, &KeywordFilter::default(), 10)
+            .await
+            .unwrap();
+        let mut found = uris(matches);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "https://example.com/guide/crawl",
+                "https://example.com/guide/query"
+            ]
+        );
+        let matches = db
+            .keyword_matches(&["seconds".to_string()], &KeywordFilter::default(), 10)
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+        // Punctuation alone matches nothing rather than failing the query
+        let matches = db
+            .keyword_matches(&["---".to_string()], &KeywordFilter::default(), 10)
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_migration_indexes_existing_chunks() {
+        let (db, _tmp) = setup_test_db().await;
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        db.insert_source(&source).await.unwrap();
+        let doc = db
+            .upsert_document(&Document::new(
+                source.id.clone(),
+                "/docs/guide.md".to_string(),
+                "hash".to_string(),
+            ))
+            .await
+            .unwrap();
+        let headings = vec![
+            SectionHeading {
+                level: 1,
+                text: "Guide".to_string(),
+            },
+            SectionHeading {
+                level: 2,
+                text: "Crawl  Settings".to_string(),
+            },
+        ];
+        let chunk = Chunk::new(
+            doc.id.clone(),
+            0,
+            "hash-0".to_string(),
+            "The crawl timeout is 30 seconds.".to_string(),
+            0,
+            32,
+            Some(headings),
+        );
+        db.upsert_chunk(&chunk).await.unwrap();
+
+        // Roll the database back to before the full-text index and section paths
+        sqlx::query(
+            "DROP TABLE chunks_fts; DROP TRIGGER chunks_fts_insert; \
+             DROP TRIGGER chunks_fts_delete; DROP TRIGGER chunks_fts_update; \
+             ALTER TABLE chunks DROP COLUMN section_path;",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        db.init_schema().await.unwrap();
+
+        let filter = KeywordFilter {
+            section: Some("guide".to_string()),
+            ..Default::default()
+        };
+        let matches = db
+            .keyword_matches(&["timeout".to_string()], &filter, 10)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        let section: (String,) = sqlx::query_as("SELECT section_path FROM chunks")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(section.0, "guide/crawl settings");
+    }
+
+    #[tokio::test]
+    async fn test_writes_retry_while_database_is_locked() {
+        let (db, tmp) = setup_test_db().await;
+
+        // A second handle that gives up on a lock at once, so only the retry can save it
+        let options = connect_options(&tmp.path().join("test.db")).busy_timeout(Duration::ZERO);
+        let contended = MetaDb {
+            pool: SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect_with(options)
+                .await
+                .unwrap(),
+        };
+
+        let mut holder = db.pool.acquire().await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut *holder)
+            .await
+            .unwrap();
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(250)).await;
+            sqlx::query("COMMIT").execute(&mut *holder).await.unwrap();
+        });
+
+        let source = Source::new(SourceType::Dir, "/docs".to_string(), None);
+        contended.insert_source(&source).await.unwrap();
+        release.await.unwrap();
+        assert!(db.get_source(&source.id).await.unwrap().is_some());
+
+        // A lock that is never released surfaces as a clear error
+        let mut holder = db.pool.acquire().await.unwrap();
+        sqlx::query("BEGIN IMMEDIATE")
+            .execute(&mut *holder)
+            .await
+            .unwrap();
+        let other = Source::new(SourceType::Dir, "/other".to_string(), None);
+        let err = contended.insert_source(&other).await.unwrap_err();
+        assert!(matches!(err, Error::DatabaseLocked(_)), "got {:?}", err);
+        sqlx::query("ROLLBACK").execute(&mut *holder).await.unwrap();
+    }
 }
@@ -10,7 +10,8 @@ CREATE TABLE IF NOT EXISTS sources (
     name TEXT,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
-    config_json TEXT
+    config_json TEXT,
+    tags TEXT
 );
 
 -- Documents: individual files or pages
@@ -23,6 +24,7 @@ CREATE TABLE IF NOT EXISTS documents (
     content_type TEXT,
     created_at TEXT NOT NULL,
     updated_at TEXT NOT NULL,
+    api_endpoint_json TEXT,
     UNIQUE(source_id, uri)
 );
 
@@ -36,6 +38,7 @@ CREATE TABLE IF NOT EXISTS chunks (
     char_start INTEGER NOT NULL,
     char_end INTEGER NOT NULL,
     headings_json TEXT,
+    section_path TEXT,
     qdrant_point_id TEXT NOT NULL,
     modality TEXT NOT NULL DEFAULT 'text',
     media_url TEXT,
@@ -45,6 +48,15 @@ CREATE TABLE IF NOT EXISTS chunks (
     UNIQUE(doc_id, chunk_index)
 );
 
+-- Document aliases: other URIs in a source serving a document's exact content
+CREATE TABLE IF NOT EXISTS document_aliases (
+    source_id TEXT NOT NULL REFERENCES sources(id),
+    uri TEXT NOT NULL,
+    doc_id TEXT NOT NULL REFERENCES documents(id),
+    created_at TEXT NOT NULL,
+    PRIMARY KEY(source_id, uri)
+);
+
 -- Ingestion runs: tracking history
 CREATE TABLE IF NOT EXISTS ingestion_runs (
     id TEXT PRIMARY KEY,
@@ -57,14 +69,43 @@ CREATE TABLE IF NOT EXISTS ingestion_runs (
     chunks_created INTEGER DEFAULT 0,
     chunks_updated INTEGER DEFAULT 0,
     chunks_deleted INTEGER DEFAULT 0,
-    errors_json TEXT
+    errors_json TEXT,
+    heartbeat_at TEXT,
+    robots_ignored INTEGER NOT NULL DEFAULT 0
 );
 
 -- Indexes for performance
 CREATE INDEX IF NOT EXISTS idx_documents_source ON documents(source_id);
 CREATE INDEX IF NOT EXISTS idx_documents_hash ON documents(content_hash);
+CREATE INDEX IF NOT EXISTS idx_aliases_doc ON document_aliases(doc_id);
 CREATE INDEX IF NOT EXISTS idx_chunks_doc ON chunks(doc_id);
 CREATE INDEX IF NOT EXISTS idx_chunks_hash ON chunks(chunk_hash);
 CREATE INDEX IF NOT EXISTS idx_chunks_point ON chunks(qdrant_point_id);
 CREATE INDEX IF NOT EXISTS idx_runs_source ON ingestion_runs(source_id);
 "#;
+
+/// Full-text index over chunk text for keyword search, kept in step with
+/// `chunks` by triggers. Created by the migration so existing databases get
+/// their chunks indexed when it first appears.
+pub const FTS_SCHEMA_SQL: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS chunks_fts USING fts5(
+    chunk_text,
+    content='chunks',
+    content_rowid='rowid'
+);
+
+CREATE TRIGGER IF NOT EXISTS chunks_fts_insert AFTER INSERT ON chunks BEGIN
+    INSERT INTO chunks_fts(rowid, chunk_text) VALUES (new.rowid, new.chunk_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS chunks_fts_delete AFTER DELETE ON chunks BEGIN
+    INSERT INTO chunks_fts(chunks_fts, rowid, chunk_text)
+    VALUES ('delete', old.rowid, old.chunk_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS chunks_fts_update AFTER UPDATE OF chunk_text ON chunks BEGIN
+    INSERT INTO chunks_fts(chunks_fts, rowid, chunk_text)
+    VALUES ('delete', old.rowid, old.chunk_text);
+    INSERT INTO chunks_fts(rowid, chunk_text) VALUES (new.rowid, new.chunk_text);
+END;
+"#;
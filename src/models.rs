@@ -1,6 +1,9 @@
 //! Model capability registry for multimodal embeddings and rerankers.
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MultimodalStrategy {
     /// Vision-language embedding model that jointly encodes text + image inputs.
     VlEmbedding,
@@ -107,6 +110,81 @@ const EMBED_MODELS: &[EmbeddingModelSpec] = &[
         supports_mrl: false,
         max_batch: 32,
     },
+    EmbeddingModelSpec {
+        id: "BAAI/bge-m3",
+        family: "bge-m3",
+        default_dimension: Some(1024),
+        modalities: &["text"],
+        capabilities: EmbeddingModelCapabilities {
+            strategy: MultimodalStrategy::DualEncoder,
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+            supports_multi_vector: false,
+        },
+        supports_mrl: false,
+        max_batch: 16,
+    },
+    EmbeddingModelSpec {
+        id: "nomic-ai/nomic-embed-text-v1.5",
+        family: "nomic",
+        default_dimension: Some(768),
+        modalities: &["text"],
+        capabilities: EmbeddingModelCapabilities {
+            strategy: MultimodalStrategy::DualEncoder,
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+            supports_multi_vector: false,
+        },
+        supports_mrl: true,
+        max_batch: 32,
+    },
+    EmbeddingModelSpec {
+        id: "Snowflake/snowflake-arctic-embed-m",
+        family: "arctic-embed",
+        default_dimension: Some(768),
+        modalities: &["text"],
+        capabilities: EmbeddingModelCapabilities {
+            strategy: MultimodalStrategy::DualEncoder,
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+            supports_multi_vector: false,
+        },
+        supports_mrl: false,
+        max_batch: 32,
+    },
+    EmbeddingModelSpec {
+        id: "Snowflake/snowflake-arctic-embed-m-v1.5",
+        family: "arctic-embed",
+        default_dimension: Some(768),
+        modalities: &["text"],
+        capabilities: EmbeddingModelCapabilities {
+            strategy: MultimodalStrategy::DualEncoder,
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+            supports_multi_vector: false,
+        },
+        supports_mrl: true,
+        max_batch: 32,
+    },
+    EmbeddingModelSpec {
+        id: "mixedbread-ai/mxbai-embed-large-v1",
+        family: "mxbai",
+        default_dimension: Some(1024),
+        modalities: &["text"],
+        capabilities: EmbeddingModelCapabilities {
+            strategy: MultimodalStrategy::DualEncoder,
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+            supports_multi_vector: false,
+        },
+        supports_mrl: true,
+        max_batch: 16,
+    },
     EmbeddingModelSpec {
         id: "Qwen/Qwen3-VL-Embedding-2B",
         family: "qwen3-vl",
@@ -196,6 +274,39 @@ const RERANK_MODELS: &[RerankerModelSpec] = &[
         },
         max_batch: 32,
     },
+    RerankerModelSpec {
+        id: "BAAI/bge-reranker-v2-m3",
+        family: "bge",
+        modalities: &["text"],
+        capabilities: RerankerModelCapabilities {
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+        },
+        max_batch: 16,
+    },
+    RerankerModelSpec {
+        id: "mixedbread-ai/mxbai-rerank-base-v1",
+        family: "mxbai",
+        modalities: &["text"],
+        capabilities: RerankerModelCapabilities {
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+        },
+        max_batch: 32,
+    },
+    RerankerModelSpec {
+        id: "mixedbread-ai/mxbai-rerank-large-v1",
+        family: "mxbai",
+        modalities: &["text"],
+        capabilities: RerankerModelCapabilities {
+            supports_text: true,
+            supports_image: false,
+            supports_joint_inputs: false,
+        },
+        max_batch: 16,
+    },
     RerankerModelSpec {
         id: "Qwen/Qwen3-VL-Reranker-2B",
         family: "qwen3-vl",
@@ -322,4 +433,29 @@ mod tests {
         assert!(reranker_model_capabilities("jinaai/jina-reranker-m0").is_some());
         assert!(reranker_model_capabilities("unknown-reranker").is_none());
     }
+
+    #[test]
+    fn test_text_models_resolve_with_registry_defaults() {
+        let nomic = embedding_model_spec("nomic-ai/nomic-embed-text-v1.5").unwrap();
+        assert_eq!(nomic.default_dimension, Some(768));
+        assert!(nomic.supports_mrl);
+
+        let arctic = embedding_model_spec("Snowflake/snowflake-arctic-embed-m").unwrap();
+        assert_eq!(arctic.default_dimension, Some(768));
+        assert!(!arctic.supports_mrl);
+
+        let mxbai = embedding_model_spec("mixedbread-ai/mxbai-embed-large-v1").unwrap();
+        assert_eq!(mxbai.default_dimension, Some(1024));
+        assert!(mxbai.supports_mrl);
+        assert_eq!(mxbai.modalities, &["text"]);
+        assert!(!is_multimodal_embedding_model(mxbai.id));
+
+        for model in [
+            "BAAI/bge-reranker-v2-m3",
+            "mixedbread-ai/mxbai-rerank-base-v1",
+        ] {
+            assert!(allowlisted_reranker_models().contains(&model));
+            assert!(!is_multimodal_reranker_model(model));
+        }
+    }
 }
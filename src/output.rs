@@ -0,0 +1,240 @@
+//! Machine-readable output helpers
+
+use crate::error::Result;
+use futures::{Stream, TryStreamExt};
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Write items as newline-delimited JSON, flushing after each line so
+/// consumers can process results as they arrive
+pub fn write_ndjson<'a, T, I, W>(writer: &mut W, items: I) -> Result<()>
+where
+    T: Serialize + 'a,
+    I: IntoIterator<Item = &'a T>,
+    W: Write,
+{
+    for item in items {
+        serde_json::to_writer(&mut *writer, item)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Destination for `--json` and `--ndjson` output: stdout, or the file given
+/// with `--output-file`
+#[derive(Debug, Clone, Default)]
+pub struct JsonOutput {
+    file: Option<PathBuf>,
+}
+
+impl JsonOutput {
+    pub fn new(file: Option<PathBuf>) -> Self {
+        Self { file }
+    }
+
+    /// Write a value as pretty-printed JSON
+    pub fn write<T: Serialize + ?Sized>(&self, value: &T) -> Result<()> {
+        let json = serde_json::to_string_pretty(value)?;
+        match &self.file {
+            Some(path) => write_atomic(path, format!("{}\n", json).as_bytes()),
+            None => {
+                println!("{}", json);
+                Ok(())
+            }
+        }
+    }
+
+    /// Write items as newline-delimited JSON
+    pub fn write_ndjson<'a, T, I>(&self, items: I) -> Result<()>
+    where
+        T: Serialize + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        match &self.file {
+            Some(path) => {
+                let mut buffer = Vec::new();
+                write_ndjson(&mut buffer, items)?;
+                write_atomic(path, &buffer)
+            }
+            None => write_ndjson(&mut std::io::stdout().lock(), items),
+        }
+    }
+
+    /// Write a stream of items as newline-delimited JSON. On stdout each line
+    /// is written as soon as its item arrives; a file is still replaced in one
+    /// step once the stream ends. Returns the number of items written.
+    pub async fn write_ndjson_stream<T, S>(&self, mut items: S) -> Result<usize>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T>> + Unpin,
+    {
+        let mut count = 0;
+        match &self.file {
+            Some(path) => {
+                let mut buffer = Vec::new();
+                while let Some(item) = items.try_next().await? {
+                    write_ndjson(&mut buffer, [&item])?;
+                    count += 1;
+                }
+                write_atomic(path, &buffer)?;
+            }
+            None => {
+                while let Some(item) = items.try_next().await? {
+                    write_ndjson(&mut std::io::stdout().lock(), [&item])?;
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Write a file through a temporary sibling and a rename, so readers never
+/// see it half-written
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    let written = std::fs::write(&tmp, contents).and_then(|()| std::fs::rename(&tmp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    Ok(written?)
+}
+
+/// Whether human output on stdout should use ANSI colors; honors `NO_COLOR`
+pub fn color_enabled() -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !no_color && std::io::stdout().is_terminal()
+}
+
+/// A byte count in binary units, e.g. `1.5 KiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rank::RankedResult;
+    use serde_json::Value;
+
+    fn ranked_result(id: &str, score: f32) -> RankedResult {
+        RankedResult {
+            id: id.to_string(),
+            score,
+            vector_score: Some(score),
+            bm25_score: None,
+            fused_score: None,
+            boost_score: None,
+            rerank_score: None,
+            doc_uri: format!("/docs/{}.md", id),
+            title: Some(format!("Doc {}", id)),
+            chunk_text: "Some text\nacross lines".to_string(),
+            headings: None,
+            chunk_index: 0,
+            source_id: "src".to_string(),
+            source_type: "dir".to_string(),
+            source_uri: "/docs".to_string(),
+            modality: None,
+            media_url: None,
+            media_hash: None,
+            snippet: None,
+            context: None,
+            aliases: Vec::new(),
+            model_id: None,
+            model_mismatch: false,
+        }
+    }
+
+    #[test]
+    fn test_json_output_replaces_file_atomically() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("results.json");
+        std::fs::write(&path, "stale").unwrap();
+        let output = JsonOutput::new(Some(path.clone()));
+
+        output.write(&vec![ranked_result("a", 0.9)]).unwrap();
+        let parsed: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed[0]["id"], "a");
+
+        output
+            .write_ndjson(&[ranked_result("a", 0.9), ranked_result("b", 0.5)])
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+
+        // Only the target file is left behind
+        let entries = std::fs::read_dir(tmp.path()).unwrap().count();
+        assert_eq!(entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_write_ndjson_stream_writes_each_item_and_keeps_file_on_error() {
+        use futures::stream;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("results.ndjson");
+        let output = JsonOutput::new(Some(path.clone()));
+
+        let items = stream::iter(vec![
+            Ok(ranked_result("a", 0.9)),
+            Ok(ranked_result("b", 0.5)),
+        ]);
+        assert_eq!(output.write_ndjson_stream(items).await.unwrap(), 2);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let ids: Vec<String> = contents
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["id"].to_string())
+            .collect();
+        assert_eq!(ids, vec!["\"a\"", "\"b\""]);
+
+        // A failure part way through leaves the previous file in place
+        let items = stream::iter(vec![
+            Ok(ranked_result("c", 0.9)),
+            Err(crate::error::Error::Timeout("too slow".to_string())),
+        ]);
+        assert!(output.write_ndjson_stream(items).await.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_write_ndjson_emits_one_object_per_line() {
+        let results = vec![ranked_result("a", 0.9), ranked_result("b", 0.8)];
+        let mut out = Vec::new();
+        write_ndjson(&mut out, &results).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, expected) in lines.iter().zip(&results) {
+            let value: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["id"], expected.id.as_str());
+            assert_eq!(value["doc_uri"], expected.doc_uri.as_str());
+            assert!(value["score"].is_number());
+            assert_eq!(value["chunk_text"], "Some text\nacross lines");
+        }
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,372 @@
+//! AsciiDoc parsing and text extraction
+//!
+//! A line-oriented reader covering the structure that matters for retrieval:
+//! section titles, delimited listing/literal blocks, lists, and links. Other
+//! delimited blocks (examples, sidebars, quotes, tables) keep their content
+//! with the delimiters dropped.
+
+use super::{CodeBlock, ContentType, ExtractedLink, Heading, ParsedDocument};
+use crate::error::Result;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+/// Matches `https://url[text]`, `link:url[text]`, and `xref:target[text]` macros
+fn link_macro_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?:(link|xref):([^\s\[]+)|((?:https?|ftp|mailto):[^\s\[]+))\[([^\]]*)\]")
+            .unwrap()
+    })
+}
+
+/// Matches `<<anchor>>` and `<<anchor,text>>` cross references
+fn xref_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<<([^,>]+)(?:,\s*([^>]*))?>>").unwrap())
+}
+
+/// Parse AsciiDoc content and extract text
+pub fn parse_asciidoc(content: &str) -> Result<ParsedDocument> {
+    let mut doc = ParsedDocument::new(String::new(), ContentType::AsciiDoc);
+    let mut text = String::new();
+
+    // Language from a `[source,lang]` attribute line, applied to the next block
+    let mut pending_language: Option<Option<String>> = None;
+    let mut lines = content.lines();
+
+    while let Some(raw) = lines.next() {
+        let line = raw.trim_end();
+
+        // Comment blocks and line comments
+        if line == "////" {
+            for inner in lines.by_ref() {
+                if inner.trim_end() == "////" {
+                    break;
+                }
+            }
+            continue;
+        }
+        if line.starts_with("//") {
+            continue;
+        }
+
+        // Listing and literal blocks become code blocks
+        if is_delimiter(line, '-') || is_delimiter(line, '.') {
+            let delimiter = line.to_string();
+            let mut code = Vec::new();
+            for inner in lines.by_ref() {
+                if inner.trim_end() == delimiter {
+                    break;
+                }
+                code.push(inner);
+            }
+            let code = code.join("\n");
+            let language = if delimiter.starts_with('-') {
+                pending_language.take().flatten()
+            } else {
+                pending_language = None;
+                None
+            };
+
+            push_break(&mut text);
+            text.push_str("```\n");
+            doc.code_blocks.push(CodeBlock {
+                language,
+                content: code.clone(),
+                position: text.len(),
+            });
+            text.push_str(&code);
+            text.push_str("\n```\n\n");
+            continue;
+        }
+
+        // Other delimited blocks: keep the content, drop the fences
+        if ['=', '*', '_', '+'].iter().any(|&c| is_delimiter(line, c)) || line.starts_with("|===") {
+            continue;
+        }
+
+        // Block attribute lines such as `[source,rust]` or `[NOTE]`
+        if line.starts_with('[') && line.ends_with(']') && !line.starts_with("[[") {
+            pending_language = parse_source_attribute(line);
+            continue;
+        }
+
+        // Document attributes (`:toc: left`)
+        if is_attribute_entry(line) {
+            continue;
+        }
+
+        // Section titles: `= Title` (level 1) through `======` (level 6)
+        if let Some((level, title)) = parse_section_title(line) {
+            let title = replace_links(title, &mut doc.links);
+            if doc.title.is_none() && level == 1 {
+                doc.title = Some(title.clone());
+            }
+            push_break(&mut text);
+            doc.headings.push(Heading {
+                level,
+                text: title.clone(),
+                position: text.len(),
+            });
+            text.push_str(&title);
+            text.push_str("\n\n");
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            push_break(&mut text);
+            continue;
+        }
+
+        let line = line.trim_start();
+
+        // Block titles (`.Example`) read as a standalone line
+        if let Some(title) = line.strip_prefix('.').filter(|t| is_block_title(t)) {
+            push_break(&mut text);
+            text.push_str(&replace_links(title, &mut doc.links));
+            text.push('\n');
+            continue;
+        }
+
+        // List items
+        let line = match list_item_text(line) {
+            Some(item) => {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str("• ");
+                item
+            }
+            None => {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push(' ');
+                }
+                line
+            }
+        };
+        text.push_str(&replace_links(line, &mut doc.links));
+    }
+
+    doc.text = text.trim_end().to_string();
+    Ok(doc)
+}
+
+/// End the current paragraph with a blank line
+fn push_break(text: &mut String) {
+    if text.is_empty() || text.ends_with("\n\n") {
+        return;
+    }
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push('\n');
+}
+
+/// Delimiter lines are four or more of the same character
+fn is_delimiter(line: &str, c: char) -> bool {
+    line.len() >= 4 && line.chars().all(|ch| ch == c)
+}
+
+fn parse_section_title(line: &str) -> Option<(u8, &str)> {
+    let marks = line.chars().take_while(|&c| c == '=').count();
+    if marks == 0 || marks > 6 {
+        return None;
+    }
+    let title = line[marks..].strip_prefix(' ')?.trim();
+    if title.is_empty() {
+        return None;
+    }
+    Some((marks as u8, title))
+}
+
+/// `Some(language)` for `[source]`-style attributes, `None` for any other block attribute
+fn parse_source_attribute(line: &str) -> Option<Option<String>> {
+    let inner = &line[1..line.len() - 1];
+    let mut parts = inner.split(',').map(str::trim);
+    let style = parts.next()?;
+    if style != "source" && style != "listing" {
+        return None;
+    }
+    Some(
+        parts
+            .next()
+            .filter(|lang| !lang.is_empty() && !lang.contains('='))
+            .map(str::to_string),
+    )
+}
+
+fn is_attribute_entry(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix(':') else {
+        return false;
+    };
+    match rest.find(':') {
+        Some(end) => {
+            let name = &rest[..end];
+            !name.is_empty()
+                && name
+                    .trim_start_matches('!')
+                    .trim_end_matches('!')
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        }
+        None => false,
+    }
+}
+
+fn is_block_title(title: &str) -> bool {
+    title
+        .chars()
+        .next()
+        .is_some_and(|c| !c.is_whitespace() && c != '.')
+}
+
+fn list_item_text(line: &str) -> Option<&str> {
+    let marker_len = line
+        .chars()
+        .take_while(|&c| c == '*' || c == '-' || c == '.')
+        .count();
+    if marker_len == 0 {
+        return None;
+    }
+    line[marker_len..]
+        .strip_prefix(' ')
+        .map(str::trim_start)
+        .filter(|item| !item.is_empty())
+}
+
+/// Replace link macros with their text, recording each link
+fn replace_links(line: &str, links: &mut Vec<ExtractedLink>) -> String {
+    let line = link_macro_re().replace_all(line, |caps: &Captures| {
+        let is_xref = caps.get(1).is_some_and(|m| m.as_str() == "xref");
+        let url = caps
+            .get(2)
+            .or_else(|| caps.get(3))
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default();
+        let label = caps.get(4).map(|m| m.as_str().trim()).unwrap_or_default();
+        let display = if label.is_empty() {
+            url.clone()
+        } else {
+            label.to_string()
+        };
+        links.push(ExtractedLink {
+            is_internal: is_xref || !url.contains("://"),
+            text: (!label.is_empty()).then(|| label.to_string()),
+            url,
+        });
+        display
+    });
+
+    xref_re()
+        .replace_all(&line, |caps: &Captures| {
+            let anchor = caps[1].trim().to_string();
+            let label = caps.get(2).map(|m| m.as_str().trim().to_string());
+            links.push(ExtractedLink {
+                url: format!("#{}", anchor),
+                text: label.clone(),
+                is_internal: true,
+            });
+            label.unwrap_or(anchor)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"= Runtime Guide
+:toc: left
+:source-highlighter: rouge
+
+The runtime drives futures to completion. See https://tokio.rs[the Tokio site].
+
+== Getting Started
+
+// Internal note, not rendered
+Add the dependency:
+
+[source,toml]
+----
+[dependencies]
+tokio = { version = "1", features = ["full"] }
+----
+
+=== Spawning Tasks
+
+* Use `tokio::spawn` for concurrent work
+* Read <<shutdown,Graceful shutdown>> before exiting
+
+....
+literal output
+....
+
+==== Blocking Code
+
+[NOTE]
+====
+Blocking calls stall the worker thread.
+====
+
+== Reference
+"#;
+
+    #[test]
+    fn test_asciidoc_heading_levels() {
+        let doc = parse_asciidoc(SAMPLE).unwrap();
+
+        let headings: Vec<(u8, &str)> = doc
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str()))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                (1, "Runtime Guide"),
+                (2, "Getting Started"),
+                (3, "Spawning Tasks"),
+                (4, "Blocking Code"),
+                (2, "Reference"),
+            ]
+        );
+        assert_eq!(doc.title.as_deref(), Some("Runtime Guide"));
+        for heading in &doc.headings {
+            assert!(doc.text[heading.position..].starts_with(&heading.text));
+        }
+    }
+
+    #[test]
+    fn test_asciidoc_code_blocks() {
+        let doc = parse_asciidoc(SAMPLE).unwrap();
+
+        assert_eq!(doc.code_blocks.len(), 2);
+        assert_eq!(doc.code_blocks[0].language.as_deref(), Some("toml"));
+        assert_eq!(
+            doc.code_blocks[0].content,
+            "[dependencies]\ntokio = { version = \"1\", features = [\"full\"] }"
+        );
+        assert!(doc.text[doc.code_blocks[0].position..].starts_with("[dependencies]"));
+        assert_eq!(doc.code_blocks[1].language, None);
+        assert_eq!(doc.code_blocks[1].content, "literal output");
+    }
+
+    #[test]
+    fn test_asciidoc_text_and_links() {
+        let doc = parse_asciidoc(SAMPLE).unwrap();
+
+        assert!(doc.text.contains("See the Tokio site."));
+        assert!(doc.text.contains("• Read Graceful shutdown before exiting"));
+        assert!(doc.text.contains("Blocking calls stall the worker thread."));
+        assert!(!doc.text.contains(":toc:"));
+        assert!(!doc.text.contains("Internal note"));
+        assert!(!doc.text.contains("[NOTE]"));
+
+        assert_eq!(doc.links.len(), 2);
+        assert_eq!(doc.links[0].url, "https://tokio.rs");
+        assert_eq!(doc.links[0].text.as_deref(), Some("the Tokio site"));
+        assert!(!doc.links[0].is_internal);
+        assert_eq!(doc.links[1].url, "#shutdown");
+        assert!(doc.links[1].is_internal);
+    }
+}
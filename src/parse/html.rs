@@ -15,12 +15,7 @@ pub fn parse_html(content: &str, base_url: Option<&str>) -> Result<ParsedDocumen
     let document = Html::parse_document(content);
     let mut doc = ParsedDocument::new(String::new(), ContentType::Html);
 
-    // Extract title
-    if let Ok(selector) = Selector::parse("title") {
-        if let Some(title_elem) = document.select(&selector).next() {
-            doc.title = Some(title_elem.text().collect::<String>().trim().to_string());
-        }
-    }
+    doc.title = extract_title(&document);
 
     // Remove script and style elements from consideration
     let body_selector = Selector::parse("body").ok();
@@ -78,47 +73,7 @@ pub fn parse_html(content: &str, base_url: Option<&str>) -> Result<ParsedDocumen
         }
     }
 
-    // Extract links
-    if let Ok(selector) = Selector::parse("a[href]") {
-        let base = base_url.and_then(|u| Url::parse(u).ok());
-
-        for elem in document.select(&selector) {
-            if let Some(href) = elem.value().attr("href") {
-                let link_text = elem.text().collect::<String>().trim().to_string();
-                let link_text = if link_text.is_empty() {
-                    None
-                } else {
-                    Some(link_text)
-                };
-
-                // Resolve relative URLs
-                let url = if let Some(ref base) = base {
-                    base.join(href)
-                        .map(|u| u.to_string())
-                        .unwrap_or_else(|_| href.to_string())
-                } else {
-                    href.to_string()
-                };
-
-                // Determine if internal
-                let is_internal = if let Some(ref base) = base {
-                    if let Ok(link_url) = Url::parse(&url) {
-                        link_url.host() == base.host()
-                    } else {
-                        href.starts_with('/') || href.starts_with('#') || !href.contains("://")
-                    }
-                } else {
-                    !href.contains("://")
-                };
-
-                doc.links.push(ExtractedLink {
-                    url,
-                    text: link_text,
-                    is_internal,
-                });
-            }
-        }
-    }
+    doc.links = extract_links(&document, base_url);
 
     // Extract image/media candidates (img, picture/srcset, inline CSS backgrounds)
     // IMG tags with src
@@ -225,6 +180,70 @@ pub fn parse_html(content: &str, base_url: Option<&str>) -> Result<ParsedDocumen
     Ok(doc)
 }
 
+/// Extract just the title and links of an HTML page
+///
+/// This is all a crawl needs to move on; the full [`parse_html`] waits until
+/// the page is ingested, and is skipped for pages whose content is unchanged.
+pub fn parse_html_links(
+    content: &str,
+    base_url: Option<&str>,
+) -> (Option<String>, Vec<ExtractedLink>) {
+    let document = Html::parse_document(content);
+    (extract_title(&document), extract_links(&document, base_url))
+}
+
+fn extract_title(document: &Html) -> Option<String> {
+    let selector = Selector::parse("title").ok()?;
+    let title_elem = document.select(&selector).next()?;
+    Some(title_elem.text().collect::<String>().trim().to_string())
+}
+
+/// Links from `a[href]` elements, resolved against `base_url`
+fn extract_links(document: &Html, base_url: Option<&str>) -> Vec<ExtractedLink> {
+    let mut links = Vec::new();
+    if let Ok(selector) = Selector::parse("a[href]") {
+        let base = base_url.and_then(|u| Url::parse(u).ok());
+
+        for elem in document.select(&selector) {
+            if let Some(href) = elem.value().attr("href") {
+                let link_text = elem.text().collect::<String>().trim().to_string();
+                let link_text = if link_text.is_empty() {
+                    None
+                } else {
+                    Some(link_text)
+                };
+
+                // Resolve relative URLs
+                let url = if let Some(ref base) = base {
+                    base.join(href)
+                        .map(|u| u.to_string())
+                        .unwrap_or_else(|_| href.to_string())
+                } else {
+                    href.to_string()
+                };
+
+                // Determine if internal
+                let is_internal = if let Some(ref base) = base {
+                    if let Ok(link_url) = Url::parse(&url) {
+                        link_url.host() == base.host()
+                    } else {
+                        href.starts_with('/') || href.starts_with('#') || !href.contains("://")
+                    }
+                } else {
+                    !href.contains("://")
+                };
+
+                links.push(ExtractedLink {
+                    url,
+                    text: link_text,
+                    is_internal,
+                });
+            }
+        }
+    }
+    links
+}
+
 /// Extract just the text content from HTML (simpler version)
 pub fn extract_text_from_html(content: &str) -> String {
     let text = html2text::from_read(content.as_bytes(), 80).unwrap_or_else(|_| content.to_string());
@@ -277,6 +296,13 @@ mod tests {
         assert_eq!(doc.links.len(), 3);
         assert!(doc.links[0].is_internal);
         assert!(!doc.links[1].is_internal);
+
+        // The crawler's lighter pass finds the same links
+        let (title, links) = parse_html_links(html, Some("https://example.com"));
+        assert_eq!(title, None);
+        let urls =
+            |links: &[ExtractedLink]| links.iter().map(|l| l.url.clone()).collect::<Vec<_>>();
+        assert_eq!(urls(&links), urls(&doc.links));
     }
 
     #[test]
@@ -1,12 +1,12 @@
 //! Markdown parsing and text extraction
 
-use super::{CodeBlock, ContentType, ExtractedLink, Heading, ParsedDocument};
+use super::{CodeBlock, ContentType, ExtractedLink, Heading, ParsedDocument, Table};
 use crate::error::Result;
-use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 
 /// Parse Markdown content and extract text
 pub fn parse_markdown(content: &str) -> Result<ParsedDocument> {
-    let parser = Parser::new(content);
+    let parser = Parser::new_ext(content, Options::ENABLE_TABLES);
     let mut doc = ParsedDocument::new(String::new(), ContentType::Markdown);
 
     let mut text_parts: Vec<String> = Vec::new();
@@ -16,6 +16,8 @@ pub fn parse_markdown(content: &str) -> Result<ParsedDocument> {
     let mut code_language: Option<String> = None;
     let mut current_link_url: Option<String> = None;
     let mut current_link_text: Vec<String> = Vec::new();
+    let mut table_rows: Option<Vec<Vec<String>>> = None;
+    let mut current_cell: Option<String> = None;
     let mut char_position = 0;
 
     for event in parser {
@@ -93,6 +95,38 @@ pub fn parse_markdown(content: &str) -> Result<ParsedDocument> {
                     current_link_text.clear();
                 }
             }
+            Event::Start(Tag::Table(_)) => {
+                table_rows = Some(Vec::new());
+            }
+            Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => {
+                if let Some(rows) = table_rows.as_mut() {
+                    rows.push(Vec::new());
+                }
+            }
+            Event::Start(Tag::TableCell) => {
+                current_cell = Some(String::new());
+            }
+            Event::End(TagEnd::TableCell) => {
+                if let (Some(cell), Some(row)) = (
+                    current_cell.take(),
+                    table_rows.as_mut().and_then(|rows| rows.last_mut()),
+                ) {
+                    row.push(cell.split_whitespace().collect::<Vec<_>>().join(" "));
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                if let Some(rows) = table_rows.take() {
+                    let rendered = render_table(&rows);
+                    if !rendered.is_empty() {
+                        doc.tables.push(Table {
+                            content: rendered.clone(),
+                            position: char_position + 1,
+                        });
+                        char_position += rendered.len() + 3;
+                        text_parts.push(format!("\n{}\n\n", rendered));
+                    }
+                }
+            }
             Event::Text(text) => {
                 let text_str = text.to_string();
 
@@ -100,6 +134,11 @@ pub fn parse_markdown(content: &str) -> Result<ParsedDocument> {
                     parts.push(text_str.clone());
                 } else if in_code_block {
                     current_code.push(text_str);
+                } else if let Some(cell) = current_cell.as_mut() {
+                    cell.push_str(&text_str);
+                    if current_link_url.is_some() {
+                        current_link_text.push(text_str);
+                    }
                 } else if current_link_url.is_some() {
                     current_link_text.push(text_str.clone());
                     text_parts.push(text_str.clone());
@@ -113,6 +152,8 @@ pub fn parse_markdown(content: &str) -> Result<ParsedDocument> {
                 let code_str = format!("`{}`", code);
                 if let Some((_, ref mut parts)) = current_heading {
                     parts.push(code.to_string());
+                } else if let Some(cell) = current_cell.as_mut() {
+                    cell.push_str(&code_str);
                 } else {
                     text_parts.push(code_str.clone());
                     char_position += code_str.len();
@@ -144,10 +185,60 @@ pub fn parse_markdown(content: &str) -> Result<ParsedDocument> {
         }
     }
 
-    doc.text = text_parts.join("").trim().to_string();
+    let text = text_parts.join("");
+    let leading = text.len() - text.trim_start().len();
+    for table in &mut doc.tables {
+        table.position -= leading;
+    }
+    doc.text = text.trim().to_string();
     Ok(doc)
 }
 
+/// Render table rows as a pipe table with aligned columns, so each value
+/// stays visibly associated with its header
+fn render_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let cell = |row: &[String], col: usize| -> String {
+        row.get(col)
+            .map(|c| c.replace('|', "\\|"))
+            .unwrap_or_default()
+    };
+    let widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            rows.iter()
+                .map(|row| cell(row, col).chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+
+    let render_row = |values: Vec<String>| -> String {
+        let padded: Vec<String> = values
+            .iter()
+            .zip(&widths)
+            .map(|(value, &width)| {
+                let pad = width - value.chars().count();
+                format!("{}{}", value, " ".repeat(pad))
+            })
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    for (i, row) in rows.iter().enumerate() {
+        lines.push(render_row((0..columns).map(|col| cell(row, col)).collect()));
+        if i == 0 {
+            lines.push(render_row(widths.iter().map(|&w| "-".repeat(w)).collect()));
+        }
+    }
+    lines.join("\n")
+}
+
 fn heading_level_to_u8(level: HeadingLevel) -> u8 {
     match level {
         HeadingLevel::H1 => 1,
@@ -219,4 +310,29 @@ fn main() {
         assert_eq!(doc.code_blocks[0].language, Some("python".to_string()));
         assert_eq!(doc.code_blocks[1].language, None);
     }
+
+    #[test]
+    fn test_tables_keep_rows_and_columns() {
+        let markdown = "Intro.\n\n\
+            | Flag | Effect |\n\
+            |------|--------|\n\
+            | `--json` | Machine-readable [output](#output) |\n\
+            | `-k` | Number of results |\n\n\
+            After.";
+        let doc = parse_markdown(markdown).unwrap();
+
+        assert_eq!(doc.tables.len(), 1);
+        let table = &doc.tables[0];
+        assert_eq!(
+            table.content,
+            "| Flag     | Effect                  |\n\
+             | -------- | ----------------------- |\n\
+             | `--json` | Machine-readable output |\n\
+             | `-k`     | Number of results       |"
+        );
+        assert!(doc.text[table.position..].starts_with(&table.content));
+        assert!(doc.text.starts_with("Intro."));
+        assert!(doc.text.ends_with("After."));
+        assert_eq!(doc.links[0].url, "#output");
+    }
 }
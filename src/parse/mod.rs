@@ -3,15 +3,27 @@
 //! This module handles:
 //! - HTML parsing and text extraction
 //! - Markdown processing
+//! - AsciiDoc processing
+//! - reStructuredText processing
+//! - Jupyter notebook processing
+//! - OpenAPI / Swagger spec processing
 //! - Plain text normalization
 //! - Content type detection
 
+mod asciidoc;
 mod html;
 mod markdown;
+mod notebook;
+mod openapi;
+mod rst;
 mod text;
 
+pub use asciidoc::*;
 pub use html::*;
 pub use markdown::*;
+pub use notebook::*;
+pub use openapi::*;
+pub use rst::*;
 pub use text::*;
 
 use crate::error::Result;
@@ -22,6 +34,9 @@ use std::path::Path;
 pub enum ContentType {
     Html,
     Markdown,
+    AsciiDoc,
+    Rst,
+    Notebook,
     PlainText,
     Unknown,
 }
@@ -32,8 +47,10 @@ impl ContentType {
         match path.extension().and_then(|e| e.to_str()) {
             Some("html") | Some("htm") => ContentType::Html,
             Some("md") | Some("markdown") | Some("mdx") => ContentType::Markdown,
+            Some("adoc") | Some("asciidoc") => ContentType::AsciiDoc,
             Some("txt") | Some("text") => ContentType::PlainText,
-            Some("rst") => ContentType::PlainText, // Treat RST as plain text for now
+            Some("rst") | Some("rest") => ContentType::Rst,
+            Some("ipynb") => ContentType::Notebook,
             _ => ContentType::Unknown,
         }
     }
@@ -45,6 +62,10 @@ impl ContentType {
             ContentType::Html
         } else if mime_lower.contains("text/markdown") {
             ContentType::Markdown
+        } else if mime_lower.contains("text/asciidoc") {
+            ContentType::AsciiDoc
+        } else if mime_lower.contains("text/x-rst") {
+            ContentType::Rst
         } else if mime_lower.contains("text/plain") {
             ContentType::PlainText
         } else {
@@ -92,6 +113,9 @@ pub struct ParsedDocument {
     /// Code blocks with language info
     pub code_blocks: Vec<CodeBlock>,
 
+    /// Tables rendered into the text, kept whole when chunking
+    pub tables: Vec<Table>,
+
     /// Links found in the document
     pub links: Vec<ExtractedLink>,
 
@@ -125,6 +149,23 @@ pub struct CodeBlock {
     pub position: usize,
 }
 
+/// A table rendered as aligned rows in the extracted text
+#[derive(Debug, Clone)]
+pub struct Table {
+    /// Rendered table text, header row first
+    pub content: String,
+
+    /// Character position in the extracted text
+    pub position: usize,
+}
+
+impl Table {
+    /// Byte range the table occupies in the extracted text
+    pub fn span(&self) -> (usize, usize) {
+        (self.position, self.position + self.content.len())
+    }
+}
+
 /// An extracted link
 #[derive(Debug, Clone)]
 pub struct ExtractedLink {
@@ -162,6 +203,7 @@ impl ParsedDocument {
             content_type,
             headings: Vec::new(),
             code_blocks: Vec::new(),
+            tables: Vec::new(),
             links: Vec::new(),
             media: Vec::new(),
         }
@@ -194,6 +236,9 @@ pub fn parse_content(
     match content_type {
         ContentType::Html => parse_html(content, base_url),
         ContentType::Markdown => parse_markdown(content),
+        ContentType::AsciiDoc => parse_asciidoc(content),
+        ContentType::Rst => parse_rst(content),
+        ContentType::Notebook => parse_notebook(content, false),
         ContentType::PlainText | ContentType::Unknown => Ok(parse_plain_text(content)),
     }
 }
@@ -205,6 +250,11 @@ pub fn is_binary_content(data: &[u8]) -> bool {
     data[..check_len].iter().any(|&b| b == 0)
 }
 
+/// Check if PDF extraction feature is available
+pub fn is_pdf_available() -> bool {
+    cfg!(feature = "pdf")
+}
+
 /// Check if file should be skipped based on extension
 pub fn should_skip_file(path: &Path) -> bool {
     let skip_extensions = [
@@ -277,6 +327,22 @@ mod tests {
             ContentType::from_extension(Path::new("test.md")),
             ContentType::Markdown
         );
+        assert_eq!(
+            ContentType::from_extension(Path::new("guide.adoc")),
+            ContentType::AsciiDoc
+        );
+        assert_eq!(
+            ContentType::from_extension(Path::new("guide.asciidoc")),
+            ContentType::AsciiDoc
+        );
+        assert_eq!(
+            ContentType::from_extension(Path::new("index.rst")),
+            ContentType::Rst
+        );
+        assert_eq!(
+            ContentType::from_extension(Path::new("analysis.ipynb")),
+            ContentType::Notebook
+        );
         assert_eq!(
             ContentType::from_extension(Path::new("test.txt")),
             ContentType::PlainText
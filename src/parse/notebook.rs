@@ -0,0 +1,273 @@
+//! Jupyter notebook (`.ipynb`) parsing
+//!
+//! Markdown cells are parsed as Markdown and become prose, with their headings
+//! and links kept. Code cells become fenced code blocks tagged with the
+//! notebook's kernel language. Cell outputs are optional since they are often
+//! large and noisy.
+
+use super::{parse_markdown, CodeBlock, ContentType, Heading, ParsedDocument};
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct Notebook {
+    #[serde(default)]
+    cells: Vec<Cell>,
+    #[serde(default)]
+    metadata: NotebookMetadata,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NotebookMetadata {
+    kernelspec: Option<KernelSpec>,
+    language_info: Option<LanguageInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KernelSpec {
+    language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LanguageInfo {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cell {
+    cell_type: String,
+    #[serde(default)]
+    source: MultilineText,
+    #[serde(default)]
+    outputs: Vec<Output>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Output {
+    output_type: String,
+    #[serde(default)]
+    text: Option<MultilineText>,
+    #[serde(default)]
+    data: Option<serde_json::Map<String, Value>>,
+    #[serde(default)]
+    ename: Option<String>,
+    #[serde(default)]
+    evalue: Option<String>,
+}
+
+/// Notebook text fields are either a string or a list of lines
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MultilineText {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for MultilineText {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl MultilineText {
+    fn join(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Lines(lines) => lines.concat(),
+        }
+    }
+}
+
+impl Output {
+    fn text(&self) -> Option<String> {
+        let text = match self.output_type.as_str() {
+            "stream" => self.text.as_ref().map(MultilineText::join),
+            "execute_result" | "display_data" => self
+                .data
+                .as_ref()
+                .and_then(|data| data.get("text/plain"))
+                .and_then(|v| serde_json::from_value::<MultilineText>(v.clone()).ok())
+                .map(|t| t.join()),
+            "error" => Some(format!(
+                "{}: {}",
+                self.ename.as_deref().unwrap_or("Error"),
+                self.evalue.as_deref().unwrap_or_default()
+            )),
+            _ => None,
+        }?;
+        let text = text.trim_end();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+/// Parse a Jupyter notebook, optionally including code cell outputs
+pub fn parse_notebook(content: &str, include_outputs: bool) -> Result<ParsedDocument> {
+    let notebook: Notebook = serde_json::from_str(content)
+        .map_err(|e| Error::Parse(format!("Invalid notebook JSON: {}", e)))?;
+    let language = notebook
+        .metadata
+        .kernelspec
+        .and_then(|k| k.language)
+        .or_else(|| notebook.metadata.language_info.and_then(|l| l.name))
+        .filter(|l| !l.is_empty());
+
+    let mut doc = ParsedDocument::new(String::new(), ContentType::Notebook);
+    let mut text = String::new();
+
+    for cell in &notebook.cells {
+        let source = cell.source.join();
+        if source.trim().is_empty() && cell.outputs.is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+
+        match cell.cell_type.as_str() {
+            "markdown" => {
+                let offset = text.len();
+                let parsed = parse_markdown(&source)?;
+                if doc.title.is_none() {
+                    doc.title = parsed.title;
+                }
+                doc.headings
+                    .extend(parsed.headings.into_iter().map(|h| Heading {
+                        position: offset + h.position,
+                        ..h
+                    }));
+                doc.code_blocks
+                    .extend(parsed.code_blocks.into_iter().map(|c| CodeBlock {
+                        position: offset + c.position,
+                        ..c
+                    }));
+                doc.links.extend(parsed.links);
+                text.push_str(&parsed.text);
+            }
+            "code" => {
+                let code = source.trim_end();
+                if !code.is_empty() {
+                    text.push_str("```");
+                    text.push_str(language.as_deref().unwrap_or_default());
+                    text.push('\n');
+                    doc.code_blocks.push(CodeBlock {
+                        language: language.clone(),
+                        content: code.to_string(),
+                        position: text.len(),
+                    });
+                    text.push_str(code);
+                    text.push_str("\n```");
+                }
+
+                if include_outputs {
+                    for output in cell.outputs.iter().filter_map(Output::text) {
+                        text.push_str("\n\nOutput:\n```\n");
+                        text.push_str(&output);
+                        text.push_str("\n```");
+                    }
+                }
+            }
+            // Raw cells are passed through as-is
+            _ => text.push_str(source.trim()),
+        }
+    }
+
+    doc.text = text.trim().to_string();
+    Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTEBOOK: &str = r###"{
+  "cells": [
+    {
+      "cell_type": "markdown",
+      "metadata": {},
+      "source": ["# Loading Data\n", "\n", "Read the CSV with [pandas](https://pandas.pydata.org)."]
+    },
+    {
+      "cell_type": "code",
+      "execution_count": 1,
+      "metadata": {},
+      "source": ["import pandas as pd\n", "df = pd.read_csv(\"data.csv\")\n", "df.shape"],
+      "outputs": [
+        {
+          "output_type": "execute_result",
+          "data": { "text/plain": ["(1200, 8)"] },
+          "execution_count": 1,
+          "metadata": {}
+        }
+      ]
+    },
+    {
+      "cell_type": "markdown",
+      "metadata": {},
+      "source": "## Cleaning\n\nDrop rows with missing values."
+    },
+    {
+      "cell_type": "code",
+      "execution_count": 2,
+      "metadata": {},
+      "source": "df = df.dropna()\nprint(len(df))",
+      "outputs": [
+        { "output_type": "stream", "name": "stdout", "text": ["1150\n"] }
+      ]
+    }
+  ],
+  "metadata": {
+    "kernelspec": { "display_name": "Python 3", "language": "python", "name": "python3" }
+  },
+  "nbformat": 4,
+  "nbformat_minor": 5
+}"###;
+
+    #[test]
+    fn test_notebook_markdown_and_code_cells() {
+        let doc = parse_notebook(NOTEBOOK, false).unwrap();
+
+        assert_eq!(doc.title.as_deref(), Some("Loading Data"));
+        assert!(doc.text.contains("Read the CSV with pandas."));
+        assert!(doc.text.contains("Drop rows with missing values."));
+        assert!(doc.text.contains("```python\nimport pandas as pd"));
+        assert!(!doc.text.contains("1150"));
+
+        let headings: Vec<(u8, &str)> = doc
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str()))
+            .collect();
+        assert_eq!(headings, vec![(1, "Loading Data"), (2, "Cleaning")]);
+        // The second heading is positioned within its own cell, after the first code cell
+        assert!(doc.headings[1].position > doc.code_blocks[0].position);
+
+        assert_eq!(doc.code_blocks.len(), 2);
+        assert!(doc
+            .code_blocks
+            .iter()
+            .all(|c| c.language.as_deref() == Some("python")));
+        assert_eq!(
+            doc.code_blocks[1].content,
+            "df = df.dropna()\nprint(len(df))"
+        );
+        assert!(doc.text[doc.code_blocks[1].position..].starts_with("df = df.dropna()"));
+        assert_eq!(doc.links[0].url, "https://pandas.pydata.org");
+    }
+
+    #[test]
+    fn test_notebook_outputs_are_optional() {
+        let doc = parse_notebook(NOTEBOOK, true).unwrap();
+        assert!(doc.text.contains("Output:\n```\n(1200, 8)\n```"));
+        assert!(doc.text.contains("Output:\n```\n1150\n```"));
+        assert_eq!(doc.code_blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_notebook_is_a_parse_error() {
+        assert!(matches!(
+            parse_notebook("not json", false),
+            Err(Error::Parse(_))
+        ));
+    }
+}
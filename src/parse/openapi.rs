@@ -0,0 +1,550 @@
+//! OpenAPI / Swagger spec parsing
+//!
+//! Each path + method pair becomes one operation with a Markdown body built
+//! from its summary, description, parameters, request body and responses.
+//! Both OpenAPI 3.x and Swagger 2.0 documents are read, as JSON or YAML.
+//! Local `$ref`s to parameters, request bodies and responses are resolved;
+//! schemas are shown by name, not expanded.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// HTTP methods an OpenAPI path item may define, in display order
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// One API operation (a path + method pair) from a spec
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiOperation {
+    /// Upper-case HTTP method
+    pub method: String,
+    /// Path template, e.g. `/pets/{petId}`
+    pub path: String,
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    /// Markdown body describing the operation
+    pub text: String,
+}
+
+/// The identity of an API operation, stored with its document so every
+/// chunk's payload can carry it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiEndpoint {
+    /// Upper-case HTTP method
+    pub method: String,
+    /// Path template, e.g. `/pets/{petId}`
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ApiOperation {
+    /// The operation's method, path, operation ID and tags
+    pub fn endpoint(&self) -> ApiEndpoint {
+        ApiEndpoint {
+            method: self.method.clone(),
+            path: self.path.clone(),
+            operation_id: self.operation_id.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+
+    /// Document title, e.g. `GET /pets/{petId}: Info for a specific pet`
+    pub fn title(&self) -> String {
+        match &self.summary {
+            Some(summary) => format!("{} {}: {}", self.method, self.path, summary),
+            None => format!("{} {}", self.method, self.path),
+        }
+    }
+
+    /// Stable document URI: the spec URI plus a JSON pointer to the operation
+    pub fn uri(&self, spec_uri: &str) -> String {
+        format!(
+            "{}#/paths/{}/{}",
+            spec_uri,
+            escape_pointer(&self.path),
+            self.method.to_lowercase()
+        )
+    }
+}
+
+/// Parse an OpenAPI 3.x or Swagger 2.0 document (JSON or YAML) into its
+/// operations, ordered by path and then method
+pub fn parse_openapi(text: &str) -> Result<Vec<ApiOperation>> {
+    let spec: Value = match serde_json::from_str(text) {
+        Ok(spec) => spec,
+        Err(_) => parse_yaml(text)
+            .map_err(|e| Error::Parse(format!("OpenAPI spec is neither JSON nor YAML: {}", e)))?,
+    };
+    if spec.get("openapi").is_none() && spec.get("swagger").is_none() {
+        return Err(Error::Parse(
+            "Not an OpenAPI spec: missing 'openapi' or 'swagger' version".to_string(),
+        ));
+    }
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or_else(|| Error::Parse("OpenAPI spec has no 'paths' object".to_string()))?;
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let item = resolve(&spec, item);
+        let shared_params = item.get("parameters").and_then(Value::as_array);
+        for method in METHODS {
+            let Some(op) = item.get(*method).and_then(Value::as_object) else {
+                continue;
+            };
+            operations.push(parse_operation(&spec, path, method, op, shared_params));
+        }
+    }
+    Ok(operations)
+}
+
+fn parse_operation(
+    spec: &Value,
+    path: &str,
+    method: &str,
+    op: &Map<String, Value>,
+    shared_params: Option<&Vec<Value>>,
+) -> ApiOperation {
+    let method = method.to_uppercase();
+    let summary = non_empty(op.get("summary"));
+    let description = non_empty(op.get("description"));
+    let operation_id = non_empty(op.get("operationId"));
+    let tags: Vec<String> = op
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let deprecated = op.get("deprecated").and_then(Value::as_bool) == Some(true);
+
+    let mut text = format!("# {} {}\n\n", method, path);
+    if let Some(summary) = &summary {
+        text.push_str(&format!("{}\n\n", summary));
+    }
+    if let Some(description) = &description {
+        text.push_str(&format!("{}\n\n", description));
+    }
+    if let Some(operation_id) = &operation_id {
+        text.push_str(&format!("Operation ID: `{}`\n\n", operation_id));
+    }
+    if !tags.is_empty() {
+        text.push_str(&format!("Tags: {}\n\n", tags.join(", ")));
+    }
+    if deprecated {
+        text.push_str("Deprecated: this operation should no longer be used.\n\n");
+    }
+
+    // Operation parameters override path-level ones with the same name and location
+    let mut params: Vec<&Value> = Vec::new();
+    let own_params = op.get("parameters").and_then(Value::as_array);
+    for param in own_params.into_iter().flatten() {
+        params.push(resolve(spec, param));
+    }
+    for param in shared_params.into_iter().flatten() {
+        let param = resolve(spec, param);
+        let key = param_key(param);
+        if !params.iter().any(|p| param_key(p) == key) {
+            params.push(param);
+        }
+    }
+
+    let mut body_lines = Vec::new();
+    let mut param_lines = Vec::new();
+    for param in params {
+        // Swagger 2.0 describes the request body as an `in: body` parameter
+        if param.get("in").and_then(Value::as_str) == Some("body") {
+            body_lines.push(describe_body(param));
+        } else {
+            param_lines.push(describe_param(param));
+        }
+    }
+    if let Some(body) = op.get("requestBody") {
+        body_lines.push(describe_body(resolve(spec, body)));
+    }
+
+    if !param_lines.is_empty() {
+        text.push_str(&format!("## Parameters\n\n{}\n\n", param_lines.join("\n")));
+    }
+    if !body_lines.is_empty() {
+        text.push_str(&format!(
+            "## Request body\n\n{}\n\n",
+            body_lines.join("\n\n")
+        ));
+    }
+    if let Some(responses) = op.get("responses").and_then(Value::as_object) {
+        let lines: Vec<String> = responses
+            .iter()
+            .map(|(status, response)| describe_response(status, resolve(spec, response)))
+            .collect();
+        if !lines.is_empty() {
+            text.push_str(&format!("## Responses\n\n{}\n", lines.join("\n")));
+        }
+    }
+
+    ApiOperation {
+        method,
+        path: path.to_string(),
+        operation_id,
+        summary,
+        tags,
+        deprecated,
+        text: text.trim_end().to_string(),
+    }
+}
+
+/// Parse the first YAML document in `text` into a JSON value
+fn parse_yaml(text: &str) -> std::result::Result<Value, String> {
+    let docs = YamlLoader::load_from_str(text).map_err(|e| e.to_string())?;
+    let doc = docs.into_iter().next().ok_or("empty document")?;
+    Ok(yaml_to_json(doc))
+}
+
+/// Convert a YAML node to JSON; mapping keys become strings, as in JSON
+fn yaml_to_json(yaml: Yaml) -> Value {
+    match yaml {
+        Yaml::Null | Yaml::BadValue | Yaml::Alias(_) => Value::Null,
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Integer(i) => Value::from(i),
+        Yaml::Real(s) => s
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map_or(Value::String(s), Value::Number),
+        Yaml::String(s) => Value::String(s),
+        Yaml::Array(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => Value::Object(
+            hash.into_iter()
+                .map(|(key, value)| {
+                    let key = match yaml_to_json(key) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+    }
+}
+
+fn describe_param(param: &Value) -> String {
+    let name = param.get("name").and_then(Value::as_str).unwrap_or("?");
+    let mut traits = Vec::new();
+    if let Some(location) = param.get("in").and_then(Value::as_str) {
+        traits.push(location.to_string());
+    }
+    if param.get("required").and_then(Value::as_bool) == Some(true) {
+        traits.push("required".to_string());
+    }
+    // OpenAPI 3 nests the type in `schema`; Swagger 2.0 puts it on the parameter
+    if let Some(kind) = param
+        .get("schema")
+        .and_then(schema_name)
+        .or_else(|| schema_name(param))
+    {
+        traits.push(kind);
+    }
+
+    let mut line = format!("- `{}`", name);
+    if !traits.is_empty() {
+        line.push_str(&format!(" ({})", traits.join(", ")));
+    }
+    if let Some(description) = non_empty(param.get("description")) {
+        line.push_str(&format!(": {}", description));
+    }
+    line
+}
+
+fn describe_body(body: &Value) -> String {
+    let mut parts = Vec::new();
+    if let Some(description) = non_empty(body.get("description")) {
+        parts.push(description);
+    }
+    if body.get("required").and_then(Value::as_bool) == Some(true) {
+        parts.push("Required.".to_string());
+    }
+    if let Some(content) = body.get("content").and_then(Value::as_object) {
+        for (media_type, media) in content {
+            match media.get("schema").and_then(schema_name) {
+                Some(schema) => parts.push(format!("`{}`: {}", media_type, schema)),
+                None => parts.push(format!("`{}`", media_type)),
+            }
+        }
+    } else if let Some(schema) = body.get("schema").and_then(schema_name) {
+        parts.push(format!("Schema: {}", schema));
+    }
+    parts.join(" ")
+}
+
+fn describe_response(status: &str, response: &Value) -> String {
+    let mut line = format!("- `{}`", status);
+    let mut kinds = Vec::new();
+    if let Some(content) = response.get("content").and_then(Value::as_object) {
+        for (media_type, media) in content {
+            match media.get("schema").and_then(schema_name) {
+                Some(schema) => kinds.push(format!("{} {}", media_type, schema)),
+                None => kinds.push(media_type.clone()),
+            }
+        }
+    } else if let Some(schema) = response.get("schema").and_then(schema_name) {
+        kinds.push(schema);
+    }
+    if !kinds.is_empty() {
+        line.push_str(&format!(" ({})", kinds.join(", ")));
+    }
+    if let Some(description) = non_empty(response.get("description")) {
+        line.push_str(&format!(": {}", description));
+    }
+    line
+}
+
+/// Short name for a schema: its `$ref` target, or its type
+fn schema_name(schema: &Value) -> Option<String> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference.rsplit('/').next().map(unescape_pointer);
+    }
+    let kind = schema.get("type").and_then(Value::as_str)?;
+    if kind == "array" {
+        let items = schema.get("items").and_then(schema_name);
+        return Some(match items {
+            Some(items) => format!("array of {}", items),
+            None => "array".to_string(),
+        });
+    }
+    Some(match schema.get("format").and_then(Value::as_str) {
+        Some(format) => format!("{} ({})", kind, format),
+        None => kind.to_string(),
+    })
+}
+
+/// Follow a local `$ref` (e.g. `#/components/parameters/limit`), keeping the
+/// value itself when it is not a reference or the target is missing
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    // Bounded so reference cycles cannot loop forever
+    for _ in 0..8 {
+        match current
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| spec.pointer(pointer))
+        {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+fn param_key(param: &Value) -> (Option<&str>, Option<&str>) {
+    (
+        param.get("name").and_then(Value::as_str),
+        param.get("in").and_then(Value::as_str),
+    )
+}
+
+fn non_empty(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Escape a JSON pointer segment (RFC 6901)
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = r##"
+openapi: 3.0.0
+info:
+  title: Petstore
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      summary: List all pets
+      operationId: listPets
+      tags: [pets]
+      parameters:
+        - $ref: "#/components/parameters/limit"
+      responses:
+        "200":
+          description: A paged array of pets
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Pets"
+    post:
+      summary: Create a pet
+      operationId: createPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "201":
+          description: Null response
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        description: The id of the pet to retrieve
+        schema:
+          type: string
+    get:
+      summary: Info for a specific pet
+      deprecated: true
+      responses:
+        "200":
+          description: Expected response to a valid request
+components:
+  parameters:
+    limit:
+      name: limit
+      in: query
+      description: How many items to return at one time (max 100)
+      schema:
+        type: integer
+        format: int32
+  schemas:
+    Pet:
+      type: object
+    Pets:
+      type: array
+"##;
+
+    #[test]
+    fn test_parse_openapi_one_operation_per_method() {
+        let operations = parse_openapi(PETSTORE).unwrap();
+        let endpoints: Vec<(&str, &str)> = operations
+            .iter()
+            .map(|op| (op.method.as_str(), op.path.as_str()))
+            .collect();
+        assert_eq!(
+            endpoints,
+            vec![
+                ("GET", "/pets"),
+                ("POST", "/pets"),
+                ("GET", "/pets/{petId}")
+            ]
+        );
+
+        let list = &operations[0];
+        assert_eq!(list.operation_id.as_deref(), Some("listPets"));
+        assert_eq!(list.tags, vec!["pets"]);
+        assert!(list.text.starts_with("# GET /pets\n\nList all pets"));
+        assert!(list
+            .text
+            .contains("- `limit` (query, integer (int32)): How many items to return"));
+        assert!(list
+            .text
+            .contains("- `200` (application/json Pets): A paged array of pets"));
+
+        let create = &operations[1];
+        assert!(create
+            .text
+            .contains("## Request body\n\nRequired. `application/json`: Pet"));
+
+        // Path-level parameters apply to each operation under the path
+        let show = &operations[2];
+        assert!(show.deprecated);
+        assert_eq!(show.title(), "GET /pets/{petId}: Info for a specific pet");
+        assert!(show
+            .text
+            .contains("- `petId` (path, required, string): The id of the pet to retrieve"));
+    }
+
+    #[test]
+    fn test_operation_uri_and_endpoint() {
+        let operations = parse_openapi(PETSTORE).unwrap();
+        let show = &operations[2];
+        assert_eq!(
+            show.uri("https://example.com/openapi.yaml"),
+            "https://example.com/openapi.yaml#/paths/~1pets~1{petId}/get"
+        );
+
+        let endpoint = show.endpoint();
+        assert_eq!(endpoint.method, "GET");
+        assert_eq!(endpoint.path, "/pets/{petId}");
+        assert_eq!(endpoint.operation_id, show.operation_id);
+        assert_eq!(endpoint.tags, show.tags);
+        let json = serde_json::to_string(&endpoint).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ApiEndpoint>(&json).unwrap(),
+            endpoint
+        );
+    }
+
+    #[test]
+    fn test_parse_swagger_json_and_reject_other_documents() {
+        let swagger = r##"{
+            "swagger": "2.0",
+            "paths": {
+                "/users": {
+                    "post": {
+                        "parameters": [
+                            {"name": "user", "in": "body", "description": "User to add", "schema": {"$ref": "#/definitions/User"}},
+                            {"name": "dryRun", "in": "query", "type": "boolean"}
+                        ],
+                        "responses": {"200": {"description": "OK", "schema": {"type": "array", "items": {"$ref": "#/definitions/User"}}}}
+                    }
+                }
+            }
+        }"##;
+        let operations = parse_openapi(swagger).unwrap();
+        assert_eq!(operations.len(), 1);
+        let text = &operations[0].text;
+        assert!(text.contains("## Parameters\n\n- `dryRun` (query, boolean)"));
+        assert!(text.contains("## Request body\n\nUser to add Schema: User"));
+        assert!(text.contains("- `200` (array of User): OK"));
+
+        assert!(parse_openapi("name: not a spec").is_err());
+        assert!(parse_openapi("{\"openapi\": \"3.0.0\"}").is_err());
+    }
+
+    #[test]
+    fn test_yaml_integer_keys_and_anchors() {
+        let spec = r#"
+openapi: 3.0.0
+paths:
+  /health:
+    get:
+      responses: &ok
+        200:
+          description: Healthy
+  /ready:
+    get:
+      responses: *ok
+"#;
+        let operations = parse_openapi(spec).unwrap();
+        assert_eq!(operations.len(), 2);
+        for operation in &operations {
+            assert!(operation.text.contains("- `200`: Healthy"));
+        }
+    }
+}
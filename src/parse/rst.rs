@@ -0,0 +1,449 @@
+//! reStructuredText parsing and text extraction
+//!
+//! Section levels are not fixed in RST: each new adornment style (underline
+//! character, with or without an overline) gets the next level in the order
+//! it first appears. Code directives and `::` literal blocks become code
+//! blocks; other directives are dropped, except admonitions, whose body is
+//! kept as text.
+
+use super::{CodeBlock, ContentType, ExtractedLink, Heading, ParsedDocument};
+use crate::error::Result;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+/// Directives whose body is prose worth indexing
+const ADMONITIONS: &[&str] = &[
+    "admonition",
+    "attention",
+    "caution",
+    "danger",
+    "error",
+    "hint",
+    "important",
+    "note",
+    "seealso",
+    "tip",
+    "warning",
+    "versionadded",
+    "versionchanged",
+    "deprecated",
+];
+
+/// Directives whose body is code
+const CODE_DIRECTIVES: &[&str] = &["code-block", "code", "sourcecode"];
+
+/// Matches `` `text <url>`_ `` hyperlinks
+fn hyperlink_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"`([^`<]*?)\s*<([^`>]+)>`__?").unwrap())
+}
+
+/// Matches interpreted text roles such as `` :ref:`label` `` or `` :func:`name <target>` ``
+fn role_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r":[\w:.+-]+:`([^`<]*?)(?:\s*<[^`>]*>)?`").unwrap())
+}
+
+/// Parse reStructuredText content and extract text
+pub fn parse_rst(content: &str) -> Result<ParsedDocument> {
+    let mut doc = ParsedDocument::new(String::new(), ContentType::Rst);
+    let mut text = String::new();
+
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    // Adornment styles in order of first appearance: (character, has overline)
+    let mut styles: Vec<(char, bool)> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        // Section title with overline: ===\nTitle\n===
+        if let Some(c) = adornment_char(line) {
+            if let (Some(title), Some(under)) = (lines.get(i + 1), lines.get(i + 2)) {
+                let title = title.trim();
+                if !title.is_empty() && adornment_char(under) == Some(c) && *under == line {
+                    push_heading(&mut doc, &mut text, &mut styles, (c, true), title);
+                    i += 3;
+                    continue;
+                }
+            }
+            // A lone adornment line is a transition
+            i += 1;
+            continue;
+        }
+
+        // Section title with underline only: Title\n=====
+        if !line.trim().is_empty() && !line.starts_with(char::is_whitespace) {
+            if let Some(c) = lines.get(i + 1).and_then(|under| {
+                adornment_char(under).filter(|_| under.chars().count() >= line.chars().count())
+            }) {
+                push_heading(&mut doc, &mut text, &mut styles, (c, false), line.trim());
+                i += 2;
+                continue;
+            }
+        }
+
+        // Explicit markup: directives, targets, and comments
+        if let Some(rest) = line.strip_prefix("..") {
+            if rest.is_empty() || rest.starts_with(' ') {
+                i = handle_explicit_markup(&mut doc, &mut text, &lines, i, rest.trim());
+                continue;
+            }
+        }
+
+        if line.trim().is_empty() {
+            push_break(&mut text);
+            i += 1;
+            continue;
+        }
+
+        // Paragraph line; a trailing `::` introduces a literal block
+        let (body, literal) = match line.strip_suffix("::") {
+            Some("") => ("", true),
+            Some(body) if body.ends_with(char::is_whitespace) => (body.trim_end(), true),
+            Some(body) => (&line[..body.len() + 1], true),
+            None => (line, false),
+        };
+        if !body.is_empty() {
+            let body = body.trim_start();
+            let body = match list_item_text(body) {
+                Some(item) => {
+                    if !text.is_empty() && !text.ends_with('\n') {
+                        text.push('\n');
+                    }
+                    text.push_str("• ");
+                    item
+                }
+                None => {
+                    if !text.is_empty() && !text.ends_with('\n') {
+                        text.push(' ');
+                    }
+                    body
+                }
+            };
+            text.push_str(&clean_inline(body, &mut doc.links));
+        }
+        i += 1;
+
+        if literal {
+            let (block, next) = indented_block(&lines, i);
+            if !block.is_empty() {
+                push_code_block(&mut doc, &mut text, None, &block);
+            }
+            i = next;
+        }
+    }
+
+    doc.text = text.trim_end().to_string();
+    Ok(doc)
+}
+
+/// Handle a `..` line at `start`, returning the index of the next unconsumed line
+fn handle_explicit_markup(
+    doc: &mut ParsedDocument,
+    text: &mut String,
+    lines: &[&str],
+    start: usize,
+    markup: &str,
+) -> usize {
+    let Some((name, argument)) = markup.split_once("::") else {
+        // Comment or hyperlink target: skip it and its indented continuation
+        if let Some(url) = markup
+            .strip_prefix('_')
+            .and_then(|target| target.split_once(": "))
+            .map(|(_, url)| url.trim())
+            .filter(|url| url.contains("://"))
+        {
+            doc.links.push(ExtractedLink {
+                url: url.to_string(),
+                text: None,
+                is_internal: false,
+            });
+        }
+        return indented_block(lines, start + 1).1;
+    };
+
+    let name = name.trim();
+    let argument = argument.trim();
+    let (body, next) = indented_block(lines, start + 1);
+    let body = strip_directive_options(&body);
+
+    if CODE_DIRECTIVES.contains(&name) {
+        let language = argument.split_whitespace().next().map(str::to_string);
+        if !body.is_empty() {
+            push_code_block(doc, text, language, &body);
+        }
+    } else if ADMONITIONS.contains(&name) {
+        push_break(text);
+        if !argument.is_empty() {
+            text.push_str(&clean_inline(argument, &mut doc.links));
+            text.push('\n');
+        }
+        let prose = body
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| clean_inline(l, &mut doc.links))
+            .collect::<Vec<_>>()
+            .join(" ");
+        text.push_str(&prose);
+        push_break(text);
+    }
+
+    next
+}
+
+fn push_heading(
+    doc: &mut ParsedDocument,
+    text: &mut String,
+    styles: &mut Vec<(char, bool)>,
+    style: (char, bool),
+    title: &str,
+) {
+    let level = match styles.iter().position(|s| *s == style) {
+        Some(index) => index + 1,
+        None => {
+            styles.push(style);
+            styles.len()
+        }
+    };
+    let level = level.min(6) as u8;
+    let title = clean_inline(title, &mut doc.links);
+
+    if doc.title.is_none() && level == 1 {
+        doc.title = Some(title.clone());
+    }
+    push_break(text);
+    doc.headings.push(Heading {
+        level,
+        text: title.clone(),
+        position: text.len(),
+    });
+    text.push_str(&title);
+    text.push_str("\n\n");
+}
+
+fn push_code_block(
+    doc: &mut ParsedDocument,
+    text: &mut String,
+    language: Option<String>,
+    code: &str,
+) {
+    push_break(text);
+    text.push_str("```\n");
+    doc.code_blocks.push(CodeBlock {
+        language,
+        content: code.to_string(),
+        position: text.len(),
+    });
+    text.push_str(code);
+    text.push_str("\n```\n\n");
+}
+
+/// End the current paragraph with a blank line
+fn push_break(text: &mut String) {
+    if text.is_empty() || text.ends_with("\n\n") {
+        return;
+    }
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push('\n');
+}
+
+/// The repeated punctuation character of an adornment line, if `line` is one
+fn adornment_char(line: &str) -> Option<char> {
+    let first = line.chars().next()?;
+    let is_adornment =
+        first.is_ascii_punctuation() && line.len() >= 3 && line.chars().all(|c| c == first);
+    is_adornment.then_some(first)
+}
+
+/// Collect the indented lines starting at `start`, dedented.
+///
+/// Leading blank lines are skipped and trailing ones dropped. Returns the
+/// block and the index of the first line after it.
+fn indented_block(lines: &[&str], start: usize) -> (String, usize) {
+    let mut end = start;
+    let mut last_content = start;
+    while end < lines.len() {
+        let line = lines[end];
+        if line.trim().is_empty() {
+            end += 1;
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            break;
+        }
+        end += 1;
+        last_content = end;
+    }
+
+    let block = &lines[start..last_content];
+    let indent = block
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let dedented: Vec<&str> = block
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or(""))
+        .skip_while(|l| l.is_empty())
+        .collect();
+
+    (dedented.join("\n"), last_content.max(start))
+}
+
+/// Drop a directive's leading `:option: value` lines
+fn strip_directive_options(body: &str) -> String {
+    let mut lines = body.lines().peekable();
+    while lines.next_if(|l| is_option_line(l)).is_some() {}
+    lines
+        .skip_while(|l| l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_option_line(line: &str) -> bool {
+    line.strip_prefix(':')
+        .and_then(|rest| rest.find(':'))
+        .is_some_and(|end| end > 0)
+}
+
+fn list_item_text(line: &str) -> Option<&str> {
+    for marker in ["- ", "* ", "+ ", "#. "] {
+        if let Some(item) = line.strip_prefix(marker) {
+            return Some(item.trim_start());
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        return line[digits..].strip_prefix(". ").map(str::trim_start);
+    }
+    None
+}
+
+/// Replace hyperlinks and roles with their text, recording links
+fn clean_inline(line: &str, links: &mut Vec<ExtractedLink>) -> String {
+    let line = hyperlink_re().replace_all(line, |caps: &Captures| {
+        let label = caps[1].trim();
+        let url = caps[2].trim().to_string();
+        links.push(ExtractedLink {
+            is_internal: !url.contains("://"),
+            text: (!label.is_empty()).then(|| label.to_string()),
+            url: url.clone(),
+        });
+        if label.is_empty() {
+            url
+        } else {
+            label.to_string()
+        }
+    });
+    role_re().replace_all(&line, "$1").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+=============
+Runtime Guide
+=============
+
+The runtime drives futures. See `Tokio <https://tokio.rs>`_ and :ref:`spawning`.
+
+Getting Started
+===============
+
+.. note::
+   Requires Python 3.10 or newer.
+
+Install the package:
+
+.. code-block:: python
+   :linenos:
+
+   import asyncio
+
+   asyncio.run(main())
+
+Spawning Tasks
+--------------
+
+- Call :func:`asyncio.create_task`
+- Await the task before exiting
+
+Example output::
+
+    task finished
+
+.. toctree::
+   :maxdepth: 2
+
+   api
+
+Reference
+=========
+
+.. _spawning:
+"#;
+
+    #[test]
+    fn test_rst_section_levels() {
+        let doc = parse_rst(SAMPLE).unwrap();
+
+        let headings: Vec<(u8, &str)> = doc
+            .headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str()))
+            .collect();
+        assert_eq!(
+            headings,
+            vec![
+                (1, "Runtime Guide"),
+                (2, "Getting Started"),
+                (3, "Spawning Tasks"),
+                (2, "Reference"),
+            ]
+        );
+        assert_eq!(doc.title.as_deref(), Some("Runtime Guide"));
+        for heading in &doc.headings {
+            assert!(doc.text[heading.position..].starts_with(&heading.text));
+        }
+    }
+
+    #[test]
+    fn test_rst_code_blocks() {
+        let doc = parse_rst(SAMPLE).unwrap();
+
+        assert_eq!(doc.code_blocks.len(), 2);
+        assert_eq!(doc.code_blocks[0].language.as_deref(), Some("python"));
+        assert_eq!(
+            doc.code_blocks[0].content,
+            "import asyncio\n\nasyncio.run(main())"
+        );
+        assert!(doc.text[doc.code_blocks[0].position..].starts_with("import asyncio"));
+        assert_eq!(doc.code_blocks[1].language, None);
+        assert_eq!(doc.code_blocks[1].content, "task finished");
+    }
+
+    #[test]
+    fn test_rst_strips_directive_noise() {
+        let doc = parse_rst(SAMPLE).unwrap();
+
+        assert!(doc.text.contains("See Tokio and spawning."));
+        assert!(doc.text.contains("Requires Python 3.10 or newer."));
+        assert!(doc.text.contains("• Call asyncio.create_task"));
+        assert!(doc.text.contains("Example output:"));
+        assert!(!doc.text.contains(".."));
+        assert!(!doc.text.contains(":linenos:"));
+        assert!(!doc.text.contains("maxdepth"));
+        assert!(!doc.text.contains("toctree"));
+
+        assert_eq!(doc.links.len(), 1);
+        assert_eq!(doc.links[0].url, "https://tokio.rs");
+        assert_eq!(doc.links[0].text.as_deref(), Some("Tokio"));
+    }
+}
@@ -26,6 +26,7 @@ pub fn parse_plain_text(content: &str) -> ParsedDocument {
         content_type: ContentType::PlainText,
         headings: Vec::new(),
         code_blocks: Vec::new(),
+        tables: Vec::new(),
         links: Vec::new(),
         media: Vec::new(),
     }
@@ -1,12 +1,25 @@
 //! Shared progress and logging helpers to keep progress bars pinned.
 
-use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget};
-use std::io::{self, Write};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::OnceLock;
+use std::time::Duration;
 use tracing_subscriber::fmt::MakeWriter;
 
 static MULTI_PROGRESS: OnceLock<MultiProgress> = OnceLock::new();
 
+/// Progress bars are off until the CLI enables them, so library and MCP callers stay quiet
+static PROGRESS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Set by `--quiet` to silence human-facing output printed with `say!`
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Live [`StdoutGuard`]s; while any exist, stdout carries a protocol stream
+static STDOUT_RESERVATIONS: AtomicUsize = AtomicUsize::new(0);
+
+const TICK_CHARS: &str = "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏";
+
 fn multi_progress() -> &'static MultiProgress {
     MULTI_PROGRESS.get_or_init(|| {
         let mp = MultiProgress::new();
@@ -19,6 +32,134 @@ pub fn add_progress_bar(len: u64) -> ProgressBar {
     multi_progress().add(ProgressBar::new(len))
 }
 
+/// Whether progress bars should be drawn for the given output mode
+pub fn should_show_progress(json: bool, stdout_is_tty: bool) -> bool {
+    !json && stdout_is_tty
+}
+
+/// Enable or disable progress bars for the rest of the process
+pub fn set_progress_enabled(enabled: bool) {
+    PROGRESS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Enable progress bars unless JSON output was requested or stdout is not a terminal
+pub fn init_progress(json: bool) {
+    set_progress_enabled(should_show_progress(json, io::stdout().is_terminal()));
+}
+
+pub fn progress_enabled() -> bool {
+    PROGRESS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Silence human-facing output for the rest of the process
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Reserves stdout for a protocol stream, such as the MCP server's JSON-RPC
+/// frames, until dropped
+///
+/// While a guard is alive, `say!` writes to stderr instead of stdout. Code in
+/// `crate::mcp` is additionally denied `clippy::print_stdout`, so a stray
+/// `println!` there fails the lint gate.
+#[must_use = "stdout is released as soon as the guard is dropped"]
+pub struct StdoutGuard(());
+
+impl StdoutGuard {
+    pub fn reserve() -> Self {
+        STDOUT_RESERVATIONS.fetch_add(1, Ordering::SeqCst);
+        Self(())
+    }
+}
+
+impl Drop for StdoutGuard {
+    fn drop(&mut self) {
+        STDOUT_RESERVATIONS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Whether a [`StdoutGuard`] currently reserves stdout
+pub fn stdout_reserved() -> bool {
+    STDOUT_RESERVATIONS.load(Ordering::SeqCst) > 0
+}
+
+/// `println!` for status chatter, which `--quiet` silences and a
+/// [`StdoutGuard`] diverts to stderr
+///
+/// Command results (query hits, source and run listings, JSON, IDs,
+/// completions) keep using `println!` so `--quiet` never hides them.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !$crate::progress::is_quiet() {
+            if $crate::progress::stdout_reserved() {
+                eprintln!($($arg)*);
+            } else {
+                println!($($arg)*);
+            }
+        }
+    };
+}
+
+/// Start a progress bar with an ETA, or `None` when progress is disabled or there is no work
+pub fn start_progress_bar(len: usize, message: &str) -> Option<ProgressBar> {
+    if len == 0 || !progress_enabled() {
+        return None;
+    }
+
+    let pb = add_progress_bar(len as u64);
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} (ETA {eta}) {msg}",
+        )
+        .unwrap()
+        .tick_chars(TICK_CHARS),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    Some(pb)
+}
+
+/// Start a spinner for work of unknown length (e.g. crawling)
+pub fn start_spinner(message: &str) -> Option<ProgressBar> {
+    if !progress_enabled() {
+        return None;
+    }
+
+    let pb = multi_progress().add(ProgressBar::new_spinner());
+    pb.set_style(
+        ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] {pos} {msg}")
+            .unwrap()
+            .tick_chars(TICK_CHARS),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    Some(pb)
+}
+
+pub fn advance_progress(pb: &Option<ProgressBar>) {
+    if let Some(pb) = pb {
+        pb.inc(1);
+    }
+}
+
+/// Show the running count of embedded chunks next to the progress label
+pub fn set_progress_chunks(pb: &Option<ProgressBar>, label: &str, chunks: usize) {
+    if let Some(pb) = pb {
+        pb.set_message(format!("{} ({} chunks embedded)", label, chunks));
+    }
+}
+
+pub fn finish_progress(pb: Option<ProgressBar>, message: &str) {
+    if let Some(pb) = pb {
+        pb.finish_with_message(message.to_string());
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct LogWriterFactory;
 
@@ -84,3 +225,31 @@ impl<'a> MakeWriter<'a> for LogWriterFactory {
         LogWriter::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stdout_guard_reserves_until_last_guard_drops() {
+        assert!(!stdout_reserved());
+        let outer = StdoutGuard::reserve();
+        let inner = StdoutGuard::reserve();
+        assert!(stdout_reserved());
+        drop(inner);
+        assert!(stdout_reserved());
+        drop(outer);
+        assert!(!stdout_reserved());
+    }
+
+    #[test]
+    fn test_progress_suppressed_in_json_mode() {
+        assert!(should_show_progress(false, true));
+        assert!(!should_show_progress(true, true));
+        assert!(!should_show_progress(false, false));
+
+        set_progress_enabled(should_show_progress(true, true));
+        assert!(start_progress_bar(10, "Processing files").is_none());
+        assert!(start_spinner("Crawling").is_none());
+    }
+}
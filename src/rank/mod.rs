@@ -3,19 +3,70 @@
 //! This module handles:
 //! - Merging vector search results
 //! - Optional BM25 keyword scoring
+//! - Boosting title and heading matches
 //! - Score normalization
+//! - Query expansion from a synonyms file
 
+mod synonyms;
+
+pub use synonyms::Synonyms;
+
+use crate::chunk::heading_texts;
 use crate::store::SearchResult;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Map a raw Qdrant cosine score onto a 0–1 similarity.
+///
+/// With the default cosine distance Qdrant reports the cosine similarity
+/// itself, in `[-1, 1]` (dot and Euclidean scores are already rescaled into
+/// 0–1 by the store, so this leaves them alone). Unrelated and opposing vectors (≤ 0) map
+/// to 0, and values a quantized search rounds past 1 map to 1; everything in
+/// between is kept as-is, so a `min_score` of 0.5 still means a cosine
+/// similarity of 0.5.
+pub fn normalize_score(score: f32) -> f32 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score.clamp(0.0, 1.0)
+    }
+}
+
+/// Map a raw reranker relevance logit onto a 0–1 probability.
+///
+/// Cross-encoder rerankers return unbounded logits, so clamping them would
+/// flatten every strongly relevant result to 1 and every irrelevant one to 0.
+/// The logistic function keeps their order and spacing.
+pub fn rerank_probability(logit: f32) -> f32 {
+    if logit.is_nan() {
+        0.0
+    } else {
+        1.0 / (1.0 + (-logit).exp())
+    }
+}
 
 /// A ranked search result with combined scoring
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RankedResult {
     pub id: String,
+    /// Final 0–1 similarity used for ranking, including any title or heading
+    /// boost (`min_score` filters on [`RankedResult::base_score`] instead)
     pub score: f32,
-    pub vector_score: f32,
+    /// Normalized cosine similarity from the vector search (unset for
+    /// keyword-only queries, which skip it)
+    pub vector_score: Option<f32>,
+    /// BM25 score scaled to 0–1 against the best keyword match in the batch
     pub bm25_score: Option<f32>,
+    /// Weighted combination of the vector and BM25 scores (only set by hybrid ranking)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fused_score: Option<f32>,
+    /// Share of the distance to 1.0 closed for query terms found in the
+    /// title or heading trail (only set when a boost applied)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost_score: Option<f32>,
+    /// Normalized reranker relevance (only set when the result was reranked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
     pub doc_uri: String,
     pub title: Option<String>,
     pub chunk_text: String,
@@ -27,19 +78,70 @@ pub struct RankedResult {
     pub modality: Option<String>,
     pub media_url: Option<String>,
     pub media_hash: Option<String>,
+    /// Chunk excerpt with query terms highlighted (only set when highlighting is requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Matched chunk expanded with its neighbors (only set when context is requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextWindow>,
+    /// Other URIs serving the same document content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Embedding model that produced the stored vector, when recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    /// Whether the stored vector came from a different model than the query's
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub model_mismatch: bool,
+}
+
+impl RankedResult {
+    /// Score before any title or heading boost: the fused score with hybrid
+    /// search, else the vector or keyword score. `min_score` applies to this,
+    /// so boosts reorder results without letting weak matches through.
+    pub fn base_score(&self) -> f32 {
+        self.fused_score
+            .or(self.vector_score)
+            .or(self.bm25_score)
+            .unwrap_or(self.score)
+    }
+}
+
+/// Raise `score` by `boost` of its distance to 1.0, so boosted scores approach
+/// 1.0 without reaching it and keep their order
+fn apply_boost(score: f32, boost: f32) -> f32 {
+    normalize_score(score + boost.clamp(0.0, 1.0) * (1.0 - score))
+}
+
+/// A matched chunk together with the surrounding chunks of the same document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextWindow {
+    /// Neighboring chunks joined in document order, with overlaps removed
+    pub text: String,
+    /// First chunk index included in the window
+    pub start_chunk: i32,
+    /// Last chunk index included in the window
+    pub end_chunk: i32,
+    /// Byte range of the matched chunk within `text`
+    pub match_start: usize,
+    pub match_end: usize,
 }
 
 impl From<SearchResult> for RankedResult {
     fn from(result: SearchResult) -> Self {
+        let score = normalize_score(result.score);
         Self {
             id: result.id,
-            score: result.score,
-            vector_score: result.score,
+            score,
+            vector_score: Some(score),
             bm25_score: None,
+            fused_score: None,
+            boost_score: None,
+            rerank_score: None,
             doc_uri: result.payload.doc_uri,
             title: result.payload.title,
-            chunk_text: String::new(), // Will be filled from SQLite
-            headings: result.payload.headings,
+            chunk_text: String::new(), // Filled by a batched SQLite lookup at query time
+            headings: result.payload.headings.map(|trail| heading_texts(&trail)),
             chunk_index: result.payload.chunk_index,
             source_id: result.payload.source_id,
             source_type: result.payload.source_type,
@@ -47,6 +149,11 @@ impl From<SearchResult> for RankedResult {
             modality: result.payload.modality,
             media_url: result.payload.media_url,
             media_hash: result.payload.media_hash,
+            snippet: None,
+            context: None,
+            aliases: Vec::new(),
+            model_id: result.payload.model_id,
+            model_mismatch: false,
         }
     }
 }
@@ -55,6 +162,8 @@ impl From<SearchResult> for RankedResult {
 pub struct Ranker {
     bm25_weight: f32,
     vector_weight: f32,
+    title_boost: f32,
+    heading_boost: f32,
 }
 
 impl Ranker {
@@ -63,9 +172,68 @@ impl Ranker {
         Self {
             bm25_weight,
             vector_weight: 1.0 - bm25_weight,
+            title_boost: 0.0,
+            heading_boost: 0.0,
         }
     }
 
+    /// Set the most a title or heading match can add to a result's score
+    pub fn with_boosts(mut self, title_boost: f32, heading_boost: f32) -> Self {
+        self.title_boost = title_boost;
+        self.heading_boost = heading_boost;
+        self
+    }
+
+    /// Raise the score of results whose title or heading trail contains
+    /// query terms, and re-sort them.
+    ///
+    /// Each boost is scaled by the share of query terms found (as
+    /// case-insensitive substrings, like BM25), and their sum closes that
+    /// share of the gap between the score and 1.0, so boosted results keep
+    /// their relative order instead of tying at 1.0.
+    pub fn boost_matches(
+        &self,
+        results: Vec<RankedResult>,
+        query_terms: &[String],
+    ) -> Vec<RankedResult> {
+        if query_terms.is_empty() || (self.title_boost <= 0.0 && self.heading_boost <= 0.0) {
+            return results;
+        }
+
+        let share = |text: &str| {
+            let text = text.to_lowercase();
+            let found = query_terms
+                .iter()
+                .filter(|term| text.contains(term.to_lowercase().as_str()))
+                .count();
+            found as f32 / query_terms.len() as f32
+        };
+
+        let mut ranked: Vec<RankedResult> = results
+            .into_iter()
+            .map(|mut result| {
+                let title = result.title.as_deref().map_or(0.0, share);
+                let headings = result
+                    .headings
+                    .as_ref()
+                    .map_or(0.0, |trail| share(&trail.join(" ")));
+                let boost = (self.title_boost * title + self.heading_boost * headings).min(1.0);
+                if boost > 0.0 {
+                    result.boost_score = Some(boost);
+                    result.score = apply_boost(result.score, boost);
+                }
+                result
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        ranked
+    }
+
     /// Rank results using vector scores only
     pub fn rank_vector_only(&self, results: Vec<SearchResult>) -> Vec<RankedResult> {
         let mut ranked: Vec<RankedResult> = results.into_iter().map(RankedResult::from).collect();
@@ -77,21 +245,53 @@ impl Ranker {
         ranked
     }
 
-    /// Merge vector results with BM25 scores
+    /// Merge vector results with BM25 scores.
+    ///
+    /// BM25 scores are unbounded, so they are divided by the best BM25 score
+    /// among the results before weighting; the combined score stays in 0–1.
     pub fn rank_hybrid(
         &self,
         vector_results: Vec<SearchResult>,
         bm25_scores: &HashMap<String, f32>,
     ) -> Vec<RankedResult> {
-        let mut ranked: Vec<RankedResult> = vector_results
+        let ranked = vector_results.into_iter().map(RankedResult::from).collect();
+        self.fuse_bm25(ranked, bm25_scores)
+    }
+
+    /// Weight BM25 scores (keyed by result ID) into already ranked vector
+    /// results and re-sort them by the combined score, keeping any title or
+    /// heading boost on top
+    pub fn fuse_bm25(
+        &self,
+        results: Vec<RankedResult>,
+        bm25_scores: &HashMap<String, f32>,
+    ) -> Vec<RankedResult> {
+        let max_bm25 = results
+            .iter()
+            .filter_map(|r| bm25_scores.get(&r.id).copied())
+            .fold(0.0f32, f32::max);
+
+        let mut ranked: Vec<RankedResult> = results
             .into_iter()
-            .map(|r| {
-                let mut result = RankedResult::from(r);
-                result.bm25_score = bm25_scores.get(&result.id).copied();
+            .map(|mut result| {
+                result.bm25_score = bm25_scores.get(&result.id).map(|&bm25| {
+                    if max_bm25 > 0.0 {
+                        normalize_score(bm25 / max_bm25)
+                    } else {
+                        0.0
+                    }
+                });
 
                 // Combine scores
                 let bm25 = result.bm25_score.unwrap_or(0.0);
-                result.score = self.vector_weight * result.vector_score + self.bm25_weight * bm25;
+                result.score = normalize_score(
+                    self.vector_weight * result.vector_score.unwrap_or(0.0)
+                        + self.bm25_weight * bm25,
+                );
+                result.fused_score = Some(result.score);
+                if let Some(boost) = result.boost_score {
+                    result.score = apply_boost(result.score, boost);
+                }
 
                 result
             })
@@ -105,39 +305,55 @@ impl Ranker {
         ranked
     }
 
-    /// Filter results by minimum score
+    /// Filter results by minimum normalized score, before any boost
     pub fn filter_by_score(&self, results: Vec<RankedResult>, min_score: f32) -> Vec<RankedResult> {
         results
             .into_iter()
-            .filter(|r| r.score >= min_score)
+            .filter(|r| r.base_score() >= min_score)
             .collect()
     }
 
-    /// Deduplicate results by doc_uri (keep highest scoring chunk per doc)
-    pub fn dedupe_by_doc(&self, results: Vec<RankedResult>) -> Vec<RankedResult> {
-        let mut by_doc: HashMap<String, RankedResult> = HashMap::new();
-
-        for result in results {
-            let entry = by_doc.entry(result.doc_uri.clone());
-            match entry {
-                std::collections::hash_map::Entry::Occupied(mut e) => {
-                    if result.score > e.get().score {
-                        e.insert(result);
-                    }
-                }
-                std::collections::hash_map::Entry::Vacant(e) => {
-                    e.insert(result);
-                }
-            }
-        }
-
-        let mut deduped: Vec<RankedResult> = by_doc.into_values().collect();
-        deduped.sort_by(|a, b| {
+    /// Deduplicate results by doc_uri (keep highest scoring chunk per doc).
+    ///
+    /// The sort is stable, so ties keep their incoming rank order and the
+    /// output is deterministic.
+    pub fn dedupe_by_doc(&self, mut results: Vec<RankedResult>) -> Vec<RankedResult> {
+        results.sort_by(|a, b| {
             b.score
                 .partial_cmp(&a.score)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
-        deduped
+
+        let mut seen: HashSet<String> = HashSet::new();
+        results.retain(|r| seen.insert(r.doc_uri.clone()));
+        results
+    }
+
+    /// Apply dedupe (if requested) and then the result limit, so a deduped
+    /// query still returns up to `limit` distinct documents
+    pub fn limit_results(
+        &self,
+        results: Vec<RankedResult>,
+        limit: usize,
+        dedupe_docs: bool,
+    ) -> Vec<RankedResult> {
+        self.page_results(results, 0, limit, dedupe_docs)
+    }
+
+    /// Return `limit` results after skipping the first `offset`, deduplicating first if requested
+    pub fn page_results(
+        &self,
+        results: Vec<RankedResult>,
+        offset: usize,
+        limit: usize,
+        dedupe_docs: bool,
+    ) -> Vec<RankedResult> {
+        let results = if dedupe_docs {
+            self.dedupe_by_doc(results)
+        } else {
+            results
+        };
+        results.into_iter().skip(offset).take(limit).collect()
     }
 }
 
@@ -249,6 +465,212 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_dedupe_by_doc_breaks_ties_by_rank_order() {
+        let ranker = Ranker::new(0.0);
+        let results = vec![
+            make_search_result("a", 0.8, "/doc1"),
+            make_search_result("b", 0.8, "/doc2"),
+            make_search_result("c", 0.8, "/doc1"),
+            make_search_result("d", 0.8, "/doc3"),
+        ];
+
+        for _ in 0..10 {
+            let ranked = ranker.rank_vector_only(results.clone());
+            let ids: Vec<String> = ranker
+                .dedupe_by_doc(ranked)
+                .into_iter()
+                .map(|r| r.id)
+                .collect();
+            assert_eq!(ids, vec!["a", "b", "d"]);
+        }
+    }
+
+    #[test]
+    fn test_limit_applies_after_dedupe() {
+        let ranker = Ranker::new(0.0);
+        let results = vec![
+            make_search_result("1", 0.95, "/doc1"),
+            make_search_result("2", 0.94, "/doc1"),
+            make_search_result("3", 0.93, "/doc1"),
+            make_search_result("4", 0.90, "/doc2"),
+            make_search_result("5", 0.85, "/doc2"),
+            make_search_result("6", 0.80, "/doc3"),
+            make_search_result("7", 0.70, "/doc4"),
+        ];
+
+        let ranked = ranker.rank_vector_only(results);
+        let limited = ranker.limit_results(ranked.clone(), 3, true);
+        let ids: Vec<&str> = limited.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "4", "6"]);
+
+        let limited = ranker.limit_results(ranked, 3, false);
+        let ids: Vec<&str> = limited.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_page_results_returns_disjoint_windows() {
+        let ranker = Ranker::new(0.0);
+        let results: Vec<SearchResult> = (0..20)
+            .map(|i| {
+                let score = 1.0 - i as f32 * 0.01;
+                make_search_result(&i.to_string(), score, &format!("/doc{}", i / 2))
+            })
+            .collect();
+        let ranked = ranker.rank_vector_only(results);
+
+        for dedupe in [false, true] {
+            let first = ranker.page_results(ranked.clone(), 0, 5, dedupe);
+            let second = ranker.page_results(ranked.clone(), 5, 5, dedupe);
+            assert_eq!(first.len(), 5);
+            assert_eq!(second.len(), 5);
+            assert!(second.iter().all(|r| first.iter().all(|f| f.id != r.id)));
+            if dedupe {
+                assert!(second
+                    .iter()
+                    .all(|r| first.iter().all(|f| f.doc_uri != r.doc_uri)));
+            }
+            // The pages continue where the first one stopped
+            let all = ranker.page_results(ranked.clone(), 0, 10, dedupe);
+            let ids: Vec<&str> = first.iter().chain(&second).map(|r| r.id.as_str()).collect();
+            assert_eq!(ids, all.iter().map(|r| r.id.as_str()).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_scores_are_normalized_before_filtering() {
+        let ranker = Ranker::new(0.0);
+        let results = vec![
+            make_search_result("over", 1.0004, "/doc1"),
+            make_search_result("mid", 0.6, "/doc2"),
+            make_search_result("low", 0.2, "/doc3"),
+            make_search_result("opposed", -0.4, "/doc4"),
+            make_search_result("nan", f32::NAN, "/doc5"),
+        ];
+
+        let ranked = ranker.rank_vector_only(results.clone());
+        let scores: HashMap<&str, f32> = ranked.iter().map(|r| (r.id.as_str(), r.score)).collect();
+        assert_eq!(scores["over"], 1.0);
+        assert_eq!(scores["mid"], 0.6);
+        assert_eq!(scores["low"], 0.2);
+        assert_eq!(scores["opposed"], 0.0);
+        assert_eq!(scores["nan"], 0.0);
+        assert!(ranked.iter().all(|r| r.vector_score == Some(r.score)));
+
+        let kept = ranker.filter_by_score(ranked, 0.5);
+        let ids: Vec<&str> = kept.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["over", "mid"]);
+
+        // Hybrid scores scale BM25 to the best match and stay within 0–1
+        let ranker = Ranker::new(0.5);
+        let bm25: HashMap<String, f32> = [("low", 8.0), ("mid", 2.0), ("opposed", 4.0)]
+            .into_iter()
+            .map(|(id, score)| (id.to_string(), score))
+            .collect();
+        let ranked = ranker.rank_hybrid(results, &bm25);
+        assert!(ranked.iter().all(|r| (0.0..=1.0).contains(&r.score)));
+        let find = |id: &str| ranked.iter().find(|r| r.id == id).unwrap().clone();
+        assert_eq!(find("low").bm25_score, Some(1.0));
+        assert_eq!(find("low").score, 0.6);
+        assert_eq!(find("low").fused_score, Some(0.6));
+        assert_eq!(find("low").vector_score, Some(0.2));
+        assert_eq!(find("mid").score, 0.425);
+        assert_eq!(find("opposed").score, 0.25);
+        assert_eq!(find("over").bm25_score, None);
+        assert_eq!(find("over").score, 0.5);
+
+        let kept = ranker.filter_by_score(ranked, 0.5);
+        let ids: Vec<&str> = kept.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["low", "over"]);
+    }
+
+    #[test]
+    fn test_rerank_logits_keep_their_spacing() {
+        let scores: Vec<f32> = [-6.0, 0.0, 4.0, 8.0, f32::NAN]
+            .into_iter()
+            .map(rerank_probability)
+            .collect();
+        assert_eq!(scores[1], 0.5);
+        assert!(scores[0] > 0.0 && scores[0] < 0.01);
+        // Strongly relevant logits stay apart instead of all clamping to 1
+        assert!(scores[2] < scores[3] && scores[3] < 1.0);
+        assert_eq!(scores[4], 0.0);
+    }
+
+    #[test]
+    fn test_heading_match_outranks_equal_body_match_when_boosted() {
+        use crate::chunk::SectionHeading;
+
+        let mut body = make_search_result("body", 0.7, "/docs/a.md");
+        body.payload.headings = Some(vec![SectionHeading::new(1, "Overview")]);
+        let mut heading = make_search_result("heading", 0.7, "/docs/b.md");
+        heading.payload.headings = Some(vec![
+            SectionHeading::new(1, "Configuration"),
+            SectionHeading::new(2, "Crawl timeouts"),
+        ]);
+        let results = vec![body, heading];
+        let terms = Bm25Scorer::new().tokenize("crawl timeout");
+        let ids = |ranked: &[RankedResult]| ranked.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+
+        // Without boosting the tie keeps search order
+        let plain = Ranker::new(0.0);
+        let ranked = plain.boost_matches(plain.rank_vector_only(results.clone()), &terms);
+        assert_eq!(ids(&ranked), vec!["body", "heading"]);
+        assert!(ranked.iter().all(|r| r.boost_score.is_none()));
+
+        let boosted = Ranker::new(0.0).with_boosts(0.2, 0.1);
+        let ranked = boosted.boost_matches(boosted.rank_vector_only(results.clone()), &terms);
+        assert_eq!(ids(&ranked), vec!["heading", "body"]);
+        assert_eq!(ranked[0].boost_score, Some(0.1));
+        assert!((ranked[0].score - 0.73).abs() < 1e-6);
+        assert_eq!(ranked[1].score, 0.7);
+
+        // Each field matches half the terms, so half of each boost applies
+        let mut titled = make_search_result("titled", 0.95, "/docs/c.md");
+        titled.payload.title = Some("Crawl guide".to_string());
+        titled.payload.headings = Some(vec![SectionHeading::new(1, "Timeouts")]);
+        let ranked = boosted.boost_matches(boosted.rank_vector_only(vec![titled]), &terms);
+        assert!((ranked[0].boost_score.unwrap() - 0.15).abs() < 1e-6);
+        // Boosts close part of the gap to 1.0 rather than adding to the score
+        assert!((ranked[0].score - 0.9575).abs() < 1e-6);
+
+        // Hybrid fusion keeps the boost on top of the fused score
+        let ranked = boosted.boost_matches(boosted.rank_vector_only(results), &terms);
+        let bm25: HashMap<String, f32> = [("body".to_string(), 1.0), ("heading".to_string(), 1.0)]
+            .into_iter()
+            .collect();
+        let ranked = boosted.fuse_bm25(ranked, &bm25);
+        assert_eq!(ids(&ranked), vec!["heading", "body"]);
+        let fused = ranked[0].fused_score.unwrap();
+        assert!((ranked[0].score - (fused + 0.1 * (1.0 - fused))).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_boosted_hits_keep_their_order_and_filter_on_base_score() {
+        use crate::chunk::SectionHeading;
+
+        let hit = |id: &str, score: f32| {
+            let mut result = make_search_result(id, score, &format!("/docs/{}.md", id));
+            result.payload.headings = Some(vec![SectionHeading::new(1, "Crawl timeouts")]);
+            result
+        };
+        let results = vec![hit("strong", 0.95), hit("good", 0.9), hit("weak", 0.4)];
+        let terms = Bm25Scorer::new().tokenize("crawl timeout");
+        let ranker = Ranker::new(0.0).with_boosts(0.0, 0.5);
+        let ranked = ranker.boost_matches(ranker.rank_vector_only(results), &terms);
+
+        // Adding the boost would clamp both strong hits to 1.0 and tie them
+        let ids: Vec<&str> = ranked.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["strong", "good", "weak"]);
+        assert!(ranked[0].score > ranked[1].score && ranked[0].score < 1.0);
+
+        // A boost lifts "weak" to 0.7 but min_score still sees its 0.4
+        assert!((ranked[2].score - 0.7).abs() < 1e-6);
+        let kept = ranker.filter_by_score(ranked, 0.5);
+        assert_eq!(kept.len(), 2);
+    }
+
     #[test]
     fn test_bm25_tokenize() {
         let scorer = Bm25Scorer::new();
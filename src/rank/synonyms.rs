@@ -0,0 +1,169 @@
+//! Query expansion from a user-maintained synonyms file
+//!
+//! Each line maps one or more terms to their expansions:
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! k8s => kubernetes
+//! js, ecmascript => javascript
+//! pg => postgres, postgresql
+//! ```
+
+use crate::error::{Error, Result};
+use std::path::Path;
+
+/// Term-to-expansion mappings used to widen a query before searching
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Synonyms {
+    /// Lowercased terms and their expansions, in file order
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl Synonyms {
+    /// Read and parse a synonyms file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            Error::Config(format!(
+                "Failed to read synonyms file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(&content).map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parse `term[, term...] => expansion[, expansion...]` lines
+    pub fn parse(content: &str) -> std::result::Result<Self, String> {
+        let mut entries: Vec<(String, Vec<String>)> = Vec::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((terms, expansions)) = line.split_once("=>") else {
+                return Err(format!(
+                    "line {}: expected 'term => expansion', got '{}'",
+                    number + 1,
+                    line
+                ));
+            };
+
+            let expansions = split_list(expansions);
+            let terms = split_list(terms);
+            if terms.is_empty() || expansions.is_empty() {
+                return Err(format!(
+                    "line {}: both sides of '=>' need at least one term",
+                    number + 1
+                ));
+            }
+
+            for term in terms {
+                match entries.iter_mut().find(|(existing, _)| *existing == term) {
+                    Some((_, existing)) => existing.extend(expansions.iter().cloned()),
+                    None => entries.push((term, expansions.clone())),
+                }
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Whether no mappings are defined
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append the expansions of every term found in `query`.
+    ///
+    /// The original query is kept first and unchanged, so it still dominates
+    /// the embedding; expansions already present in the query are skipped.
+    /// Multi-word terms match whole-word runs, ignoring case and punctuation.
+    pub fn expand(&self, query: &str) -> String {
+        let words: Vec<String> = query
+            .split_whitespace()
+            .map(|w| {
+                w.trim_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .filter(|w| !w.is_empty())
+            .collect();
+        let normalized = format!(" {} ", words.join(" "));
+
+        let mut added: Vec<&str> = Vec::new();
+        for (term, expansions) in &self.entries {
+            if !normalized.contains(&format!(" {} ", term)) {
+                continue;
+            }
+            for expansion in expansions {
+                let present = normalized.contains(&format!(" {} ", expansion));
+                if !present && !added.contains(&expansion.as_str()) {
+                    added.push(expansion);
+                }
+            }
+        }
+
+        if added.is_empty() {
+            query.to_string()
+        } else {
+            format!("{} {}", query.trim_end(), added.join(" "))
+        }
+    }
+}
+
+/// Split a comma-separated list into lowercased, whitespace-normalized terms
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|term| {
+            term.split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        })
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_expand_synonyms() {
+        let synonyms = Synonyms::parse(
+            "# aliases\n\
+             k8s => kubernetes\n\
+             \n\
+             JS, ECMAScript => javascript\n\
+             pg => postgres, postgresql\n\
+             node js => node.js\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            synonyms.expand("Deploying to K8s?"),
+            "Deploying to K8s? kubernetes"
+        );
+        assert_eq!(
+            synonyms.expand("pg and js"),
+            "pg and js javascript postgres postgresql"
+        );
+        assert_eq!(
+            synonyms.expand("Node JS streams"),
+            "Node JS streams javascript node.js"
+        );
+        // Terms already in the query are not repeated, and unmatched queries pass through
+        assert_eq!(synonyms.expand("k8s kubernetes"), "k8s kubernetes");
+        assert_eq!(synonyms.expand("rust lifetimes"), "rust lifetimes");
+        // Substrings of words don't match
+        assert_eq!(synonyms.expand("jsx"), "jsx");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        let err = Synonyms::parse("k8s => kubernetes\nk8s kubernetes\n").unwrap_err();
+        assert!(err.contains("line 2"), "{err}");
+        assert!(Synonyms::parse("=> kubernetes").is_err());
+        assert!(Synonyms::parse("").unwrap().is_empty());
+    }
+}
@@ -9,31 +9,73 @@ mod payload;
 
 pub use payload::*;
 
-use crate::config::{Config, EmbeddingDimensionSource, ResolvedEmbeddingConfig};
+use crate::config::{
+    Config, EmbeddingDimensionSource, Quantization, ResolvedEmbeddingConfig, StoreConfig,
+    VectorDistance,
+};
 use crate::error::{Error, Result};
 use qdrant_client::qdrant::{
-    CreateCollectionBuilder, DeletePointsBuilder, Distance, Filter, GetCollectionInfoResponse,
-    PointId, PointStruct, ScalarQuantizationBuilder, SearchPointsBuilder, VectorParamsBuilder,
+    CreateCollection, CreateCollectionBuilder, CreateFieldIndexCollection,
+    CreateFieldIndexCollectionBuilder, DeletePointsBuilder, Distance, FieldType, Filter,
+    GetCollectionInfoResponse, HnswConfigDiffBuilder, PayloadSchemaInfo, PointId, PointStruct,
+    PointsIdsList, ScalarQuantizationBuilder, SearchParamsBuilder, SearchPoints,
+    SearchPointsBuilder, SetPayloadPointsBuilder, VectorParamsBuilder,
 };
 use qdrant_client::Qdrant;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use tracing::{debug, info};
 use uuid::Uuid;
 
+/// Payload fields used by `SearchFilter`, indexed so filtered searches stay fast
+pub const FILTERABLE_FIELDS: &[&str] = &["source_id", "source_type", "model_id", "section_paths"];
+
 /// Information about a Qdrant collection
 #[derive(Debug, Clone)]
 pub struct CollectionInfo {
     pub points_count: u64,
     pub indexed_vectors_count: u64,
     pub status: String,
+    /// Size of each vector the collection stores, keyed "default" when unnamed
+    pub vector_sizes: Vec<(String, u64)>,
+    /// Dimension the configured model produces, when known offline
+    pub expected_dimension: Option<usize>,
+    /// Why the configured model's vectors cannot be written to the collection
+    pub dimension_warning: Option<String>,
+}
+
+impl CollectionInfo {
+    /// Size of the collection's single vector
+    pub fn vector_size(&self) -> Option<u64> {
+        match self.vector_sizes.as_slice() {
+            [(_, size)] => Some(*size),
+            _ => None,
+        }
+    }
+
+    /// Whether the collection fits the configured model; `None` when either
+    /// side's dimension is unknown
+    pub fn dimension_matches(&self) -> Option<bool> {
+        if self.dimension_warning.is_some() {
+            return Some(false);
+        }
+        match (self.vector_size(), self.expected_dimension) {
+            (Some(_), Some(_)) => Some(true),
+            _ => None,
+        }
+    }
 }
 
 /// Qdrant store handle
 pub struct QdrantStore {
     client: Qdrant,
+    url: String,
     collection: String,
     dimension: usize,
     embedding_context: Option<EmbeddingContext>,
+    distance: VectorDistance,
+    settings: StoreConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -58,13 +100,35 @@ impl From<&ResolvedEmbeddingConfig> for EmbeddingContext {
 impl QdrantStore {
     /// Connect to Qdrant using config
     pub async fn connect(config: &Config, embedding: &ResolvedEmbeddingConfig) -> Result<Self> {
-        Self::new(
+        let mut store = Self::new(
             &config.qdrant_url,
             &config.collection_name,
             embedding.dimension,
             Some(embedding),
         )
-        .await
+        .await?;
+        store.apply_config(config);
+        Ok(store)
+    }
+
+    /// Connect to Qdrant without resolving the embedding model
+    ///
+    /// For commands that never write vectors. Uses the cached model details
+    /// when present, otherwise the best dimension known from config.
+    pub async fn connect_offline(config: &Config) -> Result<Self> {
+        if let Some(embedding) = config.cached_embedding_config() {
+            return Self::connect(config, &embedding).await;
+        }
+        let dimension = config.offline_embedding_dimension().unwrap_or_default();
+        let mut store =
+            Self::new(&config.qdrant_url, &config.collection_name, dimension, None).await?;
+        store.apply_config(config);
+        Ok(store)
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        self.distance = config.embedding.distance;
+        self.settings = config.store.clone();
     }
 
     /// Create a new store connection directly with URL and collection name
@@ -79,18 +143,38 @@ impl QdrantStore {
         let client = Qdrant::from_url(url)
             .skip_compatibility_check()
             .build()
-            .map_err(|e| Error::Qdrant(e.to_string()))?;
+            .map_err(|e| Error::Qdrant(format!("Invalid qdrant_url '{}': {}", url, e)))?;
 
         let store = Self {
             client,
+            url: url.to_string(),
             collection: collection.to_string(),
             dimension,
             embedding_context: embedding.map(EmbeddingContext::from),
+            distance: VectorDistance::default(),
+            settings: StoreConfig::default(),
         };
 
         Ok(store)
     }
 
+    /// Check that Qdrant answers, since the client only connects on first use
+    pub async fn ensure_reachable(&self) -> Result<()> {
+        self.client
+            .health_check()
+            .await
+            .map_err(|e| Error::QdrantUnreachable {
+                url: self.url.clone(),
+                reason: e.to_string(),
+            })?;
+        Ok(())
+    }
+
+    /// Name of the collection this store reads and writes
+    pub fn collection_name(&self) -> &str {
+        &self.collection
+    }
+
     /// Get the expected vector dimension for this store
     pub fn dimension(&self) -> usize {
         self.dimension
@@ -104,56 +188,140 @@ impl QdrantStore {
         if exists {
             debug!("Collection {} already exists", self.collection);
 
-            if let Some(sizes) = self.collection_vector_sizes().await? {
-                if sizes.len() > 1 {
-                    let detail = describe_vector_sizes(&sizes);
-                    return Err(Error::Qdrant(format!(
-                        "Collection '{}' uses named vectors ({}) which are not supported by this store",
-                        self.collection, detail
-                    )));
-                }
-
-                if let Some((_, size)) = sizes.first() {
-                    let size = *size as usize;
-                    if size != self.dimension {
-                        let detail = if let Some(ctx) = &self.embedding_context {
-                            format!(
-                                "model '{}' (family '{}') expects {} from {}",
-                                ctx.model_id, ctx.family, ctx.dimension, ctx.dimension_source
-                            )
-                        } else {
-                            format!("config expects {}", self.dimension)
-                        };
-                        return Err(Error::Qdrant(format!(
-                            "Collection '{}' has vector size {}, but {}. Remediation: set a new collection name or migrate/reindex with the expected dimension.",
-                            self.collection, size, detail
-                        )));
-                    }
-                }
-            }
+            let info = self.client.collection_info(&self.collection).await?;
+            self.check_collection_config(&info)?;
 
+            self.ensure_payload_indexes().await?;
             return Ok(());
         }
 
         info!(
-            "Creating collection {} with dimension {}",
-            self.collection, self.dimension
+            "Creating collection {} with dimension {}, {} distance, and {} quantization",
+            self.collection, self.dimension, self.distance, self.settings.quantization
         );
 
-        let vectors_config = VectorParamsBuilder::new(self.dimension as u64, Distance::Cosine);
-
         self.client
-            .create_collection(
-                CreateCollectionBuilder::new(&self.collection)
-                    .vectors_config(vectors_config)
-                    .quantization_config(ScalarQuantizationBuilder::default()),
-            )
+            .create_collection(self.create_collection_request())
             .await?;
 
         info!("Collection {} created successfully", self.collection);
+        self.ensure_payload_indexes().await?;
+        Ok(())
+    }
+
+    /// Build the request that creates the collection with the configured
+    /// dimension, distance metric, quantization, and HNSW tuning
+    fn create_collection_request(&self) -> CreateCollection {
+        let vectors_config =
+            VectorParamsBuilder::new(self.dimension as u64, qdrant_distance(self.distance));
+        let mut request =
+            CreateCollectionBuilder::new(&self.collection).vectors_config(vectors_config);
+
+        if self.settings.quantization == Quantization::Scalar {
+            let mut scalar = ScalarQuantizationBuilder::default();
+            if let Some(quantile) = self.settings.quantization_quantile {
+                scalar = scalar.quantile(quantile);
+            }
+            if self.settings.quantization_always_ram {
+                scalar = scalar.always_ram(true);
+            }
+            request = request.quantization_config(scalar);
+        }
+
+        let hnsw = &self.settings.hnsw;
+        if hnsw.m.is_some() || hnsw.ef_construct.is_some() || hnsw.full_scan_threshold.is_some() {
+            let mut diff = HnswConfigDiffBuilder::default();
+            if let Some(m) = hnsw.m {
+                diff = diff.m(m);
+            }
+            if let Some(ef_construct) = hnsw.ef_construct {
+                diff = diff.ef_construct(ef_construct);
+            }
+            if let Some(threshold) = hnsw.full_scan_threshold {
+                diff = diff.full_scan_threshold(threshold);
+            }
+            request = request.hnsw_config(diff);
+        }
+
+        request.build()
+    }
+
+    /// Check that an existing collection matches the configured vector size
+    /// and distance metric
+    fn check_collection_config(&self, info: &GetCollectionInfoResponse) -> Result<()> {
+        if let Some(sizes) = extract_vector_sizes(info) {
+            if let Some(problem) = self.vector_size_problem(&sizes) {
+                return Err(Error::Qdrant(problem));
+            }
+        }
+
+        if let Some(distance) = extract_vector_distance(info) {
+            if distance != qdrant_distance(self.distance) {
+                return Err(Error::Qdrant(format!(
+                    "Collection '{}' uses {} distance, but embedding.distance is {}. Remediation: set embedding.distance to match, or set a new collection name (or run 'librarian db reset --yes') and re-ingest.",
+                    self.collection,
+                    describe_distance(distance),
+                    self.distance
+                )));
+            }
+        }
+
         Ok(())
     }
 
+    /// Why vectors of this store's dimension cannot go into a collection with
+    /// these vector sizes, if they cannot
+    fn vector_size_problem(&self, sizes: &[(String, u64)]) -> Option<String> {
+        if sizes.len() > 1 {
+            return Some(format!(
+                "Collection '{}' uses named vectors ({}) which are not supported by this store",
+                self.collection,
+                describe_vector_sizes(sizes)
+            ));
+        }
+
+        let size = sizes.first()?.1 as usize;
+        if size == self.dimension {
+            return None;
+        }
+        let detail = if let Some(ctx) = &self.embedding_context {
+            format!(
+                "model '{}' (family '{}') expects {} from {}",
+                ctx.model_id, ctx.family, ctx.dimension, ctx.dimension_source
+            )
+        } else {
+            format!("config expects {}", self.dimension)
+        };
+        Some(format!(
+            "Collection '{}' has vector size {}, but {}. Remediation: set a new collection name or migrate/reindex with the expected dimension.",
+            self.collection, size, detail
+        ))
+    }
+
+    /// Create keyword indexes for any filterable payload field that lacks one.
+    ///
+    /// Returns the fields that were indexed; fields already indexed are left
+    /// alone, so this is safe to run repeatedly.
+    pub async fn ensure_payload_indexes(&self) -> Result<Vec<String>> {
+        let info = self.client.collection_info(&self.collection).await?;
+        let existing = info
+            .result
+            .map(|result| result.payload_schema)
+            .unwrap_or_default();
+
+        let mut created = Vec::new();
+        for request in payload_index_requests(&self.collection, &existing) {
+            debug!(
+                "Creating payload index on {} for collection {}",
+                request.field_name, self.collection
+            );
+            created.push(request.field_name.clone());
+            self.client.create_field_index(request).await?;
+        }
+
+        Ok(created)
+    }
+
     /// Check if the collection exists
     pub async fn collection_exists(&self) -> Result<bool> {
         let exists = self.client.collection_exists(&self.collection).await?;
@@ -193,23 +361,58 @@ impl QdrantStore {
         }
 
         let info = self.client.collection_info(&self.collection).await?;
-        if let Some(result) = info.result {
-            Ok(Some(CollectionInfo {
-                points_count: result.points_count.unwrap_or(0),
-                indexed_vectors_count: result.indexed_vectors_count.unwrap_or(0),
-                status: format!("{:?}", result.status()),
-            }))
+        Ok(self.describe_collection(&info))
+    }
+
+    /// Summarize a collection, checking its vectors against this store's
+    /// dimension the way `ensure_collection` does
+    ///
+    /// Offline stores may not know the model's dimension; then only named
+    /// vectors are reported as a problem.
+    fn describe_collection(&self, info: &GetCollectionInfoResponse) -> Option<CollectionInfo> {
+        let result = info.result.as_ref()?;
+        let vector_sizes = extract_vector_sizes(info).unwrap_or_default();
+        let expected_dimension = (self.dimension > 0).then_some(self.dimension);
+        let dimension_warning = if expected_dimension.is_some() || vector_sizes.len() > 1 {
+            self.vector_size_problem(&vector_sizes)
         } else {
-            Ok(None)
-        }
+            None
+        };
+
+        Some(CollectionInfo {
+            points_count: result.points_count.unwrap_or(0),
+            indexed_vectors_count: result.indexed_vectors_count.unwrap_or(0),
+            status: format!("{:?}", result.status()),
+            vector_sizes,
+            expected_dimension,
+            dimension_warning,
+        })
     }
 
-    /// Upsert ChunkPoint objects (converts to PointStruct internally)
+    /// Upsert ChunkPoint objects (converts to PointStruct internally).
+    ///
+    /// Points are sent in batches of `store.upsert_batch_size` so large
+    /// reindexes stay under gRPC message limits.
     pub async fn upsert_points(&self, points: Vec<ChunkPoint>) -> Result<()> {
         if points.is_empty() {
             return Ok(());
         }
 
+        debug!(
+            "Upserting {} points to collection {} in batches of {}",
+            points.len(),
+            self.collection,
+            self.settings.upsert_batch_size
+        );
+
+        upsert_in_batches(points, self.settings.upsert_batch_size, |batch| {
+            self.upsert_batch(batch)
+        })
+        .await
+    }
+
+    /// Upsert a single batch of points in one request
+    async fn upsert_batch(&self, points: Vec<ChunkPoint>) -> Result<()> {
         if let Some(mismatch) = points.iter().find(|p| p.vector.len() != self.dimension) {
             let detail = if let Some(ctx) = &self.embedding_context {
                 format!(
@@ -227,12 +430,6 @@ impl QdrantStore {
             )));
         }
 
-        debug!(
-            "Upserting {} points to collection {}",
-            points.len(),
-            self.collection
-        );
-
         let point_structs: Vec<PointStruct> =
             points.into_iter().map(|p| p.to_point_struct()).collect();
 
@@ -270,6 +467,32 @@ impl QdrantStore {
         Ok(())
     }
 
+    /// Point the `doc_uri` payload of `point_ids` at `uri`, e.g. after a
+    /// document moved to one of its aliases
+    pub async fn set_doc_uri(&self, point_ids: &[Uuid], uri: &str) -> Result<()> {
+        if point_ids.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<PointId> = point_ids
+            .iter()
+            .map(|id| PointId::from(id.to_string()))
+            .collect();
+        let payload = HashMap::from([(
+            "doc_uri".to_string(),
+            qdrant_client::qdrant::Value::from(uri.to_string()),
+        )]);
+
+        self.client
+            .set_payload(
+                SetPayloadPointsBuilder::new(&self.collection, payload)
+                    .points_selector(PointsIdsList { ids }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     /// Search for similar vectors
     pub async fn search(
         &self,
@@ -282,17 +505,8 @@ impl QdrantStore {
             self.collection, limit
         );
 
-        let mut search_builder =
-            SearchPointsBuilder::new(&self.collection, query_vector, limit as u64)
-                .with_payload(true);
-
-        if let Some(f) = filter {
-            if let Some(qdrant_filter) = f.to_qdrant_filter() {
-                search_builder = search_builder.filter(qdrant_filter);
-            }
-        }
-
-        let response = self.client.search_points(search_builder).await?;
+        let request = self.search_request(query_vector, limit, filter);
+        let response = self.client.search_points(request).await?;
 
         let results: Vec<SearchResult> = response
             .result
@@ -307,7 +521,7 @@ impl QdrantStore {
 
                 SearchResult {
                     id: point_id_to_string(p.id),
-                    score: p.score,
+                    score: similarity_from_score(self.distance, p.score),
                     payload,
                 }
             })
@@ -316,7 +530,40 @@ impl QdrantStore {
         Ok(results)
     }
 
+    /// Build a search request, applying the filter and `store.hnsw.search_ef`
+    fn search_request(
+        &self,
+        query_vector: Vec<f32>,
+        limit: usize,
+        filter: Option<SearchFilter>,
+    ) -> SearchPoints {
+        let mut search_builder =
+            SearchPointsBuilder::new(&self.collection, query_vector, limit as u64)
+                .with_payload(true);
+
+        if let Some(f) = filter {
+            if let Some(qdrant_filter) = f.to_qdrant_filter() {
+                search_builder = search_builder.filter(qdrant_filter);
+            }
+        }
+
+        if let Some(ef) = self.settings.hnsw.search_ef {
+            search_builder = search_builder.params(SearchParamsBuilder::default().hnsw_ef(ef));
+        }
+
+        search_builder.build()
+    }
+
     /// Get collection statistics
+    /// Vector size of the existing collection, if it uses a single unnamed vector
+    pub async fn collection_dimension(&self) -> Result<Option<usize>> {
+        let info = self.client.collection_info(&self.collection).await?;
+        Ok(match extract_vector_sizes(&info).as_deref() {
+            Some([(_, size)]) => Some(*size as usize),
+            _ => None,
+        })
+    }
+
     pub async fn get_stats(&self) -> Result<CollectionStats> {
         let info = self.client.collection_info(&self.collection).await?;
 
@@ -333,45 +580,163 @@ impl QdrantStore {
 
     /// List all point IDs (for orphan detection) - scrolls through all points
     pub async fn list_all_point_ids(&self) -> Result<Vec<Uuid>> {
-        use qdrant_client::qdrant::ScrollPointsBuilder;
-
         let mut all_ids = Vec::new();
-        let mut offset: Option<PointId> = None;
-        let batch_size = 1000u32;
+        self.for_each_point_id_page(|page| all_ids.extend_from_slice(page))
+            .await?;
+        Ok(all_ids)
+    }
 
-        loop {
-            let mut scroll_builder = ScrollPointsBuilder::new(&self.collection)
-                .limit(batch_size)
-                .with_payload(false)
-                .with_vectors(false);
+    /// Scroll through every point ID, handing each page to `on_page` so callers
+    /// never need the whole collection in memory
+    pub async fn for_each_point_id_page<F>(&self, mut on_page: F) -> Result<()>
+    where
+        F: FnMut(&[Uuid]),
+    {
+        let mut pages = self.point_id_pages();
+        while let Some(page) = pages.next_page().await? {
+            on_page(&page);
+        }
+        Ok(())
+    }
 
-            if let Some(ref o) = offset {
-                scroll_builder = scroll_builder.offset(o.clone());
-            }
+    /// Page through every point ID, for callers that need to await between pages
+    pub fn point_id_pages(&self) -> PointIdPages<'_> {
+        PointIdPages {
+            store: self,
+            offset: None,
+            done: false,
+        }
+    }
 
-            let response = self.client.scroll(scroll_builder).await?;
+    /// The IDs among `point_ids` that have a point in the collection
+    pub async fn existing_point_ids(&self, point_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        use qdrant_client::qdrant::GetPointsBuilder;
 
-            let points = response.result;
-            if points.is_empty() {
-                break;
-            }
+        if point_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let ids: Vec<PointId> = point_ids
+            .iter()
+            .map(|id| PointId::from(id.to_string()))
+            .collect();
+        let response = self
+            .client
+            .get_points(
+                GetPointsBuilder::new(&self.collection, ids)
+                    .with_payload(false)
+                    .with_vectors(false),
+            )
+            .await?;
+        Ok(response
+            .result
+            .iter()
+            .filter_map(|point| point.id.as_ref().and_then(point_id_to_uuid))
+            .collect())
+    }
+}
 
-            for point in &points {
-                if let Some(ref id) = point.id {
-                    if let Some(uuid) = point_id_to_uuid(id) {
-                        all_ids.push(uuid);
-                    }
-                }
-            }
+/// Point IDs of a collection, scrolled a page at a time
+pub struct PointIdPages<'a> {
+    store: &'a QdrantStore,
+    offset: Option<PointId>,
+    done: bool,
+}
 
-            offset = response.next_page_offset;
-            if offset.is_none() {
-                break;
-            }
+impl PointIdPages<'_> {
+    /// Next page of up to 1000 point IDs, or `None` once every point was returned
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Uuid>>> {
+        use qdrant_client::qdrant::ScrollPointsBuilder;
+
+        if self.done {
+            return Ok(None);
         }
 
-        Ok(all_ids)
+        let mut scroll_builder = ScrollPointsBuilder::new(&self.store.collection)
+            .limit(1000)
+            .with_payload(false)
+            .with_vectors(false);
+        if let Some(ref o) = self.offset {
+            scroll_builder = scroll_builder.offset(o.clone());
+        }
+
+        let response = self.store.client.scroll(scroll_builder).await?;
+        self.offset = response.next_page_offset;
+        self.done = self.offset.is_none() || response.result.is_empty();
+        if response.result.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            response
+                .result
+                .iter()
+                .filter_map(|point| point.id.as_ref().and_then(point_id_to_uuid))
+                .collect(),
+        ))
+    }
+}
+
+/// Build index requests for the filterable fields missing from `existing`
+fn payload_index_requests(
+    collection: &str,
+    existing: &HashMap<String, PayloadSchemaInfo>,
+) -> Vec<CreateFieldIndexCollection> {
+    FILTERABLE_FIELDS
+        .iter()
+        .filter(|field| !existing.contains_key(**field))
+        .map(|field| {
+            CreateFieldIndexCollectionBuilder::new(collection, *field, FieldType::Keyword)
+                .wait(true)
+                .build()
+        })
+        .collect()
+}
+
+/// Send `items` through `upsert` in order, at most `batch_size` at a time.
+///
+/// Every batch is attempted even if an earlier one fails; a single failure is
+/// returned as-is and several are combined into one error.
+async fn upsert_in_batches<T, F, Fut>(
+    mut items: Vec<T>,
+    batch_size: usize,
+    mut upsert: F,
+) -> Result<()>
+where
+    F: FnMut(Vec<T>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let batch_size = batch_size.max(1);
+    let total = items.len().div_ceil(batch_size);
+    let mut errors = Vec::new();
+    let mut index = 0;
+
+    while !items.is_empty() {
+        let rest = items.split_off(batch_size.min(items.len()));
+        let batch = std::mem::replace(&mut items, rest);
+        index += 1;
+        if let Err(e) = upsert(batch).await {
+            errors.push((index, e));
+        }
     }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    if total == 1 {
+        return Err(errors.remove(0).1);
+    }
+
+    let detail = errors
+        .iter()
+        .map(|(index, e)| format!("batch {}: {}", index, e))
+        .collect::<Vec<_>>()
+        .join("; ");
+    Err(Error::Qdrant(format!(
+        "{} of {} upsert batches failed: {}",
+        errors.len(),
+        total,
+        detail
+    )))
 }
 
 fn describe_vector_sizes(sizes: &[(String, u64)]) -> String {
@@ -382,6 +747,49 @@ fn describe_vector_sizes(sizes: &[(String, u64)]) -> String {
         .join(", ")
 }
 
+/// Qdrant metric for a configured distance
+fn qdrant_distance(distance: VectorDistance) -> Distance {
+    match distance {
+        VectorDistance::Cosine => Distance::Cosine,
+        VectorDistance::Dot => Distance::Dot,
+        VectorDistance::Euclidean => Distance::Euclid,
+    }
+}
+
+/// Name of a Qdrant metric as written in `embedding.distance`
+fn describe_distance(distance: Distance) -> String {
+    match distance {
+        Distance::Cosine => VectorDistance::Cosine.to_string(),
+        Distance::Dot => VectorDistance::Dot.to_string(),
+        Distance::Euclid => VectorDistance::Euclidean.to_string(),
+        other => other.as_str_name().to_ascii_lowercase(),
+    }
+}
+
+/// Turn a Qdrant search score into a similarity where higher is better.
+///
+/// Cosine scores already are. Dot products are unbounded, so they go through
+/// the logistic function, which keeps their order and spacing instead of
+/// letting every large product tie at 1. Euclidean scores are distances, so
+/// they are mapped to `1 / (1 + distance)`, which is 1 for identical vectors.
+fn similarity_from_score(distance: VectorDistance, score: f32) -> f32 {
+    match distance {
+        VectorDistance::Cosine => score,
+        VectorDistance::Dot => 1.0 / (1.0 + (-score).exp()),
+        VectorDistance::Euclidean => 1.0 / (1.0 + score.max(0.0)),
+    }
+}
+
+fn extract_vector_distance(info: &GetCollectionInfoResponse) -> Option<Distance> {
+    let params = info.result.as_ref()?.config.as_ref()?.params.as_ref()?;
+    match params.vectors_config.as_ref()?.config.as_ref()? {
+        qdrant_client::qdrant::vectors_config::Config::Params(params) => {
+            Distance::try_from(params.distance).ok()
+        }
+        qdrant_client::qdrant::vectors_config::Config::ParamsMap(_) => None,
+    }
+}
+
 fn extract_vector_sizes(info: &GetCollectionInfoResponse) -> Option<Vec<(String, u64)>> {
     let result = info.result.as_ref()?;
     let config = result.config.as_ref()?;
@@ -407,13 +815,6 @@ fn extract_vector_sizes(info: &GetCollectionInfoResponse) -> Option<Vec<(String,
     }
 }
 
-impl QdrantStore {
-    async fn collection_vector_sizes(&self) -> Result<Option<Vec<(String, u64)>>> {
-        let info = self.client.collection_info(&self.collection).await?;
-        Ok(extract_vector_sizes(&info))
-    }
-}
-
 /// Search result
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -428,6 +829,11 @@ pub struct SearchFilter {
     pub source_ids: Option<Vec<String>>,
     pub source_types: Option<Vec<String>>,
     pub path_prefix: Option<String>,
+    /// Only match chunks embedded with this model
+    pub model_id: Option<String>,
+    /// Only match chunks whose heading trail starts with this normalized
+    /// section path (see [`crate::chunk::normalize_section_path`])
+    pub section: Option<String>,
 }
 
 impl SearchFilter {
@@ -439,6 +845,8 @@ impl SearchFilter {
         if let Some(ref source_ids) = self.source_ids {
             if source_ids.len() == 1 {
                 must_conditions.push(Condition::matches("source_id", source_ids[0].clone()));
+            } else if source_ids.len() > 1 {
+                must_conditions.push(Condition::matches("source_id", source_ids.clone()));
             }
         }
 
@@ -448,6 +856,14 @@ impl SearchFilter {
             }
         }
 
+        if let Some(ref model_id) = self.model_id {
+            must_conditions.push(Condition::matches("model_id", model_id.clone()));
+        }
+
+        if let Some(ref section) = self.section {
+            must_conditions.push(Condition::matches("section_paths", section.clone()));
+        }
+
         if must_conditions.is_empty() {
             return None;
         }
@@ -525,12 +941,29 @@ mod tests {
     use crate::error::Error;
     use uuid::Uuid;
 
+    #[tokio::test]
+    async fn test_collection_override_targets_another_collection() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.paths.base_dir = tmp.path().to_path_buf();
+
+        let default_store = QdrantStore::connect_offline(&config).await.unwrap();
+        config.apply_collection_override("other_docs").unwrap();
+        let store = QdrantStore::connect_offline(&config).await.unwrap();
+
+        assert_eq!(default_store.collection, Config::default().collection_name);
+        assert_eq!(store.collection, "other_docs");
+        assert_ne!(store.collection, default_store.collection);
+    }
+
     #[test]
     fn test_search_filter_to_qdrant() {
         let filter = SearchFilter {
             source_ids: Some(vec!["test-source".to_string()]),
             source_types: Some(vec!["dir".to_string()]),
             path_prefix: None,
+            model_id: None,
+            section: None,
         };
 
         let qdrant_filter = filter.to_qdrant_filter();
@@ -538,6 +971,265 @@ mod tests {
         assert_eq!(qdrant_filter.unwrap().must.len(), 2);
     }
 
+    #[test]
+    fn test_search_filter_multiple_source_ids() {
+        let filter = SearchFilter {
+            source_ids: Some(vec!["a".to_string(), "b".to_string()]),
+            source_types: None,
+            path_prefix: None,
+            model_id: None,
+            section: None,
+        };
+
+        let qdrant_filter = filter.to_qdrant_filter().expect("filter should be built");
+        assert_eq!(qdrant_filter.must.len(), 1);
+    }
+
+    #[test]
+    fn test_payload_index_requests_cover_filterable_fields() {
+        let requests = payload_index_requests("docs", &HashMap::new());
+        let fields: Vec<&str> = requests.iter().map(|r| r.field_name.as_str()).collect();
+        assert_eq!(fields, FILTERABLE_FIELDS);
+        for request in &requests {
+            assert_eq!(request.collection_name, "docs");
+            assert_eq!(request.field_type, Some(FieldType::Keyword as i32));
+        }
+
+        // Every field a search filter can match on gets an index
+        let filter = SearchFilter {
+            source_ids: Some(vec!["a".to_string()]),
+            source_types: Some(vec!["dir".to_string()]),
+            path_prefix: None,
+            model_id: Some("BAAI/bge-small-en-v1.5".to_string()),
+            section: Some("guide/configuration".to_string()),
+        };
+        for condition in filter.to_qdrant_filter().unwrap().must {
+            match condition.condition_one_of {
+                Some(qdrant_client::qdrant::condition::ConditionOneOf::Field(field)) => {
+                    assert!(fields.contains(&field.key.as_str()), "{}", field.key)
+                }
+                other => panic!("unexpected condition {other:?}"),
+            }
+        }
+
+        // Already-indexed fields are skipped
+        let mut existing = HashMap::new();
+        existing.insert("source_id".to_string(), PayloadSchemaInfo::default());
+        let requests = payload_index_requests("docs", &existing);
+        let fields: Vec<&str> = requests.iter().map(|r| r.field_name.as_str()).collect();
+        assert_eq!(fields, vec!["source_type", "model_id", "section_paths"]);
+    }
+
+    fn collection_info(size: u64, distance: Distance) -> GetCollectionInfoResponse {
+        use qdrant_client::qdrant::{
+            vectors_config, CollectionConfig, CollectionParams, VectorParams, VectorsConfig,
+        };
+
+        let params = VectorParams {
+            size,
+            distance: distance as i32,
+            ..Default::default()
+        };
+        GetCollectionInfoResponse {
+            result: Some(qdrant_client::qdrant::CollectionInfo {
+                config: Some(CollectionConfig {
+                    params: Some(CollectionParams {
+                        vectors_config: Some(VectorsConfig {
+                            config: Some(vectors_config::Config::Params(params)),
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn created_vector_params(request: &CreateCollection) -> qdrant_client::qdrant::VectorParams {
+        let config = request
+            .vectors_config
+            .as_ref()
+            .and_then(|v| v.config.as_ref());
+        match config {
+            Some(qdrant_client::qdrant::vectors_config::Config::Params(params)) => *params,
+            other => panic!("unexpected vectors config {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collection_uses_configured_distance() {
+        let mut store = QdrantStore::new("http://127.0.0.1:6334", "docs", 3, None)
+            .await
+            .expect("store should initialize");
+
+        let params = created_vector_params(&store.create_collection_request());
+        assert_eq!(params.size, 3);
+        assert_eq!(params.distance, Distance::Cosine as i32);
+        store
+            .check_collection_config(&collection_info(3, Distance::Cosine))
+            .expect("matching collection should pass");
+
+        store.distance = VectorDistance::Dot;
+        let params = created_vector_params(&store.create_collection_request());
+        assert_eq!(params.distance, Distance::Dot as i32);
+
+        // A collection created with another metric is rejected
+        let err = store
+            .check_collection_config(&collection_info(3, Distance::Cosine))
+            .expect_err("distance mismatch should be reported");
+        let message = err.to_string();
+        assert!(message.contains("uses cosine distance"), "{message}");
+        assert!(message.contains("embedding.distance is dot"), "{message}");
+
+        store.distance = VectorDistance::Euclidean;
+        let params = created_vector_params(&store.create_collection_request());
+        assert_eq!(params.distance, Distance::Euclid as i32);
+        assert_eq!(similarity_from_score(VectorDistance::Euclidean, 0.0), 1.0);
+        assert_eq!(similarity_from_score(VectorDistance::Euclidean, 1.0), 0.5);
+        assert_eq!(similarity_from_score(VectorDistance::Cosine, 0.7), 0.7);
+        assert_eq!(similarity_from_score(VectorDistance::Dot, 0.0), 0.5);
+
+        // Large dot products stay ordered instead of tying at 1
+        let high = similarity_from_score(VectorDistance::Dot, 3.0);
+        let higher = similarity_from_score(VectorDistance::Dot, 5.0);
+        assert!(0.9 < high && high < higher && higher < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_collection_status_reports_dimension_mismatch() {
+        let store = QdrantStore::new("http://127.0.0.1:6334", "docs", 3, None)
+            .await
+            .expect("store should initialize");
+
+        let matching = store
+            .describe_collection(&collection_info(3, Distance::Cosine))
+            .unwrap();
+        assert_eq!(matching.vector_size(), Some(3));
+        assert_eq!(matching.expected_dimension, Some(3));
+        assert_eq!(matching.dimension_matches(), Some(true));
+        assert!(matching.dimension_warning.is_none());
+
+        let mismatched = store
+            .describe_collection(&collection_info(384, Distance::Cosine))
+            .unwrap();
+        assert_eq!(mismatched.vector_size(), Some(384));
+        assert_eq!(mismatched.dimension_matches(), Some(false));
+        let warning = mismatched.dimension_warning.unwrap();
+        assert!(warning.contains("has vector size 384"), "{warning}");
+        assert!(warning.contains("config expects 3"), "{warning}");
+
+        // Without a known model dimension there is nothing to compare against
+        let offline = QdrantStore::new("http://127.0.0.1:6334", "docs", 0, None)
+            .await
+            .unwrap()
+            .describe_collection(&collection_info(384, Distance::Cosine))
+            .unwrap();
+        assert_eq!(offline.expected_dimension, None);
+        assert_eq!(offline.dimension_matches(), None);
+    }
+
+    #[tokio::test]
+    async fn test_collection_quantization_follows_config() {
+        use qdrant_client::qdrant::quantization_config::Quantization as QdrantQuantization;
+
+        let mut store = QdrantStore::new("http://127.0.0.1:6334", "docs", 3, None)
+            .await
+            .expect("store should initialize");
+
+        let request = store.create_collection_request();
+        match request.quantization_config.and_then(|q| q.quantization) {
+            Some(QdrantQuantization::Scalar(scalar)) => {
+                assert_eq!(scalar.quantile, None);
+                assert_eq!(scalar.always_ram, None);
+            }
+            other => panic!("expected scalar quantization, got {other:?}"),
+        }
+
+        store.settings.quantization_quantile = Some(0.95);
+        store.settings.quantization_always_ram = true;
+        let request = store.create_collection_request();
+        match request.quantization_config.and_then(|q| q.quantization) {
+            Some(QdrantQuantization::Scalar(scalar)) => {
+                assert_eq!(scalar.quantile, Some(0.95));
+                assert_eq!(scalar.always_ram, Some(true));
+            }
+            other => panic!("expected scalar quantization, got {other:?}"),
+        }
+
+        store.settings.quantization = Quantization::None;
+        let request = store.create_collection_request();
+        assert!(request.quantization_config.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_settings_reach_collection_and_search_requests() {
+        let mut store = QdrantStore::new("http://127.0.0.1:6334", "docs", 3, None)
+            .await
+            .expect("store should initialize");
+
+        // Qdrant's defaults apply when nothing is tuned
+        assert!(store.create_collection_request().hnsw_config.is_none());
+        let search = store.search_request(vec![0.1, 0.2, 0.3], 5, None);
+        assert!(search.params.is_none());
+
+        store.settings.hnsw = crate::config::HnswConfig {
+            m: Some(32),
+            ef_construct: Some(256),
+            full_scan_threshold: Some(20000),
+            search_ef: Some(128),
+        };
+        let hnsw = store
+            .create_collection_request()
+            .hnsw_config
+            .expect("hnsw config should be set");
+        assert_eq!(hnsw.m, Some(32));
+        assert_eq!(hnsw.ef_construct, Some(256));
+        assert_eq!(hnsw.full_scan_threshold, Some(20000));
+
+        let search = store.search_request(vec![0.1, 0.2, 0.3], 5, None);
+        let params = search.params.expect("search params should be set");
+        assert_eq!(params.hnsw_ef, Some(128));
+        assert_eq!(search.limit, 5);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_in_batches_splits_and_aggregates_errors() {
+        let mut calls: Vec<Vec<usize>> = Vec::new();
+        upsert_in_batches((0..600).collect(), 256, |batch| {
+            calls.push(batch);
+            async { Ok(()) }
+        })
+        .await
+        .expect("all batches should succeed");
+
+        let sizes: Vec<usize> = calls.iter().map(|b| b.len()).collect();
+        assert_eq!(sizes, vec![256, 256, 88]);
+        let sent: Vec<usize> = calls.into_iter().flatten().collect();
+        assert_eq!(sent, (0..600).collect::<Vec<_>>());
+
+        // Later batches are still sent after a failure, and failures are combined
+        let mut attempted = 0;
+        let err = upsert_in_batches((0..10).collect(), 3, |batch: Vec<usize>| {
+            attempted += 1;
+            let fail = batch.contains(&0) || batch.contains(&9);
+            async move {
+                if fail {
+                    Err(Error::Qdrant("boom".to_string()))
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await
+        .expect_err("failed batches should be reported");
+        assert_eq!(attempted, 4);
+        let message = err.to_string();
+        assert!(message.contains("2 of 4 upsert batches failed"));
+        assert!(message.contains("batch 1:") && message.contains("batch 4:"));
+    }
+
     #[tokio::test]
     async fn test_upsert_points_rejects_dimension_mismatch() {
         let store = QdrantStore::new("http://127.0.0.1:6334", "test_collection", 3, None)
@@ -1,5 +1,7 @@
 //! Payload schema for Qdrant points
 
+use crate::chunk::{deserialize_heading_trail, heading_texts, section_paths, SectionHeading};
+use crate::parse::ApiEndpoint;
 use qdrant_client::qdrant::{PointStruct, Value as QdrantValue};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -28,7 +30,7 @@ pub struct ChunkPayload {
     /// Source ID (stable UUID)
     pub source_id: String,
 
-    /// Source type ("dir", "url", "sitemap")
+    /// Source type ("dir", "url", "sitemap", "urls", "openapi")
     pub source_type: String,
 
     /// Source URI (directory path or base URL)
@@ -44,9 +46,14 @@ pub struct ChunkPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
 
-    /// Headings hierarchy above this chunk
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub headings: Option<Vec<String>>,
+    /// Heading trail above this chunk, outermost first; older points store
+    /// bare heading strings
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_heading_trail"
+    )]
+    pub headings: Option<Vec<SectionHeading>>,
 
     /// Chunk index within the document
     pub chunk_index: i32,
@@ -68,6 +75,27 @@ pub struct ChunkPayload {
     /// Cached media content hash if available
     #[serde(skip_serializing_if = "Option::is_none")]
     pub media_hash: Option<String>,
+
+    /// HTTP method of the API operation (openapi sources)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_method: Option<String>,
+
+    /// Path template of the API operation (openapi sources)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_path: Option<String>,
+
+    /// `operationId` of the API operation (openapi sources)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_operation_id: Option<String>,
+
+    /// Tags of the API operation (openapi sources)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_tags: Vec<String>,
+
+    /// Embedding model that produced this point's vector; unset on points
+    /// written before models were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
 }
 
 impl ChunkPayload {
@@ -95,9 +123,22 @@ impl ChunkPayload {
             modality: Some("text".to_string()),
             media_url: None,
             media_hash: None,
+            api_method: None,
+            api_path: None,
+            api_operation_id: None,
+            api_tags: Vec::new(),
+            model_id: None,
         }
     }
 
+    /// Tag the chunk with the API operation its document describes
+    pub fn set_api_endpoint(&mut self, endpoint: &ApiEndpoint) {
+        self.api_method = Some(endpoint.method.clone());
+        self.api_path = Some(endpoint.path.clone());
+        self.api_operation_id = endpoint.operation_id.clone();
+        self.api_tags = endpoint.tags.clone();
+    }
+
     /// Convert to Qdrant payload format
     pub fn to_qdrant_payload(self) -> HashMap<String, QdrantValue> {
         let mut map = HashMap::new();
@@ -122,7 +163,7 @@ impl ChunkPayload {
         }
 
         if let Some(ref headings) = self.headings {
-            let values: Vec<QdrantValue> = headings.iter().map(|s| string_to_qdrant(s)).collect();
+            let values: Vec<QdrantValue> = headings.iter().map(heading_to_qdrant).collect();
             map.insert(
                 "headings".to_string(),
                 QdrantValue {
@@ -131,6 +172,20 @@ impl ChunkPayload {
                     )),
                 },
             );
+
+            // Leading paths of the trail, so `--section` can filter in Qdrant
+            let values: Vec<QdrantValue> = section_paths(&heading_texts(headings))
+                .iter()
+                .map(|path| string_to_qdrant(path))
+                .collect();
+            map.insert(
+                "section_paths".to_string(),
+                QdrantValue {
+                    kind: Some(qdrant_client::qdrant::value::Kind::ListValue(
+                        qdrant_client::qdrant::ListValue { values },
+                    )),
+                },
+            );
         }
 
         if let Some(ref modality) = self.modality {
@@ -145,6 +200,38 @@ impl ChunkPayload {
             map.insert("media_hash".to_string(), string_to_qdrant(media_hash));
         }
 
+        if let Some(ref api_method) = self.api_method {
+            map.insert("api_method".to_string(), string_to_qdrant(api_method));
+        }
+
+        if let Some(ref api_path) = self.api_path {
+            map.insert("api_path".to_string(), string_to_qdrant(api_path));
+        }
+
+        if let Some(ref api_operation_id) = self.api_operation_id {
+            map.insert(
+                "api_operation_id".to_string(),
+                string_to_qdrant(api_operation_id),
+            );
+        }
+
+        if !self.api_tags.is_empty() {
+            let values: Vec<QdrantValue> =
+                self.api_tags.iter().map(|t| string_to_qdrant(t)).collect();
+            map.insert(
+                "api_tags".to_string(),
+                QdrantValue {
+                    kind: Some(qdrant_client::qdrant::value::Kind::ListValue(
+                        qdrant_client::qdrant::ListValue { values },
+                    )),
+                },
+            );
+        }
+
+        if let Some(ref model_id) = self.model_id {
+            map.insert("model_id".to_string(), string_to_qdrant(model_id));
+        }
+
         map
     }
 }
@@ -157,6 +244,18 @@ fn string_to_qdrant(s: &str) -> QdrantValue {
     }
 }
 
+fn heading_to_qdrant(heading: &SectionHeading) -> QdrantValue {
+    let fields = HashMap::from([
+        ("level".to_string(), int_to_qdrant(heading.level as i64)),
+        ("text".to_string(), string_to_qdrant(&heading.text)),
+    ]);
+    QdrantValue {
+        kind: Some(qdrant_client::qdrant::value::Kind::StructValue(
+            qdrant_client::qdrant::Struct { fields },
+        )),
+    }
+}
+
 fn int_to_qdrant(i: i64) -> QdrantValue {
     QdrantValue {
         kind: Some(qdrant_client::qdrant::value::Kind::IntegerValue(i)),
@@ -179,6 +278,11 @@ impl From<Map<String, Value>> for ChunkPayload {
             modality: Some("text".to_string()),
             media_url: None,
             media_hash: None,
+            api_method: None,
+            api_path: None,
+            api_operation_id: None,
+            api_tags: Vec::new(),
+            model_id: None,
         })
     }
 }
@@ -207,4 +311,82 @@ mod tests {
         let parsed: ChunkPayload = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.source_id, "source-123");
     }
+
+    #[test]
+    fn test_payload_carries_api_endpoint() {
+        let mut payload = ChunkPayload::new(
+            "source-123".to_string(),
+            "openapi".to_string(),
+            "/specs/petstore.yaml".to_string(),
+            "doc-456".to_string(),
+            "/specs/petstore.yaml#/paths/~1pets/get".to_string(),
+            0,
+            "hash123".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        );
+        payload.set_api_endpoint(&ApiEndpoint {
+            method: "GET".to_string(),
+            path: "/pets".to_string(),
+            operation_id: Some("listPets".to_string()),
+            tags: vec!["pets".to_string()],
+        });
+
+        let map = payload.to_qdrant_payload();
+        assert_eq!(map["api_method"], string_to_qdrant("GET"));
+        assert_eq!(map["api_path"], string_to_qdrant("/pets"));
+        assert_eq!(map["api_operation_id"], string_to_qdrant("listPets"));
+        let Some(qdrant_client::qdrant::value::Kind::ListValue(tags)) = &map["api_tags"].kind
+        else {
+            panic!("api_tags should be a list");
+        };
+        assert_eq!(tags.values, vec![string_to_qdrant("pets")]);
+    }
+
+    #[test]
+    fn test_payload_headings_keep_levels_and_read_old_strings() {
+        let mut payload = ChunkPayload::new(
+            "source-123".to_string(),
+            "dir".to_string(),
+            "/docs".to_string(),
+            "doc-456".to_string(),
+            "/docs/readme.md".to_string(),
+            0,
+            "hash123".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        );
+        let trail = vec![
+            SectionHeading::new(1, "API"),
+            SectionHeading::new(3, "Auth"),
+        ];
+        payload.headings = Some(trail.clone());
+
+        let qdrant = payload.clone().to_qdrant_payload();
+        let Some(qdrant_client::qdrant::value::Kind::ListValue(paths)) =
+            &qdrant["section_paths"].kind
+        else {
+            panic!("section_paths should be a list");
+        };
+        assert_eq!(
+            paths.values,
+            vec![string_to_qdrant("api"), string_to_qdrant("api/auth")]
+        );
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(
+            json["headings"],
+            serde_json::json!([{"level": 1, "text": "API"}, {"level": 3, "text": "Auth"}])
+        );
+        let Value::Object(map) = json else { panic!() };
+        assert_eq!(ChunkPayload::from(map.clone()).headings, Some(trail));
+
+        let mut old = map;
+        old.insert("headings".to_string(), serde_json::json!(["API", "Auth"]));
+        assert_eq!(
+            ChunkPayload::from(old).headings,
+            Some(vec![
+                SectionHeading::new(1, "API"),
+                SectionHeading::new(2, "Auth")
+            ])
+        );
+    }
 }
@@ -0,0 +1,106 @@
+//! Stand-ins shared by unit tests that ingest, watch or reindex without an
+//! embedding backend
+
+use crate::config::{EmbeddingDimensionSource, ResolvedEmbeddingConfig};
+use crate::embed::Embedder;
+use crate::embedding_backend::{EmbeddingBackendConfig, EmbeddingBackendKind};
+use crate::error::Result;
+use crate::models::MultimodalStrategy;
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+mod qdrant;
+
+pub use qdrant::FakeQdrant;
+
+/// Dimension of the vectors the test embedders return
+pub const TEST_DIMENSION: usize = 3;
+
+/// A resolved embedding model of [`TEST_DIMENSION`] that never needs its backend
+pub fn test_embedding_config(supports_image: bool, multi_vector: bool) -> ResolvedEmbeddingConfig {
+    ResolvedEmbeddingConfig {
+        model_id: "test-model".to_string(),
+        family: "test".to_string(),
+        modalities: if supports_image {
+            vec!["text".to_string(), "image".to_string()]
+        } else {
+            vec!["text".to_string()]
+        },
+        dimension: TEST_DIMENSION,
+        native_dimension: TEST_DIMENSION,
+        dimension_source: EmbeddingDimensionSource::Config,
+        backend: EmbeddingBackendConfig {
+            kind: EmbeddingBackendKind::Http,
+            url: "http://localhost:7997".to_string(),
+        },
+        strategy: if multi_vector {
+            MultimodalStrategy::LateInteraction
+        } else {
+            MultimodalStrategy::DualEncoder
+        },
+        supports_text: true,
+        supports_image,
+        supports_joint_inputs: false,
+        supports_multi_vector: multi_vector,
+        supports_mrl: false,
+        max_batch: 32,
+    }
+}
+
+/// Embeds every text as a zero vector
+pub struct NoopEmbedder;
+
+#[async_trait]
+impl Embedder for NoopEmbedder {
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| vec![0.0; TEST_DIMENSION]).collect())
+    }
+
+    fn dimension(&self) -> usize {
+        TEST_DIMENSION
+    }
+
+    fn model_name(&self) -> &str {
+        "noop"
+    }
+}
+
+/// Records the texts and image paths it is asked to embed
+#[derive(Default)]
+pub struct RecordingEmbedder {
+    texts: Mutex<Vec<String>>,
+    images: Mutex<Vec<String>>,
+}
+
+impl RecordingEmbedder {
+    pub fn texts(&self) -> Vec<String> {
+        self.texts.lock().unwrap().clone()
+    }
+
+    pub fn images(&self) -> Vec<String> {
+        self.images.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Embedder for RecordingEmbedder {
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let vectors = texts.iter().map(|_| vec![0.5; TEST_DIMENSION]).collect();
+        self.texts.lock().unwrap().extend(texts);
+        Ok(vectors)
+    }
+
+    async fn embed_images(&self, images: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let vectors = images.iter().map(|_| vec![1.0; TEST_DIMENSION]).collect();
+        self.images.lock().unwrap().extend(images);
+        Ok(vectors)
+    }
+
+    fn dimension(&self) -> usize {
+        TEST_DIMENSION
+    }
+
+    fn model_name(&self) -> &str {
+        "recording"
+    }
+}
@@ -0,0 +1,325 @@
+//! An in-memory stand-in for Qdrant's points service, so tests can ingest
+//! documents that chunk and embed without a running Qdrant
+
+use super::TEST_DIMENSION;
+use crate::store::QdrantStore;
+use qdrant_client::qdrant::point_id::PointIdOptions;
+use qdrant_client::qdrant::points_selector::PointsSelectorOneOf;
+use qdrant_client::qdrant::points_server::{Points, PointsServer};
+use qdrant_client::qdrant::{
+    ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
+    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
+    DiscoverBatchPoints, DiscoverBatchResponse, DiscoverPoints, DiscoverResponse, FacetCounts,
+    FacetResponse, GetPoints, GetResponse, PointId, PointsOperationResponse, PointsSelector,
+    QueryBatchPoints, QueryBatchResponse, QueryGroupsResponse, QueryPointGroups, QueryPoints,
+    QueryResponse, RecommendBatchPoints, RecommendBatchResponse, RecommendGroupsResponse,
+    RecommendPointGroups, RecommendPoints, RecommendResponse, RetrievedPoint, ScrollPoints,
+    ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
+    SearchMatrixOffsetsResponse, SearchMatrixPairsResponse, SearchMatrixPoints, SearchPointGroups,
+    SearchPoints, SearchResponse, SetPayloadPoints, UpdateBatchPoints, UpdateBatchResponse,
+    UpdatePointVectors, UpdateResult, UpdateStatus, UpsertPoints, Value,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+
+/// Payloads of the points held by a [`FakeQdrant`], keyed by point ID
+type PointMap = Arc<Mutex<HashMap<String, HashMap<String, Value>>>>;
+
+/// A Qdrant gRPC endpoint that keeps upserted points in memory.
+///
+/// Upsert, delete, get and set-payload work on a single implicit collection;
+/// every other call fails as unimplemented. The server stops when dropped.
+pub struct FakeQdrant {
+    url: String,
+    points: PointMap,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl FakeQdrant {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let points = PointMap::default();
+        let service = PointsServer::new(PointsService {
+            points: points.clone(),
+        });
+        let server = tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+                .unwrap();
+        });
+        Self {
+            url,
+            points,
+            server,
+        }
+    }
+
+    /// A store writing to this server
+    pub async fn store(&self) -> QdrantStore {
+        QdrantStore::new(&self.url, "test_collection", TEST_DIMENSION, None)
+            .await
+            .unwrap()
+    }
+
+    /// IDs of the points currently stored
+    pub fn point_ids(&self) -> BTreeSet<String> {
+        self.points.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+impl Drop for FakeQdrant {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+struct PointsService {
+    points: PointMap,
+}
+
+fn point_key(id: &PointId) -> Option<String> {
+    match id.point_id_options.as_ref()? {
+        PointIdOptions::Num(num) => Some(num.to_string()),
+        PointIdOptions::Uuid(uuid) => Some(uuid.clone()),
+    }
+}
+
+/// Keys of the points a selector lists, or `None` for a filter, which is not supported
+fn selected_keys(selector: Option<&PointsSelector>) -> Option<Vec<String>> {
+    match selector?.points_selector_one_of.as_ref()? {
+        PointsSelectorOneOf::Points(list) => Some(list.ids.iter().filter_map(point_key).collect()),
+        PointsSelectorOneOf::Filter(_) => None,
+    }
+}
+
+fn completed() -> Response<PointsOperationResponse> {
+    Response::new(PointsOperationResponse {
+        result: Some(UpdateResult {
+            operation_id: None,
+            status: UpdateStatus::Completed as i32,
+        }),
+        ..Default::default()
+    })
+}
+
+#[tonic::async_trait]
+impl Points for PointsService {
+    async fn upsert(
+        &self,
+        request: Request<UpsertPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let mut points = self.points.lock().unwrap();
+        for point in request.into_inner().points {
+            if let Some(key) = point.id.as_ref().and_then(point_key) {
+                points.insert(key, point.payload);
+            }
+        }
+        Ok(completed())
+    }
+
+    async fn delete(
+        &self,
+        request: Request<DeletePoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let keys = selected_keys(request.get_ref().points.as_ref())
+            .ok_or_else(|| Status::unimplemented("filter selectors"))?;
+        let mut points = self.points.lock().unwrap();
+        for key in keys {
+            points.remove(&key);
+        }
+        Ok(completed())
+    }
+
+    async fn get(&self, request: Request<GetPoints>) -> Result<Response<GetResponse>, Status> {
+        let points = self.points.lock().unwrap();
+        let result = request
+            .into_inner()
+            .ids
+            .into_iter()
+            .filter(|id| point_key(id).is_some_and(|key| points.contains_key(&key)))
+            .map(|id| RetrievedPoint {
+                id: Some(id),
+                ..Default::default()
+            })
+            .collect();
+        Ok(Response::new(GetResponse {
+            result,
+            ..Default::default()
+        }))
+    }
+
+    async fn set_payload(
+        &self,
+        request: Request<SetPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let request = request.into_inner();
+        let keys = selected_keys(request.points_selector.as_ref())
+            .ok_or_else(|| Status::unimplemented("filter selectors"))?;
+        let mut points = self.points.lock().unwrap();
+        for key in keys {
+            if let Some(payload) = points.get_mut(&key) {
+                payload.extend(request.payload.clone());
+            }
+        }
+        Ok(completed())
+    }
+
+    async fn update_vectors(
+        &self,
+        _: Request<UpdatePointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("update_vectors"))
+    }
+
+    async fn delete_vectors(
+        &self,
+        _: Request<DeletePointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("delete_vectors"))
+    }
+
+    async fn overwrite_payload(
+        &self,
+        _: Request<SetPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("overwrite_payload"))
+    }
+
+    async fn delete_payload(
+        &self,
+        _: Request<DeletePayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("delete_payload"))
+    }
+
+    async fn clear_payload(
+        &self,
+        _: Request<ClearPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("clear_payload"))
+    }
+
+    async fn create_field_index(
+        &self,
+        _: Request<CreateFieldIndexCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("create_field_index"))
+    }
+
+    async fn delete_field_index(
+        &self,
+        _: Request<DeleteFieldIndexCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        Err(Status::unimplemented("delete_field_index"))
+    }
+
+    async fn search(&self, _: Request<SearchPoints>) -> Result<Response<SearchResponse>, Status> {
+        Err(Status::unimplemented("search"))
+    }
+
+    async fn search_batch(
+        &self,
+        _: Request<SearchBatchPoints>,
+    ) -> Result<Response<SearchBatchResponse>, Status> {
+        Err(Status::unimplemented("search_batch"))
+    }
+
+    async fn search_groups(
+        &self,
+        _: Request<SearchPointGroups>,
+    ) -> Result<Response<SearchGroupsResponse>, Status> {
+        Err(Status::unimplemented("search_groups"))
+    }
+
+    async fn scroll(&self, _: Request<ScrollPoints>) -> Result<Response<ScrollResponse>, Status> {
+        Err(Status::unimplemented("scroll"))
+    }
+
+    async fn recommend(
+        &self,
+        _: Request<RecommendPoints>,
+    ) -> Result<Response<RecommendResponse>, Status> {
+        Err(Status::unimplemented("recommend"))
+    }
+
+    async fn recommend_batch(
+        &self,
+        _: Request<RecommendBatchPoints>,
+    ) -> Result<Response<RecommendBatchResponse>, Status> {
+        Err(Status::unimplemented("recommend_batch"))
+    }
+
+    async fn recommend_groups(
+        &self,
+        _: Request<RecommendPointGroups>,
+    ) -> Result<Response<RecommendGroupsResponse>, Status> {
+        Err(Status::unimplemented("recommend_groups"))
+    }
+
+    async fn discover(
+        &self,
+        _: Request<DiscoverPoints>,
+    ) -> Result<Response<DiscoverResponse>, Status> {
+        Err(Status::unimplemented("discover"))
+    }
+
+    async fn discover_batch(
+        &self,
+        _: Request<DiscoverBatchPoints>,
+    ) -> Result<Response<DiscoverBatchResponse>, Status> {
+        Err(Status::unimplemented("discover_batch"))
+    }
+
+    async fn count(&self, _: Request<CountPoints>) -> Result<Response<CountResponse>, Status> {
+        Err(Status::unimplemented("count"))
+    }
+
+    async fn update_batch(
+        &self,
+        _: Request<UpdateBatchPoints>,
+    ) -> Result<Response<UpdateBatchResponse>, Status> {
+        Err(Status::unimplemented("update_batch"))
+    }
+
+    async fn query(&self, _: Request<QueryPoints>) -> Result<Response<QueryResponse>, Status> {
+        Err(Status::unimplemented("query"))
+    }
+
+    async fn query_batch(
+        &self,
+        _: Request<QueryBatchPoints>,
+    ) -> Result<Response<QueryBatchResponse>, Status> {
+        Err(Status::unimplemented("query_batch"))
+    }
+
+    async fn query_groups(
+        &self,
+        _: Request<QueryPointGroups>,
+    ) -> Result<Response<QueryGroupsResponse>, Status> {
+        Err(Status::unimplemented("query_groups"))
+    }
+
+    async fn facet(&self, _: Request<FacetCounts>) -> Result<Response<FacetResponse>, Status> {
+        Err(Status::unimplemented("facet"))
+    }
+
+    async fn search_matrix_pairs(
+        &self,
+        _: Request<SearchMatrixPoints>,
+    ) -> Result<Response<SearchMatrixPairsResponse>, Status> {
+        Err(Status::unimplemented("search_matrix_pairs"))
+    }
+
+    async fn search_matrix_offsets(
+        &self,
+        _: Request<SearchMatrixPoints>,
+    ) -> Result<Response<SearchMatrixOffsetsResponse>, Status> {
+        Err(Status::unimplemented("search_matrix_offsets"))
+    }
+}
@@ -0,0 +1,145 @@
+//! Time bounds for history filters such as `runs --since 7d`
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// A half-open window `[since, until)` over run start times; either end may be open
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    /// Inclusive lower bound
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> Self {
+        Self { since, until }
+    }
+
+    /// Whether neither end is bounded
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    /// Whether `time` falls inside the window
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time < until)
+    }
+
+    /// Whether a stored RFC3339 timestamp falls inside the window; unparseable
+    /// timestamps only match an unbounded window
+    pub fn contains_rfc3339(&self, timestamp: &str) -> bool {
+        match DateTime::parse_from_rfc3339(timestamp) {
+            Ok(time) => self.contains(time.with_timezone(&Utc)),
+            Err(_) => self.is_unbounded(),
+        }
+    }
+}
+
+/// Parse a time bound: an RFC3339 timestamp, a `YYYY-MM-DD` date (midnight
+/// UTC), or a relative age such as `30m`, `12h`, `7d` or `2w` counted back
+/// from `now`
+pub fn parse_time_bound(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_time(Default::default()).and_utc());
+    }
+    parse_relative_age(input)
+        .map(|age| now - age)
+        .ok_or_else(|| {
+            format!(
+                "invalid time '{}': expected RFC3339 (2024-05-01T12:00:00Z), a date (2024-05-01) or a relative age (30m, 12h, 7d, 2w)",
+                input
+            )
+        })
+}
+
+/// Parse `<number><unit>` with unit `s`, `m`, `h`, `d` or `w`
+fn parse_relative_age(input: &str) -> Option<Duration> {
+    let unit_at = input.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = input.split_at(unit_at);
+    let amount: i64 = amount.parse().ok()?;
+    match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// `clap` value parser for `--since`/`--until`, relative to the current time
+pub fn parse_time_arg(input: &str) -> Result<DateTime<Utc>, String> {
+    parse_time_bound(input, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_absolute_time_bounds() {
+        let now = at("2024-05-10T12:00:00Z");
+        assert_eq!(
+            parse_time_bound("2024-05-01T08:30:00+02:00", now).unwrap(),
+            at("2024-05-01T06:30:00Z")
+        );
+        assert_eq!(
+            parse_time_bound("2024-05-01", now).unwrap(),
+            at("2024-05-01T00:00:00Z")
+        );
+        assert!(parse_time_bound("2024-13-01", now).is_err());
+        assert!(parse_time_bound("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn test_parse_relative_time_bounds() {
+        let now = at("2024-05-10T12:00:00Z");
+        assert_eq!(
+            parse_time_bound("7d", now).unwrap(),
+            at("2024-05-03T12:00:00Z")
+        );
+        assert_eq!(
+            parse_time_bound("12h", now).unwrap(),
+            at("2024-05-10T00:00:00Z")
+        );
+        assert_eq!(
+            parse_time_bound("30m", now).unwrap(),
+            at("2024-05-10T11:30:00Z")
+        );
+        assert_eq!(
+            parse_time_bound("2w", now).unwrap(),
+            at("2024-04-26T12:00:00Z")
+        );
+        assert_eq!(parse_time_bound("0s", now).unwrap(), now);
+        for bad in ["7", "d", "7y", "-7d", "7 d", ""] {
+            assert!(parse_time_bound(bad, now).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_range_includes_since_and_excludes_until() {
+        let range = TimeRange::new(
+            Some(at("2024-05-01T00:00:00Z")),
+            Some(at("2024-05-02T00:00:00Z")),
+        );
+        assert!(range.contains(at("2024-05-01T00:00:00Z")));
+        assert!(range.contains(at("2024-05-01T23:59:59Z")));
+        assert!(!range.contains(at("2024-05-02T00:00:00Z")));
+        assert!(!range.contains(at("2024-04-30T23:59:59Z")));
+
+        assert!(range.contains_rfc3339("2024-05-01T12:00:00+00:00"));
+        assert!(!range.contains_rfc3339("not a time"));
+        assert!(TimeRange::default().contains_rfc3339("not a time"));
+    }
+}
@@ -0,0 +1,284 @@
+//! End-to-end checks of the librarian binary
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn librarian(config_path: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_librarian"))
+        .arg("--config")
+        .arg(config_path)
+        .args(args)
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_quiet_status_prints_nothing() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+    // Status reports an unreachable Qdrant rather than failing on it
+    std::fs::write(&config_path, "qdrant_url = \"http://127.0.0.1:9\"\n").unwrap();
+
+    let output = librarian(&config_path, &["status"]);
+    assert!(output.status.success());
+    assert!(!output.stdout.is_empty());
+
+    let output = librarian(&config_path, &["--quiet", "status"]);
+    assert!(output.status.success());
+    assert!(
+        output.stdout.is_empty(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    // Output that was asked for explicitly is still written
+    let output = librarian(&config_path, &["--quiet", "--json", "status"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("\"qdrant_connected\": false"));
+}
+
+#[test]
+fn test_exit_codes_distinguish_failures() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+
+    let output = librarian(&config_path, &["sources"]);
+    assert_eq!(output.status.code(), Some(2), "missing config");
+
+    std::fs::write(&config_path, "qdrant_url = \"http://127.0.0.1:9\"\n").unwrap();
+
+    let output = librarian(&config_path, &["sources", "--no-such-flag"]);
+    assert_eq!(output.status.code(), Some(64), "bad arguments");
+
+    let output = librarian(&config_path, &["info", "missing"]);
+    assert_eq!(output.status.code(), Some(4), "unknown source");
+
+    let output = librarian(&config_path, &["prune", "--dry-run"]);
+    assert_eq!(output.status.code(), Some(3), "Qdrant down");
+
+    let output = librarian(
+        &config_path,
+        &["--json", "query", "nothing", "--keyword-only"],
+    );
+    assert_eq!(output.status.code(), Some(8), "no results");
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["results"], serde_json::json!([]));
+}
+
+#[test]
+fn test_invalid_queries_fail_before_contacting_backends() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+    // Nothing listens on either port, so reaching a backend would exit with 3
+    std::fs::write(
+        &config_path,
+        "qdrant_url = \"http://127.0.0.1:9\"\n\n[embedding]\nurl = \"http://127.0.0.1:9\"\n\n\
+         [reranker]\nenabled = true\n",
+    )
+    .unwrap();
+
+    let output = librarian(&config_path, &["query", "   "]);
+    assert_eq!(output.status.code(), Some(64), "empty query");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("query text is empty"));
+
+    let output = librarian(&config_path, &["query", "crawl", "--offset", "100000"]);
+    assert_eq!(output.status.code(), Some(64), "offset past max_results");
+
+    let output = librarian(
+        &config_path,
+        &[
+            "query",
+            "crawl",
+            "--limit",
+            "10",
+            "--rerank-candidates",
+            "5",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(64), "too few rerank candidates");
+}
+
+#[test]
+fn test_output_file_receives_json_instead_of_stdout() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "qdrant_url = \"http://127.0.0.1:9\"\n").unwrap();
+    let out_path = tmp.path().join("status.json");
+    let out_arg = out_path.to_str().unwrap();
+
+    let output = librarian(
+        &config_path,
+        &["status", "--json", "--output-file", out_arg],
+    );
+    assert!(output.status.success());
+    assert!(
+        output.stdout.is_empty(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let status: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+    assert_eq!(status["qdrant_connected"], false);
+
+    // Without a JSON format there is nothing to write
+    let output = librarian(&config_path, &["status", "--output-file", out_arg]);
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_json_log_format_writes_parseable_lines_to_stderr() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "qdrant_url = \"http://127.0.0.1:9\"\n").unwrap();
+
+    let output = librarian(
+        &config_path,
+        &[
+            "--log-format",
+            "json",
+            "--log-level",
+            "librarian=debug",
+            "--json",
+            "status",
+        ],
+    );
+    assert!(output.status.success());
+    // Logs never reach stdout, which carries only the command's own JSON
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["qdrant_connected"], false);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{e}: {line}")))
+        .collect();
+    assert!(!lines.is_empty());
+    assert!(lines
+        .iter()
+        .all(|line| line["level"].is_string() && line["fields"].is_object()));
+    assert!(lines
+        .iter()
+        .any(|line| line["target"] == "librarian::config" && line["level"] == "DEBUG"));
+
+    let output = librarian(&config_path, &["--log-format", "yaml", "status"]);
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ingest_url_rerun_keeps_stored_crawl_limits() {
+    use librarian::meta::{MetaDb, Source, SourceType};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    let page = |links: &str| {
+        ResponseTemplate::new(200).set_body_raw(
+            format!("<html><body><h1>Guide</h1><p>Some text.</p>{links}</body></html>"),
+            "text/html",
+        )
+    };
+    Mock::given(method("GET"))
+        .and(path("/docs/"))
+        .respond_with(page(r#"<a href="/docs/a">a</a><a href="/docs/b">b</a>"#))
+        .mount(&server)
+        .await;
+    for leaf in ["/docs/a", "/docs/b"] {
+        Mock::given(method("GET"))
+            .and(path(leaf))
+            .respond_with(page(""))
+            .mount(&server)
+            .await;
+    }
+    let seed = format!("{}/docs/", server.uri());
+
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        "qdrant_url = \"http://127.0.0.1:9\"\n\n[crawl]\nrate_limit_per_host = 100.0\n",
+    )
+    .unwrap();
+    let db = MetaDb::new(&tmp.path().join("metadata.db")).await.unwrap();
+    let source = Source::new(SourceType::Url, seed.clone(), None);
+    db.insert_source(&source).await.unwrap();
+    drop(db);
+
+    let run = |args: Vec<String>| {
+        let config_path = config_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            librarian(&config_path, &args)
+        })
+    };
+    let output = run(vec![
+        "reconfigure".into(),
+        source.id.clone(),
+        "--max-pages".into(),
+        "1".into(),
+    ])
+    .await
+    .unwrap();
+    assert!(output.status.success());
+
+    // Re-running without --max-pages crawls with the stored limit
+    let output = run(vec![
+        "--json".into(),
+        "ingest".into(),
+        "url".into(),
+        seed.clone(),
+        "--dry-run".into(),
+    ])
+    .await
+    .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["crawl"]["pages_fetched"], 1);
+
+    let output = run(vec![
+        "--json".into(),
+        "reconfigure".into(),
+        source.id.clone(),
+    ])
+    .await
+    .unwrap();
+    let stored: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stored["max_pages"], 1);
+    assert!(stored.get("max_depth").is_none());
+
+    // A stored --use-sitemap can be turned back off
+    for (flag, expected) in [("--use-sitemap", true), ("--no-use-sitemap", false)] {
+        let output = run(vec![
+            "--json".into(),
+            "reconfigure".into(),
+            source.id.clone(),
+            flag.into(),
+        ])
+        .await
+        .unwrap();
+        let stored: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(stored["use_sitemap"], expected, "{flag}");
+    }
+}
+
+#[test]
+fn test_quiet_keeps_command_results() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(&config_path, "qdrant_url = \"http://127.0.0.1:9\"\n").unwrap();
+
+    for args in [
+        &["--quiet", "runs"][..],
+        &["--quiet", "sources"],
+        &["--quiet", "config", "get", "qdrant_url"],
+    ] {
+        let output = librarian(&config_path, args);
+        assert!(output.status.success(), "{args:?}");
+        assert!(!output.stdout.is_empty(), "{args:?} printed nothing");
+    }
+}